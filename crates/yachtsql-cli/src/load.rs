@@ -0,0 +1,286 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow, bail};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use yachtsql::{DataType, Schema, YachtSQLSession};
+
+/// A `--load TABLE=PATH` argument.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadSpec {
+    pub table: String,
+    pub path: PathBuf,
+}
+
+impl LoadSpec {
+    pub fn parse(spec: &str) -> Result<Self> {
+        let (table, path) = spec
+            .split_once('=')
+            .filter(|(table, path)| !table.is_empty() && !path.is_empty())
+            .ok_or_else(|| anyhow!("expected TABLE=PATH, got {spec}"))?;
+        Ok(Self {
+            table: table.to_string(),
+            path: PathBuf::from(path),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FileFormat {
+    Csv,
+    Json,
+    Parquet,
+}
+
+impl FileFormat {
+    fn from_path(path: &Path) -> Result<Self> {
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_ascii_lowercase);
+        match extension.as_deref() {
+            Some("csv") => Ok(FileFormat::Csv),
+            Some("jsonl" | "ndjson" | "json") => Ok(FileFormat::Json),
+            Some("parquet") => Ok(FileFormat::Parquet),
+            _ => bail!(
+                "cannot tell the format of {}; expected a .csv, .jsonl or .parquet file",
+                path.display()
+            ),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            FileFormat::Csv => "CSV",
+            FileFormat::Json => "JSON",
+            FileFormat::Parquet => "PARQUET",
+        }
+    }
+}
+
+/// Appends the rows of a CSV, newline-delimited JSON or Parquet file to a table.
+///
+/// A missing table is created first, with columns taken from the CSV header, the
+/// JSON keys or the Parquet schema. CSV and JSON column types are inferred as
+/// `INT64`, `FLOAT64`, `BOOL` or `STRING`; create the table beforehand for
+/// anything else. CSV files must start with a header row.
+pub async fn load_file(session: &YachtSQLSession, spec: &LoadSpec) -> Result<()> {
+    let format = FileFormat::from_path(&spec.path)?;
+    let path = spec
+        .path
+        .canonicalize()
+        .with_context(|| format!("Failed to open {}", spec.path.display()))?;
+
+    if session.catalog().get_table_schema(&spec.table).is_none() {
+        let columns = match format {
+            FileFormat::Csv => infer_csv_columns(&path)?,
+            FileFormat::Json => infer_json_columns(&path)?,
+            FileFormat::Parquet => parquet_columns(&path)?,
+        };
+        let columns: Vec<String> = columns
+            .iter()
+            .map(|(name, data_type)| format!("`{}` {}", name.replace('`', "\\`"), data_type))
+            .collect();
+        session
+            .run(&format!(
+                "CREATE TABLE {} ({})",
+                spec.table,
+                columns.join(", ")
+            ))
+            .await
+            .with_context(|| format!("Failed to create table {}", spec.table))?;
+    }
+
+    let skip_header = if format == FileFormat::Csv {
+        ", skip_leading_rows=1"
+    } else {
+        ""
+    };
+    session
+        .run(&format!(
+            "LOAD DATA INTO {} FROM FILES (format='{}', uris=['{}']{})",
+            spec.table,
+            format.name(),
+            path.display(),
+            skip_header
+        ))
+        .await
+        .with_context(|| format!("Failed to load {} into {}", spec.path.display(), spec.table))?;
+    Ok(())
+}
+
+fn infer_csv_columns(path: &Path) -> Result<Vec<(String, DataType)>> {
+    let mut lines = BufReader::new(File::open(path)?).lines();
+    let header = lines
+        .next()
+        .transpose()?
+        .ok_or_else(|| anyhow!("{} is empty", path.display()))?;
+    let mut columns: Vec<(String, Option<DataType>)> = split_csv_line(&header)
+        .into_iter()
+        .map(|name| (name, None))
+        .collect();
+
+    for line in lines {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        for ((_, data_type), field) in columns.iter_mut().zip(split_csv_line(&line)) {
+            if !field.is_empty() {
+                *data_type = Some(widen(data_type.take(), csv_type(&field)));
+            }
+        }
+    }
+
+    Ok(columns
+        .into_iter()
+        .map(|(name, data_type)| (name, data_type.unwrap_or(DataType::String)))
+        .collect())
+}
+
+/// Splits a CSV line the way `LOAD DATA` does: fields are trimmed and may be
+/// double-quoted, with `""` standing for a quote.
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                chars.next();
+                current.push('"');
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut current).trim().to_string()),
+            c => current.push(c),
+        }
+    }
+    fields.push(current.trim().to_string());
+    fields
+}
+
+fn csv_type(field: &str) -> DataType {
+    if field.parse::<i64>().is_ok() {
+        DataType::Int64
+    } else if field.parse::<f64>().is_ok() {
+        DataType::Float64
+    } else if field.eq_ignore_ascii_case("true") || field.eq_ignore_ascii_case("false") {
+        DataType::Bool
+    } else {
+        DataType::String
+    }
+}
+
+/// The narrowest of `INT64`, `FLOAT64`, `BOOL` and `STRING` that holds values of
+/// both types.
+fn widen(current: Option<DataType>, next: DataType) -> DataType {
+    match (current, next) {
+        (None, next) => next,
+        (Some(current), next) if current == next => current,
+        (Some(DataType::Int64 | DataType::Float64), DataType::Int64 | DataType::Float64) => {
+            DataType::Float64
+        }
+        _ => DataType::String,
+    }
+}
+
+/// Columns of a newline-delimited JSON file, one per distinct key.
+fn infer_json_columns(path: &Path) -> Result<Vec<(String, DataType)>> {
+    let mut columns: Vec<(String, Option<DataType>)> = Vec::new();
+    for (index, line) in BufReader::new(File::open(path)?).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let object: serde_json::Map<String, serde_json::Value> = serde_json::from_str(&line)
+            .with_context(|| format!("{}:{}: expected a JSON object", path.display(), index + 1))?;
+        for (key, value) in object {
+            let position = match columns
+                .iter()
+                .position(|(name, _)| name.eq_ignore_ascii_case(&key))
+            {
+                Some(position) => position,
+                None => {
+                    columns.push((key.clone(), None));
+                    columns.len() - 1
+                }
+            };
+            let data_type = &mut columns[position].1;
+            let next = match &value {
+                serde_json::Value::Null => continue,
+                serde_json::Value::Bool(_) => DataType::Bool,
+                serde_json::Value::Number(n) if n.is_i64() => DataType::Int64,
+                serde_json::Value::Number(_) => DataType::Float64,
+                serde_json::Value::String(_) => DataType::String,
+                serde_json::Value::Array(_) | serde_json::Value::Object(_) => bail!(
+                    "cannot infer a type for nested field {key} in {}; create the table first",
+                    path.display()
+                ),
+            };
+            let widened = widen(data_type.take(), next.clone());
+            if widened == DataType::String && next != DataType::String {
+                bail!(
+                    "field {key} in {} has values of different types; create the table first",
+                    path.display()
+                );
+            }
+            *data_type = Some(widened);
+        }
+    }
+
+    Ok(columns
+        .into_iter()
+        .map(|(name, data_type)| (name, data_type.unwrap_or(DataType::String)))
+        .collect())
+}
+
+fn parquet_columns(path: &Path) -> Result<Vec<(String, DataType)>> {
+    let builder = ParquetRecordBatchReaderBuilder::try_new(File::open(path)?)
+        .with_context(|| format!("Failed to read Parquet file {}", path.display()))?;
+    let schema = Schema::from_arrow_schema(builder.schema())?;
+    Ok(schema
+        .fields()
+        .iter()
+        .map(|field| (field.name.clone(), field.data_type.clone()))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_load_spec() {
+        assert_eq!(
+            LoadSpec::parse("sales.orders=data/orders.csv").unwrap(),
+            LoadSpec {
+                table: "sales.orders".to_string(),
+                path: PathBuf::from("data/orders.csv"),
+            }
+        );
+        assert!(LoadSpec::parse("orders").is_err());
+        assert!(LoadSpec::parse("=orders.csv").is_err());
+    }
+
+    #[test]
+    fn test_csv_types_widen() {
+        let inferred = ["1", "2.5", "x"]
+            .iter()
+            .fold(None, |current, field| Some(widen(current, csv_type(field))));
+        assert_eq!(inferred, Some(DataType::String));
+        assert_eq!(
+            widen(Some(DataType::Int64), csv_type("2.5")),
+            DataType::Float64
+        );
+        assert_eq!(
+            widen(Some(DataType::Bool), csv_type("TRUE")),
+            DataType::Bool
+        );
+        assert_eq!(
+            split_csv_line(" a ,\"b, \"\"c\"\"\",,"),
+            ["a", "b, \"c\"", "", ""]
+        );
+    }
+}
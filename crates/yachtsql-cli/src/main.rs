@@ -1,65 +1,128 @@
+mod load;
+mod output;
+mod repl;
+mod script;
+
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
-use comfy_table::Table;
-use comfy_table::presets::UTF8_FULL;
-use yachtsql::YachtSQLEngine;
+use yachtsql::{QueryParameter, YachtSQLEngine};
+
+use crate::load::LoadSpec;
+use crate::output::OutputFormat;
+use crate::repl::Repl;
 
 #[derive(Parser)]
 #[command(name = "yachtsql")]
 #[command(about = "YachtSQL - Lightweight in-memory SQL database", long_about = None)]
 struct Cli {
+    /// Without a subcommand, starts the interactive shell
     #[command(subcommand)]
-    command: Commands,
+    command: Option<Commands>,
 }
 
 #[derive(Subcommand)]
 enum Commands {
-    Query { sql: String },
+    Query {
+        sql: String,
+        /// Persist tables in this directory instead of discarding them on exit
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+        #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+    },
+    /// Start an interactive shell that keeps one session across statements
+    Shell {
+        /// Persist tables in this directory instead of discarding them on exit
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+        #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+    },
+    /// Run a script file statement by statement, stopping at the first error
+    ///
+    /// Exits with 1 when a statement fails and 3 when an ASSERT fails.
+    Run {
+        file: PathBuf,
+        /// Bind a query parameter, as NAME=VALUE or NAME:TYPE=VALUE
+        #[arg(long = "param", value_name = "NAME=VALUE", value_parser = script::parse_param)]
+        params: Vec<QueryParameter>,
+        /// Load a CSV, JSONL or Parquet file into a table before running the script
+        #[arg(long = "load", value_name = "TABLE=PATH", value_parser = LoadSpec::parse)]
+        loads: Vec<LoadSpec>,
+        /// Persist tables in this directory instead of discarding them on exit
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+        #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+    },
 }
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> Result<ExitCode> {
     let cli = Cli::parse();
 
     match cli.command {
-        Commands::Query { sql } => {
-            execute_query(&sql).await?;
+        Some(Commands::Query {
+            sql,
+            data_dir,
+            format,
+        }) => {
+            execute_query(&sql, data_dir, format).await?;
+        }
+        Some(Commands::Shell { data_dir, format }) => {
+            let engine = open_engine(data_dir).await?;
+            Repl::new(engine.create_session(), format).run().await?;
+        }
+        None => {
+            let engine = open_engine(None).await?;
+            Repl::new(engine.create_session(), OutputFormat::Table)
+                .run()
+                .await?;
+        }
+        Some(Commands::Run {
+            file,
+            params,
+            loads,
+            data_dir,
+            format,
+        }) => {
+            let sql = std::fs::read_to_string(&file)
+                .with_context(|| format!("Failed to read {}", file.display()))?;
+            let engine = open_engine(data_dir).await?;
+            let session = engine.create_session();
+            for spec in &loads {
+                load::load_file(&session, spec).await?;
+            }
+            let name = file.display().to_string();
+            return script::run_script(&session, &name, &sql, &params, format).await;
         }
     }
 
-    Ok(())
+    Ok(ExitCode::SUCCESS)
+}
+
+async fn open_engine(data_dir: Option<PathBuf>) -> Result<YachtSQLEngine> {
+    match data_dir {
+        Some(dir) => YachtSQLEngine::open(&dir)
+            .await
+            .with_context(|| format!("Failed to open database at {}", dir.display())),
+        None => Ok(YachtSQLEngine::new()),
+    }
 }
 
-async fn execute_query(sql: &str) -> Result<()> {
-    let engine = YachtSQLEngine::new();
+async fn execute_query(sql: &str, data_dir: Option<PathBuf>, format: OutputFormat) -> Result<()> {
+    let engine = open_engine(data_dir).await?;
     let session = engine.create_session();
     let result = session
         .query(sql)
         .await
         .context("Failed to execute SQL query")?;
-    print_query_result(&result)?;
-    Ok(())
-}
-
-fn print_query_result(result: &yachtsql::QueryResult) -> Result<()> {
-    if result.rows.is_empty() {
-        println!("(0 rows)");
-        return Ok(());
-    }
-
-    let mut table = Table::new();
-    table.load_preset(UTF8_FULL);
-
-    let headers: Vec<&str> = result.schema.iter().map(|c| c.name.as_str()).collect();
-    table.set_header(headers);
-
-    for row in &result.rows {
-        let values: Vec<String> = row.values().iter().map(|v| format!("{}", v)).collect();
-        table.add_row(values);
-    }
-
-    println!("{table}");
-    println!("({} rows)", result.rows.len());
-
+    let mut out = std::io::stdout().lock();
+    output::write_result(&mut out, &result, format)?;
+    out.flush()?;
     Ok(())
 }
@@ -0,0 +1,178 @@
+use std::io::{self, Write};
+
+use clap::ValueEnum;
+use comfy_table::Table;
+use comfy_table::presets::UTF8_FULL;
+use yachtsql::{QueryResult, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Box-drawn table
+    Table,
+    /// Comma-separated values with a header row
+    Csv,
+    /// One JSON object per row
+    Json,
+    /// One `column | value` line per column, a block per row
+    Vertical,
+}
+
+impl OutputFormat {
+    pub fn name(self) -> &'static str {
+        match self {
+            OutputFormat::Table => "table",
+            OutputFormat::Csv => "csv",
+            OutputFormat::Json => "json",
+            OutputFormat::Vertical => "vertical",
+        }
+    }
+}
+
+pub fn write_result(
+    out: &mut impl Write,
+    result: &QueryResult,
+    format: OutputFormat,
+) -> io::Result<()> {
+    match format {
+        OutputFormat::Table => write_table(out, result),
+        OutputFormat::Csv => write_csv(out, result),
+        OutputFormat::Json => write_json(out, result),
+        OutputFormat::Vertical => write_vertical(out, result),
+    }
+}
+
+fn write_table(out: &mut impl Write, result: &QueryResult) -> io::Result<()> {
+    if result.rows.is_empty() {
+        return writeln!(out, "(0 rows)");
+    }
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL);
+    table.set_header(result.column_names());
+    for row in &result.rows {
+        let values: Vec<String> = row.values().iter().map(|v| format!("{}", v)).collect();
+        table.add_row(values);
+    }
+
+    writeln!(out, "{table}")?;
+    writeln!(out, "({} rows)", result.rows.len())
+}
+
+fn write_csv(out: &mut impl Write, result: &QueryResult) -> io::Result<()> {
+    let header: Vec<String> = result
+        .column_names()
+        .iter()
+        .map(|name| csv_field(name))
+        .collect();
+    writeln!(out, "{}", header.join(","))?;
+    for row in &result.rows {
+        let fields: Vec<String> = row
+            .values()
+            .iter()
+            .map(|value| match value {
+                Value::Null => String::new(),
+                value => csv_field(&value.to_string()),
+            })
+            .collect();
+        writeln!(out, "{}", fields.join(","))?;
+    }
+    Ok(())
+}
+
+/// Quotes a field that contains a separator, a quote or a line break.
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+fn write_json(out: &mut impl Write, result: &QueryResult) -> io::Result<()> {
+    for row in &result.rows {
+        let fields: Vec<String> = result
+            .schema
+            .iter()
+            .zip(row.values())
+            .map(|(column, value)| {
+                format!(
+                    "{}:{}",
+                    serde_json::Value::String(column.name.clone()),
+                    value.to_json()
+                )
+            })
+            .collect();
+        writeln!(out, "{{{}}}", fields.join(","))?;
+    }
+    Ok(())
+}
+
+fn write_vertical(out: &mut impl Write, result: &QueryResult) -> io::Result<()> {
+    if result.rows.is_empty() {
+        return writeln!(out, "(0 rows)");
+    }
+
+    let width = result
+        .schema
+        .iter()
+        .map(|column| column.name.chars().count())
+        .max()
+        .unwrap_or(0);
+    for (index, row) in result.rows.iter().enumerate() {
+        writeln!(out, "-[ RECORD {} ]-", index + 1)?;
+        for (column, value) in result.schema.iter().zip(row.values()) {
+            writeln!(out, "{:<width$} | {}", column.name, value)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use yachtsql::ColumnInfo;
+
+    use super::*;
+
+    fn render(result: &QueryResult, format: OutputFormat) -> String {
+        let mut out = Vec::new();
+        write_result(&mut out, result, format).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    fn sample() -> QueryResult {
+        QueryResult::from_values(
+            vec![
+                ColumnInfo::new("id", "INT64"),
+                ColumnInfo::new("note", "STRING"),
+            ],
+            vec![
+                vec![Value::Int64(1), Value::String("a, \"b\"".to_string())],
+                vec![Value::Int64(2), Value::Null],
+            ],
+        )
+    }
+
+    #[test]
+    fn test_csv_quotes_fields() {
+        assert_eq!(
+            render(&sample(), OutputFormat::Csv),
+            "id,note\n1,\"a, \"\"b\"\"\"\n2,\n"
+        );
+    }
+
+    #[test]
+    fn test_json_lines_keep_column_order() {
+        assert_eq!(
+            render(&sample(), OutputFormat::Json),
+            "{\"id\":1,\"note\":\"a, \\\"b\\\"\"}\n{\"id\":2,\"note\":null}\n"
+        );
+    }
+
+    #[test]
+    fn test_vertical_aligns_column_names() {
+        assert_eq!(
+            render(&sample(), OutputFormat::Vertical),
+            "-[ RECORD 1 ]-\nid   | 1\nnote | a, \"b\"\n-[ RECORD 2 ]-\nid   | 2\nnote | NULL\n"
+        );
+    }
+}
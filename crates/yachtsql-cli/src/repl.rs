@@ -0,0 +1,379 @@
+use std::io::{self, Write};
+use std::iter::Peekable;
+use std::path::PathBuf;
+use std::str::Chars;
+use std::time::Instant;
+
+use anyhow::{Result, anyhow, bail};
+use clap::ValueEnum;
+use rustyline::DefaultEditor;
+use rustyline::error::ReadlineError;
+use yachtsql::{ColumnInfo, FieldMode, QueryResult, Value, YachtSQLSession, split_script};
+
+use crate::output::{self, OutputFormat};
+
+const PROMPT: &str = "yachtsql> ";
+const CONTINUATION_PROMPT: &str = "       -> ";
+const HISTORY_FILE: &str = ".yachtsql_history";
+
+const HELP: &str = "\
+Statements end with ';' and may span several lines.
+
+  \\q, \\quit                 quit
+  \\?, \\help                 show this help
+  \\dt                       list tables
+  \\d NAME                   describe a table
+  \\timing [on|off]          show how long each statement takes
+  \\explain [on|off]         show the plan of queries instead of running them
+  \\format [table|csv|json|vertical]
+                            set the output format";
+
+/// An interactive shell over one session, so temporary tables and variables
+/// persist from one statement to the next.
+pub struct Repl {
+    session: YachtSQLSession,
+    format: OutputFormat,
+    timing: bool,
+    explain: bool,
+}
+
+enum Flow {
+    Continue,
+    Quit,
+}
+
+impl Repl {
+    pub fn new(session: YachtSQLSession, format: OutputFormat) -> Self {
+        Self {
+            session,
+            format,
+            timing: false,
+            explain: false,
+        }
+    }
+
+    /// Reads statements and meta-commands until `\q` or end of input.
+    pub async fn run(mut self) -> Result<()> {
+        let mut editor = DefaultEditor::new()?;
+        let history = history_path();
+        if let Some(path) = &history {
+            let _ = editor.load_history(path);
+        }
+        println!(
+            "YachtSQL {}. Type \\? for help, \\q to quit.",
+            env!("CARGO_PKG_VERSION")
+        );
+
+        let mut buffer = String::new();
+        loop {
+            let prompt = if buffer.is_empty() {
+                PROMPT
+            } else {
+                CONTINUATION_PROMPT
+            };
+            let line = match editor.readline(prompt) {
+                Ok(line) => line,
+                Err(ReadlineError::Interrupted) => {
+                    buffer.clear();
+                    continue;
+                }
+                Err(ReadlineError::Eof) => break,
+                Err(e) => return Err(e.into()),
+            };
+
+            if buffer.is_empty() && line.trim_start().starts_with('\\') {
+                let command = line.trim();
+                let _ = editor.add_history_entry(command);
+                match self.meta_command(command) {
+                    Ok(Flow::Quit) => break,
+                    Ok(Flow::Continue) => {}
+                    Err(e) => eprintln!("Error: {e}"),
+                }
+                continue;
+            }
+
+            if !buffer.is_empty() {
+                buffer.push('\n');
+            }
+            buffer.push_str(&line);
+            if is_complete(&buffer) {
+                let sql = std::mem::take(&mut buffer);
+                let _ = editor.add_history_entry(sql.as_str());
+                if let Err(e) = self.execute(&sql).await {
+                    eprintln!("Error: {e}");
+                }
+            } else if buffer.trim().is_empty() {
+                buffer.clear();
+            }
+        }
+
+        if let Some(path) = &history {
+            let _ = editor.save_history(path);
+        }
+        Ok(())
+    }
+
+    /// Runs each statement in `sql`, stopping at the first error.
+    async fn execute(&self, sql: &str) -> Result<()> {
+        for statement in split_script(sql)? {
+            let text = if self.explain && statement.is_query {
+                format!("EXPLAIN {}", statement.sql)
+            } else {
+                statement.sql
+            };
+            let start = Instant::now();
+            let result = self.session.query(&text).await?;
+            let elapsed = start.elapsed();
+
+            if result.schema.is_empty() {
+                println!("OK");
+            } else {
+                self.print(&result)?;
+            }
+            if self.timing {
+                println!("Time: {:.3} ms", elapsed.as_secs_f64() * 1000.0);
+            }
+        }
+        Ok(())
+    }
+
+    fn meta_command(&mut self, line: &str) -> Result<Flow> {
+        let mut parts = line.split_whitespace();
+        let command = parts.next().unwrap_or_default();
+        let argument = parts.next();
+        if parts.next().is_some() {
+            bail!("too many arguments to {command}");
+        }
+
+        match command {
+            "\\q" | "\\quit" => return Ok(Flow::Quit),
+            "\\?" | "\\help" => println!("{HELP}"),
+            "\\dt" => self.list_tables()?,
+            "\\d" => match argument {
+                Some(table) => self.describe_table(table)?,
+                None => self.list_tables()?,
+            },
+            "\\timing" => {
+                self.timing = toggle(argument, self.timing)?;
+                println!("Timing is {}.", on_off(self.timing));
+            }
+            "\\explain" => {
+                self.explain = toggle(argument, self.explain)?;
+                println!("Explain is {}.", on_off(self.explain));
+            }
+            "\\format" => {
+                if let Some(name) = argument {
+                    self.format = OutputFormat::from_str(name, true)
+                        .map_err(|_| anyhow!("unknown output format: {name}"))?;
+                }
+                println!("Output format is {}.", self.format.name());
+            }
+            _ => bail!("unknown command {command}; try \\?"),
+        }
+        Ok(Flow::Continue)
+    }
+
+    fn list_tables(&self) -> Result<()> {
+        let rows = table_names(&self.session)
+            .into_iter()
+            .map(|name| vec![Value::String(name)])
+            .collect();
+        self.print(&QueryResult::from_values(
+            vec![ColumnInfo::new("table", "STRING")],
+            rows,
+        ))
+    }
+
+    fn describe_table(&self, name: &str) -> Result<()> {
+        let schema = self
+            .session
+            .catalog()
+            .get_table_schema(name)
+            .ok_or_else(|| anyhow!("table not found: {name}"))?;
+        let rows = schema
+            .fields()
+            .iter()
+            .map(|field| {
+                let mode = match field.mode {
+                    FieldMode::Nullable => "NULLABLE",
+                    FieldMode::Required => "REQUIRED",
+                    FieldMode::Repeated => "REPEATED",
+                };
+                vec![
+                    Value::String(field.name.clone()),
+                    Value::String(field.data_type.to_string()),
+                    Value::String(mode.to_string()),
+                    field
+                        .description
+                        .clone()
+                        .map(Value::String)
+                        .unwrap_or(Value::Null),
+                ]
+            })
+            .collect();
+        self.print(&QueryResult::from_values(
+            vec![
+                ColumnInfo::new("column", "STRING"),
+                ColumnInfo::new("type", "STRING"),
+                ColumnInfo::new("mode", "STRING"),
+                ColumnInfo::new("description", "STRING"),
+            ],
+            rows,
+        ))
+    }
+
+    fn print(&self, result: &QueryResult) -> Result<()> {
+        let mut out = io::stdout().lock();
+        output::write_result(&mut out, result, self.format)?;
+        out.flush()?;
+        Ok(())
+    }
+}
+
+/// The tables `\dt` lists: every table of the session but the
+/// `INFORMATION_SCHEMA` views.
+fn table_names(session: &YachtSQLSession) -> Vec<String> {
+    session
+        .catalog()
+        .get_table_names()
+        .into_iter()
+        .filter(|name| !name.contains("INFORMATION_SCHEMA"))
+        .collect()
+}
+
+fn history_path() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join(HISTORY_FILE))
+}
+
+fn toggle(argument: Option<&str>, current: bool) -> Result<bool> {
+    match argument {
+        None => Ok(!current),
+        Some("on") => Ok(true),
+        Some("off") => Ok(false),
+        Some(other) => bail!("expected on or off, got {other}"),
+    }
+}
+
+fn on_off(value: bool) -> &'static str {
+    if value { "on" } else { "off" }
+}
+
+/// Whether `sql` ends with a `;` outside string literals, quoted identifiers and
+/// comments, i.e. whether the shell should stop reading and run it.
+fn is_complete(sql: &str) -> bool {
+    let mut chars = sql.chars().peekable();
+    let mut complete = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' | '"' | '`' => {
+                complete = false;
+                if !skip_quoted(&mut chars, c) {
+                    return false;
+                }
+            }
+            '#' => skip_line(&mut chars),
+            '-' if chars.peek() == Some(&'-') => skip_line(&mut chars),
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if !skip_block_comment(&mut chars) {
+                    return false;
+                }
+            }
+            ';' => complete = true,
+            c if c.is_whitespace() => {}
+            _ => complete = false,
+        }
+    }
+    complete
+}
+
+/// Skips past the closing quote of a literal opened with `quote`, which may be
+/// triple-quoted. Returns false if the literal is not terminated.
+fn skip_quoted(chars: &mut Peekable<Chars<'_>>, quote: char) -> bool {
+    let mut lookahead = chars.clone();
+    let triple = lookahead.next() == Some(quote) && lookahead.next() == Some(quote);
+    if triple {
+        chars.next();
+        chars.next();
+    } else if chars.peek() == Some(&quote) {
+        chars.next();
+        return true;
+    }
+
+    let mut run = 0;
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            chars.next();
+            run = 0;
+        } else if c == quote {
+            run += 1;
+            if !triple || run == 3 {
+                return true;
+            }
+        } else {
+            run = 0;
+        }
+    }
+    false
+}
+
+fn skip_line(chars: &mut Peekable<Chars<'_>>) {
+    for c in chars.by_ref() {
+        if c == '\n' {
+            break;
+        }
+    }
+}
+
+fn skip_block_comment(chars: &mut Peekable<Chars<'_>>) -> bool {
+    while let Some(c) = chars.next() {
+        if c == '*' && chars.peek() == Some(&'/') {
+            chars.next();
+            return true;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_statement_ends_at_semicolon() {
+        assert!(!is_complete("SELECT 1"));
+        assert!(is_complete("SELECT 1;"));
+        assert!(is_complete("SELECT 1;  -- trailing comment"));
+        assert!(!is_complete("SELECT 1; SELECT 2"));
+        assert!(is_complete("SELECT 1;\nSELECT 2;"));
+    }
+
+    #[test]
+    fn test_semicolon_inside_literal_or_comment_does_not_end_statement() {
+        assert!(!is_complete("SELECT 'a;"));
+        assert!(is_complete("SELECT 'a;b';"));
+        assert!(is_complete("SELECT 'it\\'s';"));
+        assert!(!is_complete("SELECT '''a;\n';"));
+        assert!(is_complete("SELECT '''a;\n''';"));
+        assert!(is_complete("SELECT '';"));
+        assert!(!is_complete("SELECT 1 /* ; */"));
+        assert!(!is_complete("SELECT 1 -- ;"));
+        assert!(is_complete("SELECT `a;b` FROM t;"));
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_table_names_lists_session_tables() {
+        let session = yachtsql::YachtSQLEngine::new().create_session();
+        for sql in [
+            "CREATE TABLE sales.orders (id INT64)",
+            "CREATE TABLE customers (id INT64)",
+            "CREATE TEMP TABLE scratch (id INT64)",
+        ] {
+            session.execute_sql(sql).await.unwrap();
+        }
+        assert_eq!(
+            table_names(&session),
+            vec!["CUSTOMERS", "SALES.ORDERS", "SCRATCH"]
+        );
+    }
+}
@@ -0,0 +1,196 @@
+use std::io::{self, Write};
+use std::process::ExitCode;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Result, anyhow, bail};
+use yachtsql::{
+    DataType, Error, QueryFinish, QueryObserver, QueryParameter, Value, YachtSQLSession,
+    split_script,
+};
+
+use crate::output::{self, OutputFormat};
+
+/// Exit code when a statement fails.
+pub const EXIT_STATEMENT_FAILED: u8 = 1;
+/// Exit code when an `ASSERT` fails, so data checks can be told apart from
+/// broken SQL.
+pub const EXIT_ASSERTION_FAILED: u8 = 3;
+
+/// Runs `sql` statement by statement in `session`, printing the result of each
+/// query and the row count of each DML statement. Stops at the first failing
+/// statement and reports it with its position in `file`.
+pub async fn run_script(
+    session: &YachtSQLSession,
+    file: &str,
+    sql: &str,
+    params: &[QueryParameter],
+    format: OutputFormat,
+) -> Result<ExitCode> {
+    let rows_affected = Arc::new(RowsAffected::default());
+    session.add_observer(rows_affected.clone());
+    let mut out = io::stdout().lock();
+    for statement in split_script(sql)? {
+        rows_affected.reset();
+        let table = match session
+            .execute_sql_with_params(&statement.sql, params)
+            .await
+        {
+            Ok(table) => table,
+            Err(error) => {
+                out.flush()?;
+                eprintln!("{file}:{}:{}: {error}", statement.line, statement.column);
+                eprintln!("  in: {}", first_line(&statement.sql));
+                return Ok(ExitCode::from(
+                    if matches!(error, Error::AssertionFailed(_)) {
+                        EXIT_ASSERTION_FAILED
+                    } else {
+                        EXIT_STATEMENT_FAILED
+                    },
+                ));
+            }
+        };
+
+        if statement.is_query {
+            output::write_result(&mut out, &table.to_query_result()?, format)?;
+        } else {
+            match rows_affected.get() {
+                Some(rows) => writeln!(out, "{rows} rows affected")?,
+                None => writeln!(out, "OK")?,
+            }
+        }
+    }
+    out.flush()?;
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Remembers the rows affected by the last statement the session finished.
+#[derive(Default)]
+struct RowsAffected(Mutex<Option<u64>>);
+
+impl RowsAffected {
+    fn reset(&self) {
+        *self.0.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+
+    fn get(&self) -> Option<u64> {
+        *self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl QueryObserver for RowsAffected {
+    fn on_query_finish(&self, event: &QueryFinish<'_>) {
+        *self.0.lock().unwrap_or_else(|e| e.into_inner()) =
+            event.job.and_then(|job| job.rows_affected);
+    }
+}
+
+fn first_line(sql: &str) -> String {
+    let mut lines = sql.lines();
+    let first = lines.next().unwrap_or_default().trim_end();
+    if lines.next().is_some() {
+        format!("{first} ...")
+    } else {
+        first.to_string()
+    }
+}
+
+/// Parses a `--param` argument: `NAME=VALUE` infers an `INT64`, `FLOAT64`, `BOOL`
+/// or `STRING` parameter from the value, `NAME:TYPE=VALUE` sets the type, and
+/// `NULL` binds a null.
+pub fn parse_param(spec: &str) -> Result<QueryParameter> {
+    let (key, text) = spec
+        .split_once('=')
+        .ok_or_else(|| anyhow!("expected NAME=VALUE or NAME:TYPE=VALUE, got {spec}"))?;
+    let (name, type_name) = match key.split_once(':') {
+        Some((name, type_name)) => (name, Some(type_name.to_ascii_uppercase())),
+        None => (key, None),
+    };
+    if name.is_empty() {
+        bail!("missing parameter name in {spec}");
+    }
+    let invalid = || anyhow!("invalid value for parameter {name}: {text}");
+
+    let Some(type_name) = type_name else {
+        let value = if text.eq_ignore_ascii_case("NULL") {
+            Value::Null
+        } else if let Ok(n) = text.parse::<i64>() {
+            Value::Int64(n)
+        } else if let Ok(f) = text.parse::<f64>() {
+            Value::float64(f)
+        } else if let Ok(b) = text.to_ascii_lowercase().parse::<bool>() {
+            Value::Bool(b)
+        } else {
+            Value::String(text.to_string())
+        };
+        return Ok(QueryParameter::named(name, value));
+    };
+
+    let data_type = match type_name.as_str() {
+        "STRING" => DataType::String,
+        "INT64" | "INTEGER" => DataType::Int64,
+        "FLOAT64" | "FLOAT" => DataType::Float64,
+        "BOOL" | "BOOLEAN" => DataType::Bool,
+        "DATE" => DataType::Date,
+        "DATETIME" => DataType::DateTime,
+        "TIMESTAMP" => DataType::Timestamp,
+        "TIME" => DataType::Time,
+        other => bail!(
+            "unsupported parameter type {other}; use STRING, INT64, FLOAT64, BOOL, DATE, DATETIME, TIMESTAMP or TIME"
+        ),
+    };
+    let value = if text.eq_ignore_ascii_case("NULL") {
+        Value::Null
+    } else {
+        match data_type {
+            DataType::Int64 => Value::Int64(text.parse().map_err(|_| invalid())?),
+            DataType::Float64 => Value::float64(text.parse().map_err(|_| invalid())?),
+            DataType::Bool => {
+                Value::Bool(text.to_ascii_lowercase().parse().map_err(|_| invalid())?)
+            }
+            _ => Value::String(text.to_string()),
+        }
+    };
+    Ok(QueryParameter::named(name, value).with_type(data_type))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_param_infers_type() {
+        assert_eq!(
+            parse_param("min_id=10").unwrap(),
+            QueryParameter::named("min_id", Value::Int64(10))
+        );
+        assert_eq!(
+            parse_param("ratio=0.5").unwrap(),
+            QueryParameter::named("ratio", Value::float64(0.5))
+        );
+        assert_eq!(
+            parse_param("active=TRUE").unwrap(),
+            QueryParameter::named("active", Value::Bool(true))
+        );
+        assert_eq!(
+            parse_param("name=a=b").unwrap(),
+            QueryParameter::named("name", Value::String("a=b".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_param_with_type() {
+        assert_eq!(
+            parse_param("day:DATE=2024-01-02").unwrap(),
+            QueryParameter::named("day", Value::String("2024-01-02".to_string()))
+                .with_type(DataType::Date)
+        );
+        assert_eq!(
+            parse_param("code:string=007").unwrap(),
+            QueryParameter::named("code", Value::String("007".to_string()))
+                .with_type(DataType::String)
+        );
+        assert!(parse_param("n:INT64=abc").is_err());
+        assert!(parse_param("n:GEOGRAPHY=x").is_err());
+        assert!(parse_param("novalue").is_err());
+    }
+}
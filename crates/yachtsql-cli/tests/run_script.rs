@@ -0,0 +1,54 @@
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn run(dir: &Path, script: &str) -> Output {
+    let path = dir.join("script.sql");
+    std::fs::write(&path, script).unwrap();
+    Command::new(env!("CARGO_BIN_EXE_yachtsql"))
+        .arg("run")
+        .arg(&path)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_script_reports_rows_affected() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = run(
+        dir.path(),
+        "CREATE TABLE t (id INT64);\nINSERT INTO t VALUES (1), (2);\nDELETE FROM t WHERE id = 1;\n",
+    );
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(
+        stdout.lines().collect::<Vec<_>>(),
+        ["OK", "2 rows affected", "1 rows affected"]
+    );
+}
+
+#[test]
+fn test_failing_statement_exits_with_1_and_its_position() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = run(
+        dir.path(),
+        "CREATE TABLE t (id INT64);\n\n  SELECT * FROM missing;\nSELECT 1;\n",
+    );
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let location = format!("{}:3:3: ", dir.path().join("script.sql").display());
+    assert!(stderr.starts_with(&location), "{}", stderr);
+    assert!(stderr.contains("in: SELECT * FROM missing"), "{}", stderr);
+}
+
+#[test]
+fn test_failing_assert_exits_with_3() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = run(
+        dir.path(),
+        "CREATE TABLE t (id INT64);\nASSERT (SELECT COUNT(*) FROM t) > 0 AS 't is empty';\n",
+    );
+    assert_eq!(output.status.code(), Some(3));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains(":2:1: "), "{}", stderr);
+    assert!(stderr.contains("ASSERT failed: t is empty"), "{}", stderr);
+}
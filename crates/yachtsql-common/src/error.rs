@@ -5,6 +5,7 @@ use std::fmt;
 pub type Result<T> = std::result::Result<T, Error>;
 
 #[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub enum Error {
     ParseError(String),
     InvalidQuery(String),
@@ -93,6 +94,14 @@ pub enum Error {
         pattern: String,
         reason: String,
     },
+    QueryCancelled,
+    QueryTimeout {
+        timeout_ms: u64,
+    },
+    ResourcesExceeded {
+        limit_bytes: u64,
+    },
+    AssertionFailed(String),
 }
 
 impl Error {
@@ -286,6 +295,22 @@ impl Error {
             reason: reason.into(),
         }
     }
+
+    pub fn query_cancelled() -> Self {
+        Error::QueryCancelled
+    }
+
+    pub fn query_timeout(timeout_ms: u64) -> Self {
+        Error::QueryTimeout { timeout_ms }
+    }
+
+    pub fn resources_exceeded(limit_bytes: u64) -> Self {
+        Error::ResourcesExceeded { limit_bytes }
+    }
+
+    pub fn assertion_failed(msg: impl Into<String>) -> Self {
+        Error::AssertionFailed(msg.into())
+    }
 }
 
 impl fmt::Display for Error {
@@ -369,6 +394,16 @@ impl fmt::Display for Error {
             Error::RegexError { pattern, reason } => {
                 write!(f, "Regex error in pattern '{}': {}", pattern, reason)
             }
+            Error::QueryCancelled => write!(f, "Query cancelled"),
+            Error::QueryTimeout { timeout_ms } => {
+                write!(f, "Query exceeded timeout of {} ms", timeout_ms)
+            }
+            Error::ResourcesExceeded { limit_bytes } => write!(
+                f,
+                "Resources exceeded during query execution: memory limit of {} bytes exceeded",
+                limit_bytes
+            ),
+            Error::AssertionFailed(msg) => write!(f, "ASSERT failed: {}", msg),
         }
     }
 }
@@ -434,6 +469,18 @@ mod tests {
 
         let e = Error::internal("internal error");
         assert!(matches!(e, Error::Internal(_)));
+
+        let e = Error::query_cancelled();
+        assert!(matches!(e, Error::QueryCancelled));
+
+        let e = Error::query_timeout(500);
+        assert!(matches!(e, Error::QueryTimeout { timeout_ms: 500 }));
+
+        let e = Error::resources_exceeded(1024);
+        assert!(matches!(e, Error::ResourcesExceeded { limit_bytes: 1024 }));
+
+        let e = Error::assertion_failed("rows missing");
+        assert!(matches!(e, Error::AssertionFailed(_)));
     }
 
     #[test]
@@ -506,6 +553,19 @@ mod tests {
             format!("{}", Error::Internal("test".to_string())),
             "Internal error: test"
         );
+        assert_eq!(format!("{}", Error::QueryCancelled), "Query cancelled");
+        assert_eq!(
+            format!("{}", Error::QueryTimeout { timeout_ms: 250 }),
+            "Query exceeded timeout of 250 ms"
+        );
+        assert_eq!(
+            format!("{}", Error::ResourcesExceeded { limit_bytes: 4096 }),
+            "Resources exceeded during query execution: memory limit of 4096 bytes exceeded"
+        );
+        assert_eq!(
+            format!("{}", Error::AssertionFailed("test".to_string())),
+            "ASSERT failed: test"
+        );
     }
 
     #[test]
@@ -7,6 +7,7 @@
 #![allow(missing_docs)]
 
 pub mod error;
+pub mod mapping;
 pub mod result;
 pub mod types;
 
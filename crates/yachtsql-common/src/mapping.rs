@@ -0,0 +1,824 @@
+#![coverage(off)]
+
+//! Conversions between [`Value`]s and Rust types implementing serde traits.
+//!
+//! Rows deserialize like maps keyed by column name, `STRUCT` values like nested
+//! maps and `ARRAY` values like sequences. `NUMERIC`, `DATE`, `TIME`, `DATETIME`
+//! and `TIMESTAMP` values are handed to the visitor as strings in the formats
+//! `rust_decimal` and `chrono` parse, so fields of those types map directly.
+//!
+//! Serialization goes the other way and produces `STRUCT` values for structs and
+//! maps; values that serde can only represent as strings (dates, decimals) are
+//! converted to their column types by whoever stores them.
+
+use std::fmt;
+
+use chrono::SecondsFormat;
+use rust_decimal::prelude::ToPrimitive;
+use serde::de::{
+    self, DeserializeOwned, DeserializeSeed, Deserializer, IntoDeserializer, MapAccess, SeqAccess,
+    Visitor,
+};
+use serde::ser::{self, Serialize};
+
+use crate::error::{Error, Result};
+use crate::result::{ColumnInfo, Row};
+use crate::types::Value;
+
+/// Deserializes a single value into `T`.
+pub fn from_value<T: DeserializeOwned>(value: &Value) -> Result<T> {
+    T::deserialize(ValueDeserializer(value)).map_err(MappingError::into_error)
+}
+
+/// Deserializes a row into `T`, matching struct fields to columns by name.
+///
+/// Tuples and tuple structs are filled by position instead, and a row with a
+/// single column can be deserialized directly into that column's type.
+pub fn from_row<T: DeserializeOwned>(columns: &[ColumnInfo], row: &Row) -> Result<T> {
+    T::deserialize(RowDeserializer {
+        columns,
+        values: row.values(),
+    })
+    .map_err(MappingError::into_error)
+}
+
+/// Serializes `value` into a [`Value`]; structs and maps become `STRUCT` values.
+pub fn to_value<T: Serialize + ?Sized>(value: &T) -> Result<Value> {
+    value
+        .serialize(ValueSerializer)
+        .map_err(MappingError::into_error)
+}
+
+#[derive(Debug)]
+struct MappingError(String);
+
+impl fmt::Display for MappingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for MappingError {}
+
+impl de::Error for MappingError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        MappingError(msg.to_string())
+    }
+}
+
+impl ser::Error for MappingError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        MappingError(msg.to_string())
+    }
+}
+
+impl MappingError {
+    fn into_error(self) -> Error {
+        Error::schema_mismatch(self.0)
+    }
+}
+
+type MappingResult<T> = std::result::Result<T, MappingError>;
+
+struct ValueDeserializer<'a>(&'a Value);
+
+impl<'a> ValueDeserializer<'a> {
+    fn unexpected(&self) -> de::Unexpected<'a> {
+        match self.0 {
+            Value::Null | Value::Default => de::Unexpected::Unit,
+            Value::Bool(b) => de::Unexpected::Bool(*b),
+            Value::Int64(n) => de::Unexpected::Signed(*n),
+            Value::Float64(f) => de::Unexpected::Float(f.0),
+            Value::String(s) => de::Unexpected::Str(s),
+            Value::Bytes(b) => de::Unexpected::Bytes(b),
+            Value::Array(_) => de::Unexpected::Seq,
+            Value::Struct(_) | Value::Range(_) => de::Unexpected::Map,
+            Value::Numeric(_)
+            | Value::BigNumeric(_)
+            | Value::Date(_)
+            | Value::Time(_)
+            | Value::DateTime(_)
+            | Value::Timestamp(_)
+            | Value::Json(_)
+            | Value::Geography(_)
+            | Value::Interval(_) => de::Unexpected::Other("SQL value"),
+        }
+    }
+}
+
+macro_rules! deserialize_number {
+    ($($method:ident)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> MappingResult<V::Value> {
+                match self.0 {
+                    Value::Numeric(d) | Value::BigNumeric(d) if d.fract().is_zero() => {
+                        match d.to_i64() {
+                            Some(n) => visitor.visit_i64(n),
+                            None => visitor.visit_string(d.to_string()),
+                        }
+                    }
+                    Value::Numeric(d) | Value::BigNumeric(d) => match d.to_f64() {
+                        Some(f) => visitor.visit_f64(f),
+                        None => visitor.visit_string(d.to_string()),
+                    },
+                    _ => self.deserialize_any(visitor),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for ValueDeserializer<'_> {
+    type Error = MappingError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> MappingResult<V::Value> {
+        match self.0 {
+            Value::Null | Value::Default => visitor.visit_unit(),
+            Value::Bool(b) => visitor.visit_bool(*b),
+            Value::Int64(n) => visitor.visit_i64(*n),
+            Value::Float64(f) => visitor.visit_f64(f.0),
+            Value::Numeric(d) | Value::BigNumeric(d) => visitor.visit_string(d.to_string()),
+            Value::String(s) | Value::Geography(s) => visitor.visit_str(s),
+            Value::Bytes(b) => visitor.visit_bytes(b),
+            Value::Date(d) => visitor.visit_string(d.format("%Y-%m-%d").to_string()),
+            Value::Time(t) => visitor.visit_string(t.format("%H:%M:%S%.f").to_string()),
+            Value::DateTime(dt) => {
+                visitor.visit_string(dt.format("%Y-%m-%dT%H:%M:%S%.f").to_string())
+            }
+            Value::Timestamp(ts) => {
+                visitor.visit_string(ts.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+            }
+            Value::Json(json) => json
+                .clone()
+                .deserialize_any(visitor)
+                .map_err(de::Error::custom),
+            Value::Array(items) => visitor.visit_seq(ValueSeqAccess {
+                items: items.iter(),
+            }),
+            Value::Struct(fields) => visitor.visit_map(ValueMapAccess {
+                entries: fields.iter().map(|(name, value)| (name.as_str(), value)),
+                value: None,
+            }),
+            Value::Interval(_) => visitor.visit_string(self.0.to_string()),
+            Value::Range(range) => {
+                let null = Value::Null;
+                let entries = [
+                    ("start", range.start().unwrap_or(&null)),
+                    ("end", range.end().unwrap_or(&null)),
+                ];
+                visitor.visit_map(ValueMapAccess {
+                    entries: entries.into_iter(),
+                    value: None,
+                })
+            }
+        }
+    }
+
+    deserialize_number! {
+        deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+        deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64
+        deserialize_f32 deserialize_f64
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> MappingResult<V::Value> {
+        match self.0 {
+            Value::Null => visitor.visit_none(),
+            Value::Json(serde_json::Value::Null) => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> MappingResult<V::Value> {
+        match self.0 {
+            Value::Bytes(bytes) => {
+                visitor.visit_seq(de::value::SeqDeserializer::new(bytes.iter().copied()))
+            }
+            Value::Struct(fields) => visitor.visit_seq(ValueSeqAccess {
+                items: fields.iter().map(|(_, value)| value),
+            }),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> MappingResult<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> MappingResult<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> MappingResult<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> MappingResult<V::Value> {
+        match self.0 {
+            Value::String(s) => visitor.visit_enum(s.as_str().into_deserializer()),
+            Value::Json(json) => json
+                .clone()
+                .deserialize_enum(name, variants, visitor)
+                .map_err(de::Error::custom),
+            _ => Err(de::Error::invalid_type(self.unexpected(), &visitor)),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i128 u128 char str string bytes byte_buf unit unit_struct map struct
+        identifier ignored_any
+    }
+}
+
+struct ValueSeqAccess<I> {
+    items: I,
+}
+
+impl<'de, 'a, I: Iterator<Item = &'a Value>> SeqAccess<'de> for ValueSeqAccess<I> {
+    type Error = MappingError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> MappingResult<Option<T::Value>> {
+        self.items
+            .next()
+            .map(|value| seed.deserialize(ValueDeserializer(value)))
+            .transpose()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.items.size_hint().1
+    }
+}
+
+struct ValueMapAccess<'a, I> {
+    entries: I,
+    value: Option<(&'a str, &'a Value)>,
+}
+
+impl<'de, 'a, I: Iterator<Item = (&'a str, &'a Value)>> MapAccess<'de> for ValueMapAccess<'a, I> {
+    type Error = MappingError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> MappingResult<Option<K::Value>> {
+        match self.entries.next() {
+            Some((name, value)) => {
+                self.value = Some((name, value));
+                seed.deserialize(name.into_deserializer()).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> MappingResult<V::Value> {
+        let (name, value) = self
+            .value
+            .take()
+            .ok_or_else(|| de::Error::custom("value requested before key"))?;
+        seed.deserialize(ValueDeserializer(value))
+            .map_err(|e| MappingError(format!("field `{}`: {}", name, e)))
+    }
+}
+
+struct RowDeserializer<'a> {
+    columns: &'a [ColumnInfo],
+    values: &'a [Value],
+}
+
+impl<'a> RowDeserializer<'a> {
+    fn single_value(&self) -> MappingResult<ValueDeserializer<'a>> {
+        match self.values {
+            [value] => Ok(ValueDeserializer(value)),
+            _ => Err(MappingError(format!(
+                "cannot map a row of {} columns to a single value",
+                self.values.len()
+            ))),
+        }
+    }
+}
+
+macro_rules! deserialize_single_column {
+    ($($method:ident)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> MappingResult<V::Value> {
+                self.single_value()?.$method(visitor)
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for RowDeserializer<'_> {
+    type Error = MappingError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> MappingResult<V::Value> {
+        visitor.visit_map(RowMapAccess {
+            entries: self.columns.iter().zip(self.values),
+            value: None,
+        })
+    }
+
+    deserialize_single_column! {
+        deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+        deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64
+        deserialize_f32 deserialize_f64 deserialize_char deserialize_str deserialize_string
+        deserialize_bytes deserialize_byte_buf deserialize_option
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> MappingResult<V::Value> {
+        visitor.visit_seq(ValueSeqAccess {
+            items: self.values.iter(),
+        })
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> MappingResult<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> MappingResult<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> MappingResult<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> MappingResult<V::Value> {
+        self.single_value()?
+            .deserialize_enum(name, variants, visitor)
+    }
+
+    serde::forward_to_deserialize_any! {
+        i128 u128 unit unit_struct map struct identifier ignored_any
+    }
+}
+
+struct RowMapAccess<'a, I> {
+    entries: I,
+    value: Option<(&'a str, &'a Value)>,
+}
+
+impl<'de, 'a, I: Iterator<Item = (&'a ColumnInfo, &'a Value)>> MapAccess<'de>
+    for RowMapAccess<'a, I>
+{
+    type Error = MappingError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> MappingResult<Option<K::Value>> {
+        match self.entries.next() {
+            Some((column, value)) => {
+                self.value = Some((column.name.as_str(), value));
+                seed.deserialize(column.name.as_str().into_deserializer())
+                    .map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> MappingResult<V::Value> {
+        let (name, value) = self
+            .value
+            .take()
+            .ok_or_else(|| de::Error::custom("value requested before key"))?;
+        seed.deserialize(ValueDeserializer(value))
+            .map_err(|e| MappingError(format!("column `{}`: {}", name, e)))
+    }
+}
+
+struct ValueSerializer;
+
+fn variant_value(variant: &str, value: Value) -> Value {
+    Value::Struct(vec![(variant.to_string(), value)])
+}
+
+impl ser::Serializer for ValueSerializer {
+    type Ok = Value;
+    type Error = MappingError;
+    type SerializeSeq = SerializeArray;
+    type SerializeTuple = SerializeArray;
+    type SerializeTupleStruct = SerializeArray;
+    type SerializeTupleVariant = SerializeArray;
+    type SerializeMap = SerializeStruct;
+    type SerializeStruct = SerializeStruct;
+    type SerializeStructVariant = SerializeStruct;
+
+    fn serialize_bool(self, v: bool) -> MappingResult<Value> {
+        Ok(Value::Bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> MappingResult<Value> {
+        Ok(Value::Int64(v.into()))
+    }
+
+    fn serialize_i16(self, v: i16) -> MappingResult<Value> {
+        Ok(Value::Int64(v.into()))
+    }
+
+    fn serialize_i32(self, v: i32) -> MappingResult<Value> {
+        Ok(Value::Int64(v.into()))
+    }
+
+    fn serialize_i64(self, v: i64) -> MappingResult<Value> {
+        Ok(Value::Int64(v))
+    }
+
+    fn serialize_u8(self, v: u8) -> MappingResult<Value> {
+        Ok(Value::Int64(v.into()))
+    }
+
+    fn serialize_u16(self, v: u16) -> MappingResult<Value> {
+        Ok(Value::Int64(v.into()))
+    }
+
+    fn serialize_u32(self, v: u32) -> MappingResult<Value> {
+        Ok(Value::Int64(v.into()))
+    }
+
+    fn serialize_u64(self, v: u64) -> MappingResult<Value> {
+        i64::try_from(v)
+            .map(Value::Int64)
+            .map_err(|_| MappingError(format!("{} does not fit in INT64", v)))
+    }
+
+    fn serialize_f32(self, v: f32) -> MappingResult<Value> {
+        Ok(Value::float64(v.into()))
+    }
+
+    fn serialize_f64(self, v: f64) -> MappingResult<Value> {
+        Ok(Value::float64(v))
+    }
+
+    fn serialize_char(self, v: char) -> MappingResult<Value> {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn serialize_str(self, v: &str) -> MappingResult<Value> {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> MappingResult<Value> {
+        Ok(Value::Bytes(v.to_vec()))
+    }
+
+    fn serialize_none(self) -> MappingResult<Value> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> MappingResult<Value> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> MappingResult<Value> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> MappingResult<Value> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> MappingResult<Value> {
+        Ok(Value::String(variant.to_string()))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> MappingResult<Value> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> MappingResult<Value> {
+        Ok(variant_value(variant, value.serialize(self)?))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> MappingResult<SerializeArray> {
+        Ok(SerializeArray {
+            variant: None,
+            items: Vec::with_capacity(len.unwrap_or(0)),
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> MappingResult<SerializeArray> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> MappingResult<SerializeArray> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> MappingResult<SerializeArray> {
+        Ok(SerializeArray {
+            variant: Some(variant),
+            items: Vec::with_capacity(len),
+        })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> MappingResult<SerializeStruct> {
+        Ok(SerializeStruct {
+            variant: None,
+            fields: Vec::with_capacity(len.unwrap_or(0)),
+            key: None,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> MappingResult<SerializeStruct> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> MappingResult<SerializeStruct> {
+        Ok(SerializeStruct {
+            variant: Some(variant),
+            fields: Vec::with_capacity(len),
+            key: None,
+        })
+    }
+}
+
+struct SerializeArray {
+    variant: Option<&'static str>,
+    items: Vec<Value>,
+}
+
+impl SerializeArray {
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> MappingResult<()> {
+        self.items.push(value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn finish(self) -> MappingResult<Value> {
+        let array = Value::Array(self.items);
+        Ok(match self.variant {
+            Some(variant) => variant_value(variant, array),
+            None => array,
+        })
+    }
+}
+
+impl ser::SerializeSeq for SerializeArray {
+    type Ok = Value;
+    type Error = MappingError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> MappingResult<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> MappingResult<Value> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for SerializeArray {
+    type Ok = Value;
+    type Error = MappingError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> MappingResult<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> MappingResult<Value> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeArray {
+    type Ok = Value;
+    type Error = MappingError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> MappingResult<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> MappingResult<Value> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleVariant for SerializeArray {
+    type Ok = Value;
+    type Error = MappingError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> MappingResult<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> MappingResult<Value> {
+        self.finish()
+    }
+}
+
+struct SerializeStruct {
+    variant: Option<&'static str>,
+    fields: Vec<(String, Value)>,
+    key: Option<String>,
+}
+
+impl SerializeStruct {
+    fn push<T: Serialize + ?Sized>(&mut self, name: String, value: &T) -> MappingResult<()> {
+        self.fields.push((name, value.serialize(ValueSerializer)?));
+        Ok(())
+    }
+
+    fn finish(self) -> MappingResult<Value> {
+        let value = Value::Struct(self.fields);
+        Ok(match self.variant {
+            Some(variant) => variant_value(variant, value),
+            None => value,
+        })
+    }
+}
+
+impl ser::SerializeMap for SerializeStruct {
+    type Ok = Value;
+    type Error = MappingError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> MappingResult<()> {
+        match key.serialize(ValueSerializer)? {
+            Value::String(name) => {
+                self.key = Some(name);
+                Ok(())
+            }
+            other => Err(MappingError(format!(
+                "map keys must be strings, got {}",
+                other.data_type()
+            ))),
+        }
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> MappingResult<()> {
+        let name = self
+            .key
+            .take()
+            .ok_or_else(|| MappingError("map value serialized before its key".to_string()))?;
+        self.push(name, value)
+    }
+
+    fn end(self) -> MappingResult<Value> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStruct for SerializeStruct {
+    type Ok = Value;
+    type Error = MappingError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> MappingResult<()> {
+        self.push(key.to_string(), value)
+    }
+
+    fn end(self) -> MappingResult<Value> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStructVariant for SerializeStruct {
+    type Ok = Value;
+    type Error = MappingError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> MappingResult<()> {
+        self.push(key.to_string(), value)
+    }
+
+    fn end(self) -> MappingResult<Value> {
+        self.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Status {
+        Active,
+        Suspended { reason: String },
+    }
+
+    #[test]
+    fn test_to_value_struct_and_enum_variants() {
+        assert_eq!(to_value(&Status::Active).unwrap(), Value::string("Active"));
+        assert_eq!(
+            to_value(&Status::Suspended {
+                reason: "spam".to_string()
+            })
+            .unwrap(),
+            Value::struct_val(vec![(
+                "Suspended".to_string(),
+                Value::struct_val(vec![("reason".to_string(), Value::string("spam"))])
+            )])
+        );
+    }
+
+    #[test]
+    fn test_to_value_map_requires_string_keys() {
+        let map: BTreeMap<String, i32> = [("a".to_string(), 1)].into_iter().collect();
+        assert_eq!(
+            to_value(&map).unwrap(),
+            Value::struct_val(vec![("a".to_string(), Value::int64(1))])
+        );
+
+        let map: BTreeMap<i32, i32> = [(1, 1)].into_iter().collect();
+        assert!(matches!(to_value(&map), Err(Error::SchemaMismatch(_))));
+    }
+
+    #[test]
+    fn test_from_value_unit_enum_bytes_and_json() {
+        assert_eq!(
+            from_value::<Status>(&Value::string("Active")).unwrap(),
+            Status::Active
+        );
+        assert_eq!(
+            from_value::<Vec<u8>>(&Value::bytes(vec![1, 2])).unwrap(),
+            vec![1, 2]
+        );
+        let json = Value::json(serde_json::json!({"a": [1, 2]}));
+        assert_eq!(
+            from_value::<BTreeMap<String, Vec<i64>>>(&json).unwrap(),
+            [("a".to_string(), vec![1, 2])].into_iter().collect()
+        );
+    }
+
+    #[test]
+    fn test_from_value_out_of_range_integer() {
+        let err = from_value::<u8>(&Value::int64(300)).unwrap_err();
+        assert!(matches!(err, Error::SchemaMismatch(_)));
+    }
+}
@@ -1,8 +1,10 @@
 #![coverage(off)]
 
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 
+use crate::error::Result;
 use crate::types::Value;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -146,6 +148,14 @@ impl QueryResult {
         self.rows.first().and_then(|r| r.get(0))
     }
 
+    /// Deserializes every row into `T`; see [`mapping::from_row`](crate::mapping::from_row).
+    pub fn rows_as<T: DeserializeOwned>(&self) -> Result<Vec<T>> {
+        self.rows
+            .iter()
+            .map(|row| crate::mapping::from_row(&self.schema, row))
+            .collect()
+    }
+
     pub fn to_json_rows(&self) -> Vec<Vec<JsonValue>> {
         self.rows.iter().map(|row| row.to_json()).collect()
     }
@@ -6,6 +6,10 @@ use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
+mod signature;
+
+pub use signature::FunctionSignature;
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum DataType {
     Unknown,
@@ -341,6 +345,30 @@ impl Value {
         matches!(self, Value::Null)
     }
 
+    pub fn heap_size(&self) -> usize {
+        match self {
+            Value::String(s) | Value::Geography(s) => s.len(),
+            Value::Bytes(b) => b.len(),
+            Value::Json(json) => json_heap_size(json),
+            Value::Array(elements) => elements
+                .iter()
+                .map(|e| std::mem::size_of::<Value>() + e.heap_size())
+                .sum(),
+            Value::Struct(fields) => fields
+                .iter()
+                .map(|(name, v)| {
+                    std::mem::size_of::<(String, Value)>() + name.len() + v.heap_size()
+                })
+                .sum(),
+            Value::Range(range) => [&range.start, &range.end]
+                .into_iter()
+                .flatten()
+                .map(|v| std::mem::size_of::<Value>() + v.heap_size())
+                .sum(),
+            _ => 0,
+        }
+    }
+
     pub fn data_type(&self) -> DataType {
         match self {
             Value::Null => DataType::Unknown,
@@ -677,6 +705,23 @@ impl fmt::Display for Value {
 
 impl Eq for Value {}
 
+fn json_heap_size(value: &serde_json::Value) -> usize {
+    match value {
+        serde_json::Value::String(s) => s.len(),
+        serde_json::Value::Array(arr) => arr
+            .iter()
+            .map(|v| std::mem::size_of::<serde_json::Value>() + json_heap_size(v))
+            .sum(),
+        serde_json::Value::Object(obj) => obj
+            .iter()
+            .map(|(k, v)| {
+                std::mem::size_of::<(String, serde_json::Value)>() + k.len() + json_heap_size(v)
+            })
+            .sum(),
+        _ => 0,
+    }
+}
+
 fn hash_json_value<H: std::hash::Hasher>(value: &serde_json::Value, state: &mut H) {
     use std::hash::Hash;
     match value {
@@ -1671,4 +1716,27 @@ mod tests {
         let struct_type = DataType::Struct(vec![]);
         assert_eq!(format!("{}", struct_type), "STRUCT<>");
     }
+
+    #[test]
+    fn test_value_heap_size() {
+        assert_eq!(Value::int64(1).heap_size(), 0);
+        assert_eq!(Value::Null.heap_size(), 0);
+        assert_eq!(Value::string("hello").heap_size(), 5);
+        assert_eq!(Value::bytes(vec![1, 2, 3]).heap_size(), 3);
+        let array = Value::array(vec![Value::string("ab"), Value::int64(1)]);
+        assert_eq!(array.heap_size(), 2 * std::mem::size_of::<Value>() + 2);
+        let nested = Value::struct_val(vec![("k".to_string(), Value::string("xyz"))]);
+        assert_eq!(
+            nested.heap_size(),
+            std::mem::size_of::<(String, Value)>() + 1 + 3
+        );
+        let json = Value::json(serde_json::json!({"ab": ["xyz", 1]}));
+        assert_eq!(
+            json.heap_size(),
+            std::mem::size_of::<(String, serde_json::Value)>()
+                + 2
+                + 2 * std::mem::size_of::<serde_json::Value>()
+                + 3
+        );
+    }
 }
@@ -0,0 +1,125 @@
+#![coverage(off)]
+
+use super::DataType;
+use crate::error::{Error, Result};
+
+/// Argument and return types of a function implemented outside SQL.
+///
+/// An argument declared as [`DataType::Unknown`] accepts any type. Other
+/// arguments accept their own type plus the implicit widenings BigQuery applies
+/// to function arguments: `INT64` to `NUMERIC`, `BIGNUMERIC` and `FLOAT64`, and
+/// `NUMERIC` to `BIGNUMERIC` and `FLOAT64`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionSignature {
+    pub arguments: Vec<DataType>,
+    pub return_type: DataType,
+}
+
+impl FunctionSignature {
+    pub fn new(arguments: Vec<DataType>, return_type: DataType) -> Self {
+        Self {
+            arguments,
+            return_type,
+        }
+    }
+
+    /// Checks that a call of `name` with arguments of `types` matches this
+    /// signature. Arguments whose type is `Unknown` (such as `NULL`) match any
+    /// declared type.
+    pub fn check_arguments(&self, name: &str, types: &[DataType]) -> Result<()> {
+        let matches = types.len() == self.arguments.len()
+            && self
+                .arguments
+                .iter()
+                .zip(types)
+                .all(|(expected, actual)| accepts(expected, actual));
+        if matches {
+            return Ok(());
+        }
+        let actual = types
+            .iter()
+            .map(|t| t.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let supported = self
+            .arguments
+            .iter()
+            .map(|t| match t {
+                DataType::Unknown => "ANY TYPE".to_string(),
+                t => t.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        Err(Error::invalid_query(format!(
+            "No matching signature for function {name} for argument types: {actual}. \
+             Supported signature: {name}({supported})"
+        )))
+    }
+}
+
+/// Whether a value of type `actual` can be passed where `expected` is declared.
+fn accepts(expected: &DataType, actual: &DataType) -> bool {
+    match (expected, actual) {
+        (DataType::Unknown, _) | (_, DataType::Unknown) => true,
+        (DataType::Numeric(_), DataType::Numeric(_) | DataType::Int64) => true,
+        (DataType::BigNumeric, DataType::BigNumeric | DataType::Numeric(_) | DataType::Int64) => {
+            true
+        }
+        (
+            DataType::Float64,
+            DataType::Float64 | DataType::BigNumeric | DataType::Numeric(_) | DataType::Int64,
+        ) => true,
+        (DataType::Array(expected), DataType::Array(actual))
+        | (DataType::Range(expected), DataType::Range(actual)) => accepts(expected, actual),
+        (DataType::Struct(expected), DataType::Struct(actual)) => {
+            expected.len() == actual.len()
+                && expected
+                    .iter()
+                    .zip(actual)
+                    .all(|(e, a)| accepts(&e.data_type, &a.data_type))
+        }
+        (expected, actual) => expected == actual,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_exact_and_widened_types() {
+        let signature = FunctionSignature::new(
+            vec![DataType::Float64, DataType::String, DataType::Unknown],
+            DataType::Float64,
+        );
+        assert!(
+            signature
+                .check_arguments("F", &[DataType::Int64, DataType::String, DataType::Bytes])
+                .is_ok()
+        );
+        assert!(
+            signature
+                .check_arguments("F", &[DataType::Unknown, DataType::Unknown, DataType::Bool])
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn rejects_mismatched_types_and_arity() {
+        let signature = FunctionSignature::new(vec![DataType::Int64], DataType::Int64);
+        let err = signature
+            .check_arguments("F", &[DataType::String])
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid query: No matching signature for function F for argument types: STRING. \
+             Supported signature: F(INT64)"
+        );
+        assert!(signature.check_arguments("F", &[]).is_err());
+        assert!(
+            signature
+                .check_arguments("F", &[DataType::Float64])
+                .is_err()
+        );
+    }
+}
@@ -1,29 +1,46 @@
 #![coverage(off)]
 
 use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, RwLock};
 use std::time::Instant;
 
 use debug_print::debug_eprintln;
 use lazy_static::lazy_static;
 use regex::Regex;
+use rustc_hash::FxHashSet;
+use tokio::sync::mpsc;
 use tracing::{debug, info, instrument};
-use yachtsql_common::error::Result;
+use yachtsql_common::error::{Error, Result};
+use yachtsql_common::types::Value;
 use yachtsql_ir::LogicalPlan;
-use yachtsql_optimizer::OptimizerSettings;
+use yachtsql_optimizer::{OptimizerSettings, PhysicalPlan};
 use yachtsql_storage::Table;
 
+use crate::cancellation::{CancellationHandle, JOB_TIMEOUT_VARIABLE, QueryInterrupt, QueryJob};
 use crate::concurrent_catalog::ConcurrentCatalog;
 use crate::concurrent_session::ConcurrentSession;
-use crate::executor::concurrent::ConcurrentPlanExecutor;
+use crate::dry_run::DryRunResult;
+use crate::durable::{DurableSession, DurableStore};
+use crate::executor::concurrent::{ConcurrentPlanExecutor, emit_in_batches};
+use crate::executor::plan_schema_to_schema;
+use crate::job_history::{JOBS_TABLE_NAMES, JobHistory};
+use crate::memory::{MEMORY_LIMIT_VARIABLE, MemoryBudget};
 use crate::metrics::QueryMetrics;
+use crate::observer::{QueryObserver, QueryObservers, QueryTrace};
 use crate::physical_planner::PhysicalPlanner;
-use crate::plan::PhysicalPlanExt;
+use crate::plan::{PhysicalPlanExt, TableAccessSet};
 use crate::plan_cache::{CacheInvalidation, PlanCache, get_cache_invalidation};
+use crate::prepared::PreparedStatement;
+use crate::query_params::{
+    BoundParameters, QueryParameter, bind_parameters, check_parameter_types,
+};
+use crate::query_stream::{QueryStream, STREAM_BUFFERED_BATCHES};
+use crate::typed_rows::rows_to_table;
 
 const PLAN_CACHE_SIZE: NonZeroUsize = NonZeroUsize::new(10000).unwrap();
 
-fn preprocess_range_types(sql: &str) -> String {
+pub(crate) fn preprocess_range_types(sql: &str) -> String {
     lazy_static! {
         static ref RANGE_TYPE_RE: Regex =
             Regex::new(r"(?i)\bRANGE\s*<\s*(DATE|DATETIME|TIMESTAMP)\s*>")
@@ -55,35 +72,113 @@ fn is_cacheable_plan(plan: &LogicalPlan) -> bool {
     )
 }
 
+/// A physical plan annotated for execution, with the tables it locks.
+pub(crate) struct ExecutionPlan {
+    pub(crate) plan: PhysicalPlan,
+    pub(crate) accesses: TableAccessSet,
+}
+
+#[derive(Debug, Clone)]
+pub struct StatementResult {
+    pub sql: String,
+    pub is_query: bool,
+    pub table: Table,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ScriptResult {
+    pub statements: Vec<StatementResult>,
+}
+
+impl ScriptResult {
+    pub fn last_query_result(&self) -> Option<&Table> {
+        self.statements
+            .iter()
+            .rev()
+            .find(|s| s.is_query)
+            .map(|s| &s.table)
+    }
+
+    pub fn into_last_query_result(self) -> Option<Table> {
+        self.statements
+            .into_iter()
+            .rev()
+            .find(|s| s.is_query)
+            .map(|s| s.table)
+    }
+}
+
 pub struct AsyncQueryExecutor {
     catalog: Arc<ConcurrentCatalog>,
     session: Arc<ConcurrentSession>,
     plan_cache: Arc<RwLock<PlanCache>>,
+    cached_schema_version: Arc<AtomicU64>,
     metrics: Arc<QueryMetrics>,
+    observers: QueryObservers,
+    jobs: Option<JobHistory>,
+    durable: Option<DurableSession>,
 }
 
 impl AsyncQueryExecutor {
     pub fn new() -> Self {
-        Self {
-            catalog: Arc::new(ConcurrentCatalog::new()),
-            session: Arc::new(ConcurrentSession::new()),
-            plan_cache: Arc::new(RwLock::new(PlanCache::new(PLAN_CACHE_SIZE))),
-            metrics: Arc::new(QueryMetrics::new()),
-        }
+        Self::from_catalog_and_session(ConcurrentCatalog::new(), ConcurrentSession::new())
     }
 
     pub fn from_catalog_and_session(
         catalog: ConcurrentCatalog,
         session: ConcurrentSession,
     ) -> Self {
+        let schema_version = catalog.schema_version();
         Self {
             catalog: Arc::new(catalog),
             session: Arc::new(session),
             plan_cache: Arc::new(RwLock::new(PlanCache::new(PLAN_CACHE_SIZE))),
+            cached_schema_version: Arc::new(AtomicU64::new(schema_version)),
             metrics: Arc::new(QueryMetrics::new()),
+            observers: QueryObservers::default(),
+            jobs: None,
+            durable: None,
         }
     }
 
+    /// Logs every change this executor commits to `store`, making it durable.
+    /// The executor's catalog should be a session scope of the store's catalog.
+    pub fn with_durable_store(mut self, store: Arc<DurableStore>) -> Self {
+        self.durable = Some(DurableSession::new(store, &self.catalog));
+        self
+    }
+
+    /// Records every statement this executor runs in `jobs` and exposes the
+    /// history as the read-only `INFORMATION_SCHEMA.JOBS` table.
+    pub fn with_job_history(mut self, jobs: JobHistory) -> Self {
+        for name in JOBS_TABLE_NAMES {
+            self.catalog
+                .register_system_table(name, Arc::new(jobs.clone()));
+        }
+        self.jobs = Some(jobs);
+        self
+    }
+
+    /// Returns an executor over a copy-on-write fork of this executor's catalog and
+    /// session state. The fork gets its own plan cache and metrics, and starts out
+    /// with this executor's observers and job history.
+    pub fn fork(&self) -> Self {
+        let mut fork = Self::from_catalog_and_session(self.catalog.fork(), self.session.fork());
+        fork.observers = self.observers.copy();
+        fork.jobs = self.jobs.clone();
+        fork
+    }
+
+    /// Registers `observer` to receive events about every statement this executor
+    /// and its clones run from now on; see [`QueryObserver`].
+    pub fn add_observer(&self, observer: Arc<dyn QueryObserver>) {
+        self.observers.add(observer);
+    }
+
+    pub(crate) fn trace_query(&self, sql: &str) -> QueryTrace {
+        self.observers.start(sql, self.jobs.as_ref())
+    }
+
     fn get_optimizer_settings(&self) -> OptimizerSettings {
         use yachtsql_optimizer::{OptimizationLevel, RuleFlags};
 
@@ -170,58 +265,299 @@ impl AsyncQueryExecutor {
 
     #[instrument(skip(self), fields(sql_length = sql.len()))]
     pub async fn execute_sql(&self, sql: &str) -> Result<Table> {
+        self.execute_bound_sql(sql, None, &CancellationHandle::default())
+            .await
+    }
+
+    #[instrument(skip(self, cancel), fields(sql_length = sql.len()))]
+    pub async fn execute_sql_with_cancellation(
+        &self,
+        sql: &str,
+        cancel: &CancellationHandle,
+    ) -> Result<Table> {
+        self.execute_bound_sql(sql, None, cancel).await
+    }
+
+    pub fn start_query(&self, sql: &str) -> QueryJob {
+        let cancel = CancellationHandle::new();
+        let executor = self.clone();
+        let sql = sql.to_string();
+        let task_cancel = cancel.clone();
+        let task = tokio::spawn(async move {
+            executor
+                .execute_sql_with_cancellation(&sql, &task_cancel)
+                .await
+        });
+        QueryJob::new(cancel, task)
+    }
+
+    #[instrument(skip(self, params), fields(sql_length = sql.len(), param_count = params.len()))]
+    pub async fn execute_sql_with_params(
+        &self,
+        sql: &str,
+        params: &[QueryParameter],
+    ) -> Result<Table> {
+        let markers = yachtsql_parser::extract_parameter_markers(sql)?;
+        let bound = bind_parameters(&markers, params)?;
+        self.execute_bound_sql(&markers.sql, Some(bound), &CancellationHandle::default())
+            .await
+    }
+
+    #[instrument(skip(self), fields(sql_length = sql.len()))]
+    pub fn prepare(&self, sql: &str) -> Result<PreparedStatement> {
+        PreparedStatement::new(self.clone(), sql)
+    }
+
+    /// Parses, plans and optimizes `sql` without running it, like a BigQuery
+    /// `dryRun` query. Fails with the error the statement would fail with at
+    /// planning time.
+    pub fn dry_run(&self, sql: &str) -> Result<DryRunResult> {
+        let sql = preprocess_range_types(sql);
+        let logical = self.plan_logical(&sql)?;
+        let physical = self.optimize(&logical)?;
+        let execution = self.plan_execution(&physical);
+        Ok(DryRunResult::new(
+            &logical,
+            &execution.accesses,
+            &self.catalog,
+        ))
+    }
+
+    async fn execute_bound_sql(
+        &self,
+        sql: &str,
+        params: Option<BoundParameters>,
+        cancel: &CancellationHandle,
+    ) -> Result<Table> {
         let sql = preprocess_range_types(sql);
         debug!(sql = %sql, "Executing SQL query");
         let start = Instant::now();
+        let mut trace = self.trace_query(&sql);
+
+        let result = async {
+            let logical = self.plan_logical(&sql)?;
+            if let Some(params) = &params {
+                check_parameter_types(&logical, &params.types, &self.catalog)?;
+            }
+            let physical = self.optimize(&logical)?;
+            trace.plan_ready(&logical, &physical);
+
+            let execution = self.plan_execution(&physical);
+            self.run_plan(&sql, &logical, execution, params, cancel, start, &mut trace)
+                .await
+        }
+        .await;
+        trace.finish(&result);
+        result
+    }
+
+    fn sync_plan_cache(&self, cache: &mut PlanCache) {
+        let current = self.catalog.schema_version();
+        if self.cached_schema_version.swap(current, Ordering::AcqRel) != current {
+            cache.clear();
+        }
+    }
 
-        let logical = {
-            let mut cache = self.plan_cache.write().unwrap_or_else(|e| e.into_inner());
-            match cache.get(&sql) {
-                Some(plan) => plan,
-                None => {
-                    let plan = yachtsql_parser::parse_and_plan(&sql, self)?;
-                    if is_cacheable_plan(&plan) {
-                        cache.insert(&sql, plan.clone());
-                    }
-                    plan
+    pub(crate) fn plan_logical(&self, sql: &str) -> Result<LogicalPlan> {
+        let mut cache = self.plan_cache.write().unwrap_or_else(|e| e.into_inner());
+        self.sync_plan_cache(&mut cache);
+        match cache.get(sql) {
+            Some(plan) => Ok(plan),
+            None => {
+                let plan = yachtsql_parser::parse_and_plan(sql, self)?;
+                if is_cacheable_plan(&plan) {
+                    cache.insert(sql, plan.clone());
                 }
+                Ok(plan)
             }
-        };
+        }
+    }
 
+    pub(crate) fn optimize(&self, logical: &LogicalPlan) -> Result<PhysicalPlan> {
         let settings = self.get_optimizer_settings();
-        let physical = yachtsql_optimizer::optimize_with_settings(&logical, &settings)?;
-
+        let physical = yachtsql_optimizer::optimize_with_settings(logical, &settings)?;
         debug!("Query planned, executing");
+        Ok(physical)
+    }
+
+    pub(crate) fn plan_cache_version(&self) -> u64 {
+        self.plan_cache
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .version()
+    }
+
+    pub(crate) fn is_plan_stale(&self, objects: &FxHashSet<String>, version: u64) -> bool {
+        let mut cache = self.plan_cache.write().unwrap_or_else(|e| e.into_inner());
+        self.sync_plan_cache(&mut cache);
+        cache.is_stale(objects, version)
+    }
+
+    fn job_timeout_ms(&self) -> Option<u64> {
+        self.session
+            .get_system_variable(JOB_TIMEOUT_VARIABLE)
+            .and_then(|v| v.as_i64())
+            .filter(|ms| *ms > 0)
+            .map(|ms| ms as u64)
+    }
 
+    fn memory_limit_bytes(&self) -> Option<usize> {
+        self.session
+            .get_system_variable(MEMORY_LIMIT_VARIABLE)
+            .and_then(|v| v.as_i64())
+            .filter(|bytes| *bytes > 0)
+            .map(|bytes| bytes as usize)
+    }
+
+    pub(crate) fn plan_execution(&self, physical: &PhysicalPlan) -> Arc<ExecutionPlan> {
         let planner = PhysicalPlanner::new(&self.catalog, &self.session);
-        let executor_plan = planner.plan(&physical);
-        let accesses = executor_plan.extract_table_accesses();
+        let plan = planner.plan(physical);
+        let accesses = plan.extract_table_accesses();
+        Arc::new(ExecutionPlan { plan, accesses })
+    }
 
-        let mut tables = self.catalog.acquire_table_locks(&accesses)?;
+    fn prepare_execution(
+        &self,
+        execution: &ExecutionPlan,
+        cancel: &CancellationHandle,
+        trace: &mut QueryTrace,
+    ) -> Result<ConcurrentPlanExecutor> {
+        trace.record_tables(&execution.accesses, &self.catalog);
+
+        let mut tables = self.catalog.acquire_table_locks(&execution.accesses)?;
         tables.set_catalog(Arc::clone(&self.catalog));
 
-        let executor = ConcurrentPlanExecutor::new(
+        Ok(ConcurrentPlanExecutor::new(
             Arc::clone(&self.catalog),
             Arc::clone(&self.session),
             tables,
-        );
+        )
+        .with_interrupt(QueryInterrupt::new(cancel.clone(), self.job_timeout_ms()))
+        .with_memory_budget(MemoryBudget::new(self.memory_limit_bytes())))
+    }
+
+    #[instrument(skip(self), fields(sql_length = sql.len()))]
+    pub async fn execute_sql_stream(&self, sql: &str, batch_size: usize) -> Result<QueryStream> {
+        let sql = preprocess_range_types(sql);
+        debug!(sql = %sql, "Streaming SQL query");
+        let start = Instant::now();
+        let mut trace = self.trace_query(&sql);
+
+        let planned = self.plan_logical(&sql).and_then(|logical| {
+            let physical = self.optimize(&logical)?;
+            Ok((logical, physical))
+        });
+        let (logical, physical) = match planned {
+            Ok(planned) => planned,
+            Err(e) => {
+                trace.finish_with(Err(&e));
+                return Err(e);
+            }
+        };
+        trace.plan_ready(&logical, &physical);
+        let (sender, receiver) = mpsc::channel(STREAM_BUFFERED_BATCHES);
+        let cancel = CancellationHandle::new();
+
+        if !is_cacheable_plan(&logical) {
+            let execution = self.plan_execution(&physical);
+            let result = self
+                .run_plan(&sql, &logical, execution, None, &cancel, start, &mut trace)
+                .await;
+            trace.finish(&result);
+            let table = result?;
+            let schema = table.schema().clone();
+            tokio::task::spawn_blocking(move || {
+                let sent = emit_in_batches(&table, batch_size, &mut |batch| {
+                    sender.blocking_send(Ok(batch)).is_ok()
+                });
+                if let Err(e) = sent {
+                    let _ = sender.blocking_send(Err(e));
+                }
+            });
+            return Ok(QueryStream::new(schema, receiver, cancel));
+        }
+
+        let schema = plan_schema_to_schema(physical.schema());
+        let execution = self.plan_execution(&physical);
+        let executor = match self.prepare_execution(&execution, &cancel, &mut trace) {
+            Ok(executor) => executor,
+            Err(e) => {
+                trace.finish_with(Err(&e));
+                return Err(e);
+            }
+        };
+        let metrics = Arc::clone(&self.metrics);
+        tokio::task::spawn_blocking(move || {
+            let mut rows = 0;
+            let result = executor.execute_plan_batched(&execution.plan, batch_size, &mut |batch| {
+                rows += batch.row_count();
+                sender.blocking_send(Ok(batch)).is_ok()
+            });
+            metrics.record_query(start.elapsed(), result.is_err());
+            trace.finish_with(result.as_ref().map(|_| rows));
+            if let Err(e) = result {
+                let _ = sender.blocking_send(Err(e));
+            }
+        });
+
+        Ok(QueryStream::new(schema, receiver, cancel))
+    }
+
+    pub(crate) async fn run_plan(
+        &self,
+        sql: &str,
+        logical: &LogicalPlan,
+        execution: Arc<ExecutionPlan>,
+        params: Option<BoundParameters>,
+        cancel: &CancellationHandle,
+        start: Instant,
+        trace: &mut QueryTrace,
+    ) -> Result<Table> {
+        let mut pending_write = match &self.durable {
+            Some(durable) if !is_cacheable_plan(logical) => {
+                Some(durable.begin_write(logical, &self.catalog).await?)
+            }
+            _ => None,
+        };
+        let executor = self.prepare_execution(&execution, cancel, trace)?;
+        if let Some(pending_write) = &mut pending_write {
+            pending_write.observe_tables(&executor.tables);
+        }
+        if let Some(params) = params {
+            executor.bind_variables(params.values);
+        }
         let (result, executor) = tokio::task::spawn_blocking(move || {
-            let result = executor.execute_plan(&executor_plan);
+            let result = executor.execute_plan(&execution.plan);
             (result, executor)
         })
         .await
         .map_err(|e| yachtsql_common::error::Error::internal(e.to_string()))?;
 
-        executor.tables.commit_writes();
+        trace.record_dml_rows(executor.dml_rows());
+        let logged = match pending_write {
+            Some(pending_write) => {
+                pending_write.finish(sql, &self.catalog, &executor.tables, result.is_ok())
+            }
+            None => Ok(()),
+        };
+        if logged.is_ok() {
+            executor.tables.commit_writes();
+        }
+        let result = match logged {
+            Err(e) if result.is_ok() => Err(e),
+            _ => result,
+        };
 
-        match get_cache_invalidation(&logical) {
+        match get_cache_invalidation(logical) {
             CacheInvalidation::All => {
                 let mut cache = self.plan_cache.write().unwrap_or_else(|e| e.into_inner());
                 cache.clear();
+                self.publish_schema_change();
             }
             CacheInvalidation::Objects(objects) if !objects.is_empty() => {
                 let mut cache = self.plan_cache.write().unwrap_or_else(|e| e.into_inner());
                 cache.invalidate_objects(&objects);
+                self.publish_schema_change();
             }
             _ => {}
         }
@@ -245,6 +581,16 @@ impl AsyncQueryExecutor {
         result
     }
 
+    fn publish_schema_change(&self) {
+        let version = self.catalog.bump_schema_version();
+        let _ = self.cached_schema_version.compare_exchange(
+            version - 1,
+            version,
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        );
+    }
+
     pub async fn execute_batch(&self, queries: Vec<String>) -> Vec<Result<Table>> {
         let mut results = Vec::with_capacity(queries.len());
         for sql in queries {
@@ -253,6 +599,69 @@ impl AsyncQueryExecutor {
         results
     }
 
+    #[instrument(skip(self), fields(sql_length = sql.len()))]
+    pub async fn execute_script(&self, sql: &str) -> Result<ScriptResult> {
+        let statements = yachtsql_parser::split_script(sql)?;
+        debug!(statement_count = statements.len(), "Executing SQL script");
+
+        let mut results = Vec::with_capacity(statements.len());
+        for statement in statements {
+            let table = self.execute_sql(&statement.sql).await?;
+            results.push(StatementResult {
+                sql: statement.sql,
+                is_query: statement.is_query,
+                table,
+            });
+        }
+        Ok(ScriptResult {
+            statements: results,
+        })
+    }
+
+    /// Appends `rows` to `table_name` without going through SQL.
+    ///
+    /// Each row is a `STRUCT` value whose fields are matched to the table's columns
+    /// by name; columns without a field are `NULL`. Strings are converted to
+    /// `NUMERIC`, `DATE`, `TIME`, `DATETIME` and `TIMESTAMP` columns. Returns the
+    /// number of rows appended.
+    pub async fn insert_rows(&self, table_name: &str, rows: Vec<Value>) -> Result<u64> {
+        let start = Instant::now();
+        let mut pending_write = match &self.durable {
+            Some(durable) => Some(durable.begin_append(table_name, &self.catalog).await?),
+            None => None,
+        };
+        let mut accesses = TableAccessSet::new();
+        accesses.add_write(table_name.to_string());
+        let mut tables = self.catalog.acquire_table_locks(&accesses)?;
+        tables.set_catalog(Arc::clone(&self.catalog));
+        if let Some(pending_write) = &mut pending_write {
+            pending_write.observe_tables(&tables);
+        }
+
+        let key = self.catalog.resolve_table_name(table_name);
+        let result = tables
+            .with_table_mut(&key, |table| {
+                let appended = rows_to_table(table.schema(), rows)?;
+                *table = table.concat(&appended)?;
+                Ok(appended.row_count() as u64)
+            })
+            .unwrap_or_else(|| Err(Error::table_not_found(table_name)));
+
+        let logged = match pending_write {
+            Some(pending_write) => pending_write.finish("", &self.catalog, &tables, result.is_ok()),
+            None => Ok(()),
+        };
+        if logged.is_ok() {
+            tables.commit_writes();
+        }
+        let result = match logged {
+            Err(e) if result.is_ok() => Err(e),
+            _ => result,
+        };
+        self.metrics.record_query(start.elapsed(), result.is_err());
+        result
+    }
+
     pub fn catalog(&self) -> &ConcurrentCatalog {
         &self.catalog
     }
@@ -277,7 +686,11 @@ impl Clone for AsyncQueryExecutor {
             catalog: Arc::clone(&self.catalog),
             session: Arc::clone(&self.session),
             plan_cache: Arc::clone(&self.plan_cache),
+            cached_schema_version: Arc::clone(&self.cached_schema_version),
             metrics: Arc::clone(&self.metrics),
+            observers: self.observers.clone(),
+            jobs: self.jobs.clone(),
+            durable: self.durable.clone(),
         }
     }
 }
@@ -303,14 +716,10 @@ impl yachtsql_parser::CatalogProvider for AsyncQueryExecutor {
     }
 
     fn get_function(&self, name: &str) -> Option<yachtsql_parser::FunctionDefinition> {
-        self.catalog
-            .get_function(name)
-            .map(|f| yachtsql_parser::FunctionDefinition {
-                name: f.name.clone(),
-                parameters: f.parameters.clone(),
-                return_type: f.return_type.clone(),
-                body: f.body.clone(),
-                is_aggregate: f.is_aggregate,
-            })
+        self.catalog.function_definition(name)
+    }
+
+    fn has_session_dataset(&self) -> bool {
+        self.catalog.is_session_scoped()
     }
 }
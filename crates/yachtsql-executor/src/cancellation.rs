@@ -0,0 +1,120 @@
+#![coverage(off)]
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use tokio::task::JoinHandle;
+use yachtsql_common::error::{Error, Result};
+use yachtsql_storage::Table;
+
+pub const JOB_TIMEOUT_VARIABLE: &str = "@@JOB_TIMEOUT_MS";
+
+/// Rows a per-row loop processes between interrupt checks.
+const ROW_CHECK_INTERVAL: usize = 1024;
+
+#[derive(Debug, Clone, Default)]
+pub struct CancellationHandle {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationHandle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct QueryInterrupt {
+    handle: CancellationHandle,
+    deadline: Option<(Instant, u64)>,
+    tripped: Arc<AtomicBool>,
+}
+
+impl QueryInterrupt {
+    pub(crate) fn new(handle: CancellationHandle, timeout_ms: Option<u64>) -> Self {
+        let deadline = timeout_ms.map(|ms| (Instant::now() + Duration::from_millis(ms), ms));
+        Self {
+            handle,
+            deadline,
+            tripped: Arc::default(),
+        }
+    }
+
+    pub(crate) fn check(&self) -> Result<()> {
+        if self.handle.is_cancelled() {
+            self.tripped.store(true, Ordering::Relaxed);
+            return Err(Error::query_cancelled());
+        }
+        if let Some((deadline, timeout_ms)) = self.deadline
+            && Instant::now() >= deadline
+        {
+            self.tripped.store(true, Ordering::Relaxed);
+            return Err(Error::query_timeout(timeout_ms));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn is_interrupted(&self) -> bool {
+        self.check().is_err()
+    }
+
+    /// Checks for an interrupt on every `ROW_CHECK_INTERVAL`th row of a loop,
+    /// so tight per-row loops don't pay for an atomic load and a clock read
+    /// each time round.
+    pub(crate) fn check_row(&self, row: usize) -> Result<()> {
+        if row.is_multiple_of(ROW_CHECK_INTERVAL) {
+            self.check()
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Like [`check_row`](Self::check_row), for parallel loops that bail out
+    /// with an empty result: once any row has seen the interrupt, every row
+    /// sees it.
+    pub(crate) fn is_interrupted_at(&self, row: usize) -> bool {
+        self.tripped.load(Ordering::Relaxed) || self.check_row(row).is_err()
+    }
+}
+
+pub(crate) fn is_interrupt_error(error: &Error) -> bool {
+    matches!(error, Error::QueryCancelled | Error::QueryTimeout { .. })
+}
+
+pub struct QueryJob {
+    handle: CancellationHandle,
+    task: JoinHandle<Result<Table>>,
+}
+
+impl QueryJob {
+    pub(crate) fn new(handle: CancellationHandle, task: JoinHandle<Result<Table>>) -> Self {
+        Self { handle, task }
+    }
+
+    pub fn cancellation_handle(&self) -> CancellationHandle {
+        self.handle.clone()
+    }
+
+    pub fn cancel(&self) {
+        self.handle.cancel();
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+
+    pub async fn wait(self) -> Result<Table> {
+        self.task
+            .await
+            .map_err(|e| Error::internal(e.to_string()))?
+    }
+}
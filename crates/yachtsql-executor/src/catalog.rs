@@ -18,6 +18,17 @@ pub struct UserFunction {
     pub is_aggregate: bool,
 }
 
+impl UserFunction {
+    /// Whether this is a table function, called in a `FROM` clause.
+    pub fn is_table_function(&self) -> bool {
+        match &self.body {
+            FunctionBody::SqlQuery(_) => true,
+            FunctionBody::Sql(body) => matches!(body.as_ref(), Expr::Subquery(_)),
+            _ => false,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserProcedure {
     pub name: String,
@@ -537,6 +548,7 @@ impl CatalogProvider for Catalog {
                 return_type: f.return_type.clone(),
                 body: f.body.clone(),
                 is_aggregate: f.is_aggregate,
+                is_table_function: f.is_table_function(),
             })
     }
 }
@@ -0,0 +1,185 @@
+#![coverage(off)]
+
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use yachtsql_common::error::{Error, Result};
+use yachtsql_storage::Table;
+
+use crate::catalog::{ColumnDefault, SchemaMetadata, UserFunction, UserProcedure, ViewDef};
+use crate::concurrent_catalog::{ConcurrentCatalog, DroppedSchemaData};
+use crate::plan::PhysicalPlan;
+
+const CATALOG_FILE_MAGIC: &[u8; 8] = b"YACHTCAT";
+const CATALOG_FILE_VERSION: u32 = 1;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct CatalogSnapshot {
+    pub tables: Vec<(String, Table)>,
+    pub table_defaults: Vec<(String, Vec<ColumnDefault>)>,
+    pub functions: Vec<(String, UserFunction)>,
+    pub procedures: Vec<(String, UserProcedure)>,
+    pub procedure_bodies: Vec<(String, Vec<PhysicalPlan>)>,
+    pub views: Vec<(String, ViewDef)>,
+    pub schemas: Vec<String>,
+    pub schema_metadata: Vec<(String, SchemaMetadata)>,
+    pub dropped_schemas: Vec<(String, DroppedSchemaData)>,
+    pub projects: Vec<(String, Vec<String>)>,
+    pub dataset_tables: Vec<(String, Vec<String>)>,
+    pub search_path: Vec<String>,
+    pub default_project: Option<String>,
+}
+
+impl ConcurrentCatalog {
+    /// Writes the catalog's permanent objects to `path`.
+    ///
+    /// The file contains every table with its data and column defaults, views,
+    /// functions, procedures, datasets with their options, the search path and the
+    /// default project. Temporary functions, and the temporary tables of a
+    /// [`session_scope`](Self::session_scope), belong to a session and are not
+    /// saved. The file starts with a format version, so files written by an
+    /// older release keep loading with [`load_from`](Self::load_from). An
+    /// existing file at `path` is replaced atomically: if saving fails it is
+    /// left as it was.
+    pub fn save_to(&self, path: impl AsRef<Path>) -> Result<()> {
+        write_catalog_file(path.as_ref(), &self.to_snapshot())
+    }
+
+    /// Reads a catalog previously written with [`save_to`](Self::save_to).
+    pub fn load_from(path: impl AsRef<Path>) -> Result<Self> {
+        read_catalog_file(path.as_ref()).map(Self::from_snapshot)
+    }
+}
+
+/// Writes `snapshot` to `path` and syncs it to disk before returning.
+///
+/// The snapshot goes to a temporary file next to `path` that is then renamed
+/// over it, so a crash or a failed write leaves the previous file intact.
+pub(crate) fn write_catalog_file(path: &Path, snapshot: &CatalogSnapshot) -> Result<()> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp = dir.join(tmp_name);
+    let written = write_synced(&tmp, snapshot).and_then(|()| {
+        fs::rename(&tmp, path).map_err(|e| {
+            Error::io_error(
+                "save catalog",
+                format!("Failed to replace '{}': {}", path.display(), e),
+            )
+        })
+    });
+    if written.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    written?;
+    sync_dir(dir)
+}
+
+fn write_synced(path: &Path, snapshot: &CatalogSnapshot) -> Result<()> {
+    let write_error = |e: &dyn std::fmt::Display| {
+        Error::io_error(
+            "save catalog",
+            format!("Failed to write '{}': {}", path.display(), e),
+        )
+    };
+    let file = File::create(path).map_err(|e| {
+        Error::io_error(
+            "save catalog",
+            format!("Failed to create file '{}': {}", path.display(), e),
+        )
+    })?;
+    let mut writer = BufWriter::new(file);
+    write_header(&mut writer, path)?;
+    ciborium::into_writer(snapshot, &mut writer).map_err(|e| write_error(&e))?;
+    let file = writer.into_inner().map_err(|e| write_error(&e))?;
+    file.sync_all().map_err(|e| write_error(&e))
+}
+
+/// Makes a rename or a newly created file in `dir` durable.
+#[cfg(unix)]
+pub(crate) fn sync_dir(dir: &Path) -> Result<()> {
+    File::open(dir)
+        .and_then(|d| d.sync_all())
+        .map_err(|e| Error::io_error("sync directory", format!("'{}': {}", dir.display(), e)))
+}
+
+#[cfg(not(unix))]
+pub(crate) fn sync_dir(_dir: &Path) -> Result<()> {
+    Ok(())
+}
+
+pub(crate) fn read_catalog_file(path: &Path) -> Result<CatalogSnapshot> {
+    let file = File::open(path).map_err(|e| {
+        Error::io_error(
+            "load catalog",
+            format!("Failed to open file '{}': {}", path.display(), e),
+        )
+    })?;
+    let mut reader = BufReader::new(file);
+    let version = read_header(&mut reader, path)?;
+    decode_snapshot(version, &mut reader, path)
+}
+
+/// Decodes the body of a catalog file written with format `version`.
+///
+/// Every format version still supported has an arm here. A release that changes
+/// [`CatalogSnapshot`] bumps [`CATALOG_FILE_VERSION`] and keeps decoding older
+/// files through their previous layout, converting them to the current one.
+fn decode_snapshot(version: u32, reader: &mut impl Read, path: &Path) -> Result<CatalogSnapshot> {
+    let read_error = |e: &dyn std::fmt::Display| {
+        Error::io_error(
+            "load catalog",
+            format!("Failed to read '{}': {}", path.display(), e),
+        )
+    };
+    match version {
+        1 => ciborium::from_reader(reader).map_err(|e| read_error(&e)),
+        _ => Err(Error::io_error(
+            "load catalog",
+            format!(
+                "'{}' uses catalog format version {}, but this build supports versions 1 to {}",
+                path.display(),
+                version,
+                CATALOG_FILE_VERSION
+            ),
+        )),
+    }
+}
+
+fn write_header(writer: &mut impl Write, path: &Path) -> Result<()> {
+    writer
+        .write_all(CATALOG_FILE_MAGIC)
+        .and_then(|_| writer.write_all(&CATALOG_FILE_VERSION.to_le_bytes()))
+        .map_err(|e| {
+            Error::io_error(
+                "save catalog",
+                format!("Failed to write '{}': {}", path.display(), e),
+            )
+        })
+}
+
+fn read_header(reader: &mut impl Read, path: &Path) -> Result<u32> {
+    let mut magic = [0u8; 8];
+    let mut version = [0u8; 4];
+    reader
+        .read_exact(&mut magic)
+        .and_then(|_| reader.read_exact(&mut version))
+        .map_err(|_| {
+            Error::io_error(
+                "load catalog",
+                format!("'{}' is not a YachtSQL catalog file", path.display()),
+            )
+        })?;
+    if &magic != CATALOG_FILE_MAGIC {
+        return Err(Error::io_error(
+            "load catalog",
+            format!("'{}' is not a YachtSQL catalog file", path.display()),
+        ));
+    }
+    Ok(u32::from_le_bytes(version))
+}
@@ -24,6 +24,15 @@ pub fn eval_scalar_function(
 
     let n = table.row_count();
     if let ScalarFunction::Custom(name) = func {
+        let upper = name.to_uppercase();
+        if let Some(native) = evaluator
+            .user_functions()
+            .and_then(|funcs| funcs.get(&upper))
+            .and_then(|func_def| func_def.native.as_ref())
+            && let Some(column) = native.invoke_columnar(&upper, &arg_cols, n)?
+        {
+            return Ok(column);
+        }
         let mut results = Vec::with_capacity(n);
         let value_eval = create_value_evaluator(evaluator, table);
         for i in 0..n {
@@ -166,6 +175,9 @@ fn eval_custom_scalar_function(
                             name
                         )));
                     }
+                    FunctionBody::Native => {
+                        return func_def.invoke_native(&upper, args);
+                    }
                 }
             }
             Err(Error::unsupported(format!(
@@ -1,5 +1,7 @@
 #![coverage(off)]
 
+use std::borrow::Cow;
+
 use rustc_hash::FxHashMap;
 use yachtsql_common::error::Result;
 use yachtsql_common::types::Value;
@@ -11,10 +13,21 @@ pub fn eval_variable(
     name: &str,
     row_count: usize,
 ) -> Result<Column> {
+    let key = lookup_key(name);
     let value = variables
-        .and_then(|v| v.get(name))
-        .or_else(|| system_variables.and_then(|v| v.get(name)))
+        .and_then(|v| v.get(key.as_ref()))
+        .or_else(|| system_variables.and_then(|v| v.get(key.as_ref())))
         .cloned()
         .unwrap_or(Value::Null);
     Ok(Column::broadcast(value, row_count))
 }
+
+/// Query parameters are bound under upper-cased `@NAME` keys, so a reference to
+/// `@name` has to be normalized; other variables are looked up as written.
+fn lookup_key(name: &str) -> Cow<'_, str> {
+    if name.starts_with('@') && !name.starts_with("@@") {
+        Cow::Owned(name.to_uppercase())
+    } else {
+        Cow::Borrowed(name)
+    }
+}
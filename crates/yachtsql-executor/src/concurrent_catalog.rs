@@ -1,19 +1,52 @@
 #![coverage(off)]
 
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 
 use dashmap::DashMap;
 use parking_lot::RwLock;
 use rustc_hash::{FxHashMap, FxHashSet};
+use serde::{Deserialize, Serialize};
 use yachtsql_common::error::{Error, Result};
-use yachtsql_ir::Expr;
+use yachtsql_ir::{Expr, FunctionArg, FunctionBody};
+use yachtsql_parser::SESSION_DATASET;
 use yachtsql_storage::{Schema, Table};
 
 use crate::catalog::{ColumnDefault, SchemaMetadata, UserFunction, UserProcedure, ViewDef};
+use crate::catalog_file::CatalogSnapshot;
+use crate::native_function::{NativeAggregateFunction, NativeScalarFunction, NativeTableFunction};
 use crate::plan::{AccessType, PhysicalPlan, TableAccessSet};
+use crate::table_provider::TableProvider;
 
 pub type TableHandle = Arc<RwLock<Table>>;
 
+pub(crate) fn is_session_key(key: &str) -> bool {
+    key.strip_prefix(SESSION_DATASET)
+        .is_some_and(|rest| rest.starts_with('.'))
+}
+
+fn session_key(key: &str) -> Option<String> {
+    (!key.contains('.')).then(|| format!("{}.{}", SESSION_DATASET, key))
+}
+
+fn fork_tables(tables: &DashMap<String, TableHandle>) -> DashMap<String, TableHandle> {
+    tables
+        .iter()
+        .map(|entry| {
+            let table = entry.value().read().clone();
+            (entry.key().clone(), Arc::new(RwLock::new(table)))
+        })
+        .collect()
+}
+
+fn lookup_keys(name: &str) -> Vec<String> {
+    let key = name.to_uppercase();
+    match session_key(&key) {
+        Some(session) => vec![session, key],
+        None => vec![key],
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct QualifiedName {
     pub project: Option<String>,
@@ -57,7 +90,7 @@ impl QualifiedName {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DroppedSchemaData {
     pub metadata: SchemaMetadata,
     pub tables: Vec<(String, Table)>,
@@ -91,6 +124,19 @@ impl TableLockSet {
         self.catalog = Some(catalog);
     }
 
+    /// Keys `name` may be locked under: its session and permanent spellings, and
+    /// the name the catalog resolves it to.
+    fn lookup_keys(&self, name: &str) -> Vec<String> {
+        let mut keys = lookup_keys(name);
+        if let Some(catalog) = &self.catalog {
+            let resolved = catalog.resolve_table_name(name);
+            if !keys.contains(&resolved) {
+                keys.push(resolved);
+            }
+        }
+        keys
+    }
+
     pub fn add_read_table(&self, name: String, table: Table) {
         match self.read_tables.lock() {
             Ok(mut guard) => {
@@ -116,14 +162,14 @@ impl TableLockSet {
     }
 
     pub fn get_table(&self, name: &str) -> Option<Table> {
-        let upper = name.to_uppercase();
+        let keys = self.lookup_keys(name);
         let write_guard = self.write_tables.lock().unwrap_or_else(|p| p.into_inner());
-        if let Some(table) = write_guard.get(&upper) {
+        if let Some(table) = keys.iter().find_map(|key| write_guard.get(key)) {
             return Some(table.clone());
         }
         drop(write_guard);
         let read_guard = self.read_tables.lock().unwrap_or_else(|p| p.into_inner());
-        if let Some(table) = read_guard.get(&upper) {
+        if let Some(table) = keys.iter().find_map(|key| read_guard.get(key)) {
             return Some(table.clone());
         }
         None
@@ -133,20 +179,34 @@ impl TableLockSet {
     where
         F: FnOnce(&mut Table) -> R,
     {
-        let upper = name.to_uppercase();
+        let keys = self.lookup_keys(name);
         let Ok(mut write_tables) = self.write_tables.lock() else {
             return None;
         };
-        write_tables.get_mut(&upper).map(f)
+        let key = keys.iter().find(|key| write_tables.contains_key(*key))?;
+        write_tables.get_mut(key).map(f)
     }
 
     pub fn update_table(&self, name: &str, table: Table) {
-        let upper = name.to_uppercase();
+        let keys = self.lookup_keys(name);
         if let Ok(mut guard) = self.write_tables.lock() {
-            guard.insert(upper, table);
+            let key = keys
+                .iter()
+                .find(|key| guard.contains_key(*key))
+                .cloned()
+                .unwrap_or_else(|| name.to_uppercase());
+            guard.insert(key, table);
         }
     }
 
+    /// Whether `name` is locked for writing, so its changes are only published
+    /// by [`commit_writes`](Self::commit_writes).
+    pub(crate) fn holds_write(&self, name: &str) -> bool {
+        let keys = self.lookup_keys(name);
+        let guard = self.write_tables.lock().unwrap_or_else(|p| p.into_inner());
+        keys.iter().any(|key| guard.contains_key(key))
+    }
+
     pub fn snapshot_write_locked_tables(&self) -> FxHashMap<String, Table> {
         self.write_tables
             .lock()
@@ -179,39 +239,230 @@ pub struct TransactionSnapshot {
 
 #[derive(Debug)]
 pub struct ConcurrentCatalog {
-    tables: DashMap<String, TableHandle>,
-    table_defaults: DashMap<String, Vec<ColumnDefault>>,
-    functions: DashMap<String, UserFunction>,
-    procedures: DashMap<String, UserProcedure>,
-    procedure_bodies: DashMap<String, Vec<PhysicalPlan>>,
-    views: DashMap<String, ViewDef>,
-    schemas: DashMap<String, ()>,
-    schema_metadata: DashMap<String, SchemaMetadata>,
+    tables: Arc<DashMap<String, TableHandle>>,
+    providers: Arc<DashMap<String, Arc<dyn TableProvider>>>,
+    table_defaults: Arc<DashMap<String, Vec<ColumnDefault>>>,
+    functions: Arc<DashMap<String, UserFunction>>,
+    procedures: Arc<DashMap<String, UserProcedure>>,
+    procedure_bodies: Arc<DashMap<String, Vec<PhysicalPlan>>>,
+    views: Arc<DashMap<String, ViewDef>>,
+    schemas: Arc<DashMap<String, ()>>,
+    schema_metadata: Arc<DashMap<String, SchemaMetadata>>,
+    dropped_schemas: Arc<DashMap<String, DroppedSchemaData>>,
+    projects: Arc<DashMap<String, FxHashSet<String>>>,
+    dataset_tables: Arc<DashMap<String, FxHashSet<String>>>,
+    schema_version: Arc<AtomicU64>,
+    temp_tables: DashMap<String, TableHandle>,
+    temp_table_defaults: DashMap<String, Vec<ColumnDefault>>,
+    temp_functions: DashMap<String, UserFunction>,
+    native_functions: DashMap<String, NativeScalarFunction>,
+    native_aggregate_functions: DashMap<String, NativeAggregateFunction>,
+    native_table_functions: DashMap<String, NativeTableFunction>,
     search_path: RwLock<Vec<String>>,
-    dropped_schemas: DashMap<String, DroppedSchemaData>,
     transaction_snapshot: RwLock<Option<TransactionSnapshot>>,
     default_project: RwLock<Option<String>>,
-    projects: DashMap<String, FxHashSet<String>>,
-    dataset_tables: DashMap<String, FxHashSet<String>>,
+    session_scoped: bool,
 }
 
 impl ConcurrentCatalog {
     pub fn new() -> Self {
         Self {
-            tables: DashMap::new(),
-            table_defaults: DashMap::new(),
-            functions: DashMap::new(),
-            procedures: DashMap::new(),
-            procedure_bodies: DashMap::new(),
-            views: DashMap::new(),
-            schemas: DashMap::new(),
-            schema_metadata: DashMap::new(),
+            tables: Arc::new(DashMap::new()),
+            providers: Arc::new(DashMap::new()),
+            table_defaults: Arc::new(DashMap::new()),
+            functions: Arc::new(DashMap::new()),
+            procedures: Arc::new(DashMap::new()),
+            procedure_bodies: Arc::new(DashMap::new()),
+            views: Arc::new(DashMap::new()),
+            schemas: Arc::new(DashMap::new()),
+            schema_metadata: Arc::new(DashMap::new()),
+            dropped_schemas: Arc::new(DashMap::new()),
+            projects: Arc::new(DashMap::new()),
+            dataset_tables: Arc::new(DashMap::new()),
+            schema_version: Arc::new(AtomicU64::new(0)),
+            temp_tables: DashMap::new(),
+            temp_table_defaults: DashMap::new(),
+            temp_functions: DashMap::new(),
+            native_functions: DashMap::new(),
+            native_aggregate_functions: DashMap::new(),
+            native_table_functions: DashMap::new(),
             search_path: RwLock::new(Vec::new()),
-            dropped_schemas: DashMap::new(),
             transaction_snapshot: RwLock::new(None),
             default_project: RwLock::new(None),
-            projects: DashMap::new(),
-            dataset_tables: DashMap::new(),
+            session_scoped: false,
+        }
+    }
+
+    /// Returns a catalog that shares this catalog's permanent objects.
+    ///
+    /// Tables, views, functions, procedures and schemas are shared with `self`, so
+    /// DDL and DML through either catalog is visible to both. Temporary tables and
+    /// functions, the search path, the default project and the open transaction
+    /// belong to the new catalog only and are discarded when it is dropped.
+    pub fn session_scope(&self) -> Self {
+        Self {
+            tables: Arc::clone(&self.tables),
+            providers: Arc::clone(&self.providers),
+            table_defaults: Arc::clone(&self.table_defaults),
+            functions: Arc::clone(&self.functions),
+            procedures: Arc::clone(&self.procedures),
+            procedure_bodies: Arc::clone(&self.procedure_bodies),
+            views: Arc::clone(&self.views),
+            schemas: Arc::clone(&self.schemas),
+            schema_metadata: Arc::clone(&self.schema_metadata),
+            dropped_schemas: Arc::clone(&self.dropped_schemas),
+            projects: Arc::clone(&self.projects),
+            dataset_tables: Arc::clone(&self.dataset_tables),
+            schema_version: Arc::clone(&self.schema_version),
+            temp_tables: DashMap::new(),
+            temp_table_defaults: DashMap::new(),
+            temp_functions: DashMap::new(),
+            native_functions: DashMap::new(),
+            native_aggregate_functions: DashMap::new(),
+            native_table_functions: DashMap::new(),
+            search_path: RwLock::new(Vec::new()),
+            transaction_snapshot: RwLock::new(None),
+            default_project: RwLock::new(self.get_default_project()),
+            session_scoped: true,
+        }
+    }
+
+    /// Whether this catalog was created by [`session_scope`](Self::session_scope),
+    /// so that its temporary tables live in the `_SESSION` dataset.
+    pub fn is_session_scoped(&self) -> bool {
+        self.session_scoped
+    }
+
+    /// Returns an independent copy of this catalog.
+    ///
+    /// The fork starts with the same tables, views, functions, procedures and
+    /// schema metadata (including temporary objects), but changes made through
+    /// either catalog are never visible to the other. Table data is copied
+    /// lazily: columns stay shared until one side modifies them.
+    pub fn fork(&self) -> Self {
+        Self {
+            tables: Arc::new(fork_tables(&self.tables)),
+            providers: Arc::new((*self.providers).clone()),
+            table_defaults: Arc::new((*self.table_defaults).clone()),
+            functions: Arc::new((*self.functions).clone()),
+            procedures: Arc::new((*self.procedures).clone()),
+            procedure_bodies: Arc::new((*self.procedure_bodies).clone()),
+            views: Arc::new((*self.views).clone()),
+            schemas: Arc::new((*self.schemas).clone()),
+            schema_metadata: Arc::new((*self.schema_metadata).clone()),
+            dropped_schemas: Arc::new((*self.dropped_schemas).clone()),
+            projects: Arc::new((*self.projects).clone()),
+            dataset_tables: Arc::new((*self.dataset_tables).clone()),
+            schema_version: Arc::new(AtomicU64::new(0)),
+            temp_tables: fork_tables(&self.temp_tables),
+            temp_table_defaults: self.temp_table_defaults.clone(),
+            temp_functions: self.temp_functions.clone(),
+            native_functions: self.native_functions.clone(),
+            native_aggregate_functions: self.native_aggregate_functions.clone(),
+            native_table_functions: self.native_table_functions.clone(),
+            search_path: RwLock::new(self.get_search_path()),
+            transaction_snapshot: RwLock::new(None),
+            default_project: RwLock::new(self.get_default_project()),
+            session_scoped: self.session_scoped,
+        }
+    }
+
+    pub(crate) fn to_snapshot(&self) -> CatalogSnapshot {
+        fn entries<V: Clone>(map: &DashMap<String, V>) -> Vec<(String, V)> {
+            map.iter()
+                .map(|entry| (entry.key().clone(), entry.value().clone()))
+                .collect()
+        }
+        fn index_entries(map: &DashMap<String, FxHashSet<String>>) -> Vec<(String, Vec<String>)> {
+            map.iter()
+                .map(|entry| {
+                    let mut values: Vec<String> = entry.value().iter().cloned().collect();
+                    values.sort();
+                    (entry.key().clone(), values)
+                })
+                .collect()
+        }
+
+        CatalogSnapshot {
+            tables: self
+                .tables
+                .iter()
+                .map(|entry| (entry.key().clone(), entry.value().read().clone()))
+                .collect(),
+            table_defaults: entries(&self.table_defaults),
+            functions: entries(&self.functions),
+            procedures: entries(&self.procedures),
+            procedure_bodies: entries(&self.procedure_bodies),
+            views: entries(&self.views),
+            schemas: self
+                .schemas
+                .iter()
+                .map(|entry| entry.key().clone())
+                .collect(),
+            schema_metadata: entries(&self.schema_metadata),
+            dropped_schemas: entries(&self.dropped_schemas),
+            projects: index_entries(&self.projects),
+            dataset_tables: index_entries(&self.dataset_tables),
+            search_path: self.get_search_path(),
+            default_project: self.get_default_project(),
+        }
+    }
+
+    pub(crate) fn from_snapshot(snapshot: CatalogSnapshot) -> Self {
+        fn fill<V>(map: &DashMap<String, V>, entries: Vec<(String, V)>) {
+            for (key, value) in entries {
+                map.insert(key, value);
+            }
+        }
+
+        let catalog = Self::new();
+        for (key, table) in snapshot.tables {
+            catalog.tables.insert(key, Arc::new(RwLock::new(table)));
+        }
+        fill(&catalog.table_defaults, snapshot.table_defaults);
+        fill(&catalog.functions, snapshot.functions);
+        fill(&catalog.procedures, snapshot.procedures);
+        fill(&catalog.procedure_bodies, snapshot.procedure_bodies);
+        fill(&catalog.views, snapshot.views);
+        for name in snapshot.schemas {
+            catalog.schemas.insert(name, ());
+        }
+        fill(&catalog.schema_metadata, snapshot.schema_metadata);
+        fill(&catalog.dropped_schemas, snapshot.dropped_schemas);
+        for (key, datasets) in snapshot.projects {
+            catalog.projects.insert(key, datasets.into_iter().collect());
+        }
+        for (key, tables) in snapshot.dataset_tables {
+            catalog
+                .dataset_tables
+                .insert(key, tables.into_iter().collect());
+        }
+        *catalog.search_path.write() = snapshot.search_path;
+        *catalog.default_project.write() = snapshot.default_project;
+        catalog
+    }
+
+    pub(crate) fn schema_version(&self) -> u64 {
+        self.schema_version.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn bump_schema_version(&self) -> u64 {
+        self.schema_version.fetch_add(1, Ordering::AcqRel) + 1
+    }
+
+    fn tables_for(&self, key: &str) -> &DashMap<String, TableHandle> {
+        if is_session_key(key) {
+            &self.temp_tables
+        } else {
+            &self.tables
+        }
+    }
+
+    fn defaults_for(&self, key: &str) -> &DashMap<String, Vec<ColumnDefault>> {
+        if is_session_key(key) {
+            &self.temp_table_defaults
+        } else {
+            &self.table_defaults
         }
     }
 
@@ -224,6 +475,9 @@ impl ConcurrentCatalog {
     }
 
     fn add_to_indexes(&self, key: &str) {
+        if is_session_key(key) {
+            return;
+        }
         let qn = QualifiedName::parse(key);
         let project = qn
             .project
@@ -242,7 +496,37 @@ impl ConcurrentCatalog {
         }
     }
 
+    fn add_schema_to_indexes(&self, key: &str) {
+        if let Some((project, dataset)) = self.schema_index_key(key) {
+            self.projects.entry(project).or_default().insert(dataset);
+        }
+    }
+
+    fn remove_schema_from_indexes(&self, key: &str) {
+        if let Some((project, dataset)) = self.schema_index_key(key) {
+            if let Some(mut datasets) = self.projects.get_mut(&project) {
+                datasets.remove(&dataset);
+            }
+            self.dataset_tables
+                .remove(&format!("{}.{}", project, dataset));
+        }
+    }
+
+    fn schema_index_key(&self, key: &str) -> Option<(String, String)> {
+        match key.split_once('.') {
+            Some((project, dataset)) => Some((project.to_string(), dataset.to_string())),
+            None => self
+                .default_project
+                .read()
+                .clone()
+                .map(|project| (project, key.to_string())),
+        }
+    }
+
     fn remove_from_indexes(&self, key: &str) {
+        if is_session_key(key) {
+            return;
+        }
         let qn = QualifiedName::parse(key);
         let project = qn
             .project
@@ -256,23 +540,11 @@ impl ConcurrentCatalog {
         }
     }
 
+    /// Opens a transaction. Each table is snapshotted when the transaction first
+    /// writes it, so a rollback leaves other sessions' writes to the rest alone.
     pub fn begin_transaction(&self) -> Result<()> {
-        let mut tables_snapshot = FxHashMap::default();
-        for entry in self.tables.iter() {
-            match entry.value().try_read() {
-                Some(table) => {
-                    tables_snapshot.insert(entry.key().clone(), table.clone());
-                }
-                None => {
-                    return Err(Error::InvalidQuery(format!(
-                        "Cannot begin transaction: table '{}' is currently locked by another operation",
-                        entry.key()
-                    )));
-                }
-            }
-        }
         *self.transaction_snapshot.write() = Some(TransactionSnapshot {
-            tables: tables_snapshot,
+            tables: FxHashMap::default(),
         });
         Ok(())
     }
@@ -280,8 +552,8 @@ impl ConcurrentCatalog {
     pub fn begin_transaction_with_tables(&self, table_names: &[String]) -> Result<()> {
         let mut tables_snapshot = FxHashMap::default();
         for name in table_names {
-            let key = name.to_uppercase();
-            if let Some(handle) = self.tables.get(&key) {
+            let key = self.resolve_table_name(name);
+            if let Some(handle) = self.tables_for(&key).get(&key) {
                 match handle.try_read() {
                     Some(table) => {
                         tables_snapshot.insert(key, table.clone());
@@ -301,14 +573,19 @@ impl ConcurrentCatalog {
         Ok(())
     }
 
+    /// Records `table_data` as the state `name` is restored to on rollback,
+    /// unless the open transaction has already written `name`. Does nothing
+    /// outside a transaction.
     pub fn snapshot_table(&self, name: &str, table_data: Table) {
-        let mut snapshot_guard = self.transaction_snapshot.write();
-        if let Some(ref mut snapshot) = *snapshot_guard {
-            snapshot.tables.insert(name.to_uppercase(), table_data);
-        } else {
-            let mut tables = FxHashMap::default();
-            tables.insert(name.to_uppercase(), table_data);
-            *snapshot_guard = Some(TransactionSnapshot { tables });
+        self.snapshot_first_write(&self.resolve_table_name(name), || table_data);
+    }
+
+    fn snapshot_first_write(&self, key: &str, table: impl FnOnce() -> Table) {
+        let mut snapshot = self.transaction_snapshot.write();
+        if let Some(snapshot) = snapshot.as_mut()
+            && !snapshot.tables.contains_key(key)
+        {
+            snapshot.tables.insert(key.to_string(), table());
         }
     }
 
@@ -316,41 +593,127 @@ impl ConcurrentCatalog {
         *self.transaction_snapshot.write() = None;
     }
 
-    pub fn rollback(&self) {
-        let snapshot = self.transaction_snapshot.write().take();
-        if let Some(snapshot) = snapshot {
-            for (name, table_data) in snapshot.tables {
-                if let Some(handle) = self.tables.get(&name)
-                    && let Some(mut table) = handle.try_write()
-                {
-                    *table = table_data;
+    pub fn rollback(&self) -> Result<()> {
+        self.rollback_tables(|_| false).map(|_| ())
+    }
+
+    /// Ends the open transaction and restores the tables it wrote, except those
+    /// `held` selects, which are returned for the caller to restore. If another
+    /// operation holds one of the tables, nothing is restored and the
+    /// transaction stays open.
+    pub(crate) fn rollback_tables(
+        &self,
+        held: impl Fn(&str) -> bool,
+    ) -> Result<Vec<(String, Table)>> {
+        let mut snapshot_guard = self.transaction_snapshot.write();
+        let Some(snapshot) = snapshot_guard.take() else {
+            return Ok(Vec::new());
+        };
+        let handles: Vec<(String, TableHandle)> = snapshot
+            .tables
+            .keys()
+            .filter(|name| !held(name))
+            .filter_map(|name| Some((name.clone(), self.tables_for(name).get(name)?.clone())))
+            .collect();
+        let mut guards = Vec::with_capacity(handles.len());
+        for (name, handle) in &handles {
+            match handle.try_write() {
+                Some(guard) => guards.push((name, guard)),
+                None => {
+                    drop(guards);
+                    *snapshot_guard = Some(snapshot);
+                    return Err(Error::InvalidQuery(format!(
+                        "Cannot roll back transaction: table '{}' is currently locked by another operation",
+                        name
+                    )));
                 }
             }
         }
+        let mut tables = snapshot.tables;
+        for (name, mut table) in guards {
+            if let Some(table_data) = tables.remove(name) {
+                *table = table_data;
+            }
+        }
+        Ok(tables.into_iter().filter(|(name, _)| held(name)).collect())
+    }
+
+    pub(crate) fn in_transaction(&self) -> bool {
+        self.transaction_snapshot.read().is_some()
+    }
+
+    /// The rows `names` held when the open transaction began.
+    pub(crate) fn transaction_tables<'a>(
+        &self,
+        names: impl IntoIterator<Item = &'a String>,
+    ) -> Vec<(String, Table)> {
+        let snapshot = self.transaction_snapshot.read();
+        let Some(snapshot) = snapshot.as_ref() else {
+            return Vec::new();
+        };
+        names
+            .into_iter()
+            .filter_map(|name| Some((name.clone(), snapshot.tables.get(name)?.clone())))
+            .collect()
     }
 
     pub fn take_transaction_snapshot(&self) -> Option<TransactionSnapshot> {
         self.transaction_snapshot.write().take()
     }
 
-    fn resolve_table_name(&self, name: &str) -> String {
+    pub(crate) fn resolve_table_name(&self, name: &str) -> String {
         let key = name.to_uppercase();
-        if key.contains('.') || self.tables.contains_key(&key) {
+        if let Some(session) = session_key(&key)
+            && self.temp_tables.contains_key(&session)
+        {
+            return session;
+        }
+        if let Some(position) = key.find(".INFORMATION_SCHEMA.")
+            && !self.tables.contains_key(&key)
+            && self.providers.contains_key(&key[position + 1..])
+        {
+            return key[position + 1..].to_string();
+        }
+        if self.tables.contains_key(&key) || self.providers.contains_key(&key) {
             return key;
         }
+        if key.contains('.') {
+            return match self.default_project_alias(&key) {
+                Some(alias)
+                    if self.tables.contains_key(&alias) || self.providers.contains_key(&alias) =>
+                {
+                    alias
+                }
+                _ => key,
+            };
+        }
         let search_path = self.search_path.read();
         for schema in search_path.iter() {
             let qualified = format!("{}.{}", schema, key);
-            if self.tables.contains_key(&qualified) {
+            if self.tables.contains_key(&qualified) || self.providers.contains_key(&qualified) {
                 return qualified;
             }
         }
         key
     }
 
+    /// The other spelling of `key` under the default project: `DATASET.TABLE` for
+    /// `PROJECT.DATASET.TABLE` and the reverse.
+    fn default_project_alias(&self, key: &str) -> Option<String> {
+        let project = self.default_project.read().clone()?;
+        match key.split('.').count() {
+            2 => Some(format!("{}.{}", project, key)),
+            3 => key
+                .strip_prefix(&project)
+                .and_then(|rest| rest.strip_prefix('.'))
+                .map(str::to_string),
+            _ => None,
+        }
+    }
+
     pub fn get_table_handle(&self, name: &str) -> Option<TableHandle> {
         let key = self.resolve_table_name(name);
-        self.tables.get(&key).map(|r| r.clone())
+        self.tables_for(&key).get(&key).map(|r| r.clone())
     }
 
     pub fn acquire_table_locks(&self, accesses: &TableAccessSet) -> Result<TableLockSet> {
@@ -358,7 +721,7 @@ impl ConcurrentCatalog {
 
         for (table_name, access_type) in &accesses.accesses {
             let resolved = self.resolve_table_name(table_name);
-            let handle_opt = self.tables.get(&resolved);
+            let handle_opt = self.tables_for(&resolved).get(&resolved);
 
             match access_type {
                 AccessType::WriteOptional => {
@@ -369,6 +732,9 @@ impl ConcurrentCatalog {
                     }
                 }
                 AccessType::Read => {
+                    if handle_opt.is_none() && self.providers.contains_key(&resolved) {
+                        continue;
+                    }
                     let handle = handle_opt
                         .ok_or_else(|| Error::TableNotFound(table_name.clone()))?
                         .clone();
@@ -376,6 +742,12 @@ impl ConcurrentCatalog {
                     locks.add_read_table(resolved, table);
                 }
                 AccessType::Write => {
+                    if handle_opt.is_none() && self.providers.contains_key(&resolved) {
+                        return Err(Error::invalid_query(format!(
+                            "Table {} is served by a table provider and is read-only",
+                            table_name
+                        )));
+                    }
                     let handle = handle_opt
                         .ok_or_else(|| Error::TableNotFound(table_name.clone()))?
                         .clone();
@@ -401,6 +773,7 @@ impl ConcurrentCatalog {
         }
         self.dropped_schemas.remove(&key);
         self.schemas.insert(key.clone(), ());
+        self.add_schema_to_indexes(&key);
         self.schema_metadata.insert(key, SchemaMetadata::default());
         Ok(())
     }
@@ -423,6 +796,7 @@ impl ConcurrentCatalog {
         }
         self.dropped_schemas.remove(&key);
         self.schemas.insert(key.clone(), ());
+        self.add_schema_to_indexes(&key);
         self.schema_metadata.insert(key, SchemaMetadata { options });
         Ok(())
     }
@@ -464,6 +838,7 @@ impl ConcurrentCatalog {
         }
 
         self.schemas.remove(&key);
+        self.remove_schema_from_indexes(&key);
         let metadata = self
             .schema_metadata
             .remove(&key)
@@ -500,8 +875,10 @@ impl ConcurrentCatalog {
         match dropped {
             Some((_, dropped_data)) => {
                 self.schemas.insert(key.clone(), ());
+                self.add_schema_to_indexes(&key);
                 self.schema_metadata.insert(key, dropped_data.metadata);
                 for (table_key, table) in dropped_data.tables {
+                    self.add_to_indexes(&table_key);
                     self.tables.insert(table_key, Arc::new(RwLock::new(table)));
                 }
                 for (table_key, defaults) in dropped_data.table_defaults {
@@ -559,27 +936,33 @@ impl ConcurrentCatalog {
 
     pub fn create_table(&self, name: &str, schema: Schema) -> Result<()> {
         let key = name.to_uppercase();
-        if self.tables.contains_key(&key) {
+        let tables = self.tables_for(&key);
+        if tables.contains_key(&key) {
+            return Err(Error::invalid_query(format!(
+                "Table already exists: {}",
+                name
+            )));
+        }
+        if self.providers.contains_key(&key) {
             return Err(Error::invalid_query(format!(
                 "Table already exists: {}",
                 name
             )));
         }
         let table = Table::new(schema);
-        self.tables
-            .insert(key.clone(), Arc::new(RwLock::new(table)));
+        tables.insert(key.clone(), Arc::new(RwLock::new(table)));
         self.add_to_indexes(&key);
         Ok(())
     }
 
     pub fn set_table_defaults(&self, name: &str, defaults: Vec<ColumnDefault>) {
-        let key = name.to_uppercase();
-        self.table_defaults.insert(key, defaults);
+        let key = self.resolve_table_name(name);
+        self.defaults_for(&key).insert(key, defaults);
     }
 
     pub fn get_table_defaults(&self, name: &str) -> Option<Vec<ColumnDefault>> {
         let key = self.resolve_table_name(name);
-        self.table_defaults.get(&key).map(|r| r.clone())
+        self.defaults_for(&key).get(&key).map(|r| r.clone())
     }
 
     pub fn get_column_default(&self, table_name: &str, column_name: &str) -> Option<Expr> {
@@ -594,68 +977,131 @@ impl ConcurrentCatalog {
 
     pub fn insert_table(&self, name: &str, table: Table) -> Result<()> {
         let key = name.to_uppercase();
-        if self.tables.contains_key(&key) {
+        let tables = self.tables_for(&key);
+        if tables.contains_key(&key) || self.providers.contains_key(&key) {
             return Err(Error::invalid_query(format!(
                 "Table already exists: {}",
                 name
             )));
         }
-        self.tables
-            .insert(key.clone(), Arc::new(RwLock::new(table)));
+        tables.insert(key.clone(), Arc::new(RwLock::new(table)));
         self.add_to_indexes(&key);
         Ok(())
     }
 
     pub fn drop_table(&self, name: &str) -> Result<()> {
         let key = self.resolve_table_name(name);
-        if self.tables.remove(&key).is_none() {
+        if self.providers.remove(&key).is_some() {
+            self.remove_from_indexes(&key);
+            self.bump_schema_version();
+            return Ok(());
+        }
+        if self.tables_for(&key).remove(&key).is_none() {
             return Err(Error::TableNotFound(name.to_string()));
         }
+        self.defaults_for(&key).remove(&key);
         self.remove_from_indexes(&key);
         Ok(())
     }
 
     pub fn table_exists(&self, name: &str) -> bool {
         let key = self.resolve_table_name(name);
-        self.tables.contains_key(&key)
+        self.tables_for(&key).contains_key(&key) || self.providers.contains_key(&key)
+    }
+
+    /// Registers `provider` as a read-only table named `name` (for example
+    /// `project.dataset.table`). Queries scan the provider each time they
+    /// reference the table; see [`TableProvider`].
+    ///
+    /// Providers are shared by every session scope of this catalog and are not
+    /// included in saved catalogs or durable checkpoints.
+    pub fn register_table_provider(
+        &self,
+        name: &str,
+        provider: Arc<dyn TableProvider>,
+    ) -> Result<()> {
+        let key = name.to_uppercase();
+        if is_session_key(&key) {
+            return Err(Error::invalid_query(format!(
+                "Table providers cannot be registered as temp tables: {}",
+                name
+            )));
+        }
+        if self.tables.contains_key(&key) || self.providers.contains_key(&key) {
+            return Err(Error::invalid_query(format!(
+                "Table already exists: {}",
+                name
+            )));
+        }
+        self.providers.insert(key.clone(), provider);
+        self.add_to_indexes(&key);
+        self.bump_schema_version();
+        Ok(())
+    }
+
+    /// Registers `provider` as a built-in table such as `INFORMATION_SCHEMA.JOBS`.
+    /// Unlike [`register_table_provider`](Self::register_table_provider), the
+    /// table is not listed in its dataset and replaces any earlier registration.
+    pub(crate) fn register_system_table(&self, name: &str, provider: Arc<dyn TableProvider>) {
+        self.providers.insert(name.to_uppercase(), provider);
+    }
+
+    /// Removes a table provider registered under `name`, returning it.
+    pub fn deregister_table_provider(&self, name: &str) -> Option<Arc<dyn TableProvider>> {
+        let key = self.resolve_table_name(name);
+        let (_, provider) = self.providers.remove(&key)?;
+        self.remove_from_indexes(&key);
+        self.bump_schema_version();
+        Some(provider)
+    }
+
+    pub fn get_table_provider(&self, name: &str) -> Option<Arc<dyn TableProvider>> {
+        let key = self.resolve_table_name(name);
+        self.providers.get(&key).map(|r| Arc::clone(r.value()))
     }
 
     pub fn rename_table(&self, old_name: &str, new_name: &str) -> Result<()> {
         let old_key = self.resolve_table_name(old_name);
-        let new_key = new_name.to_uppercase();
+        let mut new_key = new_name.to_uppercase();
+        if is_session_key(&old_key)
+            && let Some(session) = session_key(&new_key)
+        {
+            new_key = session;
+        }
 
-        if !self.tables.contains_key(&old_key) {
+        if !self.tables_for(&old_key).contains_key(&old_key) {
             return Err(Error::TableNotFound(old_name.to_string()));
         }
-        if self.tables.contains_key(&new_key) {
+        if self.tables_for(&new_key).contains_key(&new_key) {
             return Err(Error::invalid_query(format!(
                 "Table already exists: {}",
                 new_name
             )));
         }
 
-        if let Some((_, handle)) = self.tables.remove(&old_key) {
+        if let Some((_, handle)) = self.tables_for(&old_key).remove(&old_key) {
             self.remove_from_indexes(&old_key);
-            self.tables.insert(new_key.clone(), handle);
+            self.tables_for(&new_key).insert(new_key.clone(), handle);
             self.add_to_indexes(&new_key);
         }
         Ok(())
     }
 
     pub fn replace_table(&self, name: &str, table: Table) -> Result<()> {
-        let key = name.to_uppercase();
-        if !self.tables.contains_key(&key) {
+        let key = self.resolve_table_name(name);
+        let tables = self.tables_for(&key);
+        if !tables.contains_key(&key) {
             return Err(Error::TableNotFound(name.to_string()));
         }
-        self.tables.insert(key, Arc::new(RwLock::new(table)));
+        tables.insert(key, Arc::new(RwLock::new(table)));
         Ok(())
     }
 
     pub fn create_or_replace_table(&self, name: &str, table: Table) {
         let key = name.to_uppercase();
-        let is_new = !self.tables.contains_key(&key);
-        self.tables
-            .insert(key.clone(), Arc::new(RwLock::new(table)));
+        let tables = self.tables_for(&key);
+        let is_new = !tables.contains_key(&key) && self.providers.remove(&key).is_none();
+        tables.insert(key.clone(), Arc::new(RwLock::new(table)));
         if is_new {
             self.add_to_indexes(&key);
         }
@@ -663,7 +1109,8 @@ impl ConcurrentCatalog {
 
     pub fn update_table(&self, name: &str, table: Table) {
         let key = name.to_uppercase();
-        if let Some(handle) = self.tables.get(&key) {
+        if let Some(handle) = self.tables_for(&key).get(&key) {
+            self.snapshot_first_write(&key, || handle.read().clone());
             *handle.write() = table;
         }
     }
@@ -687,21 +1134,49 @@ impl ConcurrentCatalog {
             .unwrap_or_default()
     }
 
+    /// Names of the catalog's tables, temporary tables and table providers, sorted.
+    pub fn get_table_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .tables
+            .iter()
+            .chain(self.temp_tables.iter())
+            .map(|e| e.key().clone())
+            .chain(self.providers.iter().map(|e| e.key().clone()))
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
     pub fn create_function(&self, func: UserFunction, or_replace: bool) -> Result<()> {
         let key = func.name.to_uppercase();
-        if self.functions.contains_key(&key) && !or_replace {
+        let functions = if func.is_temporary {
+            &self.temp_functions
+        } else {
+            &self.functions
+        };
+        if self.native_functions.contains_key(&key)
+            || self.native_aggregate_functions.contains_key(&key)
+            || self.native_table_functions.contains_key(&key)
+            || (functions.contains_key(&key) && !or_replace)
+        {
             return Err(Error::invalid_query(format!(
                 "Function already exists: {}",
                 func.name
             )));
         }
-        self.functions.insert(key, func);
+        functions.insert(key, func);
         Ok(())
     }
 
     pub fn drop_function(&self, name: &str) -> Result<()> {
         let key = name.to_uppercase();
-        if self.functions.remove(&key).is_none() {
+        if self.temp_functions.remove(&key).is_none()
+            && self.functions.remove(&key).is_none()
+            && self.deregister_scalar_function(&key).is_none()
+            && self.deregister_aggregate_function(&key).is_none()
+            && self.deregister_table_function(&key).is_none()
+        {
             return Err(Error::invalid_query(format!(
                 "Function not found: {}",
                 name
@@ -710,12 +1185,175 @@ impl ConcurrentCatalog {
         Ok(())
     }
 
+    /// Registers a scalar function implemented in Rust under `name`, callable from
+    /// any query in this session; see [`NativeScalarFunction`].
+    ///
+    /// Native functions belong to this catalog only: they are not shared with
+    /// other session scopes, although a fork keeps them. They are never saved.
+    pub fn register_scalar_function(
+        &self,
+        name: &str,
+        function: NativeScalarFunction,
+    ) -> Result<()> {
+        let key = name.to_uppercase();
+        if self.function_exists(&key) {
+            return Err(Error::invalid_query(format!(
+                "Function already exists: {}",
+                name
+            )));
+        }
+        self.native_functions.insert(key, function);
+        self.bump_schema_version();
+        Ok(())
+    }
+
+    /// Removes the native scalar function registered under `name`, returning it.
+    pub fn deregister_scalar_function(&self, name: &str) -> Option<NativeScalarFunction> {
+        let (_, function) = self.native_functions.remove(&name.to_uppercase())?;
+        self.bump_schema_version();
+        Some(function)
+    }
+
+    pub(crate) fn native_functions(&self) -> Vec<(String, NativeScalarFunction)> {
+        self.native_functions
+            .iter()
+            .map(|r| (r.key().clone(), r.value().clone()))
+            .collect()
+    }
+
+    /// Registers an aggregate function implemented in Rust under `name`; see
+    /// [`NativeAggregateFunction`]. Like native scalar functions, it belongs to
+    /// this catalog only.
+    pub fn register_aggregate_function(
+        &self,
+        name: &str,
+        function: NativeAggregateFunction,
+    ) -> Result<()> {
+        let key = name.to_uppercase();
+        if self.function_exists(&key) {
+            return Err(Error::invalid_query(format!(
+                "Function already exists: {}",
+                name
+            )));
+        }
+        self.native_aggregate_functions.insert(key, function);
+        self.bump_schema_version();
+        Ok(())
+    }
+
+    /// Removes the native aggregate function registered under `name`, returning it.
+    pub fn deregister_aggregate_function(&self, name: &str) -> Option<NativeAggregateFunction> {
+        let (_, function) = self
+            .native_aggregate_functions
+            .remove(&name.to_uppercase())?;
+        self.bump_schema_version();
+        Some(function)
+    }
+
+    pub(crate) fn native_aggregate_functions(&self) -> Vec<(String, NativeAggregateFunction)> {
+        self.native_aggregate_functions
+            .iter()
+            .map(|r| (r.key().clone(), r.value().clone()))
+            .collect()
+    }
+
+    /// Registers a table-valued function implemented in Rust under `name`; see
+    /// [`NativeTableFunction`]. Like other native functions, it belongs to this
+    /// catalog only.
+    pub fn register_table_function(&self, name: &str, function: NativeTableFunction) -> Result<()> {
+        let key = name.to_uppercase();
+        if self.function_exists(&key) {
+            return Err(Error::invalid_query(format!(
+                "Function already exists: {}",
+                name
+            )));
+        }
+        self.native_table_functions.insert(key, function);
+        self.bump_schema_version();
+        Ok(())
+    }
+
+    /// Removes the native table function registered under `name`, returning it.
+    pub fn deregister_table_function(&self, name: &str) -> Option<NativeTableFunction> {
+        let (_, function) = self.native_table_functions.remove(&name.to_uppercase())?;
+        self.bump_schema_version();
+        Some(function)
+    }
+
+    pub(crate) fn native_table_functions(&self) -> Vec<(String, NativeTableFunction)> {
+        self.native_table_functions
+            .iter()
+            .map(|r| (r.key().clone(), r.value().clone()))
+            .collect()
+    }
+
+    /// The definition the planner sees for `name`, covering SQL, script and
+    /// native functions.
+    pub(crate) fn function_definition(
+        &self,
+        name: &str,
+    ) -> Option<yachtsql_parser::FunctionDefinition> {
+        if let Some(f) = self.get_function(name) {
+            return Some(yachtsql_parser::FunctionDefinition {
+                is_table_function: f.is_table_function(),
+                name: f.name,
+                parameters: f.parameters,
+                return_type: f.return_type,
+                body: f.body,
+                is_aggregate: f.is_aggregate,
+            });
+        }
+        let key = name.to_uppercase();
+        let (signature, is_aggregate, is_table_function) =
+            if let Some(native) = self.native_functions.get(&key) {
+                (native.signature().clone(), false, false)
+            } else if let Some(native) = self.native_aggregate_functions.get(&key) {
+                (native.signature().clone(), true, false)
+            } else {
+                (
+                    self.native_table_functions.get(&key)?.signature().clone(),
+                    false,
+                    true,
+                )
+            };
+        Some(yachtsql_parser::FunctionDefinition {
+            name: key,
+            parameters: signature
+                .arguments
+                .iter()
+                .enumerate()
+                .map(|(i, data_type)| FunctionArg {
+                    name: format!("arg{}", i),
+                    data_type: data_type.clone(),
+                    default: None,
+                })
+                .collect(),
+            return_type: signature.return_type,
+            body: FunctionBody::Native,
+            is_aggregate,
+            is_table_function,
+        })
+    }
+
     pub fn get_function(&self, name: &str) -> Option<UserFunction> {
-        self.functions.get(&name.to_uppercase()).map(|r| r.clone())
+        let key = name.to_uppercase();
+        self.temp_functions
+            .get(&key)
+            .or_else(|| self.functions.get(&key))
+            .map(|r| r.clone())
+    }
+
+    pub(crate) fn is_temp_function(&self, name: &str) -> bool {
+        self.temp_functions.contains_key(&name.to_uppercase())
     }
 
     pub fn function_exists(&self, name: &str) -> bool {
-        self.functions.contains_key(&name.to_uppercase())
+        let key = name.to_uppercase();
+        self.temp_functions.contains_key(&key)
+            || self.functions.contains_key(&key)
+            || self.native_functions.contains_key(&key)
+            || self.native_aggregate_functions.contains_key(&key)
+            || self.native_table_functions.contains_key(&key)
     }
 
     pub fn create_procedure(
@@ -773,6 +1411,7 @@ impl ConcurrentCatalog {
     pub fn get_functions(&self) -> FxHashMap<String, UserFunction> {
         self.functions
             .iter()
+            .chain(self.temp_functions.iter())
             .map(|r| (r.key().clone(), r.value().clone()))
             .collect()
     }
@@ -824,14 +1463,16 @@ impl ConcurrentCatalog {
     }
 
     pub fn get_table_schema(&self, name: &str) -> Option<Schema> {
-        let handle = self.get_table_handle(name)?;
+        let Some(handle) = self.get_table_handle(name) else {
+            return self.get_table_provider(name).map(|p| p.schema());
+        };
         let table = handle.read();
         Some(table.schema().clone())
     }
 
     pub fn collect_table_stats(&self) -> FxHashMap<String, yachtsql_optimizer::TableStats> {
         let mut stats = FxHashMap::default();
-        for entry in self.tables.iter() {
+        for entry in self.tables.iter().chain(self.temp_tables.iter()) {
             let table_name = entry.key().clone();
             let handle = entry.value();
             let table = handle.read();
@@ -862,13 +1503,10 @@ impl yachtsql_parser::CatalogProvider for ConcurrentCatalog {
     }
 
     fn get_function(&self, name: &str) -> Option<yachtsql_parser::FunctionDefinition> {
-        self.get_function(name)
-            .map(|f| yachtsql_parser::FunctionDefinition {
-                name: f.name.clone(),
-                parameters: f.parameters.clone(),
-                return_type: f.return_type.clone(),
-                body: f.body.clone(),
-                is_aggregate: f.is_aggregate,
-            })
+        self.function_definition(name)
+    }
+
+    fn has_session_dataset(&self) -> bool {
+        self.session_scoped
     }
 }
@@ -25,6 +25,15 @@ impl ConcurrentSession {
         }
     }
 
+    /// Returns an independent copy of this session's variables and current schema.
+    pub fn fork(&self) -> Self {
+        Self {
+            variables: self.variables.clone(),
+            system_variables: RwLock::new(self.system_variables().clone()),
+            current_schema: RwLock::new(self.current_schema()),
+        }
+    }
+
     pub fn get_variable(&self, name: &str) -> Option<Value> {
         self.variables.get(&name.to_uppercase()).map(|v| v.clone())
     }
@@ -0,0 +1,83 @@
+#![coverage(off)]
+
+use yachtsql_common::result::ColumnInfo;
+use yachtsql_ir::LogicalPlan;
+
+use crate::concurrent_catalog::ConcurrentCatalog;
+use crate::job_history::statement_type;
+use crate::plan::{AccessType, TableAccessSet};
+use crate::plan_cache::plan_references;
+
+/// What planning a statement revealed, without running it; see
+/// [`AsyncQueryExecutor::dry_run`](crate::AsyncQueryExecutor::dry_run).
+#[derive(Debug, Clone)]
+pub struct DryRunResult {
+    /// `SELECT`, `INSERT`, `CREATE_TABLE`, ...
+    pub statement_type: String,
+    /// Columns of the result; empty for statements that return no rows.
+    pub schema: Vec<ColumnInfo>,
+    /// Tables the statement reads or writes, ordered by name.
+    pub referenced_tables: Vec<ReferencedTable>,
+    /// Estimated size of the referenced columns of those tables.
+    pub total_bytes_processed: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReferencedTable {
+    /// The table name as written in the statement.
+    pub name: String,
+    /// Columns of the table the statement refers to, in table order.
+    pub columns: Vec<String>,
+    /// Whether the statement modifies the table.
+    pub written: bool,
+}
+
+impl DryRunResult {
+    pub(crate) fn new(
+        logical: &LogicalPlan,
+        accesses: &TableAccessSet,
+        catalog: &ConcurrentCatalog,
+    ) -> Self {
+        let references = plan_references(logical);
+        let mut referenced_tables = Vec::with_capacity(accesses.accesses.len());
+        let mut total_bytes_processed = 0;
+        for (name, access) in &accesses.accesses {
+            let columns: Vec<String> = catalog
+                .get_table_schema(name)
+                .map(|schema| {
+                    schema
+                        .fields()
+                        .iter()
+                        .filter(|field| references.reads_column(name, &field.name))
+                        .map(|field| field.name.clone())
+                        .collect()
+                })
+                .unwrap_or_default();
+            if let Some(handle) = catalog.get_table_handle(name) {
+                let table = handle.read();
+                total_bytes_processed += columns
+                    .iter()
+                    .filter_map(|column| table.column_by_name(column))
+                    .map(|column| column.estimated_bytes() as u64)
+                    .sum::<u64>();
+            }
+            referenced_tables.push(ReferencedTable {
+                name: name.clone(),
+                columns,
+                written: *access != AccessType::Read,
+            });
+        }
+
+        Self {
+            statement_type: statement_type(logical).to_string(),
+            schema: logical
+                .schema()
+                .fields
+                .iter()
+                .map(|field| ColumnInfo::new(&field.name, field.data_type.to_bq_type()))
+                .collect(),
+            referenced_tables,
+            total_bytes_processed,
+        }
+    }
+}
@@ -0,0 +1,847 @@
+#![coverage(off)]
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufReader, ErrorKind, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Weak};
+
+use parking_lot::Mutex;
+use rustc_hash::FxHashSet;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{Mutex as AsyncMutex, MutexGuard};
+use yachtsql_common::error::{Error, Result};
+use yachtsql_ir::LogicalPlan;
+use yachtsql_storage::Table;
+
+use crate::async_executor::AsyncQueryExecutor;
+use crate::catalog_file::{read_catalog_file, sync_dir, write_catalog_file};
+use crate::concurrent_catalog::{ConcurrentCatalog, TableLockSet, is_session_key};
+use crate::concurrent_session::ConcurrentSession;
+
+const WAL_MAGIC: &[u8; 8] = b"YACHTWAL";
+const WAL_VERSION: u32 = 1;
+const WAL_HEADER_LEN: u64 = 12;
+const FRAME_HEADER_LEN: usize = 8;
+/// The log is checkpointed once it outgrows the last checkpoint, but never
+/// before it reaches the minimum or after it reaches the maximum.
+const MIN_CHECKPOINT_THRESHOLD_BYTES: u64 = 4 * 1024 * 1024;
+const MAX_CHECKPOINT_THRESHOLD_BYTES: u64 = 64 * 1024 * 1024;
+const LOCK_FILE: &str = "LOCK";
+
+fn checkpoint_path(dir: &Path, generation: u64) -> PathBuf {
+    dir.join(format!("checkpoint-{}.catalog", generation))
+}
+
+fn wal_path(dir: &Path, generation: u64) -> PathBuf {
+    dir.join(format!("wal-{}.log", generation))
+}
+
+fn io_error(operation: &str, path: &Path, e: impl std::fmt::Display) -> Error {
+    Error::io_error(operation, format!("'{}': {}", path.display(), e))
+}
+
+/// One committed change, replayed in order on top of the latest checkpoint.
+///
+/// DDL is replayed by re-running its SQL, which is deterministic once the
+/// tables it reads are in the same state. Data changes are logged by value so
+/// that functions like `RAND()` or `CURRENT_TIMESTAMP()` replay exactly.
+#[derive(Debug, Serialize, Deserialize)]
+enum WalRecord {
+    Statement {
+        sql: String,
+        default_project: Option<String>,
+        search_path: Vec<String>,
+    },
+    PutTable {
+        name: String,
+        table: Table,
+    },
+    AppendRows {
+        name: String,
+        rows: Table,
+    },
+}
+
+pub(crate) struct WalWriter {
+    dir: PathBuf,
+    generation: u64,
+    file: File,
+    len: u64,
+    checkpoint_len: u64,
+    checkpoint_deferred: bool,
+    /// Why writes are refused: a change reached memory but not the log, so
+    /// only reopening the store gets the two back in step.
+    failure: Option<String>,
+}
+
+impl WalWriter {
+    fn create(dir: &Path, generation: u64) -> Result<Self> {
+        let path = wal_path(dir, generation);
+        let mut file = OpenOptions::new()
+            .create(true)
+            .truncate(true)
+            .write(true)
+            .open(&path)
+            .map_err(|e| io_error("create write-ahead log", &path, e))?;
+        file.write_all(WAL_MAGIC)
+            .and_then(|_| file.write_all(&WAL_VERSION.to_le_bytes()))
+            .and_then(|_| file.sync_all())
+            .map_err(|e| io_error("create write-ahead log", &path, e))?;
+        sync_dir(dir)?;
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            generation,
+            file,
+            len: WAL_HEADER_LEN,
+            checkpoint_len: 0,
+            checkpoint_deferred: false,
+            failure: None,
+        })
+    }
+
+    fn open_existing(dir: &Path, generation: u64, len: u64) -> Result<Self> {
+        let path = wal_path(dir, generation);
+        let file = OpenOptions::new()
+            .write(true)
+            .open(&path)
+            .map_err(|e| io_error("open write-ahead log", &path, e))?;
+        file.set_len(len)
+            .and_then(|_| file.sync_all())
+            .map_err(|e| io_error("open write-ahead log", &path, e))?;
+        let mut writer = Self {
+            dir: dir.to_path_buf(),
+            generation,
+            file,
+            len,
+            checkpoint_len: 0,
+            checkpoint_deferred: false,
+            failure: None,
+        };
+        writer
+            .file
+            .seek(SeekFrom::Start(len))
+            .map_err(|e| io_error("open write-ahead log", &path, e))?;
+        Ok(writer)
+    }
+
+    fn check_usable(&self) -> Result<()> {
+        match &self.failure {
+            Some(reason) => Err(io_error(
+                "write to database",
+                &self.dir,
+                format!(
+                    "an earlier write could not be logged ({}); reopen the database to recover its last durable state",
+                    reason
+                ),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn wants_checkpoint(&self) -> bool {
+        self.checkpoint_deferred
+            || self.len
+                >= self.checkpoint_len.clamp(
+                    MIN_CHECKPOINT_THRESHOLD_BYTES,
+                    MAX_CHECKPOINT_THRESHOLD_BYTES,
+                )
+    }
+
+    fn append(&mut self, records: &[WalRecord]) -> Result<()> {
+        if records.is_empty() {
+            return Ok(());
+        }
+        let path = wal_path(&self.dir, self.generation);
+        let mut frames = Vec::new();
+        for record in records {
+            let mut payload = Vec::new();
+            ciborium::into_writer(record, &mut payload)
+                .map_err(|e| io_error("append to write-ahead log", &path, e))?;
+            let len = u32::try_from(payload.len()).map_err(|_| {
+                io_error(
+                    "append to write-ahead log",
+                    &path,
+                    "record exceeds 4 GiB; run a checkpoint instead",
+                )
+            })?;
+            frames.extend_from_slice(&len.to_le_bytes());
+            frames.extend_from_slice(&crc32fast::hash(&payload).to_le_bytes());
+            frames.extend_from_slice(&payload);
+        }
+        let written = self
+            .file
+            .write_all(&frames)
+            .and_then(|_| self.file.sync_data());
+        if let Err(e) = written {
+            let _ = self
+                .file
+                .set_len(self.len)
+                .and_then(|_| self.file.seek(SeekFrom::Start(self.len)));
+            return Err(io_error("append to write-ahead log", &path, e));
+        }
+        self.len += frames.len() as u64;
+        Ok(())
+    }
+}
+
+/// Reads the records of a write-ahead log, stopping at the first incomplete or
+/// corrupt frame. Returns the records and the length of the valid prefix.
+fn read_wal(path: &Path) -> Result<(Vec<WalRecord>, u64)> {
+    let file = File::open(path).map_err(|e| io_error("read write-ahead log", path, e))?;
+    let file_len = file
+        .metadata()
+        .map_err(|e| io_error("read write-ahead log", path, e))?
+        .len();
+    let mut reader = BufReader::new(file);
+
+    let mut header = [0u8; WAL_HEADER_LEN as usize];
+    if reader.read_exact(&mut header).is_err() {
+        return Ok((Vec::new(), 0));
+    }
+    if &header[..8] != WAL_MAGIC {
+        return Err(io_error(
+            "read write-ahead log",
+            path,
+            "not a YachtSQL write-ahead log",
+        ));
+    }
+    let version = u32::from_le_bytes([header[8], header[9], header[10], header[11]]);
+    if version > WAL_VERSION {
+        return Err(io_error(
+            "read write-ahead log",
+            path,
+            format!(
+                "log format version {} is newer than the supported version {}",
+                version, WAL_VERSION
+            ),
+        ));
+    }
+
+    let mut records = Vec::new();
+    let mut valid_len = WAL_HEADER_LEN;
+    loop {
+        let mut frame_header = [0u8; FRAME_HEADER_LEN];
+        if reader.read_exact(&mut frame_header).is_err() {
+            break;
+        }
+        let len = u32::from_le_bytes([
+            frame_header[0],
+            frame_header[1],
+            frame_header[2],
+            frame_header[3],
+        ]) as usize;
+        let crc = u32::from_le_bytes([
+            frame_header[4],
+            frame_header[5],
+            frame_header[6],
+            frame_header[7],
+        ]);
+        if valid_len + (FRAME_HEADER_LEN + len) as u64 > file_len {
+            break;
+        }
+        let mut payload = vec![0u8; len];
+        if reader.read_exact(&mut payload).is_err() || crc32fast::hash(&payload) != crc {
+            break;
+        }
+        let Ok(record) = ciborium::from_reader::<WalRecord, _>(payload.as_slice()) else {
+            break;
+        };
+        records.push(record);
+        valid_len += (FRAME_HEADER_LEN + len) as u64;
+    }
+    Ok((records, valid_len))
+}
+
+/// A directory holding a checkpointed catalog and the write-ahead log of the
+/// changes committed since that checkpoint.
+///
+/// The directory contains `checkpoint-<n>.catalog` (absent before the first
+/// checkpoint), `wal-<n>.log` and a `LOCK` file. Each committed statement is
+/// appended to the log and synced to disk before the statement returns. A
+/// checkpoint writes the whole catalog to a new generation `n + 1` and
+/// atomically renames it into place before the old generation is removed, so
+/// that after a crash at any point the directory holds exactly one complete
+/// generation to recover from. A record that was only partly written when the
+/// process died fails its checksum and is discarded on the next open.
+///
+/// Table writes are logged before they are committed in memory, so a failed
+/// append leaves the tables as they were. `UPDATE`, `DELETE`, `MERGE`,
+/// `TRUNCATE` and `LOAD DATA` log the whole table they change rather than the
+/// changed rows, which makes the log grow by the table's size per statement;
+/// to bound replay, the log is checkpointed as soon as it outgrows the last
+/// checkpoint (but not before 4 MiB, and always by 64 MiB). Checkpoints wait
+/// until no session has a transaction open, since they would otherwise
+/// capture its uncommitted writes.
+pub struct DurableStore {
+    dir: PathBuf,
+    catalog: ConcurrentCatalog,
+    wal: AsyncMutex<WalWriter>,
+    sessions: Mutex<Vec<Weak<ConcurrentCatalog>>>,
+    _lock: File,
+}
+
+impl std::fmt::Debug for DurableStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DurableStore")
+            .field("dir", &self.dir)
+            .finish_non_exhaustive()
+    }
+}
+
+impl DurableStore {
+    /// Opens the store in `dir`, creating the directory if needed, and recovers
+    /// the catalog from the latest checkpoint and write-ahead log.
+    ///
+    /// The directory is locked for as long as the store is alive; opening it
+    /// again, from this or another process, fails until the store is dropped.
+    pub async fn open(dir: impl AsRef<Path>) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir).map_err(|e| io_error("open database", &dir, e))?;
+
+        let lock_path = dir.join(LOCK_FILE);
+        let lock = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&lock_path)
+            .map_err(|e| io_error("open database", &lock_path, e))?;
+        lock.try_lock().map_err(|_| {
+            io_error(
+                "open database",
+                &dir,
+                "the database is already open in another engine",
+            )
+        })?;
+
+        let generation = latest_checkpoint(&dir)?;
+        let catalog = match generation {
+            Some(generation) => ConcurrentCatalog::from_snapshot(read_catalog_file(
+                &checkpoint_path(&dir, generation),
+            )?),
+            None => ConcurrentCatalog::new(),
+        };
+        let generation = generation.unwrap_or(0);
+
+        let log = wal_path(&dir, generation);
+        let mut wal = if log.exists() {
+            let (records, valid_len) = read_wal(&log)?;
+            replay(&catalog, records).await?;
+            if valid_len < WAL_HEADER_LEN {
+                WalWriter::create(&dir, generation)?
+            } else {
+                WalWriter::open_existing(&dir, generation, valid_len)?
+            }
+        } else {
+            WalWriter::create(&dir, generation)?
+        };
+        wal.checkpoint_len = fs::metadata(checkpoint_path(&dir, generation)).map_or(0, |m| m.len());
+        remove_stale_files(&dir, generation)?;
+
+        Ok(Self {
+            dir,
+            catalog,
+            wal: AsyncMutex::new(wal),
+            sessions: Mutex::new(Vec::new()),
+            _lock: lock,
+        })
+    }
+
+    /// The directory this store persists to.
+    pub fn path(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The recovered catalog. Sessions should use a
+    /// [`session_scope`](ConcurrentCatalog::session_scope) of it.
+    pub fn catalog(&self) -> &ConcurrentCatalog {
+        &self.catalog
+    }
+
+    /// Writes the whole catalog to a new checkpoint and starts an empty log.
+    ///
+    /// Waits for in-flight writes to finish. While a session has a transaction
+    /// open, the checkpoint is deferred to the first write after no transaction
+    /// is open. Changes made directly through the catalog API rather than SQL
+    /// are not logged and become durable only at a checkpoint.
+    pub async fn checkpoint(&self) -> Result<()> {
+        let mut wal = self.wal.lock().await;
+        wal.check_usable()?;
+        if self.has_open_transaction(None) {
+            wal.checkpoint_deferred = true;
+            return Ok(());
+        }
+        self.checkpoint_locked(&mut wal)
+    }
+
+    /// Tracks `catalog`, a session scope of this store's catalog, so that
+    /// checkpoints can wait for its transactions.
+    fn register_session(&self, catalog: &Arc<ConcurrentCatalog>) {
+        let mut sessions = self.sessions.lock();
+        sessions.retain(|session| session.strong_count() > 0);
+        sessions.push(Arc::downgrade(catalog));
+    }
+
+    /// Whether a session other than `except` has a transaction open.
+    fn has_open_transaction(&self, except: Option<&ConcurrentCatalog>) -> bool {
+        self.sessions
+            .lock()
+            .iter()
+            .filter_map(Weak::upgrade)
+            .filter(|session| except.is_none_or(|except| !std::ptr::eq(&**session, except)))
+            .any(|session| session.in_transaction())
+    }
+
+    /// Checkpoints, or refuses further writes if the checkpoint could not be
+    /// completed, since the log may no longer be the one recovered from.
+    fn checkpoint_locked(&self, wal: &mut WalWriter) -> Result<()> {
+        let previous = match self.write_checkpoint(wal.generation + 1) {
+            Ok(next) => std::mem::replace(wal, next),
+            Err(e) => {
+                wal.failure = Some(e.to_string());
+                return Err(e);
+            }
+        };
+        remove_if_exists(&wal_path(&self.dir, previous.generation))?;
+        remove_if_exists(&checkpoint_path(&self.dir, previous.generation))
+    }
+
+    fn write_checkpoint(&self, next: u64) -> Result<WalWriter> {
+        let target = checkpoint_path(&self.dir, next);
+        write_catalog_file(&target, &self.catalog.to_snapshot())?;
+
+        let mut wal = WalWriter::create(&self.dir, next)?;
+        wal.checkpoint_len = fs::metadata(&target).map_or(0, |m| m.len());
+        Ok(wal)
+    }
+}
+
+fn latest_checkpoint(dir: &Path) -> Result<Option<u64>> {
+    let entries = fs::read_dir(dir).map_err(|e| io_error("open database", dir, e))?;
+    let mut latest = None;
+    for entry in entries {
+        let entry = entry.map_err(|e| io_error("open database", dir, e))?;
+        let name = entry.file_name();
+        let Some(generation) = name
+            .to_str()
+            .and_then(|n| n.strip_prefix("checkpoint-"))
+            .and_then(|n| n.strip_suffix(".catalog"))
+            .and_then(|n| n.parse::<u64>().ok())
+        else {
+            continue;
+        };
+        latest = latest.max(Some(generation));
+    }
+    Ok(latest)
+}
+
+fn remove_stale_files(dir: &Path, generation: u64) -> Result<()> {
+    let entries = fs::read_dir(dir).map_err(|e| io_error("open database", dir, e))?;
+    for entry in entries {
+        let entry = entry.map_err(|e| io_error("open database", dir, e))?;
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let stale = if name.ends_with(".tmp") {
+            true
+        } else if let Some(n) = name
+            .strip_prefix("checkpoint-")
+            .and_then(|n| n.strip_suffix(".catalog"))
+        {
+            n.parse::<u64>().is_ok_and(|n| n != generation)
+        } else if let Some(n) = name
+            .strip_prefix("wal-")
+            .and_then(|n| n.strip_suffix(".log"))
+        {
+            n.parse::<u64>().is_ok_and(|n| n != generation)
+        } else {
+            false
+        };
+        if stale {
+            remove_if_exists(&path)?;
+        }
+    }
+    Ok(())
+}
+
+fn remove_if_exists(path: &Path) -> Result<()> {
+    match fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(io_error("remove file", path, e)),
+    }
+}
+
+async fn replay(catalog: &ConcurrentCatalog, records: Vec<WalRecord>) -> Result<()> {
+    let executor = AsyncQueryExecutor::from_catalog_and_session(
+        catalog.session_scope(),
+        ConcurrentSession::new(),
+    );
+    for (index, record) in records.into_iter().enumerate() {
+        let replayed = match record {
+            WalRecord::Statement {
+                sql,
+                default_project,
+                search_path,
+            } => {
+                executor.catalog().set_default_project(default_project);
+                executor.catalog().set_search_path(search_path);
+                executor.execute_sql(&sql).await.map(|_| ())
+            }
+            WalRecord::PutTable { name, table } => {
+                catalog.create_or_replace_table(&name, table);
+                Ok(())
+            }
+            WalRecord::AppendRows { name, rows } => match catalog.get_table_handle(&name) {
+                Some(handle) => {
+                    let mut table = handle.write();
+                    table.concat(&rows).map(|combined| *table = combined)
+                }
+                None => Err(Error::TableNotFound(name)),
+            },
+        };
+        replayed.map_err(|e| {
+            Error::io_error(
+                "replay write-ahead log",
+                format!("record {} could not be applied: {}", index, e),
+            )
+        })?;
+    }
+    Ok(())
+}
+
+/// What a statement has to log once it has run.
+fn permanent_table_key(catalog: &ConcurrentCatalog, name: &str) -> Option<String> {
+    let key = catalog.resolve_table_name(name);
+    (!is_session_key(&key)).then_some(key)
+}
+
+enum WalAction {
+    None,
+    Statement { images: Vec<String> },
+    Append(String),
+    Images(Vec<String>),
+    EndTransaction,
+    Checkpoint,
+}
+
+impl WalAction {
+    fn for_plan(plan: &LogicalPlan, catalog: &ConcurrentCatalog) -> Self {
+        let permanent = |name: &str| permanent_table_key(catalog, name);
+        match plan {
+            LogicalPlan::Insert { table_name, .. } => {
+                permanent(table_name).map_or(WalAction::None, WalAction::Append)
+            }
+            LogicalPlan::Update { table_name, .. }
+            | LogicalPlan::Delete { table_name, .. }
+            | LogicalPlan::Truncate { table_name }
+            | LogicalPlan::Merge {
+                target_table: table_name,
+                ..
+            }
+            | LogicalPlan::LoadData {
+                table_name,
+                temp_table: false,
+                ..
+            } => permanent(table_name).map_or(WalAction::None, |key| WalAction::Images(vec![key])),
+            LogicalPlan::CreateTable { table_name, .. }
+                if is_session_key(&table_name.to_uppercase()) =>
+            {
+                WalAction::None
+            }
+            LogicalPlan::CreateTable {
+                table_name,
+                query: Some(_),
+                ..
+            } => WalAction::Images(vec![table_name.to_uppercase()]),
+            LogicalPlan::CreateTable { query: None, .. } => {
+                WalAction::Statement { images: Vec::new() }
+            }
+            LogicalPlan::DropTable { table_names, .. } => {
+                let temporary = table_names
+                    .iter()
+                    .filter(|name| permanent(name).is_none())
+                    .count();
+                if temporary == 0 {
+                    WalAction::Statement { images: Vec::new() }
+                } else if temporary == table_names.len() {
+                    WalAction::None
+                } else {
+                    WalAction::Checkpoint
+                }
+            }
+            LogicalPlan::AlterTable { table_name, .. } => permanent(table_name)
+                .map_or(WalAction::None, |key| WalAction::Statement {
+                    images: vec![key],
+                }),
+            LogicalPlan::CreateFunction { is_temp: true, .. } => WalAction::None,
+            LogicalPlan::DropFunction { name, .. } if catalog.is_temp_function(name) => {
+                WalAction::None
+            }
+            LogicalPlan::CreateView { .. }
+            | LogicalPlan::DropView { .. }
+            | LogicalPlan::CreateSchema { .. }
+            | LogicalPlan::DropSchema { .. }
+            | LogicalPlan::UndropSchema { .. }
+            | LogicalPlan::AlterSchema { .. }
+            | LogicalPlan::CreateFunction { .. }
+            | LogicalPlan::DropFunction { .. }
+            | LogicalPlan::CreateProcedure { .. }
+            | LogicalPlan::DropProcedure { .. }
+            | LogicalPlan::CreateSnapshot { .. }
+            | LogicalPlan::DropSnapshot { .. } => WalAction::Statement { images: Vec::new() },
+            LogicalPlan::Commit | LogicalPlan::Rollback => WalAction::EndTransaction,
+            LogicalPlan::Call { .. }
+            | LogicalPlan::ExecuteImmediate { .. }
+            | LogicalPlan::Block { .. }
+            | LogicalPlan::If { .. }
+            | LogicalPlan::While { .. }
+            | LogicalPlan::Loop { .. }
+            | LogicalPlan::Repeat { .. }
+            | LogicalPlan::For { .. }
+            | LogicalPlan::TryCatch { .. } => WalAction::Checkpoint,
+            _ => WalAction::None,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct TransactionLog {
+    tables: FxHashSet<String>,
+    needs_checkpoint: bool,
+}
+
+/// How to undo a statement whose records could not be appended.
+enum Undo {
+    /// Its table writes are still uncommitted and are dropped by the caller.
+    DiscardWrites,
+    /// `COMMIT` already published the transaction; restore the tables it wrote.
+    Restore(Vec<(String, Table)>),
+    /// It changed the catalog directly, so memory is ahead of the log.
+    Impossible,
+}
+
+/// A session's handle on a [`DurableStore`].
+///
+/// Tables written inside a transaction are logged when it ends: after a commit
+/// they hold the committed rows, after a rollback the restored ones.
+#[derive(Debug, Clone)]
+pub(crate) struct DurableSession {
+    store: Arc<DurableStore>,
+    transaction: Arc<Mutex<TransactionLog>>,
+}
+
+impl DurableSession {
+    /// A handle for the session whose catalog is `catalog`.
+    pub(crate) fn new(store: Arc<DurableStore>, catalog: &Arc<ConcurrentCatalog>) -> Self {
+        store.register_session(catalog);
+        Self {
+            store,
+            transaction: Arc::new(Mutex::new(TransactionLog::default())),
+        }
+    }
+
+    /// Serializes the statement against other writes and checkpoints, and
+    /// works out what it will have to log.
+    ///
+    /// Fails if an earlier write could not be logged, or if the statement has
+    /// to be made durable by a checkpoint while another session has a
+    /// transaction open.
+    pub(crate) async fn begin_write<'a>(
+        &'a self,
+        plan: &LogicalPlan,
+        catalog: &ConcurrentCatalog,
+    ) -> Result<PendingWrite<'a>> {
+        let wal = self.store.wal.lock().await;
+        wal.check_usable()?;
+        let action = WalAction::for_plan(plan, catalog);
+        let checkpoints = match action {
+            WalAction::Checkpoint => !catalog.in_transaction(),
+            WalAction::EndTransaction => self.transaction.lock().needs_checkpoint,
+            _ => false,
+        };
+        if checkpoints && self.store.has_open_transaction(Some(catalog)) {
+            return Err(Error::invalid_query(
+                "This statement is made durable by a checkpoint, which cannot run while another session has a transaction open; retry once it has ended",
+            ));
+        }
+        let restore = match plan {
+            LogicalPlan::Commit => catalog.transaction_tables(&self.transaction.lock().tables),
+            _ => Vec::new(),
+        };
+        Ok(PendingWrite {
+            session: self,
+            wal,
+            action,
+            rows_before: None,
+            restore,
+        })
+    }
+
+    /// Like [`begin_write`](Self::begin_write), for rows appended to `table_name`
+    /// without a statement.
+    pub(crate) async fn begin_append<'a>(
+        &'a self,
+        table_name: &str,
+        catalog: &ConcurrentCatalog,
+    ) -> Result<PendingWrite<'a>> {
+        let wal = self.store.wal.lock().await;
+        wal.check_usable()?;
+        Ok(PendingWrite {
+            session: self,
+            wal,
+            action: permanent_table_key(catalog, table_name)
+                .map_or(WalAction::None, WalAction::Append),
+            rows_before: None,
+            restore: Vec::new(),
+        })
+    }
+}
+
+/// A write statement holding the log lock while it runs.
+pub(crate) struct PendingWrite<'a> {
+    session: &'a DurableSession,
+    wal: MutexGuard<'a, WalWriter>,
+    action: WalAction,
+    rows_before: Option<usize>,
+    restore: Vec<(String, Table)>,
+}
+
+impl PendingWrite<'_> {
+    /// Remembers the size of an `INSERT` target so only the new rows are logged.
+    pub(crate) fn observe_tables(&mut self, tables: &TableLockSet) {
+        if let WalAction::Append(name) = &self.action {
+            self.rows_before = tables.get_table(name).map(|t| t.row_count());
+        }
+    }
+
+    /// Logs the statement's effect. Must be called before `tables` commits its
+    /// writes, which it logs as they will be committed; if this fails they
+    /// must not be committed. `succeeded` is false when the statement failed;
+    /// any table writes it made are still logged by value.
+    ///
+    /// Changes that cannot be undone after a failed append, such as DDL, leave
+    /// the store refusing further writes until it is reopened.
+    pub(crate) fn finish(
+        self,
+        sql: &str,
+        catalog: &ConcurrentCatalog,
+        tables: &TableLockSet,
+        succeeded: bool,
+    ) -> Result<()> {
+        let PendingWrite {
+            session,
+            mut wal,
+            action,
+            rows_before,
+            restore,
+        } = self;
+        let store = &session.store;
+        let image = |name: &str| {
+            tables
+                .get_table(name)
+                .or_else(|| {
+                    catalog
+                        .get_table_handle(name)
+                        .map(|handle| handle.read().clone())
+                })
+                .map(|table| WalRecord::PutTable {
+                    name: name.to_string(),
+                    table,
+                })
+        };
+        let uncommitted = |names: &[String]| {
+            if names.iter().all(|name| tables.holds_write(name)) {
+                Undo::DiscardWrites
+            } else {
+                Undo::Impossible
+            }
+        };
+        let in_transaction = catalog.in_transaction();
+
+        let (records, undo) = match action {
+            WalAction::None => (Vec::new(), Undo::DiscardWrites),
+            WalAction::Statement { .. } if !succeeded => (Vec::new(), Undo::DiscardWrites),
+            WalAction::Statement { images } => {
+                let mut records = vec![WalRecord::Statement {
+                    sql: sql.to_string(),
+                    default_project: catalog.get_default_project(),
+                    search_path: catalog.get_search_path(),
+                }];
+                records.extend(images.iter().filter_map(|name| image(name)));
+                (records, Undo::Impossible)
+            }
+            WalAction::Append(name) if in_transaction => {
+                session.transaction.lock().tables.insert(name);
+                (Vec::new(), Undo::DiscardWrites)
+            }
+            WalAction::Images(names) if in_transaction => {
+                session.transaction.lock().tables.extend(names);
+                (Vec::new(), Undo::DiscardWrites)
+            }
+            WalAction::Checkpoint if in_transaction => {
+                session.transaction.lock().needs_checkpoint = true;
+                (Vec::new(), Undo::DiscardWrites)
+            }
+            WalAction::Append(name) => {
+                let undo = uncommitted(std::slice::from_ref(&name));
+                let appended = match (rows_before, tables.get_table(&name)) {
+                    (Some(before), Some(after)) if succeeded && after.row_count() >= before => {
+                        let indices: Vec<usize> = (before..after.row_count()).collect();
+                        after.gather_rows(&indices).ok()
+                    }
+                    _ => None,
+                };
+                let records = match appended {
+                    Some(rows) if rows.row_count() == 0 => Vec::new(),
+                    Some(rows) => vec![WalRecord::AppendRows { name, rows }],
+                    None => image(&name).into_iter().collect(),
+                };
+                (records, undo)
+            }
+            WalAction::Images(names) => (
+                names.iter().filter_map(|name| image(name)).collect(),
+                uncommitted(&names),
+            ),
+            WalAction::EndTransaction => {
+                let pending = std::mem::take(&mut *session.transaction.lock());
+                if pending.needs_checkpoint {
+                    tables.commit_writes();
+                    return store.checkpoint_locked(&mut wal);
+                }
+                let mut names: Vec<String> = pending.tables.into_iter().collect();
+                names.sort();
+                let records = names.iter().filter_map(|name| image(name)).collect();
+                let undo = if restore.is_empty() {
+                    Undo::Impossible
+                } else {
+                    Undo::Restore(restore)
+                };
+                (records, undo)
+            }
+            WalAction::Checkpoint => {
+                // The checkpoint has to see the writes it makes durable.
+                tables.commit_writes();
+                return store.checkpoint_locked(&mut wal);
+            }
+        };
+
+        if let Err(e) = wal.append(&records) {
+            match undo {
+                Undo::DiscardWrites => {}
+                Undo::Restore(tables) => {
+                    for (name, table) in tables {
+                        catalog.update_table(&name, table);
+                    }
+                }
+                Undo::Impossible => wal.failure = Some(e.to_string()),
+            }
+            return Err(e);
+        }
+        if wal.wants_checkpoint()
+            && !store.has_open_transaction(None)
+            && let Err(e) = store.checkpoint_locked(&mut wal)
+        {
+            tracing::error!("checkpoint after a logged write failed: {}", e);
+        }
+        Ok(())
+    }
+}
@@ -34,7 +34,8 @@ use yachtsql_common::error::Result;
 use yachtsql_common::types::Value;
 use yachtsql_ir::{AggregateFunction, Expr};
 
-#[derive(Clone)]
+use crate::native_function::NativeAggregateState;
+
 pub(crate) enum Accumulator {
     Count(i64),
     CountIf(i64),
@@ -105,6 +106,7 @@ pub(crate) enum Accumulator {
         sums: FxHashMap<Value, f64>,
         top_n: usize,
     },
+    Native(NativeAggregateState),
 }
 
 impl Accumulator {
@@ -373,6 +375,7 @@ impl Accumulator {
                 }
             }
             Accumulator::ApproxTopSum { .. } => {}
+            Accumulator::Native(native) => native.update(std::slice::from_ref(value))?,
         }
         Ok(())
     }
@@ -416,7 +419,8 @@ impl Accumulator {
             | Accumulator::Covariance { .. }
             | Accumulator::ApproxQuantiles { .. }
             | Accumulator::ApproxTopCount { .. }
-            | Accumulator::ApproxTopSum { .. } => {
+            | Accumulator::ApproxTopSum { .. }
+            | Accumulator::Native(_) => {
                 self.accumulate(value)?;
             }
             Accumulator::Grouping { .. } | Accumulator::GroupingId { .. } => {}
@@ -490,7 +494,8 @@ impl Accumulator {
             | Accumulator::GroupingId { .. }
             | Accumulator::ApproxQuantiles { .. }
             | Accumulator::ApproxTopCount { .. }
-            | Accumulator::ApproxTopSum { .. } => {}
+            | Accumulator::ApproxTopSum { .. }
+            | Accumulator::Native(_) => {}
         }
         Ok(())
     }
@@ -540,10 +545,10 @@ impl Accumulator {
         Ok(())
     }
 
-    pub(crate) fn finalize(&self) -> Value {
+    pub(crate) fn finalize(&self) -> Result<Value> {
         use ordered_float::OrderedFloat;
 
-        match self {
+        Ok(match self {
             Accumulator::Count(n) => Value::Int64(*n),
             Accumulator::CountIf(n) => Value::Int64(*n),
             Accumulator::Sum(sum) => sum
@@ -654,7 +659,7 @@ impl Accumulator {
                 ..
             } => {
                 if *count < 2 {
-                    return Value::Null;
+                    return Ok(Value::Null);
                 }
                 let divisor = if *is_sample {
                     (*count - 1) as f64
@@ -667,7 +672,7 @@ impl Accumulator {
                         let var_x = *m2_x / *count as f64;
                         let var_y = *m2_y / *count as f64;
                         if var_x <= 0.0 || var_y <= 0.0 {
-                            return Value::Null;
+                            return Ok(Value::Null);
                         }
                         let std_x = var_x.sqrt();
                         let std_y = var_y.sqrt();
@@ -684,7 +689,7 @@ impl Accumulator {
                 num_quantiles,
             } => {
                 if values.is_empty() {
-                    return Value::Array(vec![]);
+                    return Ok(Value::Array(vec![]));
                 }
                 let mut sorted = values.clone();
                 sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
@@ -733,7 +738,8 @@ impl Accumulator {
                     .collect();
                 Value::Array(result)
             }
-        }
+            Accumulator::Native(native) => return native.finalize(),
+        })
     }
 
     pub(crate) fn set_grouping_value(&mut self, value: i64) {
@@ -773,10 +779,11 @@ impl Accumulator {
             | Accumulator::ApproxQuantiles { .. }
             | Accumulator::ApproxTopCount { .. }
             | Accumulator::ApproxTopSum { .. } => false,
+            Accumulator::Native(native) => !native.is_distinct(),
         }
     }
 
-    pub(crate) fn merge(&mut self, other: &Self) {
+    pub(crate) fn merge(&mut self, other: &Self) -> Result<()> {
         match (self, other) {
             (Accumulator::Count(a), Accumulator::Count(b)) => *a += b,
             (Accumulator::CountIf(a), Accumulator::CountIf(b)) => *a += b,
@@ -884,7 +891,9 @@ impl Accumulator {
                     (None, None) => None,
                 };
             }
+            (Accumulator::Native(a), Accumulator::Native(b)) => a.merge(b)?,
             _ => {}
         }
+        Ok(())
     }
 }
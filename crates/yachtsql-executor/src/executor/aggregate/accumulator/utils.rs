@@ -14,6 +14,7 @@ pub(crate) fn get_agg_func(expr: &Expr) -> Option<&AggregateFunction> {
         | Expr::ScalarFunction { .. }
         | Expr::Window { .. }
         | Expr::AggregateWindow { .. }
+        | Expr::UserDefinedAggregateWindow { .. }
         | Expr::Case { .. }
         | Expr::Cast { .. }
         | Expr::IsNull { .. }
@@ -61,6 +62,7 @@ pub(crate) fn is_distinct_aggregate(expr: &Expr) -> bool {
         | Expr::ScalarFunction { .. }
         | Expr::Window { .. }
         | Expr::AggregateWindow { .. }
+        | Expr::UserDefinedAggregateWindow { .. }
         | Expr::Case { .. }
         | Expr::Cast { .. }
         | Expr::IsNull { .. }
@@ -116,6 +118,7 @@ pub(crate) fn has_ignore_nulls(expr: &Expr) -> bool {
         | Expr::ScalarFunction { .. }
         | Expr::Window { .. }
         | Expr::AggregateWindow { .. }
+        | Expr::UserDefinedAggregateWindow { .. }
         | Expr::Case { .. }
         | Expr::Cast { .. }
         | Expr::IsNull { .. }
@@ -169,6 +172,7 @@ pub(crate) fn extract_int_arg(expr: &Expr, index: usize) -> Option<i64> {
         | Expr::ScalarFunction { .. }
         | Expr::Window { .. }
         | Expr::AggregateWindow { .. }
+        | Expr::UserDefinedAggregateWindow { .. }
         | Expr::Case { .. }
         | Expr::Cast { .. }
         | Expr::IsNull { .. }
@@ -222,6 +226,7 @@ pub(crate) fn extract_string_agg_separator(expr: &Expr) -> String {
         | Expr::ScalarFunction { .. }
         | Expr::Window { .. }
         | Expr::AggregateWindow { .. }
+        | Expr::UserDefinedAggregateWindow { .. }
         | Expr::Case { .. }
         | Expr::Cast { .. }
         | Expr::IsNull { .. }
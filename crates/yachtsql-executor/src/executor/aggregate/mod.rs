@@ -2,6 +2,8 @@
 
 mod accumulator;
 
+use std::collections::hash_map::Entry;
+
 use accumulator::Accumulator;
 use rustc_hash::{FxHashMap, FxHashSet};
 
@@ -13,7 +15,10 @@ use yachtsql_ir::{AggregateFunction, Expr, PlanSchema};
 use yachtsql_storage::{Column, Record, Table};
 
 use super::plan_schema_to_schema;
-use crate::value_evaluator::ValueEvaluator;
+use crate::cancellation::QueryInterrupt;
+use crate::memory::{MemoryBudget, average_row_bytes};
+use crate::native_function::NativeAggregateFunction;
+use crate::value_evaluator::{UserFunctionDef, ValueEvaluator};
 
 pub(crate) fn compute_aggregate(
     input_table: &Table,
@@ -22,9 +27,11 @@ pub(crate) fn compute_aggregate(
     schema: &PlanSchema,
     grouping_sets: Option<&Vec<Vec<usize>>>,
     variables: &FxHashMap<String, Value>,
-    user_function_defs: &FxHashMap<String, crate::value_evaluator::UserFunctionDef>,
+    user_function_defs: &FxHashMap<String, UserFunctionDef>,
     parallel: bool,
     threshold: usize,
+    interrupt: &QueryInterrupt,
+    memory: &MemoryBudget,
 ) -> Result<Table> {
     if can_use_columnar_aggregate(aggregates, group_by, grouping_sets) {
         return execute_columnar_aggregate(input_table, aggregates, schema);
@@ -44,16 +51,25 @@ pub(crate) fn compute_aggregate(
         .iter()
         .map(|(_, c)| c.as_ref())
         .collect();
+    let natives = native_aggregates(aggregates, user_function_defs)?;
+    let group_row_bytes = if memory.is_limited() {
+        average_row_bytes(input_table) + aggregates.len() * std::mem::size_of::<Accumulator>()
+    } else {
+        0
+    };
 
     if group_by.is_empty() {
-        let mut accumulators: Vec<Accumulator> =
-            aggregates.iter().map(Accumulator::from_expr).collect();
+        let mut accumulators = new_accumulators(aggregates, &natives);
 
         let mut record = Record::with_capacity(columns.len());
         for i in 0..n {
+            interrupt.check_row(i)?;
             fill_record_from_columns(&mut record, &columns, i);
             for (acc, agg_expr) in accumulators.iter_mut().zip(aggregates.iter()) {
-                if matches!(
+                if let Accumulator::Native(native) = acc {
+                    let args = extract_agg_args(&evaluator, agg_expr, &record)?;
+                    native.update(&args)?;
+                } else if matches!(
                     acc,
                     Accumulator::SumIf(_)
                         | Accumulator::AvgIf { .. }
@@ -80,16 +96,22 @@ pub(crate) fn compute_aggregate(
             }
         }
 
-        let row: Vec<Value> = accumulators.iter().map(|a| a.finalize()).collect();
+        let row: Vec<Value> = accumulators
+            .iter()
+            .map(|a| a.finalize())
+            .collect::<Result<_>>()?;
         result.push_row(row)?;
     } else if let Some(sets) = grouping_sets {
         for grouping_set in sets {
             let active_indices_vec: Vec<usize> = grouping_set.clone();
             let active_indices_set: FxHashSet<usize> = active_indices_vec.iter().copied().collect();
             let mut group_map: GroupMap<(Vec<Accumulator>, Vec<usize>)> = FxHashMap::default();
+            let group_memory = memory.reserve(group_row_bytes);
 
             let mut record = Record::with_capacity(columns.len());
             for i in 0..n {
+                interrupt.check_row(i)?;
+                group_memory.resize_rows(group_map.len())?;
                 fill_record_from_columns(&mut record, &columns, i);
                 let mut group_key_values = Vec::new();
                 for (idx, group_expr) in group_by.iter().enumerate() {
@@ -104,8 +126,7 @@ pub(crate) fn compute_aggregate(
                 let entry = group_map
                     .entry(group_key_values)
                     .or_insert_with_key(|_key| {
-                        let mut accs: Vec<Accumulator> =
-                            aggregates.iter().map(Accumulator::from_expr).collect();
+                        let mut accs = new_accumulators(aggregates, &natives);
                         for (acc, agg_expr) in accs.iter_mut().zip(aggregates.iter()) {
                             match acc {
                                 Accumulator::Grouping { .. } => {
@@ -136,7 +157,10 @@ pub(crate) fn compute_aggregate(
                     ) {
                         continue;
                     }
-                    if matches!(
+                    if let Accumulator::Native(native) = acc {
+                        let args = extract_agg_args(&evaluator, agg_expr, &record)?;
+                        native.update(&args)?;
+                    } else if matches!(
                         acc,
                         Accumulator::SumIf(_)
                             | Accumulator::AvgIf { .. }
@@ -166,12 +190,14 @@ pub(crate) fn compute_aggregate(
 
             for (group_key, (accumulators, _active)) in group_map {
                 let mut row = group_key;
-                row.extend(accumulators.iter().map(|a| a.finalize()));
+                for acc in &accumulators {
+                    row.push(acc.finalize()?);
+                }
                 result.push_row(row)?;
             }
         }
     } else {
-        let sample_accs: Vec<Accumulator> = aggregates.iter().map(Accumulator::from_expr).collect();
+        let sample_accs = new_accumulators(aggregates, &natives);
         let can_merge = sample_accs.iter().all(|a| a.is_mergeable());
 
         if parallel && n >= threshold && can_merge {
@@ -180,6 +206,7 @@ pub(crate) fn compute_aggregate(
 
             let input_schema_ref = &input_schema;
             let columns_ref = &columns;
+            let natives_ref = &natives;
             let local_results: Vec<Result<GroupMap<Vec<Accumulator>>>> = std::thread::scope(|s| {
                 let handles: Vec<_> = (0..n)
                     .collect::<Vec<_>>()
@@ -191,24 +218,29 @@ pub(crate) fn compute_aggregate(
                                 .with_variables(variables)
                                 .with_user_functions(user_function_defs);
                             let mut local_groups: GroupMap<Vec<Accumulator>> = FxHashMap::default();
+                            let group_memory = memory.reserve(group_row_bytes);
                             let mut record = Record::with_capacity(columns_ref.len());
 
                             for &idx in &chunk_indices {
+                                interrupt.check_row(idx)?;
+                                group_memory.resize_rows(local_groups.len())?;
                                 fill_record_from_columns(&mut record, columns_ref, idx);
                                 let group_key_values: Vec<Value> = group_by
                                     .iter()
                                     .map(|e| evaluator.evaluate(e, &record))
                                     .collect::<Result<_>>()?;
 
-                                let accumulators =
-                                    local_groups.entry(group_key_values).or_insert_with(|| {
-                                        aggregates.iter().map(Accumulator::from_expr).collect()
-                                    });
+                                let accumulators = local_groups
+                                    .entry(group_key_values)
+                                    .or_insert_with(|| new_accumulators(aggregates, natives_ref));
 
                                 for (acc, agg_expr) in
                                     accumulators.iter_mut().zip(aggregates.iter())
                                 {
-                                    if matches!(
+                                    if let Accumulator::Native(native) = acc {
+                                        let args = extract_agg_args(&evaluator, agg_expr, &record)?;
+                                        native.update(&args)?;
+                                    } else if matches!(
                                         acc,
                                         Accumulator::SumIf(_)
                                             | Accumulator::AvgIf { .. }
@@ -244,27 +276,34 @@ pub(crate) fn compute_aggregate(
             for local_result in local_results {
                 let local_groups = local_result?;
                 for (key, local_accs) in local_groups {
-                    merged_groups
-                        .entry(key)
-                        .and_modify(|existing| {
-                            for (e, l) in existing.iter_mut().zip(local_accs.iter()) {
-                                e.merge(l);
+                    match merged_groups.entry(key) {
+                        Entry::Occupied(mut existing) => {
+                            for (e, l) in existing.get_mut().iter_mut().zip(local_accs.iter()) {
+                                e.merge(l)?;
                             }
-                        })
-                        .or_insert(local_accs);
+                        }
+                        Entry::Vacant(slot) => {
+                            slot.insert(local_accs);
+                        }
+                    }
                 }
             }
 
             for (group_key, accumulators) in merged_groups {
                 let mut row = group_key;
-                row.extend(accumulators.iter().map(|a| a.finalize()));
+                for acc in &accumulators {
+                    row.push(acc.finalize()?);
+                }
                 result.push_row(row)?;
             }
         } else {
             let mut groups: GroupMap<Vec<Accumulator>> = FxHashMap::default();
+            let group_memory = memory.reserve(group_row_bytes);
             let mut record = Record::with_capacity(columns.len());
 
             for i in 0..n {
+                interrupt.check_row(i)?;
+                group_memory.resize_rows(groups.len())?;
                 fill_record_from_columns(&mut record, &columns, i);
                 let group_key_values: Vec<Value> = group_by
                     .iter()
@@ -273,10 +312,13 @@ pub(crate) fn compute_aggregate(
 
                 let accumulators = groups
                     .entry(group_key_values)
-                    .or_insert_with(|| aggregates.iter().map(Accumulator::from_expr).collect());
+                    .or_insert_with(|| new_accumulators(aggregates, &natives));
 
                 for (acc, agg_expr) in accumulators.iter_mut().zip(aggregates.iter()) {
-                    if matches!(
+                    if let Accumulator::Native(native) = acc {
+                        let args = extract_agg_args(&evaluator, agg_expr, &record)?;
+                        native.update(&args)?;
+                    } else if matches!(
                         acc,
                         Accumulator::SumIf(_)
                             | Accumulator::AvgIf { .. }
@@ -306,7 +348,9 @@ pub(crate) fn compute_aggregate(
 
             for (group_key, accumulators) in groups {
                 let mut row = group_key;
-                row.extend(accumulators.iter().map(|a| a.finalize()));
+                for acc in &accumulators {
+                    row.push(acc.finalize()?);
+                }
                 result.push_row(row)?;
             }
         }
@@ -469,6 +513,42 @@ fn execute_columnar_aggregate(
     Ok(result)
 }
 
+/// The Rust implementation of each aggregate that calls a native aggregate
+/// function, in the order of `aggregates`.
+fn native_aggregates(
+    aggregates: &[Expr],
+    user_function_defs: &FxHashMap<String, UserFunctionDef>,
+) -> Result<Vec<Option<NativeAggregateFunction>>> {
+    aggregates
+        .iter()
+        .map(|agg_expr| match agg_expr {
+            Expr::UserDefinedAggregate { name, .. } => {
+                let def = user_function_defs
+                    .get(name)
+                    .ok_or_else(|| Error::invalid_query(format!("Function not found: {}", name)))?;
+                Ok(Some(def.native_aggregate_function(name)?.clone()))
+            }
+            _ => Ok(None),
+        })
+        .collect()
+}
+
+fn new_accumulators(
+    aggregates: &[Expr],
+    natives: &[Option<NativeAggregateFunction>],
+) -> Vec<Accumulator> {
+    aggregates
+        .iter()
+        .zip(natives)
+        .map(|(agg_expr, native)| match (agg_expr, native) {
+            (Expr::UserDefinedAggregate { name, distinct, .. }, Some(function)) => {
+                Accumulator::Native(function.start(name, *distinct))
+            }
+            _ => Accumulator::from_expr(agg_expr),
+        })
+        .collect()
+}
+
 fn get_record_from_columns(columns: &[&Column], idx: usize) -> Record {
     let values: Vec<Value> = columns.iter().map(|c| c.get_value(idx)).collect();
     Record::from_values(values)
@@ -508,6 +588,7 @@ fn extract_agg_arg(
         | Expr::ScalarFunction { .. }
         | Expr::Window { .. }
         | Expr::AggregateWindow { .. }
+        | Expr::UserDefinedAggregateWindow { .. }
         | Expr::Case { .. }
         | Expr::Cast { .. }
         | Expr::IsNull { .. }
@@ -543,6 +624,22 @@ fn extract_agg_arg(
     }
 }
 
+/// Evaluates every argument of a call to a native aggregate function.
+fn extract_agg_args(
+    evaluator: &ValueEvaluator,
+    agg_expr: &Expr,
+    record: &yachtsql_storage::Record,
+) -> Result<Vec<Value>> {
+    match agg_expr {
+        Expr::UserDefinedAggregate { args, .. } => args
+            .iter()
+            .map(|arg| evaluator.evaluate(arg, record))
+            .collect(),
+        Expr::Alias { expr, .. } => extract_agg_args(evaluator, expr, record),
+        _ => Ok(vec![extract_agg_arg(evaluator, agg_expr, record)?]),
+    }
+}
+
 fn extract_conditional_agg_args(
     evaluator: &ValueEvaluator,
     agg_expr: &Expr,
@@ -583,6 +680,7 @@ fn extract_conditional_agg_args(
         | Expr::ScalarFunction { .. }
         | Expr::Window { .. }
         | Expr::AggregateWindow { .. }
+        | Expr::UserDefinedAggregateWindow { .. }
         | Expr::Case { .. }
         | Expr::Cast { .. }
         | Expr::IsNull { .. }
@@ -641,6 +739,7 @@ fn extract_bivariate_args(
         | Expr::ScalarFunction { .. }
         | Expr::Window { .. }
         | Expr::AggregateWindow { .. }
+        | Expr::UserDefinedAggregateWindow { .. }
         | Expr::Case { .. }
         | Expr::Cast { .. }
         | Expr::IsNull { .. }
@@ -699,6 +798,7 @@ fn extract_order_by_keys(
         | Expr::ScalarFunction { .. }
         | Expr::Window { .. }
         | Expr::AggregateWindow { .. }
+        | Expr::UserDefinedAggregateWindow { .. }
         | Expr::Case { .. }
         | Expr::Cast { .. }
         | Expr::IsNull { .. }
@@ -746,6 +846,7 @@ fn has_order_by(agg_expr: &Expr) -> bool {
         | Expr::ScalarFunction { .. }
         | Expr::Window { .. }
         | Expr::AggregateWindow { .. }
+        | Expr::UserDefinedAggregateWindow { .. }
         | Expr::Case { .. }
         | Expr::Cast { .. }
         | Expr::IsNull { .. }
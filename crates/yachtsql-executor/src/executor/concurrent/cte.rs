@@ -9,6 +9,7 @@ use yachtsql_storage::{Column, Field, Schema, Table};
 
 use super::ConcurrentPlanExecutor;
 use crate::executor::plan_schema_to_schema;
+use crate::memory::MemoryReservation;
 use crate::plan::PhysicalPlan;
 
 impl ConcurrentPlanExecutor {
@@ -27,6 +28,7 @@ impl ConcurrentPlanExecutor {
         let cte_names: Vec<String> = ctes.iter().map(|c| c.name.to_uppercase()).collect();
         let deps = Self::build_cte_dependencies(ctes, &cte_names);
         let waves = Self::topological_waves(ctes.len(), &deps);
+        let materialized = self.memory.reserve(0);
 
         for wave in waves {
             let wave_ctes: Vec<(usize, &CteDefinition)> =
@@ -52,6 +54,7 @@ impl ConcurrentPlanExecutor {
                     if let Some(ref cols) = columns {
                         table = self.apply_cte_column_aliases(&table, cols)?;
                     }
+                    materialized.grow_for_table(&table)?;
                     self.cte_results
                         .write()
                         .unwrap_or_else(|e| e.into_inner())
@@ -60,7 +63,7 @@ impl ConcurrentPlanExecutor {
             } else {
                 for (_, cte) in wave_ctes {
                     if cte.recursive {
-                        self.execute_recursive_cte(cte)?;
+                        self.execute_recursive_cte(cte, &materialized)?;
                     } else {
                         let cte_plan = yachtsql_optimizer::optimize(&cte.query)?;
                         let mut cte_result = self.execute_plan(&cte_plan)?;
@@ -68,6 +71,7 @@ impl ConcurrentPlanExecutor {
                         if let Some(ref columns) = cte.columns {
                             cte_result = self.apply_cte_column_aliases(&cte_result, columns)?;
                         }
+                        materialized.grow_for_table(&cte_result)?;
 
                         self.cte_results
                             .write()
@@ -156,7 +160,11 @@ impl ConcurrentPlanExecutor {
         Ok(result)
     }
 
-    fn execute_recursive_cte(&self, cte: &CteDefinition) -> Result<()> {
+    fn execute_recursive_cte(
+        &self,
+        cte: &CteDefinition,
+        materialized: &MemoryReservation,
+    ) -> Result<()> {
         const MAX_RECURSION_DEPTH: usize = 500;
 
         let (anchor_terms, recursive_terms) = Self::split_recursive_cte(&cte.query, &cte.name);
@@ -179,6 +187,7 @@ impl ConcurrentPlanExecutor {
         if let Some(ref columns) = cte.columns {
             accumulated = self.apply_cte_column_aliases(&accumulated, columns)?;
         }
+        materialized.grow_for_table(&accumulated)?;
 
         self.cte_results
             .write()
@@ -190,6 +199,7 @@ impl ConcurrentPlanExecutor {
 
         while !working_set.is_empty() && iteration < MAX_RECURSION_DEPTH {
             iteration += 1;
+            self.interrupt.check()?;
 
             self.cte_results
                 .write()
@@ -221,6 +231,7 @@ impl ConcurrentPlanExecutor {
             if let Some(ref columns) = cte.columns {
                 working_set = self.apply_cte_column_aliases(&working_set, columns)?;
             }
+            materialized.grow_for_table(&working_set)?;
             accumulated = Table::from_values(schema.clone(), all_results.clone())?;
             if let Some(ref columns) = cte.columns {
                 accumulated = self.apply_cte_column_aliases(&accumulated, columns)?;
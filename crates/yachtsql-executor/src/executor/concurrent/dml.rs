@@ -137,6 +137,7 @@ impl ConcurrentPlanExecutor {
                 }
             }
 
+            self.record_dml_rows(all_rows.len());
             self.tables
                 .with_table_mut(table_name, |target| {
                     for row in all_rows {
@@ -251,6 +252,7 @@ impl ConcurrentPlanExecutor {
             rows
         };
 
+        self.record_dml_rows(rows_to_insert.len());
         self.tables
             .with_table_mut(table_name, |target| {
                 for row in rows_to_insert {
@@ -312,6 +314,7 @@ impl ConcurrentPlanExecutor {
             .any(|a| Self::expr_contains_subquery(&a.value));
 
         let mut new_table = Table::empty(base_schema.clone());
+        let mut updated = 0;
 
         match from {
             Some(from_plan) => {
@@ -422,6 +425,7 @@ impl ConcurrentPlanExecutor {
                     }
                 }
 
+                updated = updated_rows.len();
                 for (idx, target_row) in target_rows.iter().enumerate() {
                     if let Some(updated_row) = updated_rows.get(&idx) {
                         new_table.push_row(updated_row.clone())?;
@@ -449,6 +453,7 @@ impl ConcurrentPlanExecutor {
                         };
 
                         if matches {
+                            updated += 1;
                             let mut new_row = record.into_values();
                             let eval_record = Record::from_slice(&new_row);
                             for assignment in assignments {
@@ -490,7 +495,7 @@ impl ConcurrentPlanExecutor {
 
                     let threshold = self.get_parallel_threshold();
                     if self.is_parallel_enabled() && table_n >= threshold {
-                        let processed_rows: Vec<Vec<Value>> = (0..table_n)
+                        let processed_rows: Vec<(bool, Vec<Value>)> = (0..table_n)
                             .into_par_iter()
                             .map(|row_idx| {
                                 let row_values: Vec<Value> =
@@ -527,14 +532,15 @@ impl ConcurrentPlanExecutor {
                                             }
                                         }
                                     }
-                                    Ok(new_row)
+                                    Ok((true, new_row))
                                 } else {
-                                    Ok(record.into_values())
+                                    Ok((false, record.into_values()))
                                 }
                             })
                             .collect::<Result<Vec<_>>>()?;
 
-                        for row in processed_rows {
+                        for (matched, row) in processed_rows {
+                            updated += usize::from(matched);
                             new_table.push_row(row)?;
                         }
                     } else {
@@ -549,6 +555,7 @@ impl ConcurrentPlanExecutor {
                                 .unwrap_or(true);
 
                             if matches {
+                                updated += 1;
                                 let mut new_row = record.into_values();
                                 let eval_record = Record::from_slice(&new_row);
                                 for assignment in assignments {
@@ -583,6 +590,7 @@ impl ConcurrentPlanExecutor {
             }
         }
 
+        self.record_dml_rows(updated);
         self.tables
             .with_table_mut(table_name, |target| {
                 *target = new_table;
@@ -734,6 +742,7 @@ impl ConcurrentPlanExecutor {
             }
         }
 
+        self.record_dml_rows(table_n - new_table.row_count());
         self.tables
             .with_table_mut(table_name, |target| {
                 *target = new_table;
@@ -1087,6 +1096,7 @@ impl ConcurrentPlanExecutor {
             }
         }
 
+        self.record_dml_rows(updates.len() + deletes.len() + inserts.len());
         self.tables
             .with_table_mut(target_table, |target| {
                 for (idx, new_values) in &updates {
@@ -5,7 +5,7 @@ use rand::seq::SliceRandom;
 use rayon::prelude::*;
 use rustc_hash::FxHashSet;
 use yachtsql_common::error::{Error, Result};
-use yachtsql_common::types::Value;
+use yachtsql_common::types::{DataType, Value};
 use yachtsql_ir::{Expr, PlanSchema, SortExpr};
 use yachtsql_optimizer::SampleType;
 use yachtsql_storage::{Column, Field, FieldMode, Record, Schema, Table};
@@ -14,6 +14,7 @@ use super::{ConcurrentPlanExecutor, compare_values_for_sort};
 use crate::columnar_evaluator::ColumnarEvaluator;
 use crate::executor::plan_schema_to_schema;
 use crate::plan::PhysicalPlan;
+use crate::table_provider::{ScanHints, scan_provider};
 use crate::value_evaluator::ValueEvaluator;
 
 impl ConcurrentPlanExecutor {
@@ -21,6 +22,15 @@ impl ConcurrentPlanExecutor {
         &self,
         table_name: &str,
         planned_schema: &PlanSchema,
+    ) -> Result<Table> {
+        self.execute_scan_with_hints(table_name, planned_schema, ScanHints::default())
+    }
+
+    fn execute_scan_with_hints(
+        &self,
+        table_name: &str,
+        planned_schema: &PlanSchema,
+        hints: ScanHints<'_>,
     ) -> Result<Table> {
         if let Some(cte_table) = self
             .cte_results
@@ -58,6 +68,11 @@ impl ConcurrentPlanExecutor {
             return Ok(self.apply_planned_schema(&guard, planned_schema));
         }
 
+        if let Some(provider) = self.catalog.get_table_provider(table_name) {
+            let table = scan_provider(provider.as_ref(), planned_schema, hints)?;
+            return Ok(self.apply_planned_schema(&table, planned_schema));
+        }
+
         Err(Error::TableNotFound(table_name.to_string()))
     }
 
@@ -99,7 +114,32 @@ impl ConcurrentPlanExecutor {
     }
 
     pub(crate) fn execute_filter(&self, input: &PhysicalPlan, predicate: &Expr) -> Result<Table> {
-        let input_table = self.execute_plan(input)?;
+        self.execute_filter_with_reads(input, predicate, None)
+    }
+
+    fn execute_filter_with_reads(
+        &self,
+        input: &PhysicalPlan,
+        predicate: &Expr,
+        reads: Option<&[Expr]>,
+    ) -> Result<Table> {
+        let input_table = match input {
+            PhysicalPlan::TableScan {
+                table_name, schema, ..
+            } => self.execute_scan_with_hints(
+                table_name,
+                schema,
+                ScanHints {
+                    reads,
+                    predicate: Some(predicate),
+                },
+            )?,
+            _ => self.execute_plan(input)?,
+        };
+        self.filter_table(input_table, predicate)
+    }
+
+    pub(crate) fn filter_table(&self, input_table: Table, predicate: &Expr) -> Result<Table> {
         let schema = input_table.schema().clone();
 
         let has_collation = schema.fields().iter().any(|f| f.collation.is_some());
@@ -168,9 +208,37 @@ impl ConcurrentPlanExecutor {
         expressions: &[Expr],
         schema: &PlanSchema,
     ) -> Result<Table> {
-        let input_table = self.execute_plan(input)?;
+        let input_table = match input {
+            PhysicalPlan::TableScan {
+                table_name,
+                schema: scan_schema,
+                ..
+            } => self.execute_scan_with_hints(
+                table_name,
+                scan_schema,
+                ScanHints {
+                    reads: Some(expressions),
+                    predicate: None,
+                },
+            )?,
+            PhysicalPlan::Filter {
+                input: filter_input,
+                predicate,
+            } => self.execute_filter_with_reads(filter_input, predicate, Some(expressions))?,
+            _ => self.execute_plan(input)?,
+        };
+        self.project_table(input_table, expressions, schema)
+    }
+
+    pub(crate) fn project_table(
+        &self,
+        input_table: Table,
+        expressions: &[Expr],
+        schema: &PlanSchema,
+    ) -> Result<Table> {
         let input_schema = input_table.schema().clone();
-        let result_schema = plan_schema_to_schema(schema);
+        let result_schema =
+            plan_schema_to_schema(&self.resolve_variable_types(expressions, schema));
 
         if expressions.iter().any(Self::expr_contains_subquery) {
             let mut result = Table::empty(result_schema);
@@ -243,6 +311,56 @@ impl ConcurrentPlanExecutor {
         }
     }
 
+    /// A prepared statement is planned before its parameters are bound, so a column
+    /// computed only from parameters has an unknown type in the plan; take it from
+    /// the bound values instead.
+    fn resolve_variable_types(&self, expressions: &[Expr], schema: &PlanSchema) -> PlanSchema {
+        let mut schema = schema.clone();
+        if !schema
+            .fields
+            .iter()
+            .any(|f| f.data_type == DataType::Unknown)
+        {
+            return schema;
+        }
+
+        let vars = self.get_variables();
+        let sys_vars = self.get_system_variables();
+        let empty_schema = Schema::new();
+        let evaluator = ValueEvaluator::new(&empty_schema)
+            .with_variables(&vars)
+            .with_system_variables(&sys_vars);
+        let record = Record::new();
+
+        for (field, expr) in schema.fields.iter_mut().zip(expressions) {
+            if field.data_type != DataType::Unknown || Self::variable_expr_kind(expr) != Some(true)
+            {
+                continue;
+            }
+            if let Ok(value) = evaluator.evaluate(expr, &record) {
+                let data_type = value.data_type();
+                if data_type != DataType::Unknown {
+                    field.data_type = data_type;
+                }
+            }
+        }
+        schema
+    }
+
+    fn variable_expr_kind(expr: &Expr) -> Option<bool> {
+        match expr {
+            Expr::Variable { .. } => Some(true),
+            Expr::Literal(_) => Some(false),
+            Expr::Alias { expr, .. }
+            | Expr::StructAccess { expr, .. }
+            | Expr::UnaryOp { expr, .. } => Self::variable_expr_kind(expr),
+            Expr::BinaryOp { left, right, .. } => {
+                Some(Self::variable_expr_kind(left)? | Self::variable_expr_kind(right)?)
+            }
+            _ => None,
+        }
+    }
+
     pub(crate) fn execute_sample(
         &self,
         input: &PhysicalPlan,
@@ -282,6 +400,9 @@ impl ConcurrentPlanExecutor {
         sort_exprs: &[SortExpr],
     ) -> Result<Table> {
         let input_table = self.execute_plan(input)?;
+        let sort_memory = self.reserve_table(&input_table)?;
+        sort_memory
+            .grow(input_table.row_count() * sort_exprs.len() * std::mem::size_of::<Value>())?;
         let schema = input_table.schema().clone();
         let vars = self.get_variables();
         let sys_vars = self.get_system_variables();
@@ -544,6 +665,8 @@ impl ConcurrentPlanExecutor {
             &udf,
             parallel,
             threshold,
+            &self.interrupt,
+            &self.memory,
         )
     }
 
@@ -554,6 +677,9 @@ impl ConcurrentPlanExecutor {
         schema: &PlanSchema,
     ) -> Result<Table> {
         let input_table = self.execute_plan(input)?;
+        let window_memory = self.reserve_table(&input_table)?;
+        window_memory
+            .grow(input_table.row_count() * window_exprs.len() * std::mem::size_of::<Value>())?;
         let vars = self.get_variables();
         let udf = self.get_user_functions();
         crate::executor::window::compute_window(&input_table, window_exprs, schema, &vars, &udf)
@@ -4,14 +4,7 @@ use std::collections::HashMap;
 use std::fs::File;
 use std::io::{BufRead, BufReader, Write as IoWrite};
 use std::path::Path;
-use std::sync::Arc;
-
-use arrow::array::{
-    ArrayRef, BooleanBuilder, Date32Builder, Float64Builder, Int64Builder, StringBuilder,
-    TimestampMicrosecondBuilder,
-};
-use arrow::datatypes::{DataType as ArrowDataType, Field as ArrowField, Schema as ArrowSchema};
-use arrow::record_batch::RecordBatch;
+
 use chrono::{Datelike, NaiveDate, Utc};
 use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
 use parquet::arrow::arrow_writer::ArrowWriter;
@@ -67,27 +60,11 @@ impl ConcurrentPlanExecutor {
     }
 
     fn export_to_parquet(&self, data: &Table, path: &str) -> Result<Table> {
-        let schema = data.schema();
-        let num_rows = data.num_rows();
-
-        let arrow_fields: Vec<ArrowField> = schema
-            .fields()
-            .iter()
-            .map(|f| {
-                let arrow_type = Self::data_type_to_arrow(&f.data_type);
-                ArrowField::new(&f.name, arrow_type, f.is_nullable())
-            })
-            .collect();
-        let arrow_schema = Arc::new(ArrowSchema::new(arrow_fields));
-
-        let arrays = Self::build_arrow_arrays(data, schema, num_rows)?;
-
-        let batch = RecordBatch::try_new(arrow_schema.clone(), arrays)
-            .map_err(|e| Error::internal(format!("Failed to create RecordBatch: {}", e)))?;
+        let batch = data.to_record_batch()?;
 
         let file = File::create(path)
             .map_err(|e| Error::internal(format!("Failed to create file '{}': {}", path, e)))?;
-        let mut writer = ArrowWriter::try_new(file, arrow_schema, None)
+        let mut writer = ArrowWriter::try_new(file, batch.schema(), None)
             .map_err(|e| Error::internal(format!("Failed to create Parquet writer: {}", e)))?;
         writer
             .write(&batch)
@@ -219,275 +196,6 @@ impl ConcurrentPlanExecutor {
         }
     }
 
-    fn data_type_to_arrow(data_type: &DataType) -> ArrowDataType {
-        match data_type {
-            DataType::Bool => ArrowDataType::Boolean,
-            DataType::Int64 => ArrowDataType::Int64,
-            DataType::Float64 => ArrowDataType::Float64,
-            DataType::String => ArrowDataType::Utf8,
-            DataType::Date => ArrowDataType::Date32,
-            DataType::DateTime => {
-                ArrowDataType::Timestamp(arrow::datatypes::TimeUnit::Microsecond, None)
-            }
-            DataType::Timestamp => ArrowDataType::Timestamp(
-                arrow::datatypes::TimeUnit::Microsecond,
-                Some("UTC".into()),
-            ),
-            DataType::Time => ArrowDataType::Time64(arrow::datatypes::TimeUnit::Microsecond),
-            DataType::Bytes => ArrowDataType::Binary,
-            DataType::Numeric(_) => ArrowDataType::Utf8,
-            DataType::Unknown
-            | DataType::BigNumeric
-            | DataType::Geography
-            | DataType::Json
-            | DataType::Struct(_)
-            | DataType::Array(_)
-            | DataType::Interval
-            | DataType::Range(_) => ArrowDataType::Utf8,
-        }
-    }
-
-    fn build_arrow_arrays(data: &Table, schema: &Schema, num_rows: usize) -> Result<Vec<ArrayRef>> {
-        use yachtsql_storage::Column;
-
-        let mut arrays: Vec<ArrayRef> = Vec::new();
-
-        for (col_idx, field) in schema.fields().iter().enumerate() {
-            let column = data.column(col_idx);
-            let array: ArrayRef = match &field.data_type {
-                DataType::Bool => {
-                    let mut builder = BooleanBuilder::with_capacity(num_rows);
-                    match column {
-                        Some(Column::Bool { data, nulls }) => {
-                            for (i, &val) in data.iter().enumerate() {
-                                if nulls.is_null(i) {
-                                    builder.append_null();
-                                } else {
-                                    builder.append_value(val);
-                                }
-                            }
-                        }
-                        _ => {
-                            for _ in 0..num_rows {
-                                builder.append_null();
-                            }
-                        }
-                    }
-                    Arc::new(builder.finish())
-                }
-                DataType::Int64 => {
-                    let mut builder = Int64Builder::with_capacity(num_rows);
-                    match column {
-                        Some(Column::Int64 { data, nulls }) => {
-                            for (i, &val) in data.iter().enumerate() {
-                                if nulls.is_null(i) {
-                                    builder.append_null();
-                                } else {
-                                    builder.append_value(val);
-                                }
-                            }
-                        }
-                        Some(Column::Float64 { data, nulls }) => {
-                            for (i, &val) in data.iter().enumerate() {
-                                if nulls.is_null(i) {
-                                    builder.append_null();
-                                } else {
-                                    builder.append_value(val as i64);
-                                }
-                            }
-                        }
-                        _ => {
-                            for _ in 0..num_rows {
-                                builder.append_null();
-                            }
-                        }
-                    }
-                    Arc::new(builder.finish())
-                }
-                DataType::Float64 => {
-                    let mut builder = Float64Builder::with_capacity(num_rows);
-                    match column {
-                        Some(Column::Float64 { data, nulls }) => {
-                            for (i, &val) in data.iter().enumerate() {
-                                if nulls.is_null(i) {
-                                    builder.append_null();
-                                } else {
-                                    builder.append_value(val);
-                                }
-                            }
-                        }
-                        Some(Column::Int64 { data, nulls }) => {
-                            for (i, &val) in data.iter().enumerate() {
-                                if nulls.is_null(i) {
-                                    builder.append_null();
-                                } else {
-                                    builder.append_value(val as f64);
-                                }
-                            }
-                        }
-                        _ => {
-                            for _ in 0..num_rows {
-                                builder.append_null();
-                            }
-                        }
-                    }
-                    Arc::new(builder.finish())
-                }
-                DataType::Date => {
-                    let mut builder = Date32Builder::with_capacity(num_rows);
-                    match column {
-                        Some(Column::Date { data, nulls }) => {
-                            let epoch =
-                                NaiveDate::from_ymd_opt(1970, 1, 1).unwrap_or(NaiveDate::MIN);
-                            for (i, d) in data.iter().enumerate() {
-                                if nulls.is_null(i) {
-                                    builder.append_null();
-                                } else {
-                                    let days = d.signed_duration_since(epoch).num_days() as i32;
-                                    builder.append_value(days);
-                                }
-                            }
-                        }
-                        _ => {
-                            for _ in 0..num_rows {
-                                builder.append_null();
-                            }
-                        }
-                    }
-                    Arc::new(builder.finish())
-                }
-                DataType::DateTime | DataType::Timestamp => {
-                    let mut builder = TimestampMicrosecondBuilder::with_capacity(num_rows);
-                    match column {
-                        Some(Column::DateTime { data, nulls }) => {
-                            for (i, dt) in data.iter().enumerate() {
-                                if nulls.is_null(i) {
-                                    builder.append_null();
-                                } else {
-                                    let micros = dt.and_utc().timestamp_micros();
-                                    builder.append_value(micros);
-                                }
-                            }
-                        }
-                        Some(Column::Timestamp { data, nulls }) => {
-                            for (i, ts) in data.iter().enumerate() {
-                                if nulls.is_null(i) {
-                                    builder.append_null();
-                                } else {
-                                    builder.append_value(ts.timestamp_micros());
-                                }
-                            }
-                        }
-                        _ => {
-                            for _ in 0..num_rows {
-                                builder.append_null();
-                            }
-                        }
-                    }
-                    Arc::new(builder.finish())
-                }
-                DataType::Unknown
-                | DataType::Numeric(_)
-                | DataType::BigNumeric
-                | DataType::String
-                | DataType::Bytes
-                | DataType::Time
-                | DataType::Geography
-                | DataType::Json
-                | DataType::Struct(_)
-                | DataType::Array(_)
-                | DataType::Interval
-                | DataType::Range(_) => {
-                    let mut builder = StringBuilder::new();
-                    match column {
-                        Some(Column::String { data, nulls }) => {
-                            for (i, s) in data.iter().enumerate() {
-                                if nulls.is_null(i) {
-                                    builder.append_null();
-                                } else {
-                                    builder.append_value(s);
-                                }
-                            }
-                        }
-                        Some(Column::Numeric { data, nulls }) => {
-                            for (i, n) in data.iter().enumerate() {
-                                if nulls.is_null(i) {
-                                    builder.append_null();
-                                } else {
-                                    builder.append_value(n.to_string());
-                                }
-                            }
-                        }
-                        Some(Column::Bytes { data, nulls }) => {
-                            for (i, b) in data.iter().enumerate() {
-                                if nulls.is_null(i) {
-                                    builder.append_null();
-                                } else {
-                                    builder.append_value(hex::encode(b));
-                                }
-                            }
-                        }
-                        Some(Column::Time { data, nulls }) => {
-                            for (i, t) in data.iter().enumerate() {
-                                if nulls.is_null(i) {
-                                    builder.append_null();
-                                } else {
-                                    builder.append_value(t.to_string());
-                                }
-                            }
-                        }
-                        Some(Column::Json { data, nulls }) => {
-                            for (i, j) in data.iter().enumerate() {
-                                if nulls.is_null(i) {
-                                    builder.append_null();
-                                } else {
-                                    builder.append_value(j.to_string());
-                                }
-                            }
-                        }
-                        Some(Column::Array { data, nulls, .. }) => {
-                            for (i, a) in data.iter().enumerate() {
-                                if nulls.is_null(i) {
-                                    builder.append_null();
-                                } else {
-                                    builder.append_value(format!("{:?}", a));
-                                }
-                            }
-                        }
-                        Some(Column::Struct { data, nulls, .. }) => {
-                            for (i, s) in data.iter().enumerate() {
-                                if nulls.is_null(i) {
-                                    builder.append_null();
-                                } else {
-                                    builder.append_value(format!("{:?}", s));
-                                }
-                            }
-                        }
-                        Some(col) => {
-                            for i in 0..num_rows {
-                                if col.is_null(i) {
-                                    builder.append_null();
-                                } else {
-                                    let val = col.get_value(i);
-                                    builder.append_value(Self::value_to_csv_string(&val));
-                                }
-                            }
-                        }
-                        None => {
-                            for _ in 0..num_rows {
-                                builder.append_null();
-                            }
-                        }
-                    }
-                    Arc::new(builder.finish())
-                }
-            };
-            arrays.push(array);
-        }
-
-        Ok(arrays)
-    }
-
     pub(crate) fn execute_load(
         &self,
         table_name: &str,
@@ -579,8 +287,6 @@ impl ConcurrentPlanExecutor {
     }
 
     fn load_parquet(&self, path: &str, schema: &Schema) -> Result<Vec<Vec<Value>>> {
-        use arrow::array::AsArray;
-
         let file = File::open(path)
             .map_err(|e| Error::internal(format!("Failed to open file '{}': {}", path, e)))?;
 
@@ -590,143 +296,42 @@ impl ConcurrentPlanExecutor {
             .map_err(|e| Error::internal(format!("Failed to build Parquet reader: {}", e)))?;
 
         let mut rows = Vec::new();
-        let target_columns: Vec<String> = schema.fields().iter().map(|f| f.name.clone()).collect();
-        let target_types: Vec<DataType> = schema
-            .fields()
-            .iter()
-            .map(|f| f.data_type.clone())
-            .collect();
-
         for batch_result in reader {
             let batch = batch_result
                 .map_err(|e| Error::internal(format!("Failed to read batch: {}", e)))?;
+            let data = Table::from_record_batch(&batch)?;
 
-            let parquet_schema = batch.schema();
-            let parquet_columns: Vec<String> = parquet_schema
+            let columns: Vec<_> = schema
                 .fields()
                 .iter()
-                .map(|f| f.name().to_string())
-                .collect();
-
-            let column_mapping: Vec<Option<usize>> = target_columns
-                .iter()
-                .map(|target_col| {
-                    parquet_columns
+                .map(|target| {
+                    let index = data
+                        .schema()
+                        .fields()
                         .iter()
-                        .position(|pc| pc.eq_ignore_ascii_case(target_col))
+                        .position(|f| f.name.eq_ignore_ascii_case(&target.name))?;
+                    Some((data.column(index)?, &target.data_type))
                 })
                 .collect();
 
-            for row_idx in 0..batch.num_rows() {
-                let mut row_values = Vec::with_capacity(target_columns.len());
-                for (col_idx, parquet_col_idx) in column_mapping.iter().enumerate() {
-                    let value = match parquet_col_idx {
-                        Some(pci) => {
-                            let array = batch.column(*pci);
-                            self.arrow_array_to_value(array, row_idx, &target_types[col_idx])?
-                        }
-                        None => Value::null(),
-                    };
-                    row_values.push(value);
-                }
-                rows.push(row_values);
+            for row_idx in 0..data.row_count() {
+                rows.push(
+                    columns
+                        .iter()
+                        .map(|column| match column {
+                            Some((column, target_type)) => {
+                                parquet_value(column.get_value(row_idx), target_type)
+                            }
+                            None => Value::null(),
+                        })
+                        .collect(),
+                );
             }
         }
 
         Ok(rows)
     }
 
-    fn arrow_array_to_value(
-        &self,
-        array: &ArrayRef,
-        row_idx: usize,
-        target_type: &DataType,
-    ) -> Result<Value> {
-        use arrow::array::AsArray;
-
-        if array.is_null(row_idx) {
-            return Ok(Value::null());
-        }
-
-        let value = match array.data_type() {
-            ArrowDataType::Boolean => {
-                let arr = array.as_boolean();
-                Value::bool_val(arr.value(row_idx))
-            }
-            ArrowDataType::Int64 => {
-                let arr = array.as_primitive::<arrow::datatypes::Int64Type>();
-                Value::int64(arr.value(row_idx))
-            }
-            ArrowDataType::Int32 => {
-                let arr = array.as_primitive::<arrow::datatypes::Int32Type>();
-                Value::int64(arr.value(row_idx) as i64)
-            }
-            ArrowDataType::Float64 => {
-                let arr = array.as_primitive::<arrow::datatypes::Float64Type>();
-                Value::float64(arr.value(row_idx))
-            }
-            ArrowDataType::Float32 => {
-                let arr = array.as_primitive::<arrow::datatypes::Float32Type>();
-                Value::float64(arr.value(row_idx) as f64)
-            }
-            ArrowDataType::Utf8 => {
-                let arr = array.as_string::<i32>();
-                Value::string(arr.value(row_idx).to_string())
-            }
-            ArrowDataType::LargeUtf8 => {
-                let arr = array.as_string::<i64>();
-                Value::string(arr.value(row_idx).to_string())
-            }
-            ArrowDataType::Date32 => {
-                let arr = array.as_primitive::<arrow::datatypes::Date32Type>();
-                let days = arr.value(row_idx);
-                let date = chrono::NaiveDate::from_num_days_from_ce_opt(days + 719163)
-                    .ok_or_else(|| Error::internal(format!("Invalid date value: {} days", days)))?;
-                Value::date(date)
-            }
-            ArrowDataType::Date64 => {
-                let arr = array.as_primitive::<arrow::datatypes::Date64Type>();
-                let millis = arr.value(row_idx);
-                let date = chrono::DateTime::from_timestamp_millis(millis)
-                    .map(|dt| dt.naive_utc().date())
-                    .ok_or_else(|| {
-                        Error::internal(format!("Invalid date value: {} milliseconds", millis))
-                    })?;
-                Value::date(date)
-            }
-            ArrowDataType::Timestamp(_, _) => {
-                let arr = array.as_primitive::<arrow::datatypes::TimestampMicrosecondType>();
-                let micros = arr.value(row_idx);
-                match target_type {
-                    DataType::DateTime => {
-                        let dt = chrono::DateTime::from_timestamp_micros(micros)
-                            .map(|dt| dt.naive_utc())
-                            .ok_or_else(|| {
-                                Error::internal(format!(
-                                    "Invalid datetime value: {} microseconds",
-                                    micros
-                                ))
-                            })?;
-                        Value::datetime(dt)
-                    }
-                    _ => {
-                        let ts =
-                            chrono::DateTime::from_timestamp_micros(micros).ok_or_else(|| {
-                                Error::internal(format!(
-                                    "Invalid timestamp value: {} microseconds",
-                                    micros
-                                ))
-                            })?;
-                        Value::timestamp(ts)
-                    }
-                }
-            }
-            _ => Value::string(format!("{:?}", array)),
-        };
-
-        Ok(value)
-    }
-
     fn load_json(&self, path: &str, schema: &Schema) -> Result<Vec<Vec<Value>>> {
         let file = File::open(path)
             .map_err(|e| Error::internal(format!("Failed to open file '{}': {}", path, e)))?;
@@ -979,6 +584,16 @@ impl ConcurrentPlanExecutor {
     }
 }
 
+/// Parquet stores `DATETIME` and `TIMESTAMP` alike, differing only in whether a
+/// time zone is attached, so a file's timestamps are read as the target column's.
+fn parquet_value(value: Value, target_type: &DataType) -> Value {
+    match (value, target_type) {
+        (Value::Timestamp(ts), DataType::DateTime) => Value::datetime(ts.naive_utc()),
+        (Value::DateTime(dt), DataType::Timestamp) => Value::timestamp(dt.and_utc()),
+        (value, _) => value,
+    }
+}
+
 #[allow(clippy::collapsible_if)]
 fn validate_file_path(path: &str) -> Result<String> {
     if path.contains("..") {
@@ -11,6 +11,7 @@ use yachtsql_ir::{Expr, JoinType, PlanSchema};
 use yachtsql_storage::{Column, Record, Schema, Table};
 
 use super::{ConcurrentPlanExecutor, plan_schema_to_schema};
+use crate::memory::RowCount;
 use crate::plan::PhysicalPlan;
 use crate::value_evaluator::ValueEvaluator;
 
@@ -184,6 +185,9 @@ impl ConcurrentPlanExecutor {
             .with_user_functions(&udf);
 
         let mut result = Table::empty(result_schema.clone());
+        let output = self
+            .memory
+            .reserve_for_rows(&[&left_table, &right_table], 0);
         let left_n = left_table.row_count();
         let right_n = right_table.row_count();
         let left_columns: Vec<&Column> = left_table
@@ -209,6 +213,9 @@ impl ConcurrentPlanExecutor {
                     let row_batches: Vec<Vec<Vec<Value>>> = (0..left_n)
                         .into_par_iter()
                         .map(|left_idx| {
+                            if self.interrupt.is_interrupted() || output.is_exceeded() {
+                                return Default::default();
+                            }
                             let mut matches = Vec::new();
                             for right_idx in 0..right_n {
                                 let mut combined_values: Vec<Value> =
@@ -236,8 +243,10 @@ impl ConcurrentPlanExecutor {
                             }
                             matches
                         })
+                        .inspect(|rows| output.record_rows(rows.row_count()))
                         .collect();
 
+                    output.check()?;
                     for batch in row_batches {
                         for row in batch {
                             result.push_row(row)?;
@@ -248,6 +257,8 @@ impl ConcurrentPlanExecutor {
                         Vec::with_capacity(left_width + right_width);
                     let mut eval_record = Record::with_capacity(left_width + right_width);
                     for left_idx in 0..left_n {
+                        self.interrupt.check()?;
+                        output.resize_rows(result.row_count())?;
                         for right_idx in 0..right_n {
                             combined_values.clear();
                             combined_values
@@ -279,6 +290,9 @@ impl ConcurrentPlanExecutor {
                     let row_batches: Vec<Vec<Vec<Value>>> = (0..left_n)
                         .into_par_iter()
                         .map(|left_idx| {
+                            if self.interrupt.is_interrupted() || output.is_exceeded() {
+                                return Default::default();
+                            }
                             let mut output_rows = Vec::new();
                             let mut found_match = false;
                             for right_idx in 0..right_n {
@@ -315,8 +329,10 @@ impl ConcurrentPlanExecutor {
                             }
                             output_rows
                         })
+                        .inspect(|rows| output.record_rows(rows.row_count()))
                         .collect();
 
+                    output.check()?;
                     for batch in row_batches {
                         for row in batch {
                             result.push_row(row)?;
@@ -327,6 +343,8 @@ impl ConcurrentPlanExecutor {
                         Vec::with_capacity(left_width + right_width);
                     let mut eval_record = Record::with_capacity(left_width + right_width);
                     for left_idx in 0..left_n {
+                        self.interrupt.check()?;
+                        output.resize_rows(result.row_count())?;
                         let mut found_match = false;
                         for right_idx in 0..right_n {
                             combined_values.clear();
@@ -368,6 +386,9 @@ impl ConcurrentPlanExecutor {
                     let row_batches: Vec<Vec<Vec<Value>>> = (0..right_n)
                         .into_par_iter()
                         .map(|right_idx| {
+                            if self.interrupt.is_interrupted() || output.is_exceeded() {
+                                return Default::default();
+                            }
                             let mut output_rows = Vec::new();
                             let mut found_match = false;
                             for left_idx in 0..left_n {
@@ -405,8 +426,10 @@ impl ConcurrentPlanExecutor {
                             }
                             output_rows
                         })
+                        .inspect(|rows| output.record_rows(rows.row_count()))
                         .collect();
 
+                    output.check()?;
                     for batch in row_batches {
                         for row in batch {
                             result.push_row(row)?;
@@ -417,6 +440,8 @@ impl ConcurrentPlanExecutor {
                         Vec::with_capacity(left_width + right_width);
                     let mut eval_record = Record::with_capacity(left_width + right_width);
                     for right_idx in 0..right_n {
+                        self.interrupt.check()?;
+                        output.resize_rows(result.row_count())?;
                         let mut found_match = false;
                         for left_idx in 0..left_n {
                             combined_values.clear();
@@ -460,6 +485,8 @@ impl ConcurrentPlanExecutor {
                     FxHashSet::with_capacity_and_hasher(right_n, Default::default());
 
                 for left_idx in 0..left_n {
+                    self.interrupt.check()?;
+                    output.resize_rows(result.row_count())?;
                     let mut found_match = false;
                     for right_idx in 0..right_n {
                         combined_values.clear();
@@ -494,6 +521,8 @@ impl ConcurrentPlanExecutor {
                     }
                 }
                 for right_idx in 0..right_n {
+                    self.interrupt.check()?;
+                    output.resize_rows(result.row_count())?;
                     if !matched_right.contains(&right_idx) {
                         combined_values.clear();
                         combined_values.extend(std::iter::repeat_n(Value::Null, left_width));
@@ -509,6 +538,9 @@ impl ConcurrentPlanExecutor {
                     let row_batches: Vec<Vec<Vec<Value>>> = (0..left_n)
                         .into_par_iter()
                         .map(|left_idx| {
+                            if self.interrupt.is_interrupted() || output.is_exceeded() {
+                                return Default::default();
+                            }
                             let mut rows = Vec::with_capacity(right_n);
                             for right_idx in 0..right_n {
                                 let mut combined_values: Vec<Value> =
@@ -521,8 +553,10 @@ impl ConcurrentPlanExecutor {
                             }
                             rows
                         })
+                        .inspect(|rows| output.record_rows(rows.row_count()))
                         .collect();
 
+                    output.check()?;
                     for batch in row_batches {
                         for row in batch {
                             result.push_row(row)?;
@@ -532,6 +566,8 @@ impl ConcurrentPlanExecutor {
                     let mut combined_values: Vec<Value> =
                         Vec::with_capacity(left_width + right_width);
                     for left_idx in 0..left_n {
+                        self.interrupt.check()?;
+                        output.resize_rows(result.row_count())?;
                         for right_idx in 0..right_n {
                             combined_values.clear();
                             combined_values
@@ -549,6 +585,9 @@ impl ConcurrentPlanExecutor {
                     let row_batches: Vec<Option<Vec<Value>>> = (0..left_n)
                         .into_par_iter()
                         .map(|left_idx| {
+                            if self.interrupt.is_interrupted() || output.is_exceeded() {
+                                return Default::default();
+                            }
                             for right_idx in 0..right_n {
                                 let mut combined_values: Vec<Value> =
                                     Vec::with_capacity(left_width + right_width);
@@ -579,8 +618,10 @@ impl ConcurrentPlanExecutor {
                             }
                             None
                         })
+                        .inspect(|rows| output.record_rows(rows.row_count()))
                         .collect();
 
+                    output.check()?;
                     for row in row_batches.into_iter().flatten() {
                         result.push_row(row)?;
                     }
@@ -589,6 +630,8 @@ impl ConcurrentPlanExecutor {
                         Vec::with_capacity(left_width + right_width);
                     let mut eval_record = Record::with_capacity(left_width + right_width);
                     for left_idx in 0..left_n {
+                        self.interrupt.check()?;
+                        output.resize_rows(result.row_count())?;
                         let mut found_match = false;
                         for right_idx in 0..right_n {
                             combined_values.clear();
@@ -626,6 +669,9 @@ impl ConcurrentPlanExecutor {
                     let row_batches: Vec<Option<Vec<Value>>> = (0..left_n)
                         .into_par_iter()
                         .map(|left_idx| {
+                            if self.interrupt.is_interrupted() || output.is_exceeded() {
+                                return Default::default();
+                            }
                             for right_idx in 0..right_n {
                                 let mut combined_values: Vec<Value> =
                                     Vec::with_capacity(left_width + right_width);
@@ -654,8 +700,10 @@ impl ConcurrentPlanExecutor {
                                 left_columns.iter().map(|c| c.get_value(left_idx)).collect();
                             Some(left_row)
                         })
+                        .inspect(|rows| output.record_rows(rows.row_count()))
                         .collect();
 
+                    output.check()?;
                     for row in row_batches.into_iter().flatten() {
                         result.push_row(row)?;
                     }
@@ -664,6 +712,8 @@ impl ConcurrentPlanExecutor {
                         Vec::with_capacity(left_width + right_width);
                     let mut eval_record = Record::with_capacity(left_width + right_width);
                     for left_idx in 0..left_n {
+                        self.interrupt.check()?;
+                        output.resize_rows(result.row_count())?;
                         let mut found_match = false;
                         for right_idx in 0..right_n {
                             combined_values.clear();
@@ -701,6 +751,9 @@ impl ConcurrentPlanExecutor {
                     let row_batches: Vec<Option<Vec<Value>>> = (0..right_n)
                         .into_par_iter()
                         .map(|right_idx| {
+                            if self.interrupt.is_interrupted() || output.is_exceeded() {
+                                return Default::default();
+                            }
                             for left_idx in 0..left_n {
                                 let mut combined_values: Vec<Value> =
                                     Vec::with_capacity(left_width + right_width);
@@ -731,8 +784,10 @@ impl ConcurrentPlanExecutor {
                             }
                             None
                         })
+                        .inspect(|rows| output.record_rows(rows.row_count()))
                         .collect();
 
+                    output.check()?;
                     for row in row_batches.into_iter().flatten() {
                         result.push_row(row)?;
                     }
@@ -741,6 +796,8 @@ impl ConcurrentPlanExecutor {
                         Vec::with_capacity(left_width + right_width);
                     let mut eval_record = Record::with_capacity(left_width + right_width);
                     for right_idx in 0..right_n {
+                        self.interrupt.check()?;
+                        output.resize_rows(result.row_count())?;
                         let mut found_match = false;
                         for left_idx in 0..left_n {
                             combined_values.clear();
@@ -780,6 +837,9 @@ impl ConcurrentPlanExecutor {
                     let row_batches: Vec<Option<Vec<Value>>> = (0..right_n)
                         .into_par_iter()
                         .map(|right_idx| {
+                            if self.interrupt.is_interrupted() || output.is_exceeded() {
+                                return Default::default();
+                            }
                             for left_idx in 0..left_n {
                                 let mut combined_values: Vec<Value> =
                                     Vec::with_capacity(left_width + right_width);
@@ -810,8 +870,10 @@ impl ConcurrentPlanExecutor {
                                 .collect();
                             Some(right_row)
                         })
+                        .inspect(|rows| output.record_rows(rows.row_count()))
                         .collect();
 
+                    output.check()?;
                     for row in row_batches.into_iter().flatten() {
                         result.push_row(row)?;
                     }
@@ -820,6 +882,8 @@ impl ConcurrentPlanExecutor {
                         Vec::with_capacity(left_width + right_width);
                     let mut eval_record = Record::with_capacity(left_width + right_width);
                     for right_idx in 0..right_n {
+                        self.interrupt.check()?;
+                        output.resize_rows(result.row_count())?;
                         let mut found_match = false;
                         for left_idx in 0..left_n {
                             combined_values.clear();
@@ -912,6 +976,15 @@ impl ConcurrentPlanExecutor {
         let udf = self.get_user_functions();
         let threshold = self.get_parallel_threshold();
 
+        let _hash_table_memory = self.reserve_table(if right_n <= left_n {
+            &right_table
+        } else {
+            &left_table
+        })?;
+        let output = self
+            .memory
+            .reserve_for_rows(&[&left_table, &right_table], 0);
+
         match join_type {
             JoinType::Inner => {
                 let build_on_right = right_n <= left_n;
@@ -964,6 +1037,7 @@ impl ConcurrentPlanExecutor {
                     let mut build_record = Record::with_capacity(build_cols.len());
                     let mut build_values: Vec<Value> = Vec::with_capacity(build_cols.len());
                     for build_idx in 0..build_n {
+                        self.interrupt.check_row(build_idx)?;
                         build_values.clear();
                         build_values.extend(build_cols.iter().map(|c| c.get_value(build_idx)));
                         build_record.set_from_slice(&build_values);
@@ -986,6 +1060,11 @@ impl ConcurrentPlanExecutor {
                         (0..probe_n)
                             .into_par_iter()
                             .map(|probe_idx| {
+                                if self.interrupt.is_interrupted_at(probe_idx)
+                                    || output.is_exceeded()
+                                {
+                                    return Default::default();
+                                }
                                 let key_values =
                                     extract_key_values_direct(probe_cols, probe_idx, indices);
                                 if key_values.iter().any(|v| matches!(v, Value::Null)) {
@@ -1018,11 +1097,17 @@ impl ConcurrentPlanExecutor {
                                     })
                                     .collect()
                             })
+                            .inspect(|rows| output.record_rows(rows.row_count()))
                             .collect()
                     } else {
                         (0..probe_n)
                             .into_par_iter()
                             .map(|probe_idx| {
+                                if self.interrupt.is_interrupted_at(probe_idx)
+                                    || output.is_exceeded()
+                                {
+                                    return Default::default();
+                                }
                                 let probe_evaluator = ValueEvaluator::new(probe_schema)
                                     .with_variables(&vars)
                                     .with_system_variables(&sys_vars)
@@ -1068,10 +1153,12 @@ impl ConcurrentPlanExecutor {
                                     })
                                     .collect()
                             })
+                            .inspect(|rows| output.record_rows(rows.row_count()))
                             .collect()
                     };
 
                     let mut result = Table::empty(result_schema);
+                    output.check()?;
                     for batch in row_batches {
                         for row in batch {
                             result.push_row(row)?;
@@ -1083,6 +1170,8 @@ impl ConcurrentPlanExecutor {
                     let mut combined: Vec<Value> = Vec::with_capacity(left_width + right_width);
                     if let Some(ref indices) = probe_key_indices {
                         for probe_idx in 0..probe_n {
+                            self.interrupt.check_row(probe_idx)?;
+                            output.resize_rows(result.row_count())?;
                             let key_values =
                                 extract_key_values_direct(probe_cols, probe_idx, indices);
                             if key_values.iter().any(|v| matches!(v, Value::Null)) {
@@ -1120,6 +1209,8 @@ impl ConcurrentPlanExecutor {
                     let mut probe_record = Record::with_capacity(probe_cols.len());
                     let mut probe_values: Vec<Value> = Vec::with_capacity(probe_cols.len());
                     for probe_idx in 0..probe_n {
+                        self.interrupt.check_row(probe_idx)?;
+                        output.resize_rows(result.row_count())?;
                         probe_values.clear();
                         probe_values.extend(probe_cols.iter().map(|c| c.get_value(probe_idx)));
                         probe_record.set_from_slice(&probe_values);
@@ -1165,6 +1256,7 @@ impl ConcurrentPlanExecutor {
                 let mut right_record = Record::with_capacity(right_cols.len());
                 let mut right_values: Vec<Value> = Vec::with_capacity(right_cols.len());
                 for right_idx in 0..right_n {
+                    self.interrupt.check_row(right_idx)?;
                     right_values.clear();
                     right_values.extend(right_cols.iter().map(|c| c.get_value(right_idx)));
                     right_record.set_from_slice(&right_values);
@@ -1191,6 +1283,8 @@ impl ConcurrentPlanExecutor {
                 let mut left_values: Vec<Value> = Vec::with_capacity(left_cols.len());
 
                 for left_idx in 0..left_n {
+                    self.interrupt.check_row(left_idx)?;
+                    output.resize_rows(result.row_count())?;
                     left_values.clear();
                     left_values.extend(left_cols.iter().map(|c| c.get_value(left_idx)));
                     left_record.set_from_slice(&left_values);
@@ -1236,6 +1330,7 @@ impl ConcurrentPlanExecutor {
                 let mut left_record = Record::with_capacity(left_cols.len());
                 let mut left_values: Vec<Value> = Vec::with_capacity(left_cols.len());
                 for left_idx in 0..left_n {
+                    self.interrupt.check_row(left_idx)?;
                     left_values.clear();
                     left_values.extend(left_cols.iter().map(|c| c.get_value(left_idx)));
                     left_record.set_from_slice(&left_values);
@@ -1262,6 +1357,8 @@ impl ConcurrentPlanExecutor {
                 let mut right_values: Vec<Value> = Vec::with_capacity(right_cols.len());
 
                 for right_idx in 0..right_n {
+                    self.interrupt.check_row(right_idx)?;
+                    output.resize_rows(result.row_count())?;
                     right_values.clear();
                     right_values.extend(right_cols.iter().map(|c| c.get_value(right_idx)));
                     right_record.set_from_slice(&right_values);
@@ -1307,6 +1404,7 @@ impl ConcurrentPlanExecutor {
                 let mut right_record = Record::with_capacity(right_cols.len());
                 let mut right_values: Vec<Value> = Vec::with_capacity(right_cols.len());
                 for right_idx in 0..right_n {
+                    self.interrupt.check_row(right_idx)?;
                     right_values.clear();
                     right_values.extend(right_cols.iter().map(|c| c.get_value(right_idx)));
                     right_record.set_from_slice(&right_values);
@@ -1335,6 +1433,8 @@ impl ConcurrentPlanExecutor {
                 let mut left_values: Vec<Value> = Vec::with_capacity(left_cols.len());
 
                 for left_idx in 0..left_n {
+                    self.interrupt.check_row(left_idx)?;
+                    output.resize_rows(result.row_count())?;
                     left_values.clear();
                     left_values.extend(left_cols.iter().map(|c| c.get_value(left_idx)));
                     left_record.set_from_slice(&left_values);
@@ -1370,6 +1470,8 @@ impl ConcurrentPlanExecutor {
                 }
 
                 for right_idx in 0..right_n {
+                    self.interrupt.check_row(right_idx)?;
+                    output.resize_rows(result.row_count())?;
                     if !matched_right.contains(&right_idx) {
                         combined.clear();
                         combined.extend(std::iter::repeat_n(Value::Null, left_width));
@@ -1394,6 +1496,7 @@ impl ConcurrentPlanExecutor {
                 let mut right_record = Record::with_capacity(right_cols.len());
                 let mut right_values: Vec<Value> = Vec::with_capacity(right_cols.len());
                 for right_idx in 0..right_n {
+                    self.interrupt.check_row(right_idx)?;
                     right_values.clear();
                     right_values.extend(right_cols.iter().map(|c| c.get_value(right_idx)));
                     right_record.set_from_slice(&right_values);
@@ -1419,6 +1522,8 @@ impl ConcurrentPlanExecutor {
                 let mut left_values: Vec<Value> = Vec::with_capacity(left_cols.len());
 
                 for left_idx in 0..left_n {
+                    self.interrupt.check_row(left_idx)?;
+                    output.resize_rows(result.row_count())?;
                     left_values.clear();
                     left_values.extend(left_cols.iter().map(|c| c.get_value(left_idx)));
                     left_record.set_from_slice(&left_values);
@@ -1451,6 +1556,7 @@ impl ConcurrentPlanExecutor {
                 let mut right_record = Record::with_capacity(right_cols.len());
                 let mut right_values: Vec<Value> = Vec::with_capacity(right_cols.len());
                 for right_idx in 0..right_n {
+                    self.interrupt.check_row(right_idx)?;
                     right_values.clear();
                     right_values.extend(right_cols.iter().map(|c| c.get_value(right_idx)));
                     right_record.set_from_slice(&right_values);
@@ -1476,6 +1582,8 @@ impl ConcurrentPlanExecutor {
                 let mut left_values: Vec<Value> = Vec::with_capacity(left_cols.len());
 
                 for left_idx in 0..left_n {
+                    self.interrupt.check_row(left_idx)?;
+                    output.resize_rows(result.row_count())?;
                     left_values.clear();
                     left_values.extend(left_cols.iter().map(|c| c.get_value(left_idx)));
                     left_record.set_from_slice(&left_values);
@@ -1506,6 +1614,7 @@ impl ConcurrentPlanExecutor {
                 let mut left_record = Record::with_capacity(left_cols.len());
                 let mut left_values: Vec<Value> = Vec::with_capacity(left_cols.len());
                 for left_idx in 0..left_n {
+                    self.interrupt.check_row(left_idx)?;
                     left_values.clear();
                     left_values.extend(left_cols.iter().map(|c| c.get_value(left_idx)));
                     left_record.set_from_slice(&left_values);
@@ -1531,6 +1640,8 @@ impl ConcurrentPlanExecutor {
                 let mut right_values: Vec<Value> = Vec::with_capacity(right_cols.len());
 
                 for right_idx in 0..right_n {
+                    self.interrupt.check_row(right_idx)?;
+                    output.resize_rows(result.row_count())?;
                     right_values.clear();
                     right_values.extend(right_cols.iter().map(|c| c.get_value(right_idx)));
                     right_record.set_from_slice(&right_values);
@@ -1563,6 +1674,7 @@ impl ConcurrentPlanExecutor {
                 let mut left_record = Record::with_capacity(left_cols.len());
                 let mut left_values: Vec<Value> = Vec::with_capacity(left_cols.len());
                 for left_idx in 0..left_n {
+                    self.interrupt.check_row(left_idx)?;
                     left_values.clear();
                     left_values.extend(left_cols.iter().map(|c| c.get_value(left_idx)));
                     left_record.set_from_slice(&left_values);
@@ -1588,6 +1700,8 @@ impl ConcurrentPlanExecutor {
                 let mut right_values: Vec<Value> = Vec::with_capacity(right_cols.len());
 
                 for right_idx in 0..right_n {
+                    self.interrupt.check_row(right_idx)?;
+                    output.resize_rows(result.row_count())?;
                     right_values.clear();
                     right_values.extend(right_cols.iter().map(|c| c.get_value(right_idx)));
                     right_record.set_from_slice(&right_values);
@@ -9,26 +9,87 @@ mod io;
 mod join;
 mod scripting;
 mod set_ops;
+mod stream;
 mod subquery;
 mod unnest;
 mod utils;
 
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, RwLock};
 
 use rustc_hash::FxHashMap;
+pub(crate) use stream::emit_in_batches;
 use tracing::instrument;
 pub(crate) use utils::{coerce_value, compare_values_for_sort, default_value_for_type};
 use yachtsql_common::error::{Error, Result};
 use yachtsql_common::types::Value;
-use yachtsql_ir::{Expr, PlanSchema};
+use yachtsql_ir::{Expr, FunctionBody, PlanSchema};
 use yachtsql_storage::{Record, Schema, Table};
 
+use crate::cancellation::QueryInterrupt;
 use crate::concurrent_catalog::{ConcurrentCatalog, TableLockSet};
 use crate::concurrent_session::ConcurrentSession;
 pub(crate) use crate::executor::plan_schema_to_schema;
+use crate::memory::{MemoryBudget, MemoryReservation};
 use crate::plan::PhysicalPlan;
 use crate::value_evaluator::{UserFunctionDef, ValueEvaluator};
 
+fn user_function_defs(catalog: &ConcurrentCatalog) -> FxHashMap<String, UserFunctionDef> {
+    let mut defs: FxHashMap<String, UserFunctionDef> = catalog
+        .get_functions()
+        .iter()
+        .map(|(name, func)| {
+            (
+                name.clone(),
+                UserFunctionDef {
+                    parameters: func.parameters.iter().map(|p| p.name.clone()).collect(),
+                    body: func.body.clone(),
+                    native: None,
+                    native_aggregate: None,
+                    native_table: None,
+                },
+            )
+        })
+        .collect();
+    for (name, function) in catalog.native_functions() {
+        defs.insert(
+            name,
+            UserFunctionDef {
+                parameters: Vec::new(),
+                body: FunctionBody::Native,
+                native: Some(function),
+                native_aggregate: None,
+                native_table: None,
+            },
+        );
+    }
+    for (name, function) in catalog.native_aggregate_functions() {
+        defs.insert(
+            name,
+            UserFunctionDef {
+                parameters: Vec::new(),
+                body: FunctionBody::Native,
+                native: None,
+                native_aggregate: Some(function),
+                native_table: None,
+            },
+        );
+    }
+    for (name, function) in catalog.native_table_functions() {
+        defs.insert(
+            name,
+            UserFunctionDef {
+                parameters: Vec::new(),
+                body: FunctionBody::Native,
+                native: None,
+                native_aggregate: None,
+                native_table: Some(function),
+            },
+        );
+    }
+    defs
+}
+
 #[derive(Clone)]
 pub struct ConcurrentPlanExecutor {
     pub(crate) catalog: Arc<ConcurrentCatalog>,
@@ -38,6 +99,9 @@ pub struct ConcurrentPlanExecutor {
     pub(crate) system_variables: Arc<RwLock<FxHashMap<String, Value>>>,
     pub(crate) cte_results: Arc<RwLock<FxHashMap<String, Table>>>,
     pub(crate) user_function_defs: Arc<RwLock<FxHashMap<String, UserFunctionDef>>>,
+    pub(crate) interrupt: QueryInterrupt,
+    pub(crate) memory: MemoryBudget,
+    dml_rows: Arc<AtomicU64>,
 }
 
 impl ConcurrentPlanExecutor {
@@ -46,19 +110,7 @@ impl ConcurrentPlanExecutor {
         session: Arc<ConcurrentSession>,
         tables: TableLockSet,
     ) -> Self {
-        let user_function_defs = catalog
-            .get_functions()
-            .iter()
-            .map(|(name, func)| {
-                (
-                    name.clone(),
-                    UserFunctionDef {
-                        parameters: func.parameters.iter().map(|p| p.name.clone()).collect(),
-                        body: func.body.clone(),
-                    },
-                )
-            })
-            .collect();
+        let user_function_defs = user_function_defs(&catalog);
 
         let variables: FxHashMap<String, Value> = session
             .variables()
@@ -76,9 +128,42 @@ impl ConcurrentPlanExecutor {
             system_variables: Arc::new(RwLock::new(system_variables)),
             cte_results: Arc::new(RwLock::new(FxHashMap::default())),
             user_function_defs: Arc::new(RwLock::new(user_function_defs)),
+            interrupt: QueryInterrupt::default(),
+            memory: MemoryBudget::default(),
+            dml_rows: Arc::new(AtomicU64::new(0)),
         }
     }
 
+    pub(crate) fn with_interrupt(mut self, interrupt: QueryInterrupt) -> Self {
+        self.interrupt = interrupt;
+        self
+    }
+
+    pub(crate) fn with_memory_budget(mut self, memory: MemoryBudget) -> Self {
+        self.memory = memory;
+        self
+    }
+
+    pub(crate) fn record_dml_rows(&self, rows: usize) {
+        self.dml_rows.fetch_add(rows as u64, Ordering::Relaxed);
+    }
+
+    /// Rows inserted, updated or deleted by the DML statements this executor ran.
+    pub(crate) fn dml_rows(&self) -> u64 {
+        self.dml_rows.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn reserve_table(&self, table: &Table) -> Result<MemoryReservation> {
+        self.memory.reserve_table(table)
+    }
+
+    pub(crate) fn bind_variables(&self, values: FxHashMap<String, Value>) {
+        self.variables
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .extend(values);
+    }
+
     pub(crate) fn get_system_variables(
         &self,
     ) -> std::sync::RwLockReadGuard<'_, FxHashMap<String, Value>> {
@@ -88,20 +173,7 @@ impl ConcurrentPlanExecutor {
     }
 
     fn refresh_user_functions(&self) {
-        let new_defs: FxHashMap<String, UserFunctionDef> = self
-            .catalog
-            .get_functions()
-            .iter()
-            .map(|(name, func)| {
-                (
-                    name.clone(),
-                    UserFunctionDef {
-                        parameters: func.parameters.iter().map(|p| p.name.clone()).collect(),
-                        body: func.body.clone(),
-                    },
-                )
-            })
-            .collect();
+        let new_defs = user_function_defs(&self.catalog);
         *self
             .user_function_defs
             .write()
@@ -159,6 +231,13 @@ impl ConcurrentPlanExecutor {
 
     #[instrument(skip(self))]
     pub fn execute_plan(&self, plan: &PhysicalPlan) -> Result<Table> {
+        self.interrupt.check()?;
+        let result = self.execute_plan_node(plan);
+        self.interrupt.check()?;
+        result
+    }
+
+    fn execute_plan_node(&self, plan: &PhysicalPlan) -> Result<Table> {
         match plan {
             PhysicalPlan::TableScan {
                 table_name, schema, ..
@@ -499,7 +578,7 @@ impl ConcurrentPlanExecutor {
                 Ok(Table::empty(Schema::new()))
             }
             PhysicalPlan::Rollback => {
-                self.rollback_transaction();
+                self.rollback_transaction()?;
                 Ok(Table::empty(Schema::new()))
             }
             PhysicalPlan::TryCatch {
@@ -626,7 +705,7 @@ impl ConcurrentPlanExecutor {
                 } else {
                     "Assertion failed".to_string()
                 };
-                Err(Error::InvalidQuery(format!("ASSERT failed: {}", msg)))
+                Err(Error::assertion_failed(msg))
             }
             _ => Err(Error::InvalidQuery(
                 "ASSERT condition must evaluate to a boolean".into(),
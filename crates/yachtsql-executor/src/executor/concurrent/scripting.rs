@@ -7,6 +7,7 @@ use yachtsql_optimizer::optimize;
 use yachtsql_storage::{Column, Record, Schema, Table};
 
 use super::{ConcurrentPlanExecutor, default_value_for_type};
+use crate::cancellation::is_interrupt_error;
 use crate::plan::{PhysicalPlan, PhysicalPlanExt};
 use crate::value_evaluator::ValueEvaluator;
 
@@ -137,31 +138,15 @@ impl ConcurrentPlanExecutor {
         Ok(last_result)
     }
 
-    pub(crate) fn rollback_transaction(&self) {
-        if let Some(mut snapshot) = self.catalog.take_transaction_snapshot() {
-            let mut restored_tables = Vec::new();
-            for (name, table_data) in &snapshot.tables {
-                if self
-                    .tables
-                    .with_table_mut(name, |table| {
-                        *table = table_data.clone();
-                    })
-                    .is_some()
-                {
-                    restored_tables.push(name.clone());
-                }
-            }
-            for name in restored_tables {
-                snapshot.tables.remove(&name);
-            }
-            for (name, table_data) in snapshot.tables {
-                if let Some(handle) = self.catalog.get_table_handle(&name)
-                    && let Some(mut table) = handle.try_write()
-                {
-                    *table = table_data;
-                }
-            }
+    pub(crate) fn rollback_transaction(&self) -> Result<()> {
+        let held = self
+            .catalog
+            .rollback_tables(|name| self.tables.holds_write(name))?;
+        for (name, table_data) in held {
+            self.tables
+                .with_table_mut(&name, |table| *table = table_data);
         }
+        Ok(())
     }
 
     pub(crate) fn execute_declare(
@@ -353,6 +338,7 @@ impl ConcurrentPlanExecutor {
         const MAX_ITERATIONS: usize = 10000;
 
         'outer: loop {
+            self.interrupt.check()?;
             let cond = {
                 let vars = self.get_variables();
                 let sys_vars = self.get_system_variables();
@@ -418,6 +404,7 @@ impl ConcurrentPlanExecutor {
         const MAX_ITERATIONS: usize = 10000;
 
         'outer: loop {
+            self.interrupt.check()?;
             for stmt in body {
                 match self.execute_plan(stmt) {
                     Ok(r) => result = r,
@@ -486,6 +473,7 @@ impl ConcurrentPlanExecutor {
         const MAX_ITERATIONS: usize = 10000;
 
         'outer: loop {
+            self.interrupt.check()?;
             for stmt in body {
                 match self.execute_plan(stmt) {
                     Ok(r) => result = r,
@@ -544,6 +532,7 @@ impl ConcurrentPlanExecutor {
             .collect();
 
         'outer: for row_idx in 0..n {
+            self.interrupt.check()?;
             let values: Vec<Value> = columns.iter().map(|c| c.get_value(row_idx)).collect();
             let struct_fields: Vec<(String, Value)> = schema_fields
                 .iter()
@@ -618,6 +607,7 @@ impl ConcurrentPlanExecutor {
                 Err(Error::InvalidQuery(msg)) if msg == "RETURN outside of function" => {
                     return Ok(last_result);
                 }
+                Err(e) if is_interrupt_error(&e) => return Err(e),
                 Err(e) => {
                     let error_message = e.to_string();
                     let stmt_text = source_sql.clone().unwrap_or_else(|| format!("{:?}", plan));
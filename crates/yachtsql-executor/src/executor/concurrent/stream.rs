@@ -0,0 +1,134 @@
+#![coverage(off)]
+
+use yachtsql_common::error::{Error, Result};
+use yachtsql_ir::PlanSchema;
+use yachtsql_storage::Table;
+
+use super::ConcurrentPlanExecutor;
+use crate::plan::PhysicalPlan;
+use crate::table_provider::{ScanHints, scan_provider};
+
+impl ConcurrentPlanExecutor {
+    pub(crate) fn execute_plan_batched(
+        &self,
+        plan: &PhysicalPlan,
+        batch_size: usize,
+        emit: &mut dyn FnMut(Table) -> bool,
+    ) -> Result<()> {
+        let batch_size = batch_size.max(1);
+        let (pipeline, limit, offset) = match plan {
+            PhysicalPlan::Limit {
+                input,
+                limit,
+                offset,
+            } => (input.as_ref(), *limit, offset.unwrap_or(0)),
+            _ => (plan, None, 0),
+        };
+
+        let Some(table_name) = Self::streamable_scan(pipeline) else {
+            let table = self.execute_plan(plan)?;
+            emit_in_batches(&table, batch_size, emit)?;
+            return Ok(());
+        };
+
+        let source = self.source_table(table_name)?;
+        let mut to_skip = offset;
+        let mut remaining = limit.unwrap_or(usize::MAX);
+        let mut start = 0;
+
+        while start < source.row_count() && remaining > 0 {
+            self.interrupt.check()?;
+            let end = (start + batch_size).min(source.row_count());
+            let chunk = source.gather_rows(&(start..end).collect::<Vec<_>>())?;
+            start = end;
+
+            let output = self.execute_pipeline_on(pipeline, chunk)?;
+
+            let rows = output.row_count();
+            let skipped = to_skip.min(rows);
+            to_skip -= skipped;
+            let taken = (rows - skipped).min(remaining);
+            if taken == 0 {
+                continue;
+            }
+            remaining -= taken;
+            let batch = if skipped == 0 && taken == rows {
+                output
+            } else {
+                output.gather_rows(&(skipped..skipped + taken).collect::<Vec<_>>())?
+            };
+            if !emit(batch) {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs the filters and projections of a [`streamable_scan`](Self::streamable_scan)
+    /// pipeline over one chunk of its source table. The chunk is fed to the scan
+    /// directly, so subqueries in the pipeline still read the whole table.
+    fn execute_pipeline_on(&self, plan: &PhysicalPlan, chunk: Table) -> Result<Table> {
+        match plan {
+            PhysicalPlan::TableScan { schema, .. } => Ok(self.apply_planned_schema(&chunk, schema)),
+            PhysicalPlan::Filter { input, predicate } => {
+                let input = self.execute_pipeline_on(input, chunk)?;
+                self.filter_table(input, predicate)
+            }
+            PhysicalPlan::Project {
+                input,
+                expressions,
+                schema,
+            } => {
+                let input = self.execute_pipeline_on(input, chunk)?;
+                self.project_table(input, expressions, schema)
+            }
+            _ => Err(Error::internal("plan is not a streamable scan pipeline")),
+        }
+    }
+
+    fn streamable_scan(plan: &PhysicalPlan) -> Option<&str> {
+        match plan {
+            PhysicalPlan::TableScan { table_name, .. } => Some(table_name),
+            PhysicalPlan::Filter { input, .. } | PhysicalPlan::Project { input, .. } => {
+                Self::streamable_scan(input)
+            }
+            _ => None,
+        }
+    }
+
+    fn source_table(&self, table_name: &str) -> Result<Table> {
+        if let Some(table) = self.tables.get_table(table_name) {
+            return Ok(table);
+        }
+        if let Some(handle) = self.catalog.get_table_handle(table_name) {
+            return Ok(handle.read().clone());
+        }
+        if let Some(provider) = self.catalog.get_table_provider(table_name) {
+            return scan_provider(provider.as_ref(), &PlanSchema::new(), ScanHints::default());
+        }
+        Err(Error::TableNotFound(table_name.to_string()))
+    }
+}
+
+/// Hands `table` to `emit` in slices of at most `batch_size` rows, stopping when
+/// `emit` returns `false`.
+pub(crate) fn emit_in_batches(
+    table: &Table,
+    batch_size: usize,
+    emit: &mut dyn FnMut(Table) -> bool,
+) -> Result<()> {
+    let batch_size = batch_size.max(1);
+    if table.row_count() <= batch_size {
+        emit(table.clone());
+        return Ok(());
+    }
+    let mut start = 0;
+    while start < table.row_count() {
+        let end = (start + batch_size).min(table.row_count());
+        if !emit(table.gather_rows(&(start..end).collect::<Vec<_>>())?) {
+            break;
+        }
+        start = end;
+    }
+    Ok(())
+}
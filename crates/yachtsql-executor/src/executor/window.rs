@@ -9,7 +9,8 @@ use yachtsql_ir::{
 use yachtsql_storage::{Column, Record, Schema, Table};
 
 use super::plan_schema_to_schema;
-use crate::value_evaluator::ValueEvaluator;
+use crate::native_function::NativeAggregateFunction;
+use crate::value_evaluator::{UserFunctionDef, ValueEvaluator};
 
 pub(crate) fn get_record_from_columns(columns: &[&Column], idx: usize) -> Record {
     let values: Vec<Value> = columns.iter().map(|c| c.get_value(idx)).collect();
@@ -25,7 +26,7 @@ pub(crate) fn compute_window(
     window_exprs: &[Expr],
     schema: &PlanSchema,
     variables: &FxHashMap<String, Value>,
-    user_function_defs: &FxHashMap<String, crate::value_evaluator::UserFunctionDef>,
+    user_function_defs: &FxHashMap<String, UserFunctionDef>,
 ) -> Result<Table> {
     let input_schema = input_table.schema().clone();
     let result_schema = plan_schema_to_schema(schema);
@@ -42,7 +43,8 @@ pub(crate) fn compute_window(
     let mut all_window_results: Vec<Vec<Value>> = vec![vec![Value::Null; n]; window_exprs.len()];
 
     for (expr_idx, window_expr) in window_exprs.iter().enumerate() {
-        let (partition_by, order_by, frame, func_type) = extract_window_spec(window_expr)?;
+        let (partition_by, order_by, frame, func_type) =
+            extract_window_spec(window_expr, user_function_defs)?;
 
         let partitions = partition_rows_columnar(n, &columns, &partition_by, &evaluator)?;
 
@@ -79,6 +81,7 @@ pub(crate) fn compute_window(
 
 fn extract_window_spec(
     expr: &Expr,
+    user_function_defs: &FxHashMap<String, UserFunctionDef>,
 ) -> Result<(
     Vec<Expr>,
     Vec<SortExpr>,
@@ -110,36 +113,53 @@ fn extract_window_spec(
             frame.clone(),
             WindowFuncType::Aggregate(*func),
         )),
-        Expr::Alias { expr: inner, .. } => extract_window_spec(inner),
+        Expr::UserDefinedAggregateWindow {
+            name,
+            partition_by,
+            order_by,
+            frame,
+            ..
+        } => {
+            let def = user_function_defs
+                .get(name)
+                .ok_or_else(|| Error::invalid_query(format!("Function not found: {}", name)))?;
+            Ok((
+                partition_by.clone(),
+                order_by.clone(),
+                frame.clone(),
+                WindowFuncType::Native(def.native_aggregate_function(name)?.clone()),
+            ))
+        }
+        Expr::Alias { expr: inner, .. } => extract_window_spec(inner, user_function_defs),
         Expr::BinaryOp { left, right, .. } => {
-            if let Ok(spec) = extract_window_spec(left) {
+            if let Ok(spec) = extract_window_spec(left, user_function_defs) {
                 Ok(spec)
             } else {
-                extract_window_spec(right)
+                extract_window_spec(right, user_function_defs)
             }
         }
-        Expr::UnaryOp { expr: inner, .. } => extract_window_spec(inner),
-        Expr::Cast { expr: inner, .. } => extract_window_spec(inner),
+        Expr::UnaryOp { expr: inner, .. } => extract_window_spec(inner, user_function_defs),
+        Expr::Cast { expr: inner, .. } => extract_window_spec(inner, user_function_defs),
         Expr::Case {
             operand,
             when_clauses,
             else_result,
         } => {
             if let Some(op) = operand
-                && let Ok(spec) = extract_window_spec(op)
+                && let Ok(spec) = extract_window_spec(op, user_function_defs)
             {
                 return Ok(spec);
             }
             for clause in when_clauses {
-                if let Ok(spec) = extract_window_spec(&clause.condition) {
+                if let Ok(spec) = extract_window_spec(&clause.condition, user_function_defs) {
                     return Ok(spec);
                 }
-                if let Ok(spec) = extract_window_spec(&clause.result) {
+                if let Ok(spec) = extract_window_spec(&clause.result, user_function_defs) {
                     return Ok(spec);
                 }
             }
             if let Some(e) = else_result
-                && let Ok(spec) = extract_window_spec(e)
+                && let Ok(spec) = extract_window_spec(e, user_function_defs)
             {
                 return Ok(spec);
             }
@@ -150,7 +170,7 @@ fn extract_window_spec(
         }
         Expr::ScalarFunction { args, .. } => {
             for arg in args {
-                if let Ok(spec) = extract_window_spec(arg) {
+                if let Ok(spec) = extract_window_spec(arg, user_function_defs) {
                     return Ok(spec);
                 }
             }
@@ -503,6 +523,20 @@ pub fn compute_window_function(
                 results = vec![agg_result; partition_size];
             }
         }
+        WindowFuncType::Native(function) => {
+            let mut args = Vec::with_capacity(partition_size);
+            let mut order_values = Vec::with_capacity(partition_size);
+            for &idx in sorted_indices {
+                args.push(extract_native_window_args(expr, evaluator, &rows[idx])?);
+                order_values.push(
+                    order_by
+                        .iter()
+                        .map(|ob| evaluator.evaluate(&ob.expr, &rows[idx]))
+                        .collect::<Result<Vec<_>>>()?,
+                );
+            }
+            results = compute_native_window(function, expr, &args, &order_values, frame)?;
+        }
     }
 
     Ok(results)
@@ -526,6 +560,84 @@ fn extract_window_arg(
     }
 }
 
+fn extract_native_window_args(
+    expr: &Expr,
+    evaluator: &ValueEvaluator,
+    record: &Record,
+) -> Result<Vec<Value>> {
+    match expr {
+        Expr::UserDefinedAggregateWindow { args, .. } => args
+            .iter()
+            .map(|arg| evaluator.evaluate(arg, record))
+            .collect(),
+        _ => Err(Error::InvalidQuery("Expected window expression".into())),
+    }
+}
+
+/// Computes a native aggregate function for each row of a sorted partition.
+/// `args` and `order_values` hold each row's arguments and `ORDER BY` keys.
+///
+/// A frame that slides forward is updated incrementally when the accumulator
+/// can retract rows, and aggregated from scratch otherwise. Without a frame,
+/// each row sees the rows up to the end of its peer group, which is the whole
+/// partition when there is no `ORDER BY`.
+fn compute_native_window(
+    function: &NativeAggregateFunction,
+    expr: &Expr,
+    args: &[Vec<Value>],
+    order_values: &[Vec<Value>],
+    frame: &Option<WindowFrame>,
+) -> Result<Vec<Value>> {
+    let Expr::UserDefinedAggregateWindow { name, distinct, .. } = expr else {
+        return Err(Error::InvalidQuery("Expected window expression".into()));
+    };
+    let partition_size = args.len();
+    let mut results = Vec::with_capacity(partition_size);
+
+    if let Some(frame) = frame {
+        let end_bound = frame.end.as_ref().unwrap_or(&WindowFrameBound::CurrentRow);
+        let mut state = function.start(name, *distinct);
+        let (mut lo, mut hi) = (0, 0);
+        for curr_pos in 0..partition_size {
+            let end = compute_frame_end(end_bound, curr_pos, partition_size) + 1;
+            let start = compute_frame_start(&frame.start, curr_pos, partition_size).min(end);
+            if !state.can_retract() || start < lo || end < hi {
+                state = function.start(name, *distinct);
+                lo = start;
+                hi = start;
+            }
+            while hi < end {
+                state.update(&args[hi])?;
+                hi += 1;
+            }
+            while lo < start {
+                state.retract(&args[lo])?;
+                lo += 1;
+            }
+            results.push(state.finalize()?);
+        }
+    } else {
+        let mut state = function.start(name, *distinct);
+        let mut group_start = 0;
+        while group_start < partition_size {
+            let mut group_end = group_start;
+            while group_end + 1 < partition_size
+                && order_values[group_end + 1] == order_values[group_start]
+            {
+                group_end += 1;
+            }
+            for row_args in &args[group_start..=group_end] {
+                state.update(row_args)?;
+            }
+            let value = state.finalize()?;
+            results.extend(std::iter::repeat_n(value, group_end - group_start + 1));
+            group_start = group_end + 1;
+        }
+    }
+
+    Ok(results)
+}
+
 fn compute_frame_start(bound: &WindowFrameBound, curr_pos: usize, _partition_size: usize) -> usize {
     match bound {
         WindowFrameBound::Preceding(None) => 0,
@@ -651,6 +763,7 @@ fn compute_aggregate(
 pub enum WindowFuncType {
     Window(WindowFunction),
     Aggregate(AggregateFunction),
+    Native(NativeAggregateFunction),
 }
 
 pub(crate) fn partition_rows_columnar(
@@ -1093,6 +1206,22 @@ pub(crate) fn compute_window_function_columnar(
                 results = vec![agg_result; partition_size];
             }
         }
+        WindowFuncType::Native(function) => {
+            let mut record = Record::with_capacity(columns.len());
+            let mut args = Vec::with_capacity(partition_size);
+            let mut order_values = Vec::with_capacity(partition_size);
+            for &idx in sorted_indices {
+                fill_record_from_columns(&mut record, columns, idx);
+                args.push(extract_native_window_args(expr, evaluator, &record)?);
+                order_values.push(
+                    order_by
+                        .iter()
+                        .map(|ob| evaluator.evaluate(&ob.expr, &record))
+                        .collect::<Result<Vec<_>>>()?,
+                );
+            }
+            results = compute_native_window(function, expr, &args, &order_values, frame)?;
+        }
     }
 
     Ok(results)
@@ -0,0 +1,304 @@
+#![coverage(off)]
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use yachtsql_common::error::{Error, Result};
+use yachtsql_common::types::{DataType, StructField, Value};
+use yachtsql_ir::{Expr, LogicalPlan};
+use yachtsql_storage::{Field, Record, Schema, Table};
+
+use crate::table_provider::TableProvider;
+
+/// Number of jobs a [`JobHistory`] keeps unless configured otherwise.
+pub const DEFAULT_JOB_HISTORY_LIMIT: usize = 1000;
+
+/// Names under which a session exposes its engine's job history.
+pub(crate) const JOBS_TABLE_NAMES: [&str; 2] =
+    ["INFORMATION_SCHEMA.JOBS", "INFORMATION_SCHEMA.JOBS_BY_USER"];
+
+/// One executed statement, as listed in `INFORMATION_SCHEMA.JOBS`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JobRecord {
+    pub job_id: String,
+    pub query: String,
+    /// `SELECT`, `INSERT`, `CREATE_TABLE`, ...; `None` when the statement could
+    /// not be planned.
+    pub statement_type: Option<String>,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    /// Tables the statement read, as written in the query.
+    pub referenced_tables: Vec<String>,
+    /// The table a DML or DDL statement wrote to.
+    pub destination_table: Option<String>,
+    /// Rows inserted, updated or deleted; `None` for statements other than DML.
+    pub rows_affected: Option<u64>,
+    /// Estimated size of the tables the statement read.
+    pub total_bytes_processed: u64,
+    pub error: Option<JobError>,
+}
+
+/// Why a job failed, in the shape of BigQuery's `error_result`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JobError {
+    pub reason: String,
+    pub message: String,
+}
+
+impl JobError {
+    pub fn from_error(error: &Error) -> Self {
+        let reason = match error {
+            Error::TableNotFound(_) | Error::FunctionNotFound(_) | Error::ColumnNotFound(_) => {
+                "notFound"
+            }
+            Error::QueryCancelled | Error::QueryTimeout { .. } => "stopped",
+            Error::ResourcesExceeded { .. } => "resourcesExceeded",
+            Error::Internal(_) => "internalError",
+            _ => "invalidQuery",
+        };
+        Self {
+            reason: reason.to_string(),
+            message: error.to_string(),
+        }
+    }
+}
+
+/// A bounded log of the statements run by an engine's sessions.
+///
+/// Once `limit` jobs are recorded, the oldest are dropped. Sessions expose the
+/// history as the read-only `INFORMATION_SCHEMA.JOBS` table, one row per job in
+/// the order the jobs finished.
+#[derive(Debug, Clone)]
+pub struct JobHistory {
+    jobs: Arc<Mutex<VecDeque<JobRecord>>>,
+    limit: usize,
+}
+
+impl JobHistory {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            jobs: Arc::new(Mutex::new(VecDeque::new())),
+            limit,
+        }
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// The recorded jobs, oldest first.
+    pub fn jobs(&self) -> Vec<JobRecord> {
+        self.jobs
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    pub fn clear(&self) {
+        self.jobs.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+
+    pub(crate) fn record(&self, job: JobRecord) {
+        if self.limit == 0 {
+            return;
+        }
+        let mut jobs = self.jobs.lock().unwrap_or_else(|e| e.into_inner());
+        while jobs.len() >= self.limit {
+            jobs.pop_front();
+        }
+        jobs.push_back(job);
+    }
+}
+
+impl Default for JobHistory {
+    fn default() -> Self {
+        Self::new(DEFAULT_JOB_HISTORY_LIMIT)
+    }
+}
+
+fn table_reference_type() -> DataType {
+    DataType::Struct(vec![
+        StructField {
+            name: "project_id".to_string(),
+            data_type: DataType::String,
+        },
+        StructField {
+            name: "dataset_id".to_string(),
+            data_type: DataType::String,
+        },
+        StructField {
+            name: "table_id".to_string(),
+            data_type: DataType::String,
+        },
+    ])
+}
+
+fn table_reference(name: &str) -> Value {
+    let parts: Vec<&str> = name.split('.').collect();
+    let (project, dataset, table) = match parts.as_slice() {
+        [project, dataset, table] => (Some(*project), Some(*dataset), *table),
+        [dataset, table] => (None, Some(*dataset), *table),
+        _ => (None, None, name),
+    };
+    let string = |part: Option<&str>| part.map_or(Value::Null, Value::string);
+    Value::Struct(vec![
+        ("project_id".to_string(), string(project)),
+        ("dataset_id".to_string(), string(dataset)),
+        ("table_id".to_string(), Value::string(table)),
+    ])
+}
+
+impl TableProvider for JobHistory {
+    fn schema(&self) -> Schema {
+        Schema::from_fields(vec![
+            Field::nullable("job_id", DataType::String),
+            Field::nullable("creation_time", DataType::Timestamp),
+            Field::nullable("start_time", DataType::Timestamp),
+            Field::nullable("end_time", DataType::Timestamp),
+            Field::nullable("job_type", DataType::String),
+            Field::nullable("statement_type", DataType::String),
+            Field::nullable("state", DataType::String),
+            Field::nullable("query", DataType::String),
+            Field::nullable(
+                "referenced_tables",
+                DataType::Array(Box::new(table_reference_type())),
+            ),
+            Field::nullable("destination_table", table_reference_type()),
+            Field::nullable("num_dml_affected_rows", DataType::Int64),
+            Field::nullable("total_bytes_processed", DataType::Int64),
+            Field::nullable(
+                "error_result",
+                DataType::Struct(vec![
+                    StructField {
+                        name: "reason".to_string(),
+                        data_type: DataType::String,
+                    },
+                    StructField {
+                        name: "message".to_string(),
+                        data_type: DataType::String,
+                    },
+                ]),
+            ),
+        ])
+    }
+
+    fn scan(&self, _projection: Option<&[usize]>, _filters: &[Expr]) -> Result<Table> {
+        let records = self
+            .jobs()
+            .into_iter()
+            .map(|job| {
+                Record::from_values(vec![
+                    Value::string(job.job_id),
+                    Value::timestamp(job.start_time),
+                    Value::timestamp(job.start_time),
+                    Value::timestamp(job.end_time),
+                    Value::string("QUERY"),
+                    job.statement_type.map_or(Value::Null, Value::string),
+                    Value::string("DONE"),
+                    Value::string(job.query),
+                    Value::array(
+                        job.referenced_tables
+                            .iter()
+                            .map(|name| table_reference(name))
+                            .collect(),
+                    ),
+                    job.destination_table
+                        .as_deref()
+                        .map_or(Value::Null, table_reference),
+                    job.rows_affected
+                        .map_or(Value::Null, |rows| Value::int64(rows as i64)),
+                    Value::int64(job.total_bytes_processed as i64),
+                    job.error.map_or(Value::Null, |error| {
+                        Value::Struct(vec![
+                            ("reason".to_string(), Value::string(error.reason)),
+                            ("message".to_string(), Value::string(error.message)),
+                        ])
+                    }),
+                ])
+            })
+            .collect();
+        Table::from_records(self.schema(), records)
+    }
+}
+
+/// The BigQuery `statement_type` of a planned statement.
+pub(crate) fn statement_type(plan: &LogicalPlan) -> &'static str {
+    match plan {
+        LogicalPlan::Insert { .. } => "INSERT",
+        LogicalPlan::Update { .. } => "UPDATE",
+        LogicalPlan::Delete { .. } => "DELETE",
+        LogicalPlan::Merge { .. } => "MERGE",
+        LogicalPlan::CreateTable { query: Some(_), .. } => "CREATE_TABLE_AS_SELECT",
+        LogicalPlan::CreateTable { .. } => "CREATE_TABLE",
+        LogicalPlan::DropTable { .. } => "DROP_TABLE",
+        LogicalPlan::AlterTable { .. } => "ALTER_TABLE",
+        LogicalPlan::Truncate { .. } => "TRUNCATE_TABLE",
+        LogicalPlan::CreateView { .. } => "CREATE_VIEW",
+        LogicalPlan::DropView { .. } => "DROP_VIEW",
+        LogicalPlan::CreateSchema { .. } => "CREATE_SCHEMA",
+        LogicalPlan::DropSchema { .. } => "DROP_SCHEMA",
+        LogicalPlan::UndropSchema { .. } => "UNDROP_SCHEMA",
+        LogicalPlan::AlterSchema { .. } => "ALTER_SCHEMA",
+        LogicalPlan::CreateFunction { .. } => "CREATE_FUNCTION",
+        LogicalPlan::DropFunction { .. } => "DROP_FUNCTION",
+        LogicalPlan::CreateProcedure { .. } => "CREATE_PROCEDURE",
+        LogicalPlan::DropProcedure { .. } => "DROP_PROCEDURE",
+        LogicalPlan::Call { .. } => "CALL",
+        LogicalPlan::ExportData { .. } => "EXPORT_DATA",
+        LogicalPlan::LoadData { .. } => "LOAD_DATA",
+        LogicalPlan::CreateSnapshot { .. } => "CREATE_SNAPSHOT_TABLE",
+        LogicalPlan::DropSnapshot { .. } => "DROP_SNAPSHOT_TABLE",
+        LogicalPlan::Assert { .. } => "ASSERT",
+        LogicalPlan::Grant { .. } => "GRANT",
+        LogicalPlan::Revoke { .. } => "REVOKE",
+        LogicalPlan::BeginTransaction => "BEGIN_TRANSACTION",
+        LogicalPlan::Commit => "COMMIT_TRANSACTION",
+        LogicalPlan::Rollback => "ROLLBACK_TRANSACTION",
+        LogicalPlan::Explain { .. } => "EXPLAIN",
+        LogicalPlan::Declare { .. }
+        | LogicalPlan::SetVariable { .. }
+        | LogicalPlan::SetMultipleVariables { .. }
+        | LogicalPlan::If { .. }
+        | LogicalPlan::While { .. }
+        | LogicalPlan::Loop { .. }
+        | LogicalPlan::Block { .. }
+        | LogicalPlan::Repeat { .. }
+        | LogicalPlan::For { .. }
+        | LogicalPlan::Return { .. }
+        | LogicalPlan::Raise { .. }
+        | LogicalPlan::ExecuteImmediate { .. }
+        | LogicalPlan::Break { .. }
+        | LogicalPlan::Continue { .. }
+        | LogicalPlan::TryCatch { .. } => "SCRIPT",
+        _ => "SELECT",
+    }
+}
+
+/// The table a statement writes to, if it has one.
+pub(crate) fn destination_table(plan: &LogicalPlan) -> Option<&str> {
+    match plan {
+        LogicalPlan::Insert { table_name, .. }
+        | LogicalPlan::Update { table_name, .. }
+        | LogicalPlan::Delete { table_name, .. }
+        | LogicalPlan::CreateTable { table_name, .. }
+        | LogicalPlan::AlterTable { table_name, .. }
+        | LogicalPlan::Truncate { table_name }
+        | LogicalPlan::LoadData { table_name, .. } => Some(table_name),
+        LogicalPlan::Merge { target_table, .. } => Some(target_table),
+        LogicalPlan::CreateSnapshot { snapshot_name, .. } => Some(snapshot_name),
+        _ => None,
+    }
+}
+
+pub(crate) fn is_dml(plan: &LogicalPlan) -> bool {
+    matches!(
+        plan,
+        LogicalPlan::Insert { .. }
+            | LogicalPlan::Update { .. }
+            | LogicalPlan::Delete { .. }
+            | LogicalPlan::Merge { .. }
+    )
+}
@@ -11,6 +11,7 @@
 #![allow(clippy::wildcard_enum_match_arm)]
 
 mod catalog;
+mod catalog_file;
 mod columnar_evaluator;
 mod error;
 mod executor;
@@ -22,24 +23,49 @@ mod session;
 pub mod value_evaluator;
 
 mod async_executor;
+mod cancellation;
 mod concurrent_catalog;
 mod concurrent_session;
+mod dry_run;
+mod durable;
+mod job_history;
+mod memory;
 mod metrics;
+mod native_function;
+mod observer;
 mod physical_planner;
 mod plan_cache;
+mod prepared;
+mod query_params;
+mod query_stream;
+mod table_provider;
+mod typed_rows;
 
 use std::num::NonZeroUsize;
 
-pub use async_executor::AsyncQueryExecutor;
+pub use async_executor::{AsyncQueryExecutor, ScriptResult, StatementResult};
+pub use cancellation::{CancellationHandle, JOB_TIMEOUT_VARIABLE, QueryJob};
 pub use catalog::{Catalog, ColumnDefault, UserFunction, UserProcedure, ViewDef};
 pub use columnar_evaluator::ColumnarEvaluator;
 pub use concurrent_catalog::{ConcurrentCatalog, TableLockSet};
 pub use concurrent_session::ConcurrentSession;
+pub use dry_run::{DryRunResult, ReferencedTable};
+pub use durable::DurableStore;
 pub use error::{Error, Result};
 pub use executor::plan_schema_to_schema;
+pub use job_history::{DEFAULT_JOB_HISTORY_LIMIT, JobError, JobHistory, JobRecord};
 use lru::LruCache;
+pub use memory::MEMORY_LIMIT_VARIABLE;
+pub use native_function::{
+    Accumulator, NativeAggregateFunction, NativeScalarFunction, NativeTableFunction,
+};
+pub use observer::{PlanReady, QueryFinish, QueryObserver, QueryStart};
 pub use physical_planner::PhysicalPlanner;
+pub use prepared::PreparedStatement;
+pub use query_params::QueryParameter;
+pub use query_stream::{DEFAULT_STREAM_BATCH_SIZE, QueryStream};
 pub use session::Session;
+pub use table_provider::TableProvider;
 pub use value_evaluator::{UserFunctionDef, ValueEvaluator, cast_value};
 use yachtsql_optimizer::PhysicalPlan;
 pub use yachtsql_storage::{Record, Table};
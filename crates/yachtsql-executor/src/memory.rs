@@ -0,0 +1,236 @@
+#![coverage(off)]
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use yachtsql_common::error::{Error, Result};
+use yachtsql_storage::Table;
+
+pub const MEMORY_LIMIT_VARIABLE: &str = "@@MEMORY_LIMIT_BYTES";
+
+#[derive(Debug)]
+struct BudgetState {
+    limit: usize,
+    used: AtomicUsize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct MemoryBudget {
+    state: Option<Arc<BudgetState>>,
+}
+
+impl MemoryBudget {
+    pub(crate) fn new(limit_bytes: Option<usize>) -> Self {
+        Self {
+            state: limit_bytes.map(|limit| {
+                Arc::new(BudgetState {
+                    limit,
+                    used: AtomicUsize::new(0),
+                })
+            }),
+        }
+    }
+
+    pub(crate) fn is_limited(&self) -> bool {
+        self.state.is_some()
+    }
+
+    pub(crate) fn reserve(&self, row_bytes: usize) -> MemoryReservation {
+        MemoryReservation {
+            budget: self.clone(),
+            row_bytes,
+            bytes: AtomicUsize::new(0),
+            exceeded: AtomicBool::new(false),
+        }
+    }
+
+    pub(crate) fn reserve_table(&self, table: &Table) -> Result<MemoryReservation> {
+        let reservation = self.reserve(0);
+        reservation.grow_for_table(table)?;
+        Ok(reservation)
+    }
+
+    pub(crate) fn reserve_for_rows(
+        &self,
+        tables: &[&Table],
+        extra_row_bytes: usize,
+    ) -> MemoryReservation {
+        if !self.is_limited() {
+            return self.reserve(0);
+        }
+        let row_bytes =
+            tables.iter().map(|t| average_row_bytes(t)).sum::<usize>() + extra_row_bytes;
+        self.reserve(row_bytes)
+    }
+
+    pub(crate) fn charge(&self, bytes: usize) -> Result<()> {
+        let Some(state) = &self.state else {
+            return Ok(());
+        };
+        let used = state.used.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        if used > state.limit {
+            state.used.fetch_sub(bytes, Ordering::Relaxed);
+            return Err(Error::resources_exceeded(state.limit as u64));
+        }
+        Ok(())
+    }
+
+    fn release(&self, bytes: usize) {
+        if let Some(state) = &self.state {
+            state.used.fetch_sub(bytes, Ordering::Relaxed);
+        }
+    }
+
+    fn limit_error(&self) -> Error {
+        let limit = self.state.as_ref().map_or(0, |s| s.limit);
+        Error::resources_exceeded(limit as u64)
+    }
+}
+
+pub(crate) fn average_row_bytes(table: &Table) -> usize {
+    if table.row_count() == 0 {
+        return 0;
+    }
+    table.estimated_bytes().div_ceil(table.row_count())
+}
+
+pub(crate) trait RowCount {
+    fn row_count(&self) -> usize;
+}
+
+impl<T> RowCount for Vec<T> {
+    fn row_count(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T> RowCount for Option<T> {
+    fn row_count(&self) -> usize {
+        usize::from(self.is_some())
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct MemoryReservation {
+    budget: MemoryBudget,
+    row_bytes: usize,
+    bytes: AtomicUsize,
+    exceeded: AtomicBool,
+}
+
+impl MemoryReservation {
+    pub(crate) fn resize(&self, bytes: usize) -> Result<()> {
+        if !self.budget.is_limited() {
+            return Ok(());
+        }
+        let current = self.bytes.load(Ordering::Relaxed);
+        if bytes <= current {
+            self.budget.release(current - bytes);
+            self.bytes.store(bytes, Ordering::Relaxed);
+            return Ok(());
+        }
+        self.grow(bytes - current)
+    }
+
+    pub(crate) fn resize_rows(&self, rows: usize) -> Result<()> {
+        self.resize(rows.saturating_mul(self.row_bytes))
+    }
+
+    pub(crate) fn grow(&self, bytes: usize) -> Result<()> {
+        match self.budget.charge(bytes) {
+            Ok(()) => {
+                self.bytes.fetch_add(bytes, Ordering::Relaxed);
+                Ok(())
+            }
+            Err(e) => {
+                self.exceeded.store(true, Ordering::Relaxed);
+                Err(e)
+            }
+        }
+    }
+
+    pub(crate) fn grow_for_table(&self, table: &Table) -> Result<()> {
+        if !self.budget.is_limited() {
+            return Ok(());
+        }
+        self.grow(table.estimated_bytes())
+    }
+
+    /// Charges `rows` rows from a parallel producer. A failure is remembered
+    /// rather than returned: producers poll [`is_exceeded`](Self::is_exceeded)
+    /// to stop early and the caller reports it through [`check`](Self::check).
+    pub(crate) fn record_rows(&self, rows: usize) {
+        if self.budget.is_limited() {
+            let _ = self.grow(rows.saturating_mul(self.row_bytes));
+        }
+    }
+
+    pub(crate) fn is_exceeded(&self) -> bool {
+        self.exceeded.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn check(&self) -> Result<()> {
+        if self.is_exceeded() {
+            return Err(self.budget.limit_error());
+        }
+        Ok(())
+    }
+}
+
+impl Drop for MemoryReservation {
+    fn drop(&mut self) {
+        self.budget.release(*self.bytes.get_mut());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unlimited_budget_never_fails() {
+        let budget = MemoryBudget::new(None);
+        let reservation = budget.reserve(1024);
+        reservation.resize_rows(usize::MAX).unwrap();
+        budget.charge(usize::MAX).unwrap();
+    }
+
+    #[test]
+    fn test_reservation_released_on_drop() {
+        let budget = MemoryBudget::new(Some(100));
+        {
+            let reservation = budget.reserve(10);
+            reservation.resize_rows(8).unwrap();
+            assert!(budget.charge(30).is_err());
+        }
+        budget.charge(100).unwrap();
+    }
+
+    #[test]
+    fn test_resize_can_shrink() {
+        let budget = MemoryBudget::new(Some(100));
+        let reservation = budget.reserve(1);
+        reservation.resize(90).unwrap();
+        reservation.resize(10).unwrap();
+        budget.charge(90).unwrap();
+        assert_eq!(
+            reservation.resize(20),
+            Err(Error::ResourcesExceeded { limit_bytes: 100 })
+        );
+    }
+
+    #[test]
+    fn test_record_rows_flags_limit() {
+        let budget = MemoryBudget::new(Some(50));
+        let reservation = budget.reserve(10);
+        reservation.record_rows(4);
+        assert!(!reservation.is_exceeded());
+        reservation.check().unwrap();
+        reservation.record_rows(4);
+        assert!(reservation.is_exceeded());
+        assert_eq!(
+            reservation.check(),
+            Err(Error::ResourcesExceeded { limit_bytes: 50 })
+        );
+    }
+}
@@ -0,0 +1,383 @@
+#![coverage(off)]
+
+use std::fmt;
+use std::sync::Arc;
+
+use rustc_hash::FxHashSet;
+use yachtsql_common::error::{Error, Result};
+use yachtsql_common::types::{DataType, FunctionSignature, StructField, Value};
+use yachtsql_storage::{Column, Schema, Table};
+
+use crate::value_evaluator::cast_value;
+
+type RowFn = dyn Fn(&[Value]) -> Result<Value> + Send + Sync;
+type ColumnarFn = dyn Fn(&[&Column]) -> Result<Column> + Send + Sync;
+type AccumulatorFactory = dyn Fn() -> Box<dyn Accumulator> + Send + Sync;
+type TableFn = dyn Fn(&[Value]) -> Result<Table> + Send + Sync;
+
+/// A scalar function implemented in Rust.
+///
+/// Register one with [`ConcurrentCatalog::register_scalar_function`] and call it
+/// from SQL like a built-in. Calls are checked against the [`FunctionSignature`]
+/// when the query is planned, and arguments are converted to the declared types
+/// before the function sees them.
+///
+/// [`ConcurrentCatalog::register_scalar_function`]: crate::ConcurrentCatalog::register_scalar_function
+#[derive(Clone)]
+pub struct NativeScalarFunction {
+    signature: FunctionSignature,
+    row: Arc<RowFn>,
+    columnar: Option<Arc<ColumnarFn>>,
+}
+
+impl NativeScalarFunction {
+    /// Creates a function that is called once per row with that row's arguments.
+    pub fn new(
+        signature: FunctionSignature,
+        function: impl Fn(&[Value]) -> Result<Value> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            signature,
+            row: Arc::new(function),
+            columnar: None,
+        }
+    }
+
+    /// Adds a columnar implementation that receives one column per argument and
+    /// returns a column with one value per row.
+    ///
+    /// It is used when every argument column already has its declared type;
+    /// otherwise the row implementation is called.
+    pub fn with_columnar(
+        mut self,
+        function: impl Fn(&[&Column]) -> Result<Column> + Send + Sync + 'static,
+    ) -> Self {
+        self.columnar = Some(Arc::new(function));
+        self
+    }
+
+    pub fn signature(&self) -> &FunctionSignature {
+        &self.signature
+    }
+
+    /// Calls the row implementation, converting the arguments to their declared
+    /// types and the result to the return type.
+    pub(crate) fn invoke(&self, name: &str, args: &[Value]) -> Result<Value> {
+        let args = conform_arguments(&self.signature, name, args)?;
+        let result = (self.row)(&args)?;
+        conform_result(&self.signature, name, result)
+    }
+
+    /// Calls the columnar implementation, or returns `None` if there is none or
+    /// the argument columns don't have the declared types.
+    pub(crate) fn invoke_columnar(
+        &self,
+        name: &str,
+        args: &[Column],
+        row_count: usize,
+    ) -> Result<Option<Column>> {
+        let Some(columnar) = &self.columnar else {
+            return Ok(None);
+        };
+        let declared = args.len() == self.signature.arguments.len()
+            && args
+                .iter()
+                .zip(&self.signature.arguments)
+                .all(|(column, data_type)| {
+                    *data_type == DataType::Unknown || column.data_type() == *data_type
+                });
+        if !declared {
+            return Ok(None);
+        }
+        let args: Vec<&Column> = args.iter().collect();
+        let result = columnar(&args)?;
+        if result.len() != row_count {
+            return Err(Error::internal(format!(
+                "Function {} returned {} values for {} rows",
+                name,
+                result.len(),
+                row_count
+            )));
+        }
+        Ok(Some(result))
+    }
+}
+
+impl fmt::Debug for NativeScalarFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NativeScalarFunction")
+            .field("signature", &self.signature)
+            .field("columnar", &self.columnar.is_some())
+            .finish()
+    }
+}
+
+/// The running state of an aggregate function implemented in Rust, for one group
+/// or window frame.
+///
+/// Rows reach [`update`](Self::update) with their arguments already converted to
+/// the declared types. `NULL` arguments are passed through, so an accumulator
+/// decides itself whether to skip them.
+pub trait Accumulator: Send {
+    /// Adds one row.
+    fn update(&mut self, args: &[Value]) -> Result<()>;
+
+    /// Removes a row previously added with `update`.
+    ///
+    /// Sliding window frames use it when [`supports_retract`](Self::supports_retract)
+    /// is true; otherwise each frame is aggregated from scratch.
+    fn retract(&mut self, _args: &[Value]) -> Result<()> {
+        Err(Error::unsupported("Accumulator does not support retract"))
+    }
+
+    fn supports_retract(&self) -> bool {
+        false
+    }
+
+    /// The intermediate state, to be folded into another accumulator of the same
+    /// function with [`merge`](Self::merge).
+    fn state(&self) -> Result<Vec<Value>>;
+
+    /// Folds in the [`state`](Self::state) of an accumulator that saw other rows
+    /// of the same group.
+    fn merge(&mut self, state: &[Value]) -> Result<()>;
+
+    /// The aggregate of the rows seen so far. It may be called more than once.
+    fn finalize(&self) -> Result<Value>;
+}
+
+/// An aggregate function implemented in Rust.
+///
+/// Register one with [`ConcurrentCatalog::register_aggregate_function`] and call
+/// it from SQL like a built-in aggregate: with `GROUP BY`, with `DISTINCT`, or as
+/// a window function with `OVER (...)`. The factory creates a fresh
+/// [`Accumulator`] for every group and window frame.
+///
+/// [`ConcurrentCatalog::register_aggregate_function`]: crate::ConcurrentCatalog::register_aggregate_function
+#[derive(Clone)]
+pub struct NativeAggregateFunction {
+    signature: FunctionSignature,
+    factory: Arc<AccumulatorFactory>,
+}
+
+impl NativeAggregateFunction {
+    pub fn new(
+        signature: FunctionSignature,
+        factory: impl Fn() -> Box<dyn Accumulator> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            signature,
+            factory: Arc::new(factory),
+        }
+    }
+
+    pub fn signature(&self) -> &FunctionSignature {
+        &self.signature
+    }
+
+    /// Starts aggregating a group. With `distinct`, repeated argument tuples are
+    /// only passed to the accumulator once.
+    pub(crate) fn start(&self, name: &str, distinct: bool) -> NativeAggregateState {
+        NativeAggregateState {
+            name: name.to_string(),
+            signature: self.signature.clone(),
+            accumulator: (self.factory)(),
+            seen: distinct.then(FxHashSet::default),
+        }
+    }
+}
+
+impl fmt::Debug for NativeAggregateFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NativeAggregateFunction")
+            .field("signature", &self.signature)
+            .finish()
+    }
+}
+
+/// An [`Accumulator`] together with the argument conversion and `DISTINCT`
+/// bookkeeping the executor applies around it.
+pub(crate) struct NativeAggregateState {
+    name: String,
+    signature: FunctionSignature,
+    accumulator: Box<dyn Accumulator>,
+    seen: Option<FxHashSet<Vec<Value>>>,
+}
+
+impl NativeAggregateState {
+    pub(crate) fn is_distinct(&self) -> bool {
+        self.seen.is_some()
+    }
+
+    pub(crate) fn can_retract(&self) -> bool {
+        !self.is_distinct() && self.accumulator.supports_retract()
+    }
+
+    pub(crate) fn update(&mut self, args: &[Value]) -> Result<()> {
+        let args = conform_arguments(&self.signature, &self.name, args)?;
+        if let Some(seen) = &mut self.seen
+            && !seen.insert(args.clone())
+        {
+            return Ok(());
+        }
+        self.accumulator.update(&args)
+    }
+
+    pub(crate) fn retract(&mut self, args: &[Value]) -> Result<()> {
+        let args = conform_arguments(&self.signature, &self.name, args)?;
+        self.accumulator.retract(&args)
+    }
+
+    pub(crate) fn merge(&mut self, other: &Self) -> Result<()> {
+        self.accumulator.merge(&other.accumulator.state()?)
+    }
+
+    pub(crate) fn finalize(&self) -> Result<Value> {
+        let result = self.accumulator.finalize()?;
+        conform_result(&self.signature, &self.name, result)
+    }
+}
+
+/// A table-valued function implemented in Rust.
+///
+/// Register one with [`ConcurrentCatalog::register_table_function`] and call it
+/// in a `FROM` clause like a table. Its arguments may refer to columns of tables
+/// listed before it, as in `FROM t, my_tvf(t.x)`; it is then called once per row
+/// of those tables. The output columns must be known when the query is planned,
+/// so the function declares a [`Schema`] and the tables it returns are matched
+/// to it by position.
+///
+/// [`ConcurrentCatalog::register_table_function`]: crate::ConcurrentCatalog::register_table_function
+#[derive(Clone)]
+pub struct NativeTableFunction {
+    signature: FunctionSignature,
+    schema: Schema,
+    function: Arc<TableFn>,
+}
+
+impl NativeTableFunction {
+    pub fn new(
+        arguments: Vec<DataType>,
+        schema: Schema,
+        function: impl Fn(&[Value]) -> Result<Table> + Send + Sync + 'static,
+    ) -> Self {
+        let row_type = DataType::Struct(
+            schema
+                .fields()
+                .iter()
+                .map(|field| StructField {
+                    name: field.name.clone(),
+                    data_type: field.data_type.clone(),
+                })
+                .collect(),
+        );
+        Self {
+            signature: FunctionSignature::new(arguments, DataType::Array(Box::new(row_type))),
+            schema,
+            function: Arc::new(function),
+        }
+    }
+
+    /// The argument types, with the rows as an `ARRAY<STRUCT<...>>` return type.
+    pub fn signature(&self) -> &FunctionSignature {
+        &self.signature
+    }
+
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    /// Calls the function and returns its rows as an array of structs, with the
+    /// values converted to the declared column types.
+    pub(crate) fn invoke(&self, name: &str, args: &[Value]) -> Result<Value> {
+        let args = conform_arguments(&self.signature, name, args)?;
+        let table = (self.function)(&args)?;
+        let fields = self.schema.fields();
+        if table.schema().field_count() != fields.len() {
+            return Err(Error::invalid_query(format!(
+                "Table function {} returned {} columns, expected {}",
+                name,
+                table.schema().field_count(),
+                fields.len()
+            )));
+        }
+        let rows = table
+            .to_records()?
+            .into_iter()
+            .map(|record| {
+                let values = record
+                    .into_values()
+                    .into_iter()
+                    .zip(fields)
+                    .map(|(value, field)| {
+                        let value = conform(value, &field.data_type).map_err(|_| {
+                            Error::invalid_query(format!(
+                                "Table function {} returned a value that is not {} for column {}",
+                                name, field.data_type, field.name
+                            ))
+                        })?;
+                        Ok((field.name.clone(), value))
+                    })
+                    .collect::<Result<_>>()?;
+                Ok(Value::Struct(values))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Value::Array(rows))
+    }
+}
+
+impl fmt::Debug for NativeTableFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NativeTableFunction")
+            .field("signature", &self.signature)
+            .finish()
+    }
+}
+
+/// Checks `args` against `signature` and converts each to its declared type.
+fn conform_arguments(
+    signature: &FunctionSignature,
+    name: &str,
+    args: &[Value],
+) -> Result<Vec<Value>> {
+    let types: Vec<DataType> = args.iter().map(Value::data_type).collect();
+    signature.check_arguments(name, &types)?;
+    args.iter()
+        .zip(&signature.arguments)
+        .map(|(arg, data_type)| conform(arg.clone(), data_type))
+        .collect()
+}
+
+/// Converts a function's result to the declared return type.
+fn conform_result(signature: &FunctionSignature, name: &str, result: Value) -> Result<Value> {
+    conform(result, &signature.return_type).map_err(|_| {
+        Error::invalid_query(format!(
+            "Function {} returned a value that is not {}",
+            name, signature.return_type
+        ))
+    })
+}
+
+/// Converts `value` to `data_type`, widening numbers where BigQuery would;
+/// `NULL`, values of an `Unknown` target and values already of the target type
+/// pass through unchanged.
+fn conform(value: Value, data_type: &DataType) -> Result<Value> {
+    if value.is_null() || *data_type == DataType::Unknown {
+        return Ok(value);
+    }
+    let actual = value.data_type();
+    match (&actual, data_type) {
+        (a, d) if a == d => Ok(value),
+        (DataType::Numeric(_), DataType::Numeric(_))
+        | (DataType::Array(_), DataType::Array(_))
+        | (DataType::Struct(_), DataType::Struct(_))
+        | (DataType::Range(_), DataType::Range(_)) => Ok(value),
+        (DataType::Int64, DataType::Numeric(_) | DataType::BigNumeric | DataType::Float64)
+        | (DataType::Numeric(_), DataType::BigNumeric | DataType::Float64)
+        | (DataType::BigNumeric, DataType::Float64) => cast_value(value, data_type, false),
+        _ => Err(Error::type_mismatch(
+            data_type.to_string(),
+            actual.to_string(),
+        )),
+    }
+}
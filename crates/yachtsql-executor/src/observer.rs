@@ -0,0 +1,247 @@
+#![coverage(off)]
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use yachtsql_common::error::{Error, Result};
+use yachtsql_ir::LogicalPlan;
+use yachtsql_optimizer::PhysicalPlan;
+use yachtsql_storage::Table;
+
+use crate::concurrent_catalog::ConcurrentCatalog;
+use crate::job_history::{self, JobError, JobHistory, JobRecord};
+use crate::plan::{AccessType, TableAccessSet};
+
+static NEXT_QUERY_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Receives events about the queries a session runs.
+///
+/// Register one with [`AsyncQueryExecutor::add_observer`]. Every statement run
+/// through `execute_sql`, a prepared statement, a stream or a script produces a
+/// [`QueryStart`], then a [`PlanReady`] once it is planned, and always a
+/// [`QueryFinish`], also when planning or execution fails. Events of one
+/// statement share a `query_id`.
+///
+/// Callbacks run on the thread executing the query, so they should return
+/// quickly.
+///
+/// [`AsyncQueryExecutor::add_observer`]: crate::AsyncQueryExecutor::add_observer
+pub trait QueryObserver: Send + Sync {
+    fn on_query_start(&self, _event: &QueryStart<'_>) {}
+
+    fn on_plan_ready(&self, _event: &PlanReady<'_>) {}
+
+    fn on_query_finish(&self, _event: &QueryFinish<'_>) {}
+}
+
+#[derive(Debug)]
+pub struct QueryStart<'a> {
+    pub query_id: u64,
+    pub sql: &'a str,
+    /// The SQL with literals replaced by `?`; see [`yachtsql_parser::fingerprint_sql`].
+    pub fingerprint: &'a str,
+}
+
+#[derive(Debug)]
+pub struct PlanReady<'a> {
+    pub query_id: u64,
+    pub sql: &'a str,
+    pub logical_plan: &'a LogicalPlan,
+    pub physical_plan: &'a PhysicalPlan,
+}
+
+#[derive(Debug)]
+pub struct QueryFinish<'a> {
+    pub query_id: u64,
+    pub sql: &'a str,
+    pub fingerprint: &'a str,
+    pub duration: Duration,
+    /// Rows in the result; 0 when the query failed.
+    pub rows: usize,
+    pub tables_read: &'a [String],
+    pub tables_written: &'a [String],
+    pub error: Option<&'a Error>,
+    /// What the statement is recorded as in the job history, if the session
+    /// keeps one.
+    pub job: Option<&'a JobRecord>,
+}
+
+/// The observers registered on an executor, shared by its clones.
+#[derive(Clone, Default)]
+pub(crate) struct QueryObservers {
+    observers: Arc<RwLock<Vec<Arc<dyn QueryObserver>>>>,
+}
+
+impl QueryObservers {
+    pub(crate) fn add(&self, observer: Arc<dyn QueryObserver>) {
+        self.observers
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(observer);
+    }
+
+    /// A separate list holding the same observers.
+    pub(crate) fn copy(&self) -> Self {
+        Self {
+            observers: Arc::new(RwLock::new(self.snapshot())),
+        }
+    }
+
+    fn snapshot(&self) -> Vec<Arc<dyn QueryObserver>> {
+        self.observers
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Starts tracing a statement, notifying the observers registered now. The
+    /// finished statement is recorded in `jobs`, if given.
+    pub(crate) fn start(&self, sql: &str, jobs: Option<&JobHistory>) -> QueryTrace {
+        let observers = self.snapshot();
+        if observers.is_empty() && jobs.is_none() {
+            return QueryTrace::default();
+        }
+        let fingerprint = if observers.is_empty() {
+            String::new()
+        } else {
+            yachtsql_parser::fingerprint_sql(sql)
+        };
+        let trace = QueryTrace {
+            query_id: NEXT_QUERY_ID.fetch_add(1, Ordering::Relaxed),
+            sql: sql.to_string(),
+            fingerprint,
+            start: Some(Instant::now()),
+            start_time: Utc::now(),
+            tables_read: Vec::new(),
+            tables_written: Vec::new(),
+            statement_type: None,
+            destination_table: None,
+            is_dml: false,
+            dml_rows: 0,
+            bytes_processed: 0,
+            observers,
+            jobs: jobs.cloned(),
+        };
+        let event = QueryStart {
+            query_id: trace.query_id,
+            sql: &trace.sql,
+            fingerprint: &trace.fingerprint,
+        };
+        for observer in &trace.observers {
+            observer.on_query_start(&event);
+        }
+        trace
+    }
+}
+
+/// One statement's progress, reported to the observers that were registered
+/// when it started and recorded in the job history. Does nothing when there
+/// were neither.
+#[derive(Default)]
+pub(crate) struct QueryTrace {
+    query_id: u64,
+    sql: String,
+    fingerprint: String,
+    start: Option<Instant>,
+    start_time: DateTime<Utc>,
+    tables_read: Vec<String>,
+    tables_written: Vec<String>,
+    statement_type: Option<&'static str>,
+    destination_table: Option<String>,
+    is_dml: bool,
+    dml_rows: u64,
+    bytes_processed: u64,
+    observers: Vec<Arc<dyn QueryObserver>>,
+    jobs: Option<JobHistory>,
+}
+
+impl QueryTrace {
+    fn is_active(&self) -> bool {
+        !self.observers.is_empty() || self.jobs.is_some()
+    }
+
+    pub(crate) fn plan_ready(&mut self, logical_plan: &LogicalPlan, physical_plan: &PhysicalPlan) {
+        if self.jobs.is_some() {
+            self.statement_type = Some(job_history::statement_type(logical_plan));
+            self.destination_table =
+                job_history::destination_table(logical_plan).map(str::to_string);
+            self.is_dml = job_history::is_dml(logical_plan);
+        }
+        let event = PlanReady {
+            query_id: self.query_id,
+            sql: &self.sql,
+            logical_plan,
+            physical_plan,
+        };
+        for observer in &self.observers {
+            observer.on_plan_ready(&event);
+        }
+    }
+
+    pub(crate) fn record_tables(&mut self, accesses: &TableAccessSet, catalog: &ConcurrentCatalog) {
+        if !self.is_active() {
+            return;
+        }
+        for (table, access) in &accesses.accesses {
+            match access {
+                AccessType::Read => {
+                    if self.jobs.is_some()
+                        && let Some(handle) = catalog.get_table_handle(table)
+                    {
+                        self.bytes_processed += handle.read().estimated_bytes() as u64;
+                    }
+                    self.tables_read.push(table.clone())
+                }
+                AccessType::Write | AccessType::WriteOptional => {
+                    self.tables_written.push(table.clone())
+                }
+            }
+        }
+    }
+
+    pub(crate) fn record_dml_rows(&mut self, rows: u64) {
+        self.dml_rows += rows;
+    }
+
+    pub(crate) fn finish(&self, result: &Result<Table>) {
+        self.finish_with(result.as_ref().map(Table::row_count));
+    }
+
+    /// Like [`finish`](Self::finish), for results delivered as a row count.
+    pub(crate) fn finish_with(&self, result: std::result::Result<usize, &Error>) {
+        if !self.is_active() {
+            return;
+        }
+        let job = self.jobs.as_ref().map(|_| JobRecord {
+            job_id: format!("job_{:012}", self.query_id),
+            query: self.sql.clone(),
+            statement_type: self.statement_type.map(str::to_string),
+            start_time: self.start_time,
+            end_time: Utc::now(),
+            referenced_tables: self.tables_read.clone(),
+            destination_table: self.destination_table.clone(),
+            rows_affected: (self.is_dml && result.is_ok()).then_some(self.dml_rows),
+            total_bytes_processed: self.bytes_processed,
+            error: result.err().map(JobError::from_error),
+        });
+        if let (Some(jobs), Some(job)) = (&self.jobs, &job) {
+            jobs.record(job.clone());
+        }
+        let event = QueryFinish {
+            query_id: self.query_id,
+            sql: &self.sql,
+            fingerprint: &self.fingerprint,
+            duration: self.start.map(|start| start.elapsed()).unwrap_or_default(),
+            rows: *result.as_ref().unwrap_or(&0),
+            tables_read: &self.tables_read,
+            tables_written: &self.tables_written,
+            error: result.err(),
+            job: job.as_ref(),
+        };
+        for observer in &self.observers {
+            observer.on_query_finish(&event);
+        }
+    }
+}
@@ -6,6 +6,7 @@ use lru::LruCache;
 use rustc_hash::{FxHashMap, FxHashSet};
 use xxhash_rust::xxh3::xxh3_64;
 use yachtsql_ir::{Expr, LogicalPlan};
+use yachtsql_parser::SESSION_DATASET;
 
 type SqlHash = u64;
 
@@ -13,9 +14,23 @@ fn hash_sql(sql: &str) -> SqlHash {
     xxh3_64(sql.as_bytes())
 }
 
+fn object_key(name: &str) -> String {
+    let key = name.to_uppercase();
+    match key
+        .strip_prefix(SESSION_DATASET)
+        .and_then(|rest| rest.strip_prefix('.'))
+    {
+        Some(table) => table.to_string(),
+        None => key,
+    }
+}
+
 pub struct PlanCache {
     plans: LruCache<SqlHash, LogicalPlan>,
     object_to_hashes: FxHashMap<String, FxHashSet<SqlHash>>,
+    version: u64,
+    cleared_at: u64,
+    object_versions: FxHashMap<String, u64>,
 }
 
 impl PlanCache {
@@ -23,9 +38,25 @@ impl PlanCache {
         Self {
             plans: LruCache::new(capacity),
             object_to_hashes: FxHashMap::default(),
+            version: 0,
+            cleared_at: 0,
+            object_versions: FxHashMap::default(),
         }
     }
 
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    pub fn is_stale(&self, objects: &FxHashSet<String>, version: u64) -> bool {
+        self.cleared_at > version
+            || objects.iter().any(|obj| {
+                self.object_versions
+                    .get(&object_key(obj))
+                    .is_some_and(|v| *v > version)
+            })
+    }
+
     pub fn get(&mut self, sql: &str) -> Option<LogicalPlan> {
         self.plans.get(&hash_sql(sql)).cloned()
     }
@@ -34,14 +65,20 @@ impl PlanCache {
         let hash = hash_sql(sql);
         let objects = extract_referenced_objects(&plan);
         for obj in objects {
-            self.object_to_hashes.entry(obj).or_default().insert(hash);
+            self.object_to_hashes
+                .entry(object_key(&obj))
+                .or_default()
+                .insert(hash);
         }
         self.plans.put(hash, plan);
     }
 
     pub fn invalidate_objects(&mut self, objects: &[String]) {
+        self.version += 1;
         for obj in objects {
-            if let Some(hashes) = self.object_to_hashes.remove(obj) {
+            let key = object_key(obj);
+            self.object_versions.insert(key.clone(), self.version);
+            if let Some(hashes) = self.object_to_hashes.remove(&key) {
                 for hash in hashes {
                     self.plans.pop(&hash);
                 }
@@ -52,33 +89,78 @@ impl PlanCache {
     pub fn clear(&mut self) {
         self.plans.clear();
         self.object_to_hashes.clear();
+        self.version += 1;
+        self.cleared_at = self.version;
+        self.object_versions.clear();
     }
 }
 
-fn extract_referenced_objects(plan: &LogicalPlan) -> FxHashSet<String> {
-    let mut objects = FxHashSet::default();
-    collect_objects_from_plan(plan, &mut objects);
-    objects
+pub(crate) fn extract_referenced_objects(plan: &LogicalPlan) -> FxHashSet<String> {
+    plan_references(plan).objects
+}
+
+/// Names a logical plan refers to.
+#[derive(Debug, Default)]
+pub(crate) struct PlanReferences {
+    /// Tables, views, functions and procedures, as written.
+    pub(crate) objects: FxHashSet<String>,
+    /// Columns read by the plan's expressions, as uppercased
+    /// `(qualifier, name)` pairs.
+    pub(crate) columns: FxHashSet<(Option<String>, String)>,
+    /// Uppercased qualifiers each scanned table is referred to by.
+    pub(crate) qualifiers: FxHashMap<String, FxHashSet<String>>,
 }
 
-fn collect_objects_from_plan(plan: &LogicalPlan, objects: &mut FxHashSet<String>) {
+impl PlanReferences {
+    /// Whether the plan reads `column` of the scanned table `table`.
+    ///
+    /// Unqualified references match a column of that name in any table.
+    pub(crate) fn reads_column(&self, table: &str, column: &str) -> bool {
+        let qualifiers = self.qualifiers.get(table);
+        let column = column.to_uppercase();
+        self.columns.iter().any(|(qualifier, name)| {
+            *name == column
+                && qualifier
+                    .as_ref()
+                    .is_none_or(|q| qualifiers.is_some_and(|qs| qs.contains(q)))
+        })
+    }
+}
+
+pub(crate) fn plan_references(plan: &LogicalPlan) -> PlanReferences {
+    let mut refs = PlanReferences::default();
+    collect_refs_from_plan(plan, &mut refs);
+    refs
+}
+
+fn collect_refs_from_plan(plan: &LogicalPlan, refs: &mut PlanReferences) {
     match plan {
-        LogicalPlan::Scan { table_name, .. } => {
-            objects.insert(table_name.clone());
-        }
-        LogicalPlan::Sample { input, .. } => collect_objects_from_plan(input, objects),
+        LogicalPlan::Scan {
+            table_name, schema, ..
+        } => {
+            refs.objects.insert(table_name.clone());
+            let qualifiers = refs.qualifiers.entry(table_name.clone()).or_default();
+            qualifiers.insert(table_name.to_uppercase());
+            qualifiers.extend(
+                schema
+                    .fields
+                    .iter()
+                    .filter_map(|field| field.table.as_ref().map(|t| t.to_uppercase())),
+            );
+        }
+        LogicalPlan::Sample { input, .. } => collect_refs_from_plan(input, refs),
         LogicalPlan::Filter {
             input, predicate, ..
         } => {
-            collect_objects_from_plan(input, objects);
-            collect_objects_from_expr(predicate, objects);
+            collect_refs_from_plan(input, refs);
+            collect_refs_from_expr(predicate, refs);
         }
         LogicalPlan::Project {
             input, expressions, ..
         } => {
-            collect_objects_from_plan(input, objects);
+            collect_refs_from_plan(input, refs);
             for expr in expressions {
-                collect_objects_from_expr(expr, objects);
+                collect_refs_from_expr(expr, refs);
             }
         }
         LogicalPlan::Aggregate {
@@ -87,12 +169,12 @@ fn collect_objects_from_plan(plan: &LogicalPlan, objects: &mut FxHashSet<String>
             aggregates,
             ..
         } => {
-            collect_objects_from_plan(input, objects);
+            collect_refs_from_plan(input, refs);
             for expr in group_by {
-                collect_objects_from_expr(expr, objects);
+                collect_refs_from_expr(expr, refs);
             }
             for expr in aggregates {
-                collect_objects_from_expr(expr, objects);
+                collect_refs_from_expr(expr, refs);
             }
         }
         LogicalPlan::Join {
@@ -101,55 +183,65 @@ fn collect_objects_from_plan(plan: &LogicalPlan, objects: &mut FxHashSet<String>
             condition,
             ..
         } => {
-            collect_objects_from_plan(left, objects);
-            collect_objects_from_plan(right, objects);
+            collect_refs_from_plan(left, refs);
+            collect_refs_from_plan(right, refs);
             if let Some(cond) = condition {
-                collect_objects_from_expr(cond, objects);
+                collect_refs_from_expr(cond, refs);
             }
         }
-        LogicalPlan::Sort { input, .. } => collect_objects_from_plan(input, objects),
-        LogicalPlan::Limit { input, .. } => collect_objects_from_plan(input, objects),
-        LogicalPlan::Distinct { input, .. } => collect_objects_from_plan(input, objects),
+        LogicalPlan::Sort { input, sort_exprs } => {
+            collect_refs_from_plan(input, refs);
+            for sort_expr in sort_exprs {
+                collect_refs_from_expr(&sort_expr.expr, refs);
+            }
+        }
+        LogicalPlan::Limit { input, .. } => collect_refs_from_plan(input, refs),
+        LogicalPlan::Distinct { input, .. } => collect_refs_from_plan(input, refs),
         LogicalPlan::Values { values, .. } => {
             for row in values {
                 for expr in row {
-                    collect_objects_from_expr(expr, objects);
+                    collect_refs_from_expr(expr, refs);
                 }
             }
         }
         LogicalPlan::Empty { .. } => {}
         LogicalPlan::SetOperation { left, right, .. } => {
-            collect_objects_from_plan(left, objects);
-            collect_objects_from_plan(right, objects);
+            collect_refs_from_plan(left, refs);
+            collect_refs_from_plan(right, refs);
         }
         LogicalPlan::Window {
             input,
             window_exprs,
             ..
         } => {
-            collect_objects_from_plan(input, objects);
+            collect_refs_from_plan(input, refs);
             for expr in window_exprs {
-                collect_objects_from_expr(expr, objects);
+                collect_refs_from_expr(expr, refs);
             }
         }
         LogicalPlan::WithCte { ctes, body } => {
             for cte in ctes {
-                collect_objects_from_plan(&cte.query, objects);
+                collect_refs_from_plan(&cte.query, refs);
+            }
+            collect_refs_from_plan(body, refs);
+        }
+        LogicalPlan::Unnest { input, columns, .. } => {
+            collect_refs_from_plan(input, refs);
+            for column in columns {
+                collect_refs_from_expr(&column.expr, refs);
             }
-            collect_objects_from_plan(body, objects);
         }
-        LogicalPlan::Unnest { input, .. } => collect_objects_from_plan(input, objects),
         LogicalPlan::Qualify {
             input, predicate, ..
         } => {
-            collect_objects_from_plan(input, objects);
-            collect_objects_from_expr(predicate, objects);
+            collect_refs_from_plan(input, refs);
+            collect_refs_from_expr(predicate, refs);
         }
         LogicalPlan::Insert {
             table_name, source, ..
         } => {
-            objects.insert(table_name.clone());
-            collect_objects_from_plan(source, objects);
+            refs.objects.insert(table_name.clone());
+            collect_refs_from_plan(source, refs);
         }
         LogicalPlan::Update {
             table_name,
@@ -158,23 +250,23 @@ fn collect_objects_from_plan(plan: &LogicalPlan, objects: &mut FxHashSet<String>
             assignments,
             ..
         } => {
-            objects.insert(table_name.clone());
+            refs.objects.insert(table_name.clone());
             if let Some(from) = from {
-                collect_objects_from_plan(from, objects);
+                collect_refs_from_plan(from, refs);
             }
             if let Some(filter) = filter {
-                collect_objects_from_expr(filter, objects);
+                collect_refs_from_expr(filter, refs);
             }
             for assignment in assignments {
-                collect_objects_from_expr(&assignment.value, objects);
+                collect_refs_from_expr(&assignment.value, refs);
             }
         }
         LogicalPlan::Delete {
             table_name, filter, ..
         } => {
-            objects.insert(table_name.clone());
+            refs.objects.insert(table_name.clone());
             if let Some(filter) = filter {
-                collect_objects_from_expr(filter, objects);
+                collect_refs_from_expr(filter, refs);
             }
         }
         LogicalPlan::Merge {
@@ -183,74 +275,74 @@ fn collect_objects_from_plan(plan: &LogicalPlan, objects: &mut FxHashSet<String>
             on,
             ..
         } => {
-            objects.insert(target_table.clone());
-            collect_objects_from_plan(source, objects);
-            collect_objects_from_expr(on, objects);
+            refs.objects.insert(target_table.clone());
+            collect_refs_from_plan(source, refs);
+            collect_refs_from_expr(on, refs);
         }
         LogicalPlan::CreateTable {
             table_name, query, ..
         } => {
-            objects.insert(table_name.clone());
+            refs.objects.insert(table_name.clone());
             if let Some(query) = query {
-                collect_objects_from_plan(query, objects);
+                collect_refs_from_plan(query, refs);
             }
         }
         LogicalPlan::DropTable { table_names, .. } => {
-            objects.extend(table_names.iter().cloned());
+            refs.objects.extend(table_names.iter().cloned());
         }
         LogicalPlan::AlterTable { table_name, .. } => {
-            objects.insert(table_name.clone());
+            refs.objects.insert(table_name.clone());
         }
         LogicalPlan::Truncate { table_name } => {
-            objects.insert(table_name.clone());
+            refs.objects.insert(table_name.clone());
         }
         LogicalPlan::CreateView { name, query, .. } => {
-            objects.insert(name.clone());
-            collect_objects_from_plan(query, objects);
+            refs.objects.insert(name.clone());
+            collect_refs_from_plan(query, refs);
         }
         LogicalPlan::DropView { name, .. } => {
-            objects.insert(name.clone());
+            refs.objects.insert(name.clone());
         }
         LogicalPlan::CreateSchema { .. } => {}
         LogicalPlan::DropSchema { .. } => {}
         LogicalPlan::UndropSchema { .. } => {}
         LogicalPlan::AlterSchema { .. } => {}
         LogicalPlan::CreateFunction { name, .. } => {
-            objects.insert(name.clone());
+            refs.objects.insert(name.clone());
         }
         LogicalPlan::DropFunction { name, .. } => {
-            objects.insert(name.clone());
+            refs.objects.insert(name.clone());
         }
         LogicalPlan::CreateProcedure { name, .. } => {
-            objects.insert(name.clone());
+            refs.objects.insert(name.clone());
         }
         LogicalPlan::DropProcedure { name, .. } => {
-            objects.insert(name.clone());
+            refs.objects.insert(name.clone());
         }
         LogicalPlan::Call {
             procedure_name,
             args,
             ..
         } => {
-            objects.insert(procedure_name.clone());
+            refs.objects.insert(procedure_name.clone());
             for arg in args {
-                collect_objects_from_expr(arg, objects);
+                collect_refs_from_expr(arg, refs);
             }
         }
-        LogicalPlan::ExportData { query, .. } => collect_objects_from_plan(query, objects),
+        LogicalPlan::ExportData { query, .. } => collect_refs_from_plan(query, refs),
         LogicalPlan::LoadData { table_name, .. } => {
-            objects.insert(table_name.clone());
+            refs.objects.insert(table_name.clone());
         }
         LogicalPlan::Declare { default, .. } => {
             if let Some(default) = default {
-                collect_objects_from_expr(default, objects);
+                collect_refs_from_expr(default, refs);
             }
         }
         LogicalPlan::SetVariable { value, .. } => {
-            collect_objects_from_expr(value, objects);
+            collect_refs_from_expr(value, refs);
         }
         LogicalPlan::SetMultipleVariables { value, .. } => {
-            collect_objects_from_expr(value, objects);
+            collect_refs_from_expr(value, refs);
         }
         LogicalPlan::If {
             condition,
@@ -258,32 +350,32 @@ fn collect_objects_from_plan(plan: &LogicalPlan, objects: &mut FxHashSet<String>
             else_branch,
             ..
         } => {
-            collect_objects_from_expr(condition, objects);
+            collect_refs_from_expr(condition, refs);
             for plan in then_branch {
-                collect_objects_from_plan(plan, objects);
+                collect_refs_from_plan(plan, refs);
             }
             if let Some(else_branch) = else_branch {
                 for plan in else_branch {
-                    collect_objects_from_plan(plan, objects);
+                    collect_refs_from_plan(plan, refs);
                 }
             }
         }
         LogicalPlan::While {
             condition, body, ..
         } => {
-            collect_objects_from_expr(condition, objects);
+            collect_refs_from_expr(condition, refs);
             for plan in body {
-                collect_objects_from_plan(plan, objects);
+                collect_refs_from_plan(plan, refs);
             }
         }
         LogicalPlan::Loop { body, .. } => {
             for plan in body {
-                collect_objects_from_plan(plan, objects);
+                collect_refs_from_plan(plan, refs);
             }
         }
         LogicalPlan::Block { body, .. } => {
             for plan in body {
-                collect_objects_from_plan(plan, objects);
+                collect_refs_from_plan(plan, refs);
             }
         }
         LogicalPlan::Repeat {
@@ -292,24 +384,24 @@ fn collect_objects_from_plan(plan: &LogicalPlan, objects: &mut FxHashSet<String>
             ..
         } => {
             for plan in body {
-                collect_objects_from_plan(plan, objects);
+                collect_refs_from_plan(plan, refs);
             }
-            collect_objects_from_expr(until_condition, objects);
+            collect_refs_from_expr(until_condition, refs);
         }
         LogicalPlan::For { query, body, .. } => {
-            collect_objects_from_plan(query, objects);
+            collect_refs_from_plan(query, refs);
             for plan in body {
-                collect_objects_from_plan(plan, objects);
+                collect_refs_from_plan(plan, refs);
             }
         }
         LogicalPlan::Return { value, .. } => {
             if let Some(value) = value {
-                collect_objects_from_expr(value, objects);
+                collect_refs_from_expr(value, refs);
             }
         }
         LogicalPlan::Raise { message, .. } => {
             if let Some(message) = message {
-                collect_objects_from_expr(message, objects);
+                collect_refs_from_expr(message, refs);
             }
         }
         LogicalPlan::ExecuteImmediate {
@@ -317,9 +409,9 @@ fn collect_objects_from_plan(plan: &LogicalPlan, objects: &mut FxHashSet<String>
             using_params,
             ..
         } => {
-            collect_objects_from_expr(sql_expr, objects);
+            collect_refs_from_expr(sql_expr, refs);
             for (expr, _) in using_params {
-                collect_objects_from_expr(expr, objects);
+                collect_refs_from_expr(expr, refs);
             }
         }
         LogicalPlan::Break { .. } => {}
@@ -329,18 +421,18 @@ fn collect_objects_from_plan(plan: &LogicalPlan, objects: &mut FxHashSet<String>
             source_name,
             ..
         } => {
-            objects.insert(snapshot_name.clone());
-            objects.insert(source_name.clone());
+            refs.objects.insert(snapshot_name.clone());
+            refs.objects.insert(source_name.clone());
         }
         LogicalPlan::DropSnapshot { snapshot_name, .. } => {
-            objects.insert(snapshot_name.clone());
+            refs.objects.insert(snapshot_name.clone());
         }
         LogicalPlan::Assert {
             condition, message, ..
         } => {
-            collect_objects_from_expr(condition, objects);
+            collect_refs_from_expr(condition, refs);
             if let Some(message) = message {
-                collect_objects_from_expr(message, objects);
+                collect_refs_from_expr(message, refs);
             }
         }
         LogicalPlan::Grant { .. } => {}
@@ -353,10 +445,10 @@ fn collect_objects_from_plan(plan: &LogicalPlan, objects: &mut FxHashSet<String>
             catch_block,
         } => {
             for (plan, _) in try_block {
-                collect_objects_from_plan(plan, objects);
+                collect_refs_from_plan(plan, refs);
             }
             for plan in catch_block {
-                collect_objects_from_plan(plan, objects);
+                collect_refs_from_plan(plan, refs);
             }
         }
         LogicalPlan::GapFill {
@@ -365,40 +457,40 @@ fn collect_objects_from_plan(plan: &LogicalPlan, objects: &mut FxHashSet<String>
             origin,
             ..
         } => {
-            collect_objects_from_plan(input, objects);
-            collect_objects_from_expr(bucket_width, objects);
+            collect_refs_from_plan(input, refs);
+            collect_refs_from_expr(bucket_width, refs);
             if let Some(origin) = origin {
-                collect_objects_from_expr(origin, objects);
+                collect_refs_from_expr(origin, refs);
             }
         }
-        LogicalPlan::Explain { input, .. } => collect_objects_from_plan(input, objects),
+        LogicalPlan::Explain { input, .. } => collect_refs_from_plan(input, refs),
     }
 }
 
-fn collect_objects_from_expr(expr: &Expr, objects: &mut FxHashSet<String>) {
+fn collect_refs_from_expr(expr: &Expr, refs: &mut PlanReferences) {
     match expr {
         Expr::ScalarFunction { args, .. } => {
             for arg in args {
-                collect_objects_from_expr(arg, objects);
+                collect_refs_from_expr(arg, refs);
             }
         }
         Expr::Aggregate { args, filter, .. } => {
             for arg in args {
-                collect_objects_from_expr(arg, objects);
+                collect_refs_from_expr(arg, refs);
             }
             if let Some(filter) = filter {
-                collect_objects_from_expr(filter, objects);
+                collect_refs_from_expr(filter, refs);
             }
         }
         Expr::UserDefinedAggregate {
             name, args, filter, ..
         } => {
-            objects.insert(name.clone());
+            refs.objects.insert(name.clone());
             for arg in args {
-                collect_objects_from_expr(arg, objects);
+                collect_refs_from_expr(arg, refs);
             }
             if let Some(filter) = filter {
-                collect_objects_from_expr(filter, objects);
+                collect_refs_from_expr(filter, refs);
             }
         }
         Expr::Window {
@@ -408,13 +500,13 @@ fn collect_objects_from_expr(expr: &Expr, objects: &mut FxHashSet<String>) {
             ..
         } => {
             for arg in args {
-                collect_objects_from_expr(arg, objects);
+                collect_refs_from_expr(arg, refs);
             }
             for e in partition_by {
-                collect_objects_from_expr(e, objects);
+                collect_refs_from_expr(e, refs);
             }
             for sort_expr in order_by {
-                collect_objects_from_expr(&sort_expr.expr, objects);
+                collect_refs_from_expr(&sort_expr.expr, refs);
             }
         }
         Expr::AggregateWindow {
@@ -424,21 +516,39 @@ fn collect_objects_from_expr(expr: &Expr, objects: &mut FxHashSet<String>) {
             ..
         } => {
             for arg in args {
-                collect_objects_from_expr(arg, objects);
+                collect_refs_from_expr(arg, refs);
+            }
+            for e in partition_by {
+                collect_refs_from_expr(e, refs);
+            }
+            for sort_expr in order_by {
+                collect_refs_from_expr(&sort_expr.expr, refs);
+            }
+        }
+        Expr::UserDefinedAggregateWindow {
+            name,
+            args,
+            partition_by,
+            order_by,
+            ..
+        } => {
+            refs.objects.insert(name.clone());
+            for arg in args {
+                collect_refs_from_expr(arg, refs);
             }
             for e in partition_by {
-                collect_objects_from_expr(e, objects);
+                collect_refs_from_expr(e, refs);
             }
             for sort_expr in order_by {
-                collect_objects_from_expr(&sort_expr.expr, objects);
+                collect_refs_from_expr(&sort_expr.expr, refs);
             }
         }
         Expr::BinaryOp { left, right, .. } => {
-            collect_objects_from_expr(left, objects);
-            collect_objects_from_expr(right, objects);
+            collect_refs_from_expr(left, refs);
+            collect_refs_from_expr(right, refs);
         }
         Expr::UnaryOp { expr, .. } => {
-            collect_objects_from_expr(expr, objects);
+            collect_refs_from_expr(expr, refs);
         }
         Expr::Case {
             operand,
@@ -446,115 +556,115 @@ fn collect_objects_from_expr(expr: &Expr, objects: &mut FxHashSet<String>) {
             else_result,
         } => {
             if let Some(operand) = operand {
-                collect_objects_from_expr(operand, objects);
+                collect_refs_from_expr(operand, refs);
             }
             for clause in when_clauses {
-                collect_objects_from_expr(&clause.condition, objects);
-                collect_objects_from_expr(&clause.result, objects);
+                collect_refs_from_expr(&clause.condition, refs);
+                collect_refs_from_expr(&clause.result, refs);
             }
             if let Some(else_result) = else_result {
-                collect_objects_from_expr(else_result, objects);
+                collect_refs_from_expr(else_result, refs);
             }
         }
         Expr::Cast { expr, .. } => {
-            collect_objects_from_expr(expr, objects);
+            collect_refs_from_expr(expr, refs);
         }
         Expr::InList { expr, list, .. } => {
-            collect_objects_from_expr(expr, objects);
+            collect_refs_from_expr(expr, refs);
             for item in list {
-                collect_objects_from_expr(item, objects);
+                collect_refs_from_expr(item, refs);
             }
         }
         Expr::InSubquery { expr, subquery, .. } => {
-            collect_objects_from_expr(expr, objects);
-            collect_objects_from_plan(subquery, objects);
+            collect_refs_from_expr(expr, refs);
+            collect_refs_from_plan(subquery, refs);
         }
         Expr::InUnnest {
             expr, array_expr, ..
         } => {
-            collect_objects_from_expr(expr, objects);
-            collect_objects_from_expr(array_expr, objects);
+            collect_refs_from_expr(expr, refs);
+            collect_refs_from_expr(array_expr, refs);
         }
         Expr::Between {
             expr, low, high, ..
         } => {
-            collect_objects_from_expr(expr, objects);
-            collect_objects_from_expr(low, objects);
-            collect_objects_from_expr(high, objects);
+            collect_refs_from_expr(expr, refs);
+            collect_refs_from_expr(low, refs);
+            collect_refs_from_expr(high, refs);
         }
         Expr::Subquery(subquery) => {
-            collect_objects_from_plan(subquery, objects);
+            collect_refs_from_plan(subquery, refs);
         }
         Expr::ScalarSubquery(subquery) => {
-            collect_objects_from_plan(subquery, objects);
+            collect_refs_from_plan(subquery, refs);
         }
         Expr::ArraySubquery(subquery) => {
-            collect_objects_from_plan(subquery, objects);
+            collect_refs_from_plan(subquery, refs);
         }
         Expr::Exists { subquery, .. } => {
-            collect_objects_from_plan(subquery, objects);
+            collect_refs_from_plan(subquery, refs);
         }
         Expr::Array { elements, .. } => {
             for elem in elements {
-                collect_objects_from_expr(elem, objects);
+                collect_refs_from_expr(elem, refs);
             }
         }
         Expr::Struct { fields } => {
             for (_, e) in fields {
-                collect_objects_from_expr(e, objects);
+                collect_refs_from_expr(e, refs);
             }
         }
         Expr::ArrayAccess { array, index } => {
-            collect_objects_from_expr(array, objects);
-            collect_objects_from_expr(index, objects);
+            collect_refs_from_expr(array, refs);
+            collect_refs_from_expr(index, refs);
         }
         Expr::StructAccess { expr, .. } => {
-            collect_objects_from_expr(expr, objects);
+            collect_refs_from_expr(expr, refs);
         }
         Expr::Like { expr, pattern, .. } => {
-            collect_objects_from_expr(expr, objects);
-            collect_objects_from_expr(pattern, objects);
+            collect_refs_from_expr(expr, refs);
+            collect_refs_from_expr(pattern, refs);
         }
         Expr::IsNull { expr, .. } => {
-            collect_objects_from_expr(expr, objects);
+            collect_refs_from_expr(expr, refs);
         }
         Expr::IsDistinctFrom { left, right, .. } => {
-            collect_objects_from_expr(left, objects);
-            collect_objects_from_expr(right, objects);
+            collect_refs_from_expr(left, refs);
+            collect_refs_from_expr(right, refs);
         }
         Expr::Lambda { body, .. } => {
-            collect_objects_from_expr(body, objects);
+            collect_refs_from_expr(body, refs);
         }
         Expr::Alias { expr, .. } => {
-            collect_objects_from_expr(expr, objects);
+            collect_refs_from_expr(expr, refs);
         }
         Expr::Extract { expr, .. } => {
-            collect_objects_from_expr(expr, objects);
+            collect_refs_from_expr(expr, refs);
         }
         Expr::Substring {
             expr,
             start,
             length,
         } => {
-            collect_objects_from_expr(expr, objects);
+            collect_refs_from_expr(expr, refs);
             if let Some(start) = start {
-                collect_objects_from_expr(start, objects);
+                collect_refs_from_expr(start, refs);
             }
             if let Some(length) = length {
-                collect_objects_from_expr(length, objects);
+                collect_refs_from_expr(length, refs);
             }
         }
         Expr::Trim {
             expr, trim_what, ..
         } => {
-            collect_objects_from_expr(expr, objects);
+            collect_refs_from_expr(expr, refs);
             if let Some(trim_what) = trim_what {
-                collect_objects_from_expr(trim_what, objects);
+                collect_refs_from_expr(trim_what, refs);
             }
         }
         Expr::Position { substr, string } => {
-            collect_objects_from_expr(substr, objects);
-            collect_objects_from_expr(string, objects);
+            collect_refs_from_expr(substr, refs);
+            collect_refs_from_expr(string, refs);
         }
         Expr::Overlay {
             expr,
@@ -562,29 +672,34 @@ fn collect_objects_from_expr(expr: &Expr, objects: &mut FxHashSet<String>) {
             overlay_from,
             overlay_for,
         } => {
-            collect_objects_from_expr(expr, objects);
-            collect_objects_from_expr(overlay_what, objects);
-            collect_objects_from_expr(overlay_from, objects);
+            collect_refs_from_expr(expr, refs);
+            collect_refs_from_expr(overlay_what, refs);
+            collect_refs_from_expr(overlay_from, refs);
             if let Some(overlay_for) = overlay_for {
-                collect_objects_from_expr(overlay_for, objects);
+                collect_refs_from_expr(overlay_for, refs);
             }
         }
         Expr::TypedString { .. } => {}
         Expr::Interval { value, .. } => {
-            collect_objects_from_expr(value, objects);
+            collect_refs_from_expr(value, refs);
         }
         Expr::AtTimeZone {
             timestamp,
             time_zone,
         } => {
-            collect_objects_from_expr(timestamp, objects);
-            collect_objects_from_expr(time_zone, objects);
+            collect_refs_from_expr(timestamp, refs);
+            collect_refs_from_expr(time_zone, refs);
         }
         Expr::JsonAccess { expr, .. } => {
-            collect_objects_from_expr(expr, objects);
+            collect_refs_from_expr(expr, refs);
+        }
+        Expr::Column { table, name, .. } => {
+            refs.columns.insert((
+                table.as_ref().map(|t| t.to_uppercase()),
+                name.to_uppercase(),
+            ));
         }
-        Expr::Column { .. }
-        | Expr::Literal(_)
+        Expr::Literal(_)
         | Expr::Wildcard { .. }
         | Expr::Placeholder { .. }
         | Expr::Variable { .. }
@@ -0,0 +1,127 @@
+#![coverage(off)]
+
+use std::sync::{Arc, RwLock};
+use std::time::Instant;
+
+use rustc_hash::FxHashSet;
+use tracing::{debug, instrument};
+use yachtsql_common::error::Result;
+use yachtsql_common::result::QueryResult;
+use yachtsql_ir::LogicalPlan;
+use yachtsql_optimizer::PhysicalPlan;
+use yachtsql_parser::ParameterMarkers;
+use yachtsql_storage::Table;
+
+use crate::async_executor::{AsyncQueryExecutor, ExecutionPlan, preprocess_range_types};
+use crate::cancellation::CancellationHandle;
+use crate::plan_cache::extract_referenced_objects;
+use crate::query_params::{QueryParameter, bind_parameters, check_parameter_types};
+
+struct PreparedPlan {
+    logical: LogicalPlan,
+    physical: PhysicalPlan,
+    execution: Arc<ExecutionPlan>,
+    objects: FxHashSet<String>,
+    version: u64,
+}
+
+pub struct PreparedStatement {
+    executor: AsyncQueryExecutor,
+    sql: String,
+    planned_sql: String,
+    markers: ParameterMarkers,
+    plan: RwLock<Arc<PreparedPlan>>,
+}
+
+impl PreparedStatement {
+    pub(crate) fn new(executor: AsyncQueryExecutor, sql: &str) -> Result<Self> {
+        let markers = yachtsql_parser::extract_parameter_markers(sql)?;
+        let planned_sql = preprocess_range_types(&markers.sql);
+        let plan = Self::build_plan(&executor, &planned_sql)?;
+        Ok(Self {
+            executor,
+            sql: sql.to_string(),
+            planned_sql,
+            markers,
+            plan: RwLock::new(Arc::new(plan)),
+        })
+    }
+
+    fn build_plan(executor: &AsyncQueryExecutor, sql: &str) -> Result<PreparedPlan> {
+        let version = executor.plan_cache_version();
+        let logical = executor.plan_logical(sql)?;
+        let physical = executor.optimize(&logical)?;
+        let objects = extract_referenced_objects(&logical);
+        let execution = executor.plan_execution(&physical);
+        Ok(PreparedPlan {
+            logical,
+            physical,
+            execution,
+            objects,
+            version,
+        })
+    }
+
+    pub fn sql(&self) -> &str {
+        &self.sql
+    }
+
+    pub fn parameter_names(&self) -> &[String] {
+        &self.markers.named
+    }
+
+    pub fn positional_parameter_count(&self) -> usize {
+        self.markers.positional
+    }
+
+    pub fn is_stale(&self) -> bool {
+        let plan = self.current_plan();
+        self.executor.is_plan_stale(&plan.objects, plan.version)
+    }
+
+    #[instrument(skip(self, params), fields(param_count = params.len()))]
+    pub async fn execute(&self, params: &[QueryParameter]) -> Result<Table> {
+        let start = Instant::now();
+        let mut trace = self.executor.trace_query(&self.planned_sql);
+
+        let result = async {
+            let bound = bind_parameters(&self.markers, params)?;
+
+            let plan = if self.is_stale() {
+                debug!(sql = %self.planned_sql, "Prepared statement invalidated, replanning");
+                let plan = Arc::new(Self::build_plan(&self.executor, &self.planned_sql)?);
+                *self.plan.write().unwrap_or_else(|e| e.into_inner()) = Arc::clone(&plan);
+                plan
+            } else {
+                self.current_plan()
+            };
+
+            check_parameter_types(&plan.logical, &bound.types, self.executor.catalog())?;
+            trace.plan_ready(&plan.logical, &plan.physical);
+
+            self.executor
+                .run_plan(
+                    &self.planned_sql,
+                    &plan.logical,
+                    Arc::clone(&plan.execution),
+                    Some(bound),
+                    &CancellationHandle::default(),
+                    start,
+                    &mut trace,
+                )
+                .await
+        }
+        .await;
+        trace.finish(&result);
+        result
+    }
+
+    pub async fn query(&self, params: &[QueryParameter]) -> Result<QueryResult> {
+        let table = self.execute(params).await?;
+        table.to_query_result()
+    }
+
+    fn current_plan(&self) -> Arc<PreparedPlan> {
+        Arc::clone(&self.plan.read().unwrap_or_else(|e| e.into_inner()))
+    }
+}
@@ -0,0 +1,533 @@
+#![coverage(off)]
+
+use rustc_hash::FxHashMap;
+use yachtsql_common::error::{Error, Result};
+use yachtsql_common::types::{DataType, Value};
+use yachtsql_ir::{BinaryOp, Expr, LogicalPlan};
+use yachtsql_parser::ParameterMarkers;
+
+use crate::concurrent_catalog::ConcurrentCatalog;
+use crate::value_evaluator::cast_value;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryParameter {
+    pub name: Option<String>,
+    pub data_type: DataType,
+    pub value: Value,
+}
+
+impl QueryParameter {
+    pub fn named(name: impl Into<String>, value: Value) -> Self {
+        let name: String = name.into();
+        Self {
+            name: Some(name.trim_start_matches('@').to_string()),
+            data_type: value.data_type(),
+            value,
+        }
+    }
+
+    pub fn positional(value: Value) -> Self {
+        Self {
+            name: None,
+            data_type: value.data_type(),
+            value,
+        }
+    }
+
+    pub fn with_type(mut self, data_type: DataType) -> Self {
+        self.data_type = data_type;
+        self
+    }
+
+    fn label(&self, position: usize) -> String {
+        match &self.name {
+            Some(name) => format!("@{}", name),
+            None => format!("?{}", position),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct BoundParameters {
+    pub(crate) values: FxHashMap<String, Value>,
+    pub(crate) types: FxHashMap<String, DataType>,
+}
+
+pub(crate) fn bind_parameters(
+    markers: &ParameterMarkers,
+    params: &[QueryParameter],
+) -> Result<BoundParameters> {
+    let named_count = params.iter().filter(|p| p.name.is_some()).count();
+    if named_count != 0 && named_count != params.len() {
+        return Err(Error::invalid_query(
+            "Query parameters must be either all named or all positional",
+        ));
+    }
+
+    let mut bound = BoundParameters::default();
+
+    for (idx, param) in params.iter().enumerate() {
+        let position = idx + 1;
+        let value = coerce_parameter_value(param, position)?;
+        let key = match &param.name {
+            Some(name) => format!("@{}", name.to_uppercase()),
+            None => format!("?{}", position),
+        };
+        if bound.values.insert(key.clone(), value).is_some() {
+            return Err(Error::invalid_query(format!(
+                "Duplicate query parameter '{}'",
+                param.label(position)
+            )));
+        }
+        bound.types.insert(key, param.data_type.clone());
+    }
+
+    if named_count > 0 {
+        if markers.positional > 0 {
+            return Err(Error::invalid_query(
+                "Query uses positional parameters but named parameters were provided",
+            ));
+        }
+        if let Some((position, param)) = params.iter().enumerate().find(|(_, param)| {
+            param.name.as_ref().is_some_and(|name| {
+                !markers
+                    .named
+                    .iter()
+                    .any(|marker| marker.eq_ignore_ascii_case(name))
+            })
+        }) {
+            return Err(Error::invalid_query(format!(
+                "Query parameter '{}' is not used in the query",
+                param.label(position + 1)
+            )));
+        }
+    } else if markers.positional != params.len() {
+        return Err(Error::invalid_query(format!(
+            "Query has {} positional parameters but {} values were provided",
+            markers.positional,
+            params.len()
+        )));
+    }
+
+    for name in &markers.named {
+        if !bound
+            .values
+            .contains_key(&format!("@{}", name.to_uppercase()))
+        {
+            return Err(Error::invalid_query(format!(
+                "Unbound query parameter '@{}'",
+                name
+            )));
+        }
+    }
+
+    Ok(bound)
+}
+
+fn coerce_parameter_value(param: &QueryParameter, position: usize) -> Result<Value> {
+    let actual = param.value.data_type();
+    if param.value.is_null() || param.data_type == DataType::Unknown || actual == param.data_type {
+        return Ok(param.value.clone());
+    }
+    if !types_compatible(&param.data_type, &actual) {
+        return Err(Error::invalid_query(format!(
+            "Query parameter '{}' is declared as {} but its value has type {}",
+            param.label(position),
+            param.data_type,
+            actual
+        )));
+    }
+    cast_value(param.value.clone(), &param.data_type, false).map_err(|e| {
+        Error::invalid_query(format!(
+            "Query parameter '{}' cannot be converted to {}: {}",
+            param.label(position),
+            param.data_type,
+            e
+        ))
+    })
+}
+
+pub(crate) fn check_parameter_types(
+    plan: &LogicalPlan,
+    types: &FxHashMap<String, DataType>,
+    catalog: &ConcurrentCatalog,
+) -> Result<()> {
+    let checker = ParameterTypeChecker { types, catalog };
+    checker.check_plan(plan)
+}
+
+struct ParameterTypeChecker<'a> {
+    types: &'a FxHashMap<String, DataType>,
+    catalog: &'a ConcurrentCatalog,
+}
+
+impl ParameterTypeChecker<'_> {
+    fn check_plan(&self, plan: &LogicalPlan) -> Result<()> {
+        match plan {
+            LogicalPlan::Filter { input, predicate }
+            | LogicalPlan::Qualify { input, predicate } => {
+                self.check_expr(predicate, &plan_fields(input))?;
+                self.check_plan(input)
+            }
+            LogicalPlan::Project {
+                input, expressions, ..
+            } => {
+                let fields = plan_fields(input);
+                for expr in expressions {
+                    self.check_expr(expr, &fields)?;
+                }
+                self.check_plan(input)
+            }
+            LogicalPlan::Aggregate {
+                input,
+                group_by,
+                aggregates,
+                ..
+            } => {
+                let fields = plan_fields(input);
+                for expr in group_by.iter().chain(aggregates) {
+                    self.check_expr(expr, &fields)?;
+                }
+                self.check_plan(input)
+            }
+            LogicalPlan::Join {
+                left,
+                right,
+                condition,
+                schema,
+                ..
+            } => {
+                if let Some(condition) = condition {
+                    let fields = schema
+                        .fields
+                        .iter()
+                        .map(|f| (f.name.clone(), f.data_type.clone()))
+                        .collect::<Vec<_>>();
+                    self.check_expr(condition, &fields)?;
+                }
+                self.check_plan(left)?;
+                self.check_plan(right)
+            }
+            LogicalPlan::Sample { input, .. }
+            | LogicalPlan::Sort { input, .. }
+            | LogicalPlan::Limit { input, .. }
+            | LogicalPlan::Distinct { input }
+            | LogicalPlan::Window { input, .. }
+            | LogicalPlan::Unnest { input, .. }
+            | LogicalPlan::Explain { input, .. } => self.check_plan(input),
+            LogicalPlan::SetOperation { left, right, .. } => {
+                self.check_plan(left)?;
+                self.check_plan(right)
+            }
+            LogicalPlan::WithCte { ctes, body } => {
+                for cte in ctes {
+                    self.check_plan(&cte.query)?;
+                }
+                self.check_plan(body)
+            }
+            LogicalPlan::Values { values, .. } => {
+                for row in values {
+                    for expr in row {
+                        self.check_expr(expr, &[])?;
+                    }
+                }
+                Ok(())
+            }
+            LogicalPlan::Insert {
+                table_name,
+                columns,
+                source,
+            } => {
+                let table_fields = self.table_fields(table_name);
+                if let LogicalPlan::Values { values, .. } = source.as_ref() {
+                    let targets: Vec<(String, DataType)> = if columns.is_empty() {
+                        table_fields.clone()
+                    } else {
+                        columns
+                            .iter()
+                            .map(|c| {
+                                let data_type = table_fields
+                                    .iter()
+                                    .find(|(name, _)| name.eq_ignore_ascii_case(c))
+                                    .map(|(_, t)| t.clone())
+                                    .unwrap_or(DataType::Unknown);
+                                (c.clone(), data_type)
+                            })
+                            .collect()
+                    };
+                    for row in values {
+                        for (expr, (column, column_type)) in row.iter().zip(&targets) {
+                            self.check_insert_value(expr, column, column_type)?;
+                        }
+                    }
+                }
+                self.check_plan(source)
+            }
+            LogicalPlan::Update {
+                table_name,
+                assignments,
+                from,
+                filter,
+                ..
+            } => {
+                let mut fields = self.table_fields(table_name);
+                if let Some(from) = from {
+                    fields.extend(plan_fields(from));
+                    self.check_plan(from)?;
+                }
+                for assignment in assignments {
+                    self.check_expr(&assignment.value, &fields)?;
+                }
+                match filter {
+                    Some(filter) => self.check_expr(filter, &fields),
+                    None => Ok(()),
+                }
+            }
+            LogicalPlan::Delete {
+                table_name,
+                filter: Some(filter),
+                ..
+            } => self.check_expr(filter, &self.table_fields(table_name)),
+            _ => Ok(()),
+        }
+    }
+
+    fn check_insert_value(&self, expr: &Expr, column: &str, column_type: &DataType) -> Result<()> {
+        let Some(param_type) = self.parameter_type(expr) else {
+            return Ok(());
+        };
+        if types_compatible(&param_type, column_type) {
+            return Ok(());
+        }
+        Err(Error::invalid_query(format!(
+            "Value has type {} which cannot be inserted into column {}, which has type {}",
+            param_type, column, column_type
+        )))
+    }
+
+    fn check_expr(&self, expr: &Expr, fields: &[(String, DataType)]) -> Result<()> {
+        match expr {
+            Expr::BinaryOp { left, op, right } => {
+                if let Some(symbol) = comparison_symbol(op) {
+                    self.check_operands(symbol, &[left, right], fields)?;
+                }
+                self.check_expr(left, fields)?;
+                self.check_expr(right, fields)
+            }
+            Expr::IsDistinctFrom { left, right, .. } => {
+                self.check_operands("IS DISTINCT FROM", &[left, right], fields)?;
+                self.check_expr(left, fields)?;
+                self.check_expr(right, fields)
+            }
+            Expr::InList { expr, list, .. } => {
+                for item in list {
+                    self.check_operands("IN", &[expr, item], fields)?;
+                    self.check_expr(item, fields)?;
+                }
+                self.check_expr(expr, fields)
+            }
+            Expr::Between {
+                expr, low, high, ..
+            } => {
+                self.check_operands("BETWEEN", &[expr, low, high], fields)?;
+                self.check_expr(expr, fields)?;
+                self.check_expr(low, fields)?;
+                self.check_expr(high, fields)
+            }
+            Expr::Like { expr, pattern, .. } => {
+                self.check_operands("LIKE", &[expr, pattern], fields)?;
+                self.check_expr(expr, fields)?;
+                self.check_expr(pattern, fields)
+            }
+            Expr::InUnnest {
+                expr, array_expr, ..
+            } => {
+                if self.is_parameter(expr) || self.is_parameter(array_expr) {
+                    let value_type = self.expr_type(expr, fields);
+                    let array_type = self.expr_type(array_expr, fields);
+                    let compatible = match &array_type {
+                        DataType::Array(element) => types_compatible(&value_type, element),
+                        DataType::Unknown => true,
+                        _ => false,
+                    };
+                    if !compatible {
+                        return Err(Error::invalid_query(format!(
+                            "No matching signature for operator IN UNNEST for argument types: {}, {}",
+                            value_type, array_type
+                        )));
+                    }
+                }
+                self.check_expr(expr, fields)?;
+                self.check_expr(array_expr, fields)
+            }
+            Expr::UnaryOp { expr, .. }
+            | Expr::IsNull { expr, .. }
+            | Expr::Alias { expr, .. }
+            | Expr::Cast { expr, .. } => self.check_expr(expr, fields),
+            Expr::ScalarFunction { args, .. } => {
+                for arg in args {
+                    self.check_expr(arg, fields)?;
+                }
+                Ok(())
+            }
+            Expr::Case {
+                operand,
+                when_clauses,
+                else_result,
+            } => {
+                if let Some(operand) = operand {
+                    for clause in when_clauses {
+                        self.check_operands("=", &[operand, &clause.condition], fields)?;
+                    }
+                    self.check_expr(operand, fields)?;
+                }
+                for clause in when_clauses {
+                    self.check_expr(&clause.condition, fields)?;
+                    self.check_expr(&clause.result, fields)?;
+                }
+                match else_result {
+                    Some(else_result) => self.check_expr(else_result, fields),
+                    None => Ok(()),
+                }
+            }
+            Expr::InSubquery { expr, subquery, .. } => {
+                self.check_expr(expr, fields)?;
+                self.check_plan(subquery)
+            }
+            Expr::Exists { subquery, .. }
+            | Expr::Subquery(subquery)
+            | Expr::ScalarSubquery(subquery)
+            | Expr::ArraySubquery(subquery) => self.check_plan(subquery),
+            _ => Ok(()),
+        }
+    }
+
+    fn check_operands(
+        &self,
+        symbol: &str,
+        operands: &[&Expr],
+        fields: &[(String, DataType)],
+    ) -> Result<()> {
+        if !operands.iter().any(|e| self.is_parameter(e)) {
+            return Ok(());
+        }
+        let types: Vec<DataType> = operands.iter().map(|e| self.expr_type(e, fields)).collect();
+        let Some(first) = types.first() else {
+            return Ok(());
+        };
+        if types.iter().all(|t| types_compatible(first, t)) {
+            return Ok(());
+        }
+        let names: Vec<String> = types.iter().map(|t| t.to_string()).collect();
+        Err(Error::invalid_query(format!(
+            "No matching signature for operator {} for argument types: {}",
+            symbol,
+            names.join(", ")
+        )))
+    }
+
+    fn is_parameter(&self, expr: &Expr) -> bool {
+        self.parameter_type(expr).is_some()
+    }
+
+    fn parameter_type(&self, expr: &Expr) -> Option<DataType> {
+        match expr {
+            Expr::Variable { name } => self.types.get(&name.to_uppercase()).cloned(),
+            Expr::Alias { expr, .. } => self.parameter_type(expr),
+            _ => None,
+        }
+    }
+
+    fn expr_type(&self, expr: &Expr, fields: &[(String, DataType)]) -> DataType {
+        match expr {
+            Expr::Variable { .. } => self.parameter_type(expr).unwrap_or(DataType::Unknown),
+            Expr::Literal(lit) => lit.data_type(),
+            Expr::Column { name, index, .. } => index
+                .and_then(|idx| fields.get(idx))
+                .or_else(|| {
+                    fields
+                        .iter()
+                        .find(|(field, _)| field.eq_ignore_ascii_case(name))
+                })
+                .map(|(_, t)| t.clone())
+                .unwrap_or(DataType::Unknown),
+            Expr::Cast { data_type, .. } | Expr::TypedString { data_type, .. } => data_type.clone(),
+            Expr::Alias { expr, .. } => self.expr_type(expr, fields),
+            Expr::StructAccess { expr, field } => match self.expr_type(expr, fields) {
+                DataType::Struct(struct_fields) => struct_fields
+                    .into_iter()
+                    .find(|f| f.name.eq_ignore_ascii_case(field))
+                    .map(|f| f.data_type)
+                    .unwrap_or(DataType::Unknown),
+                _ => DataType::Unknown,
+            },
+            _ => DataType::Unknown,
+        }
+    }
+
+    fn table_fields(&self, table_name: &str) -> Vec<(String, DataType)> {
+        self.catalog
+            .get_table_schema(table_name)
+            .map(|schema| {
+                schema
+                    .fields()
+                    .iter()
+                    .map(|f| (f.name.clone(), f.data_type.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+fn plan_fields(plan: &LogicalPlan) -> Vec<(String, DataType)> {
+    plan.schema()
+        .fields
+        .iter()
+        .map(|f| (f.name.clone(), f.data_type.clone()))
+        .collect()
+}
+
+fn comparison_symbol(op: &BinaryOp) -> Option<&'static str> {
+    match op {
+        BinaryOp::Eq => Some("="),
+        BinaryOp::NotEq => Some("!="),
+        BinaryOp::Lt => Some("<"),
+        BinaryOp::LtEq => Some("<="),
+        BinaryOp::Gt => Some(">"),
+        BinaryOp::GtEq => Some(">="),
+        _ => None,
+    }
+}
+
+fn types_compatible(a: &DataType, b: &DataType) -> bool {
+    match (a, b) {
+        (DataType::Unknown, _) | (_, DataType::Unknown) => true,
+        (DataType::Array(a), DataType::Array(b)) => types_compatible(a, b),
+        (DataType::Struct(a), DataType::Struct(b)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .zip(b)
+                    .all(|(a, b)| types_compatible(&a.data_type, &b.data_type))
+        }
+        (DataType::Range(a), DataType::Range(b)) => types_compatible(a, b),
+        (a, b) if is_numeric(a) && is_numeric(b) => true,
+        (a, b) if is_temporal(a) && is_temporal(b) => true,
+        (DataType::String, b) if is_temporal(b) || *b == DataType::Time => true,
+        (a, DataType::String) if is_temporal(a) || *a == DataType::Time => true,
+        (a, b) => a == b,
+    }
+}
+
+fn is_numeric(data_type: &DataType) -> bool {
+    matches!(
+        data_type,
+        DataType::Int64 | DataType::Float64 | DataType::Numeric(_) | DataType::BigNumeric
+    )
+}
+
+fn is_temporal(data_type: &DataType) -> bool {
+    matches!(
+        data_type,
+        DataType::Date | DataType::DateTime | DataType::Timestamp
+    )
+}
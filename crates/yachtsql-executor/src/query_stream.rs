@@ -0,0 +1,59 @@
+#![coverage(off)]
+
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::Stream;
+use tokio::sync::mpsc;
+use yachtsql_common::error::Result;
+use yachtsql_storage::{Schema, Table};
+
+use crate::cancellation::CancellationHandle;
+
+pub const DEFAULT_STREAM_BATCH_SIZE: usize = 1024;
+
+pub(crate) const STREAM_BUFFERED_BATCHES: usize = 2;
+
+pub struct QueryStream {
+    schema: Schema,
+    receiver: mpsc::Receiver<Result<Table>>,
+    handle: CancellationHandle,
+}
+
+impl QueryStream {
+    pub(crate) fn new(
+        schema: Schema,
+        receiver: mpsc::Receiver<Result<Table>>,
+        handle: CancellationHandle,
+    ) -> Self {
+        Self {
+            schema,
+            receiver,
+            handle,
+        }
+    }
+
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    pub fn cancellation_handle(&self) -> CancellationHandle {
+        self.handle.clone()
+    }
+
+    pub fn cancel(&self) {
+        self.handle.cancel();
+    }
+
+    pub async fn next_batch(&mut self) -> Option<Result<Table>> {
+        self.receiver.recv().await
+    }
+}
+
+impl Stream for QueryStream {
+    type Item = Result<Table>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_recv(cx)
+    }
+}
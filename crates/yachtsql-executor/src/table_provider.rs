@@ -0,0 +1,180 @@
+#![coverage(off)]
+
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use indexmap::IndexMap;
+use rustc_hash::FxHashSet;
+use yachtsql_common::error::Result;
+use yachtsql_common::types::Value;
+use yachtsql_ir::{BinaryOp, Expr, PlanSchema};
+use yachtsql_storage::{Column, Schema, Table};
+
+/// A table whose rows are produced by Rust code when a query scans it.
+///
+/// Register a provider with [`ConcurrentCatalog::register_table_provider`] and it
+/// can be queried like any other table. The provider is scanned each time a query
+/// reads the table, so it can serve generated data, wrap an iterator, or load a
+/// file lazily. Provider tables are read-only: DML against them fails.
+///
+/// [`ConcurrentCatalog::register_table_provider`]: crate::ConcurrentCatalog::register_table_provider
+pub trait TableProvider: Debug + Send + Sync {
+    /// The schema queries are planned against.
+    fn schema(&self) -> Schema;
+
+    /// Produces the rows for one scan.
+    ///
+    /// `projection` lists the indices of the schema fields the query reads, or is
+    /// `None` when every field may be needed. `filters` are predicates over the
+    /// schema's columns (referenced by name) that the query applies to the rows.
+    /// Both are hints: the returned table may include other columns and rows,
+    /// which the query drops afterwards. Columns are matched to the schema by
+    /// name, and schema fields missing from the result read as `NULL`.
+    fn scan(&self, projection: Option<&[usize]>, filters: &[Expr]) -> Result<Table>;
+}
+
+/// What the plan above a table scan does with its output.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct ScanHints<'a> {
+    /// Expressions evaluated over the scan output, or `None` if every column is
+    /// passed on.
+    pub(crate) reads: Option<&'a [Expr]>,
+    /// Predicate applied to the scan output.
+    pub(crate) predicate: Option<&'a Expr>,
+}
+
+/// Scans `provider` and aligns the result with its schema.
+pub(crate) fn scan_provider(
+    provider: &dyn TableProvider,
+    planned_schema: &PlanSchema,
+    hints: ScanHints<'_>,
+) -> Result<Table> {
+    let schema = provider.schema();
+    let projection = projection_hint(&schema, planned_schema, hints);
+    let filters = hints.predicate.map(filter_hints).unwrap_or_default();
+    let table = provider.scan(projection.as_deref(), &filters)?;
+
+    let mut columns = IndexMap::with_capacity(schema.field_count());
+    for field in schema.fields() {
+        let column = match table
+            .columns()
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(&field.name))
+        {
+            Some((_, column)) => Arc::clone(column),
+            None => {
+                let mut column = Column::new(&field.data_type);
+                for _ in 0..table.row_count() {
+                    column.push(Value::Null)?;
+                }
+                Arc::new(column)
+            }
+        };
+        columns.insert(field.name.clone(), column);
+    }
+    Ok(Table::from_arc_columns(schema, columns))
+}
+
+/// Maps the scan-output columns referenced by `hints` to indices in `schema`.
+/// Returns `None` when every column is needed or the references can't be
+/// determined.
+fn projection_hint(
+    schema: &Schema,
+    planned_schema: &PlanSchema,
+    hints: ScanHints<'_>,
+) -> Option<Vec<usize>> {
+    let mut referenced = FxHashSet::default();
+    for expr in hints.reads?.iter().chain(hints.predicate) {
+        if !collect_columns(expr, &mut referenced) {
+            return None;
+        }
+    }
+    if referenced.is_empty() {
+        return None;
+    }
+    let mut projection = Vec::with_capacity(referenced.len());
+    for index in referenced {
+        let name = &planned_schema.fields.get(index)?.name;
+        projection.push(
+            schema
+                .fields()
+                .iter()
+                .position(|f| f.name.eq_ignore_ascii_case(name))?,
+        );
+    }
+    projection.sort_unstable();
+    projection.dedup();
+    (projection.len() < schema.field_count()).then_some(projection)
+}
+
+/// Adds the indices of the columns `expr` reads to `indices`. Returns `false` for
+/// expressions whose column references aren't known by index.
+fn collect_columns(expr: &Expr, indices: &mut FxHashSet<usize>) -> bool {
+    let all = |exprs: &[Expr], indices: &mut FxHashSet<usize>| {
+        exprs.iter().all(|e| collect_columns(e, indices))
+    };
+    match expr {
+        Expr::Column {
+            index: Some(index), ..
+        } => {
+            indices.insert(*index);
+            true
+        }
+        Expr::Literal(_) | Expr::Parameter { .. } | Expr::Variable { .. } => true,
+        Expr::BinaryOp { left, right, .. } | Expr::IsDistinctFrom { left, right, .. } => {
+            collect_columns(left, indices) && collect_columns(right, indices)
+        }
+        Expr::UnaryOp { expr, .. }
+        | Expr::Cast { expr, .. }
+        | Expr::IsNull { expr, .. }
+        | Expr::Alias { expr, .. }
+        | Expr::Extract { expr, .. }
+        | Expr::StructAccess { expr, .. }
+        | Expr::JsonAccess { expr, .. } => collect_columns(expr, indices),
+        Expr::ScalarFunction { args, .. } => all(args, indices),
+        Expr::InList { expr, list, .. } => collect_columns(expr, indices) && all(list, indices),
+        Expr::Between {
+            expr, low, high, ..
+        } => {
+            collect_columns(expr, indices)
+                && collect_columns(low, indices)
+                && collect_columns(high, indices)
+        }
+        Expr::Like { expr, pattern, .. } => {
+            collect_columns(expr, indices) && collect_columns(pattern, indices)
+        }
+        Expr::ArrayAccess { array, index, .. } => {
+            collect_columns(array, indices) && collect_columns(index, indices)
+        }
+        Expr::Array { elements, .. } => all(elements, indices),
+        Expr::Case {
+            operand,
+            when_clauses,
+            else_result,
+        } => {
+            operand.iter().all(|e| collect_columns(e, indices))
+                && when_clauses.iter().all(|clause| {
+                    collect_columns(&clause.condition, indices)
+                        && collect_columns(&clause.result, indices)
+                })
+                && else_result.iter().all(|e| collect_columns(e, indices))
+        }
+        _ => false,
+    }
+}
+
+/// Splits a predicate into the conjuncts that must all hold.
+fn filter_hints(predicate: &Expr) -> Vec<Expr> {
+    match predicate {
+        Expr::BinaryOp {
+            left,
+            op: BinaryOp::And,
+            right,
+        } => {
+            let mut filters = filter_hints(left);
+            filters.extend(filter_hints(right));
+            filters
+        }
+        other => vec![other.clone()],
+    }
+}
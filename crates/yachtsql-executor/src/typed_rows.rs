@@ -0,0 +1,129 @@
+#![coverage(off)]
+
+use std::mem::discriminant;
+
+use yachtsql_common::error::{Error, Result};
+use yachtsql_common::types::{DataType, Value};
+use yachtsql_storage::{Schema, Table};
+
+use crate::executor::concurrent::coerce_value;
+use crate::value_evaluator::cast_value;
+
+/// Builds a table with `schema` from `STRUCT` row values, matching struct fields
+/// to columns by name. Columns without a matching field are `NULL`.
+pub(crate) fn rows_to_table(schema: &Schema, rows: Vec<Value>) -> Result<Table> {
+    let targets: Vec<(&str, &DataType)> = schema
+        .fields()
+        .iter()
+        .map(|f| (f.name.as_str(), &f.data_type))
+        .collect();
+    let mut values = Vec::with_capacity(rows.len());
+    for (index, row) in rows.into_iter().enumerate() {
+        let Value::Struct(fields) = row else {
+            return Err(Error::schema_mismatch(format!(
+                "row {} is a {} value, expected a struct or map",
+                index,
+                row.data_type()
+            )));
+        };
+        let row_values = align_fields(fields, &targets, "")
+            .map_err(|e| Error::schema_mismatch(format!("row {}: {}", index, message(e))))?;
+        values.push(row_values);
+    }
+    Table::from_values(schema.clone(), values)
+}
+
+fn message(error: Error) -> String {
+    match error {
+        Error::SchemaMismatch(msg) => msg,
+        other => other.to_string(),
+    }
+}
+
+fn align_fields(
+    fields: Vec<(String, Value)>,
+    targets: &[(&str, &DataType)],
+    path: &str,
+) -> Result<Vec<Value>> {
+    let mut aligned = vec![Value::Null; targets.len()];
+    for (name, value) in fields {
+        let Some(index) = targets
+            .iter()
+            .position(|(target, _)| target.eq_ignore_ascii_case(&name))
+        else {
+            return Err(Error::schema_mismatch(format!(
+                "no column named `{}{}`",
+                path, name
+            )));
+        };
+        let field_path = format!("{}{}", path, targets[index].0);
+        aligned[index] = coerce_field(value, targets[index].1, &field_path)?;
+    }
+    Ok(aligned)
+}
+
+fn coerce_field(value: Value, data_type: &DataType, path: &str) -> Result<Value> {
+    match (value, data_type) {
+        (Value::Null, _) => Ok(Value::Null),
+        (value, DataType::Unknown) => Ok(value),
+        (Value::Json(json), DataType::Json) => Ok(Value::Json(json)),
+        (value, DataType::Json) => Ok(Value::Json(value.to_json())),
+        (Value::Struct(fields), DataType::Struct(targets)) => {
+            let targets: Vec<(&str, &DataType)> = targets
+                .iter()
+                .map(|f| (f.name.as_str(), &f.data_type))
+                .collect();
+            let values = align_fields(fields, &targets, &format!("{}.", path))?;
+            Ok(Value::Struct(
+                targets
+                    .iter()
+                    .zip(values)
+                    .map(|((name, _), value)| (name.to_string(), value))
+                    .collect(),
+            ))
+        }
+        (Value::Array(items), DataType::Array(element_type)) => items
+            .into_iter()
+            .map(|item| coerce_field(item, element_type, path))
+            .collect::<Result<Vec<_>>>()
+            .map(Value::Array),
+        (Value::Array(items), DataType::Bytes) => items
+            .iter()
+            .map(|item| item.as_i64().and_then(|b| u8::try_from(b).ok()))
+            .collect::<Option<Vec<u8>>>()
+            .map(Value::Bytes)
+            .ok_or_else(|| mismatch(&Value::Array(items), data_type, path)),
+        (Value::String(wkt), DataType::Geography) => Ok(Value::Geography(wkt)),
+        (
+            value @ Value::String(_),
+            DataType::Date | DataType::Time | DataType::DateTime | DataType::Timestamp,
+        ) => coerce_value(value, data_type).map_err(|e| conversion_failed(e, data_type, path)),
+        (
+            value @ (Value::String(_) | Value::Int64(_) | Value::Float64(_)),
+            DataType::Numeric(_) | DataType::BigNumeric,
+        )
+        | (value @ Value::Int64(_), DataType::Float64) => {
+            cast_value(value, data_type, false).map_err(|e| conversion_failed(e, data_type, path))
+        }
+        (value, data_type) if discriminant(&value.data_type()) == discriminant(data_type) => {
+            Ok(value)
+        }
+        (value, data_type) => Err(mismatch(&value, data_type, path)),
+    }
+}
+
+fn mismatch(value: &Value, data_type: &DataType, path: &str) -> Error {
+    Error::schema_mismatch(format!(
+        "field `{}` has type {}, but the column is {}",
+        path,
+        value.data_type(),
+        data_type
+    ))
+}
+
+fn conversion_failed(error: Error, data_type: &DataType, path: &str) -> Error {
+    Error::schema_mismatch(format!(
+        "field `{}` cannot be converted to {}: {}",
+        path, data_type, error
+    ))
+}
@@ -9,6 +9,7 @@ use yachtsql_common::types::{DataType, Value};
 use yachtsql_ir::{BinaryOp, Expr, FunctionBody, Literal, ScalarFunction, UnaryOp, WhenClause};
 
 use crate::js_udf::evaluate_js_function;
+use crate::native_function::{NativeAggregateFunction, NativeScalarFunction, NativeTableFunction};
 use crate::py_udf::evaluate_py_function;
 
 #[derive(Copy, Clone)]
@@ -27,6 +28,38 @@ use crate::scalar_functions;
 pub struct UserFunctionDef {
     pub parameters: Vec<String>,
     pub body: FunctionBody,
+    /// The Rust implementation when `body` is [`FunctionBody::Native`].
+    pub native: Option<NativeScalarFunction>,
+    /// The Rust implementation of a native aggregate function.
+    pub native_aggregate: Option<NativeAggregateFunction>,
+    /// The Rust implementation of a native table function.
+    pub native_table: Option<NativeTableFunction>,
+}
+
+impl UserFunctionDef {
+    pub(crate) fn native_function(&self, name: &str) -> Result<&NativeScalarFunction> {
+        self.native.as_ref().ok_or_else(|| {
+            Error::internal(format!("Native function {} has no implementation", name))
+        })
+    }
+
+    /// Calls the native scalar or table function; a table function returns its
+    /// rows as an array of structs.
+    pub(crate) fn invoke_native(&self, name: &str, args: &[Value]) -> Result<Value> {
+        match &self.native_table {
+            Some(table_function) => table_function.invoke(name, args),
+            None => self.native_function(name)?.invoke(name, args),
+        }
+    }
+
+    pub(crate) fn native_aggregate_function(&self, name: &str) -> Result<&NativeAggregateFunction> {
+        self.native_aggregate.as_ref().ok_or_else(|| {
+            Error::internal(format!(
+                "Native aggregate function {} has no implementation",
+                name
+            ))
+        })
+    }
 }
 
 pub struct ValueEvaluator<'a> {
@@ -994,6 +1027,10 @@ impl<'a> ValueEvaluator<'a> {
                             name
                         )));
                     }
+                    FunctionBody::Native => {
+                        let result = func_def.invoke_native(&upper, args)?;
+                        return Ok(Some(result));
+                    }
                 }
             }
         }
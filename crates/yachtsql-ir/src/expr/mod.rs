@@ -76,6 +76,15 @@ pub enum Expr {
         frame: Option<WindowFrame>,
     },
 
+    UserDefinedAggregateWindow {
+        name: String,
+        args: Vec<Expr>,
+        distinct: bool,
+        partition_by: Vec<Expr>,
+        order_by: Vec<SortExpr>,
+        frame: Option<WindowFrame>,
+    },
+
     Case {
         operand: Option<Box<Expr>>,
         when_clauses: Vec<WhenClause>,
@@ -61,7 +61,12 @@ pub enum FunctionBody {
     Sql(Box<Expr>),
     SqlQuery(String),
     JavaScript(String),
-    Language { name: String, code: String },
+    Language {
+        name: String,
+        code: String,
+    },
+    /// Implemented by a Rust function registered on the session.
+    Native,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
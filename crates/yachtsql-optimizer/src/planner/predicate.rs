@@ -408,6 +408,12 @@ pub fn collect_column_indices_into(expr: &Expr, indices: &mut FxHashSet<usize>)
             partition_by,
             order_by,
             ..
+        }
+        | Expr::UserDefinedAggregateWindow {
+            args,
+            partition_by,
+            order_by,
+            ..
         } => {
             for arg in args {
                 collect_column_indices_into(arg, indices);
@@ -79,6 +79,22 @@ pub fn fold_constants(expr: &Expr) -> Expr {
             frame: frame.clone(),
         },
 
+        Expr::UserDefinedAggregateWindow {
+            name,
+            args,
+            distinct,
+            partition_by,
+            order_by,
+            frame,
+        } => Expr::UserDefinedAggregateWindow {
+            name: name.clone(),
+            args: args.iter().map(fold_constants).collect(),
+            distinct: *distinct,
+            partition_by: partition_by.iter().map(fold_constants).collect(),
+            order_by: order_by.iter().map(fold_sort_expr).collect(),
+            frame: frame.clone(),
+        },
+
         Expr::Case {
             operand,
             when_clauses,
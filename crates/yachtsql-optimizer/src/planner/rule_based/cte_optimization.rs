@@ -55,6 +55,12 @@ fn count_cte_references_in_expr(expr: &Expr, cte_name: &str) -> usize {
             partition_by,
             order_by,
             ..
+        }
+        | Expr::UserDefinedAggregateWindow {
+            args,
+            partition_by,
+            order_by,
+            ..
         } => {
             let args_count: usize = args
                 .iter()
@@ -576,6 +582,12 @@ fn expression_has_subquery(expr: &Expr) -> bool {
             partition_by,
             order_by,
             ..
+        }
+        | Expr::UserDefinedAggregateWindow {
+            args,
+            partition_by,
+            order_by,
+            ..
         } => {
             args.iter().any(expression_has_subquery)
                 || partition_by.iter().any(expression_has_subquery)
@@ -549,6 +549,22 @@ fn simplify_expr(expr: Expr) -> Expr {
             frame,
         },
 
+        Expr::UserDefinedAggregateWindow {
+            name,
+            args,
+            distinct,
+            partition_by,
+            order_by,
+            frame,
+        } => Expr::UserDefinedAggregateWindow {
+            name,
+            args: args.into_iter().map(simplify_expr).collect(),
+            distinct,
+            partition_by: partition_by.into_iter().map(simplify_expr).collect(),
+            order_by: order_by.into_iter().map(simplify_sort_expr).collect(),
+            frame,
+        },
+
         Expr::Case {
             operand,
             when_clauses,
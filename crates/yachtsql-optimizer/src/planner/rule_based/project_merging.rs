@@ -260,6 +260,35 @@ pub(crate) fn substitute_column_refs(expr: &Expr, inner_exprs: &[Expr]) -> Expr
             frame: frame.clone(),
         },
 
+        Expr::UserDefinedAggregateWindow {
+            name,
+            args,
+            distinct,
+            partition_by,
+            order_by,
+            frame,
+        } => Expr::UserDefinedAggregateWindow {
+            name: name.clone(),
+            args: args
+                .iter()
+                .map(|a| substitute_column_refs(a, inner_exprs))
+                .collect(),
+            distinct: *distinct,
+            partition_by: partition_by
+                .iter()
+                .map(|p| substitute_column_refs(p, inner_exprs))
+                .collect(),
+            order_by: order_by
+                .iter()
+                .map(|s| yachtsql_ir::SortExpr {
+                    expr: substitute_column_refs(&s.expr, inner_exprs),
+                    asc: s.asc,
+                    nulls_first: s.nulls_first,
+                })
+                .collect(),
+            frame: frame.clone(),
+        },
+
         Expr::Case {
             operand,
             when_clauses,
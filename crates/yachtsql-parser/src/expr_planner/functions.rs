@@ -2,6 +2,7 @@
 
 use sqlparser::ast;
 use yachtsql_common::error::{Error, Result};
+use yachtsql_common::types::{DataType, FunctionSignature};
 use yachtsql_ir::plan::FunctionBody;
 use yachtsql_ir::{Expr, Literal, PlanSchema, ScalarFunction, SortExpr};
 
@@ -11,6 +12,7 @@ use super::subquery::plan_array_subquery;
 use super::substitution::{apply_struct_field_names, substitute_parameters};
 use super::window::{plan_window_spec, try_window_function};
 use super::{ExprPlanner, SubqueryPlannerFn, UdfResolverFn};
+use crate::FunctionDefinition;
 
 pub fn plan_function(
     func: &ast::Function,
@@ -55,6 +57,27 @@ pub fn plan_function(
                 frame,
             });
         }
+        if let Some(resolver) = udf_resolver
+            && let Some(udf) = resolver(&name)
+            && udf.is_aggregate
+            && matches!(&udf.body, FunctionBody::Native)
+        {
+            let distinct = matches!(
+                &func.args,
+                ast::FunctionArguments::List(list) if list.duplicate_treatment == Some(ast::DuplicateTreatment::Distinct)
+            );
+            let args = extract_function_args(func, schema)?;
+            check_native_arguments(&name, &udf, &args, schema)?;
+            let (partition_by, order_by, frame) = plan_window_spec(over, schema, named_windows)?;
+            return Ok(Expr::UserDefinedAggregateWindow {
+                name,
+                args,
+                distinct,
+                partition_by,
+                order_by,
+                frame,
+            });
+        }
     }
 
     if let Some(agg_func) = try_aggregate_function(&name) {
@@ -136,6 +159,43 @@ pub fn plan_function(
         });
     }
 
+    if let Some(resolver) = udf_resolver
+        && let Some(udf) = resolver(&name)
+        && matches!(&udf.body, FunctionBody::Native)
+        && let ScalarFunction::Custom(_) = try_scalar_function(&name)?
+    {
+        if udf.is_table_function {
+            return Err(Error::invalid_query(format!(
+                "Table function {} can only be called in a FROM clause",
+                name
+            )));
+        }
+        let args = extract_function_args_full(
+            func,
+            schema,
+            subquery_planner,
+            named_windows,
+            udf_resolver,
+        )?;
+        check_native_arguments(&name, &udf, &args, schema)?;
+        if udf.is_aggregate {
+            let distinct = matches!(
+                &func.args,
+                ast::FunctionArguments::List(list) if list.duplicate_treatment == Some(ast::DuplicateTreatment::Distinct)
+            );
+            return Ok(Expr::UserDefinedAggregate {
+                name,
+                args,
+                distinct,
+                filter: None,
+            });
+        }
+        return Ok(Expr::ScalarFunction {
+            name: ScalarFunction::Custom(name),
+            args,
+        });
+    }
+
     if let Some(resolver) = udf_resolver
         && let Some(udf) = resolver(&name)
         && matches!(&udf.body, FunctionBody::Sql(_))
@@ -162,6 +222,40 @@ pub fn plan_function(
     })
 }
 
+/// Checks the arguments of a call to a native function against its declared
+/// parameter types.
+fn check_native_arguments(
+    name: &str,
+    udf: &FunctionDefinition,
+    args: &[Expr],
+    schema: &PlanSchema,
+) -> Result<()> {
+    let signature = FunctionSignature::new(
+        udf.parameters.iter().map(|p| p.data_type.clone()).collect(),
+        udf.return_type.clone(),
+    );
+    let arg_types: Vec<DataType> = args.iter().map(|a| argument_type(a, schema)).collect();
+    signature.check_arguments(name, &arg_types)
+}
+
+/// The type of a function argument when it is evident from the expression alone,
+/// or `Unknown`. Arguments of unknown type are checked again when the function
+/// is called.
+fn argument_type(expr: &Expr, schema: &PlanSchema) -> DataType {
+    match expr {
+        Expr::Literal(lit) => lit.data_type(),
+        Expr::Column { name, index, .. } => match index {
+            Some(idx) => schema.fields.get(*idx),
+            None => schema.field(name),
+        }
+        .map(|f| f.data_type.clone())
+        .unwrap_or(DataType::Unknown),
+        Expr::Cast { data_type, .. } => data_type.clone(),
+        Expr::Alias { expr, .. } => argument_type(expr, schema),
+        _ => DataType::Unknown,
+    }
+}
+
 pub fn extract_function_args_full(
     func: &ast::Function,
     schema: &PlanSchema,
@@ -249,6 +249,31 @@ pub fn substitute_expr(expr: &Expr, param_map: &FxHashMap<String, &Expr>) -> Exp
                 .collect(),
             frame: frame.clone(),
         },
+        Expr::UserDefinedAggregateWindow {
+            name,
+            args,
+            distinct,
+            partition_by,
+            order_by,
+            frame,
+        } => Expr::UserDefinedAggregateWindow {
+            name: name.clone(),
+            args: args.iter().map(|a| substitute_expr(a, param_map)).collect(),
+            distinct: *distinct,
+            partition_by: partition_by
+                .iter()
+                .map(|e| substitute_expr(e, param_map))
+                .collect(),
+            order_by: order_by
+                .iter()
+                .map(|o| SortExpr {
+                    expr: substitute_expr(&o.expr, param_map),
+                    asc: o.asc,
+                    nulls_first: o.nulls_first,
+                })
+                .collect(),
+            frame: frame.clone(),
+        },
         Expr::Like {
             expr: inner,
             pattern,
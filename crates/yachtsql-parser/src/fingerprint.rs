@@ -0,0 +1,117 @@
+#![coverage(off)]
+
+use sqlparser::dialect::BigQueryDialect;
+use sqlparser::tokenizer::{Token, Tokenizer};
+
+/// Normalizes `sql` so that statements differing only in literal values,
+/// whitespace, comments or keyword case get the same fingerprint.
+///
+/// Literals become `?`, and a comma-separated list of literals becomes a single
+/// `?`, so `WHERE id IN (1, 2)` and `WHERE id IN (3)` match. Unquoted words,
+/// keywords and identifiers alike, are uppercased; quoted identifiers are kept
+/// as written. SQL that cannot be tokenized is returned with its whitespace
+/// collapsed.
+pub fn fingerprint_sql(sql: &str) -> String {
+    let dialect = BigQueryDialect {};
+    let Ok(tokens) = Tokenizer::new(&dialect, sql).tokenize() else {
+        return sql.split_whitespace().collect::<Vec<_>>().join(" ");
+    };
+
+    let mut parts: Vec<(bool, String)> = Vec::with_capacity(tokens.len());
+    let mut space_before = false;
+    for token in tokens {
+        let text = match &token {
+            Token::Whitespace(_) => {
+                space_before = !parts.is_empty();
+                continue;
+            }
+            token if is_literal(token) => {
+                if follows_literal_and_comma(&parts) {
+                    parts.pop();
+                    space_before = false;
+                    continue;
+                }
+                "?".to_string()
+            }
+            Token::Word(word) if word.quote_style.is_none() => word.value.to_uppercase(),
+            Token::SemiColon => continue,
+            token => token.to_string(),
+        };
+        parts.push((space_before, text));
+        space_before = false;
+    }
+
+    let mut fingerprint = String::with_capacity(sql.len());
+    for (space_before, text) in parts {
+        if space_before {
+            fingerprint.push(' ');
+        }
+        fingerprint.push_str(&text);
+    }
+    fingerprint
+}
+
+fn follows_literal_and_comma(parts: &[(bool, String)]) -> bool {
+    matches!(parts, [.., (_, literal), (_, comma)] if literal == "?" && comma == ",")
+}
+
+fn is_literal(token: &Token) -> bool {
+    matches!(
+        token,
+        Token::Number(..)
+            | Token::SingleQuotedString(_)
+            | Token::DoubleQuotedString(_)
+            | Token::TripleSingleQuotedString(_)
+            | Token::TripleDoubleQuotedString(_)
+            | Token::DollarQuotedString(_)
+            | Token::SingleQuotedByteStringLiteral(_)
+            | Token::DoubleQuotedByteStringLiteral(_)
+            | Token::TripleSingleQuotedByteStringLiteral(_)
+            | Token::TripleDoubleQuotedByteStringLiteral(_)
+            | Token::SingleQuotedRawStringLiteral(_)
+            | Token::DoubleQuotedRawStringLiteral(_)
+            | Token::TripleSingleQuotedRawStringLiteral(_)
+            | Token::TripleDoubleQuotedRawStringLiteral(_)
+            | Token::NationalStringLiteral(_)
+            | Token::EscapedStringLiteral(_)
+            | Token::UnicodeStringLiteral(_)
+            | Token::HexStringLiteral(_)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replaces_literals_and_normalizes_layout() {
+        assert_eq!(
+            fingerprint_sql("select name\n  from users -- by id\n where id = 42 and tag = 'x';"),
+            "SELECT NAME FROM USERS WHERE ID = ? AND TAG = ?"
+        );
+        assert_eq!(
+            fingerprint_sql("SELECT name FROM users WHERE id = 7 AND tag = \"y\""),
+            "SELECT NAME FROM USERS WHERE ID = ? AND TAG = ?"
+        );
+    }
+
+    #[test]
+    fn collapses_literal_lists() {
+        assert_eq!(
+            fingerprint_sql("SELECT * FROM t WHERE id IN (1, 2, 3)"),
+            fingerprint_sql("SELECT * FROM t WHERE id IN (4)")
+        );
+        assert_eq!(
+            fingerprint_sql("INSERT INTO t VALUES (1, 'a'), (2, 'b')"),
+            "INSERT INTO T VALUES (?), (?)"
+        );
+    }
+
+    #[test]
+    fn keeps_quoted_identifiers() {
+        assert_eq!(
+            fingerprint_sql("SELECT `Order`.Total FROM ds.`Order` WHERE id = @id"),
+            "SELECT `Order`.TOTAL FROM DS.`Order` WHERE ID = @ID"
+        );
+    }
+}
@@ -2,10 +2,16 @@
 #![coverage(off)]
 
 mod expr_planner;
+mod fingerprint;
 mod planner;
+mod query_params;
+mod script;
 
 pub use expr_planner::ExprPlanningContext;
+pub use fingerprint::fingerprint_sql;
 pub use planner::Planner;
+pub use query_params::{ParameterMarkers, extract_parameter_markers};
+pub use script::{ScriptStatement, split_script};
 use sqlparser::dialect::BigQueryDialect;
 use sqlparser::parser::Parser;
 use yachtsql_common::error::{Error, Result};
@@ -14,6 +20,8 @@ use yachtsql_ir::plan::{FunctionArg, FunctionBody};
 use yachtsql_ir::{ColumnDef, LoadFormat, LoadOptions, LogicalPlan};
 use yachtsql_storage::Schema;
 
+pub const SESSION_DATASET: &str = "_SESSION";
+
 pub struct ViewDefinition {
     pub query: String,
     pub column_aliases: Vec<String>,
@@ -23,6 +31,12 @@ pub trait CatalogProvider {
     fn get_table_schema(&self, name: &str) -> Option<Schema>;
     fn get_view(&self, name: &str) -> Option<ViewDefinition>;
     fn get_function(&self, name: &str) -> Option<FunctionDefinition>;
+
+    /// Whether `CREATE TEMP TABLE` creates its table in the `_SESSION` dataset, as
+    /// sessions sharing a catalog do to keep temporary tables apart.
+    fn has_session_dataset(&self) -> bool {
+        false
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -32,6 +46,8 @@ pub struct FunctionDefinition {
     pub return_type: DataType,
     pub body: FunctionBody,
     pub is_aggregate: bool,
+    /// Whether the function returns a table and is called in a `FROM` clause.
+    pub is_table_function: bool,
 }
 
 pub fn parse_sql(sql: &str) -> Result<Vec<sqlparser::ast::Statement>> {
@@ -9,12 +9,15 @@ use yachtsql_ir::{
 };
 
 use super::{Planner, object_name_to_raw_string};
-use crate::CatalogProvider;
 use crate::expr_planner::ExprPlanner;
+use crate::{CatalogProvider, SESSION_DATASET};
 
 impl<'a, C: CatalogProvider> Planner<'a, C> {
     pub(super) fn plan_create_table(&self, create: &ast::CreateTable) -> Result<LogicalPlan> {
-        let table_name = object_name_to_raw_string(&create.name);
+        let mut table_name = object_name_to_raw_string(&create.name);
+        if create.temporary && !table_name.contains('.') && self.catalog.has_session_dataset() {
+            table_name = format!("{}.{}", SESSION_DATASET, table_name);
+        }
         let empty_schema = PlanSchema::new();
 
         let default_collation = match &create.table_options {
@@ -389,7 +392,9 @@ impl<'a, C: CatalogProvider> Planner<'a, C> {
             None => match &body {
                 FunctionBody::Sql(expr) => Self::infer_expr_type_static(expr, &arg_schema),
                 FunctionBody::SqlQuery(_) => DataType::Unknown,
-                FunctionBody::JavaScript(_) | FunctionBody::Language { .. } => {
+                FunctionBody::JavaScript(_)
+                | FunctionBody::Language { .. }
+                | FunctionBody::Native => {
                     return Err(Error::InvalidQuery(
                         "RETURNS clause is required for non-SQL functions".to_string(),
                     ));
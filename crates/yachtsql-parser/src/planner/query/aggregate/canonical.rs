@@ -90,6 +90,23 @@ impl<'a, C: CatalogProvider> Planner<'a, C> {
                 }
                 name
             }
+            Expr::UserDefinedAggregate {
+                name,
+                args,
+                distinct,
+                ..
+            } => {
+                let args_str = args
+                    .iter()
+                    .map(|a| Self::canonical_planned_expr_name(a))
+                    .collect::<Vec<_>>()
+                    .join(",");
+                if *distinct {
+                    format!("{}(DISTINCT{})", name, args_str)
+                } else {
+                    format!("{}({})", name, args_str)
+                }
+            }
             _ => format!("{:?}", expr),
         }
     }
@@ -103,7 +120,9 @@ impl<'a, C: CatalogProvider> Planner<'a, C> {
                     name.to_uppercase()
                 }
             }
-            Expr::Aggregate { .. } => Self::canonical_planned_agg_name(expr),
+            Expr::Aggregate { .. } | Expr::UserDefinedAggregate { .. } => {
+                Self::canonical_planned_agg_name(expr)
+            }
             Expr::BinaryOp { left, op, right } => {
                 let op_str = match op {
                     BinaryOp::Add => "+",
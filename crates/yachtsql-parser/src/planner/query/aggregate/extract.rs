@@ -55,7 +55,7 @@ impl<'a, C: CatalogProvider> Planner<'a, C> {
         }
 
         match expr {
-            Expr::Aggregate { .. } => {
+            Expr::Aggregate { .. } | Expr::UserDefinedAggregate { .. } => {
                 let canonical = Self::canonical_planned_agg_name(expr);
                 if let Some(idx) = agg_names.iter().position(|n| n == &canonical) {
                     return Expr::Column {
@@ -493,6 +493,70 @@ impl<'a, C: CatalogProvider> Planner<'a, C> {
                     frame: frame.clone(),
                 }
             }
+            Expr::UserDefinedAggregateWindow {
+                name,
+                args,
+                distinct,
+                partition_by,
+                order_by,
+                frame,
+            } => {
+                let new_args: Vec<Expr> = args
+                    .iter()
+                    .map(|a| {
+                        self.replace_aggregates_with_columns(
+                            a,
+                            agg_names,
+                            agg_exprs,
+                            agg_fields,
+                            input_schema,
+                            group_by_count,
+                            extracted,
+                            group_by_exprs,
+                        )
+                    })
+                    .collect();
+                let new_partition_by: Vec<Expr> = partition_by
+                    .iter()
+                    .map(|e| {
+                        self.replace_aggregates_with_columns(
+                            e,
+                            agg_names,
+                            agg_exprs,
+                            agg_fields,
+                            input_schema,
+                            group_by_count,
+                            extracted,
+                            group_by_exprs,
+                        )
+                    })
+                    .collect();
+                let new_order_by: Vec<yachtsql_ir::SortExpr> = order_by
+                    .iter()
+                    .map(|se| yachtsql_ir::SortExpr {
+                        expr: self.replace_aggregates_with_columns(
+                            &se.expr,
+                            agg_names,
+                            agg_exprs,
+                            agg_fields,
+                            input_schema,
+                            group_by_count,
+                            extracted,
+                            group_by_exprs,
+                        ),
+                        asc: se.asc,
+                        nulls_first: se.nulls_first,
+                    })
+                    .collect();
+                Expr::UserDefinedAggregateWindow {
+                    name: name.clone(),
+                    args: new_args,
+                    distinct: *distinct,
+                    partition_by: new_partition_by,
+                    order_by: new_order_by,
+                    frame: frame.clone(),
+                }
+            }
             Expr::ArrayAccess { array, index } => {
                 let new_array = self.replace_aggregates_with_columns(
                     array,
@@ -4,13 +4,15 @@ use regex::Regex;
 use rustc_hash::FxHashMap;
 use sqlparser::ast::{self, Statement, TableFactor};
 use yachtsql_common::error::{Error, Result};
-use yachtsql_common::types::DataType;
-use yachtsql_ir::{Expr, FunctionBody, JoinType, LogicalPlan, PlanField, PlanSchema, UnnestColumn};
+use yachtsql_common::types::{DataType, FunctionSignature};
+use yachtsql_ir::{
+    Expr, FunctionBody, JoinType, LogicalPlan, PlanField, PlanSchema, ScalarFunction, UnnestColumn,
+};
 
 use super::super::object_name_to_raw_string;
 use super::Planner;
 use crate::expr_planner::ExprPlanner;
-use crate::{CatalogProvider, parse_sql};
+use crate::{CatalogProvider, FunctionDefinition, parse_sql};
 
 impl<'a, C: CatalogProvider> Planner<'a, C> {
     pub(in crate::planner) fn plan_from(
@@ -107,6 +109,14 @@ impl<'a, C: CatalogProvider> Planner<'a, C> {
                     }
                     if let Some(func_def) = self.catalog.get_function(&table_name) {
                         match &func_def.body {
+                            FunctionBody::Native if func_def.is_table_function => self
+                                .plan_native_table_function(
+                                    &table_name,
+                                    &func_def,
+                                    tbl_args,
+                                    alias,
+                                    left_schema,
+                                )?,
                             FunctionBody::Sql(body_expr) => match body_expr.as_ref() {
                                 Expr::Subquery(subquery_plan) => {
                                     let alias_name = alias.as_ref().map(|a| a.name.value.as_str());
@@ -458,6 +468,89 @@ impl<'a, C: CatalogProvider> Planner<'a, C> {
         }
     }
 
+    /// Plans a call to a native table function as an `UNNEST` of the rows it
+    /// returns, so that arguments referring to earlier `FROM` items make it a
+    /// lateral call like `FROM t, UNNEST(t.arr)`.
+    fn plan_native_table_function(
+        &self,
+        table_name: &str,
+        func_def: &FunctionDefinition,
+        tbl_args: &ast::TableFunctionArgs,
+        alias: &Option<ast::TableAlias>,
+        left_schema: Option<&PlanSchema>,
+    ) -> Result<LogicalPlan> {
+        let context_schema = match (left_schema, self.outer_schema.borrow().as_ref()) {
+            (Some(ls), Some(os)) => ls.clone().merge(os.clone()),
+            (Some(ls), None) => ls.clone(),
+            (None, Some(os)) => os.clone(),
+            (None, None) => PlanSchema::new(),
+        };
+        let args = tbl_args
+            .args
+            .iter()
+            .map(|arg| match arg {
+                ast::FunctionArg::Unnamed(ast::FunctionArgExpr::Expr(e)) => {
+                    ExprPlanner::plan_expr(e, &context_schema)
+                }
+                _ => Err(Error::unsupported("Unsupported function argument type")),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let signature = FunctionSignature::new(
+            func_def
+                .parameters
+                .iter()
+                .map(|p| p.data_type.clone())
+                .collect(),
+            func_def.return_type.clone(),
+        );
+        let arg_types: Vec<DataType> = args
+            .iter()
+            .map(|a| self.infer_expr_type(a, &context_schema))
+            .collect();
+        signature.check_arguments(&func_def.name, &arg_types)?;
+
+        let DataType::Array(row_type) = &func_def.return_type else {
+            return Err(Error::internal(format!(
+                "Table function {} does not return rows",
+                table_name
+            )));
+        };
+        let DataType::Struct(columns) = row_type.as_ref() else {
+            return Err(Error::internal(format!(
+                "Table function {} does not return rows",
+                table_name
+            )));
+        };
+        let alias_name = alias
+            .as_ref()
+            .map_or_else(|| table_name.to_string(), |a| a.name.value.clone());
+        let fields = columns
+            .iter()
+            .map(|column| {
+                let mut field = PlanField::new(column.name.clone(), column.data_type.clone());
+                field.table = Some(alias_name.clone());
+                field
+            })
+            .collect();
+
+        Ok(LogicalPlan::Unnest {
+            input: Box::new(LogicalPlan::Empty {
+                schema: PlanSchema::new(),
+            }),
+            columns: vec![UnnestColumn {
+                expr: Expr::ScalarFunction {
+                    name: ScalarFunction::Custom(func_def.name.clone()),
+                    args,
+                },
+                alias: Some(alias_name),
+                with_offset: false,
+                offset_alias: None,
+            }],
+            schema: PlanSchema::from_fields(fields),
+        })
+    }
+
     fn plan_join(
         &self,
         left: LogicalPlan,
@@ -3,6 +3,7 @@
 use sqlparser::ast::{self, SetExpr};
 use yachtsql_common::error::{Error, Result};
 use yachtsql_common::types::DataType;
+use yachtsql_ir::plan::FunctionBody;
 use yachtsql_ir::{LogicalPlan, PlanField, PlanSchema, SetOperationType};
 
 use super::Planner;
@@ -152,10 +153,17 @@ impl<'a, C: CatalogProvider> Planner<'a, C> {
 
         if let Some(ref selection) = select.selection {
             let subquery_planner = |query: &ast::Query| self.plan_query(query);
-            let predicate = ExprPlanner::plan_expr_with_subquery(
+            let native_resolver = |name: &str| {
+                self.catalog
+                    .get_function(name)
+                    .filter(|f| f.body == FunctionBody::Native)
+            };
+            let predicate = ExprPlanner::plan_expr_with_udf_resolver(
                 selection,
                 plan.schema(),
                 Some(&subquery_planner),
+                &[],
+                Some(&native_resolver),
             )?;
             plan = LogicalPlan::Filter {
                 input: Box::new(plan),
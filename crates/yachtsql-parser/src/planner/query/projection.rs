@@ -145,7 +145,7 @@ impl<'a, C: CatalogProvider> Planner<'a, C> {
         let input_field_count = input.schema().fields.len();
         let mut window_schema_fields = input.schema().fields.clone();
         for (j, wf) in window_funcs.iter().enumerate() {
-            let window_type = Self::compute_expr_type(wf, input.schema());
+            let window_type = self.infer_expr_type(wf, input.schema());
             window_schema_fields.push(PlanField::new(format!("__window_{}", j), window_type));
         }
         let window_schema = PlanSchema::from_fields(window_schema_fields);
@@ -179,7 +179,9 @@ impl<'a, C: CatalogProvider> Planner<'a, C> {
 
     pub(super) fn expr_has_window(expr: &Expr) -> bool {
         match expr {
-            Expr::Window { .. } | Expr::AggregateWindow { .. } => true,
+            Expr::Window { .. }
+            | Expr::AggregateWindow { .. }
+            | Expr::UserDefinedAggregateWindow { .. } => true,
             Expr::BinaryOp { left, right, .. } => {
                 Self::expr_has_window(left) || Self::expr_has_window(right)
             }
@@ -206,7 +208,9 @@ impl<'a, C: CatalogProvider> Planner<'a, C> {
 
     pub(super) fn extract_window_function(expr: &Expr) -> Option<Expr> {
         match expr {
-            Expr::Window { .. } | Expr::AggregateWindow { .. } => Some(expr.clone()),
+            Expr::Window { .. }
+            | Expr::AggregateWindow { .. }
+            | Expr::UserDefinedAggregateWindow { .. } => Some(expr.clone()),
             Expr::BinaryOp { left, right, .. } => {
                 Self::extract_window_function(left).or_else(|| Self::extract_window_function(right))
             }
@@ -250,7 +254,9 @@ impl<'a, C: CatalogProvider> Planner<'a, C> {
 
     pub(super) fn replace_window_with_column(expr: Expr, col_name: &str, col_idx: usize) -> Expr {
         match expr {
-            Expr::Window { .. } | Expr::AggregateWindow { .. } => Expr::Column {
+            Expr::Window { .. }
+            | Expr::AggregateWindow { .. }
+            | Expr::UserDefinedAggregateWindow { .. } => Expr::Column {
                 table: None,
                 name: col_name.to_string(),
                 index: Some(col_idx),
@@ -3,7 +3,10 @@
 use sqlparser::ast;
 use yachtsql_common::error::{Error, Result};
 use yachtsql_common::types::{DataType, StructField};
-use yachtsql_ir::{ConstraintType, DateTimeField, Expr, PlanField, PlanSchema, TableConstraint};
+use yachtsql_ir::plan::FunctionBody;
+use yachtsql_ir::{
+    ConstraintType, DateTimeField, Expr, PlanField, PlanSchema, ScalarFunction, TableConstraint,
+};
 use yachtsql_storage::Schema;
 
 use super::super::object_name_to_raw_string;
@@ -13,6 +16,21 @@ use crate::expr_planner::ExprPlanner;
 
 impl<'a, C: CatalogProvider> Planner<'a, C> {
     pub(super) fn infer_expr_type(&self, expr: &Expr, schema: &PlanSchema) -> DataType {
+        let native_name = match expr {
+            Expr::ScalarFunction {
+                name: ScalarFunction::Custom(name),
+                ..
+            }
+            | Expr::UserDefinedAggregate { name, .. }
+            | Expr::UserDefinedAggregateWindow { name, .. } => Some(name),
+            _ => None,
+        };
+        if let Some(name) = native_name
+            && let Some(func) = self.catalog.get_function(name)
+            && func.body == FunctionBody::Native
+        {
+            return func.return_type;
+        }
         Self::compute_expr_type(expr, schema)
     }
 
@@ -385,15 +403,16 @@ impl<'a, C: CatalogProvider> Planner<'a, C> {
             | ScalarFunction::IsInf
             | ScalarFunction::BoolFromJson => DataType::Bool,
 
-            ScalarFunction::Split
-            | ScalarFunction::ArrayConcat
-            | ScalarFunction::ArrayReverse
-            | ScalarFunction::GenerateArray
-            | ScalarFunction::GenerateDateArray
-            | ScalarFunction::GenerateTimestampArray => {
+            ScalarFunction::Split | ScalarFunction::ArrayConcat | ScalarFunction::ArrayReverse => {
                 DataType::Array(Box::new(DataType::Unknown))
             }
 
+            ScalarFunction::GenerateArray => DataType::Array(Box::new(DataType::Int64)),
+            ScalarFunction::GenerateDateArray => DataType::Array(Box::new(DataType::Date)),
+            ScalarFunction::GenerateTimestampArray => {
+                DataType::Array(Box::new(DataType::Timestamp))
+            }
+
             ScalarFunction::FromBase64 | ScalarFunction::FromHex => DataType::Bytes,
 
             ScalarFunction::ToJson
@@ -0,0 +1,86 @@
+#![coverage(off)]
+
+use sqlparser::dialect::BigQueryDialect;
+use sqlparser::tokenizer::{Token, Tokenizer};
+use yachtsql_common::error::{Error, Result};
+
+use crate::script::{byte_offset, line_start_offsets};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParameterMarkers {
+    pub sql: String,
+    pub named: Vec<String>,
+    pub positional: usize,
+}
+
+pub fn extract_parameter_markers(sql: &str) -> Result<ParameterMarkers> {
+    let dialect = BigQueryDialect {};
+    let tokens = Tokenizer::new(&dialect, sql)
+        .tokenize_with_location()
+        .map_err(|e| Error::parse_error(e.to_string()))?;
+    let line_starts = line_start_offsets(sql);
+
+    let mut markers = ParameterMarkers::default();
+    let mut rewritten = String::with_capacity(sql.len());
+    let mut copied_to = 0;
+
+    for token in &tokens {
+        match &token.token {
+            Token::Placeholder(p) if p == "?" => {
+                let offset = byte_offset(sql, &line_starts, token.span.start);
+                markers.positional += 1;
+                rewritten.push_str(&sql[copied_to..offset]);
+                rewritten.push_str(&format!("?{}", markers.positional));
+                copied_to = offset + 1;
+            }
+            Token::Word(w) if w.quote_style.is_none() && is_named_parameter(&w.value) => {
+                let name = &w.value[1..];
+                if !markers.named.iter().any(|n| n.eq_ignore_ascii_case(name)) {
+                    markers.named.push(name.to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if markers.positional > 0 && !markers.named.is_empty() {
+        return Err(Error::invalid_query(
+            "Query cannot mix named (@name) and positional (?) parameters",
+        ));
+    }
+
+    rewritten.push_str(&sql[copied_to..]);
+    markers.sql = rewritten;
+    Ok(markers)
+}
+
+fn is_named_parameter(word: &str) -> bool {
+    word.len() > 1 && word.starts_with('@') && !word.starts_with("@@")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rewrites_positional_markers() {
+        let markers = extract_parameter_markers("SELECT ? + ?, '?' FROM t WHERE x = ?").unwrap();
+        assert_eq!(markers.sql, "SELECT ?1 + ?2, '?' FROM t WHERE x = ?3");
+        assert_eq!(markers.positional, 3);
+        assert!(markers.named.is_empty());
+    }
+
+    #[test]
+    fn collects_named_markers() {
+        let markers =
+            extract_parameter_markers("SELECT @a, @@time_zone, '@b' FROM t WHERE x = @A OR y = @c")
+                .unwrap();
+        assert_eq!(markers.named, vec!["a".to_string(), "c".to_string()]);
+        assert_eq!(markers.positional, 0);
+    }
+
+    #[test]
+    fn rejects_mixed_markers() {
+        assert!(extract_parameter_markers("SELECT @a, ?").is_err());
+    }
+}
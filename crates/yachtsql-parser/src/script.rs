@@ -0,0 +1,106 @@
+#![coverage(off)]
+
+use sqlparser::ast::Statement;
+use sqlparser::dialect::BigQueryDialect;
+use sqlparser::parser::Parser;
+use sqlparser::tokenizer::{Location, Token, TokenWithSpan, Tokenizer};
+use yachtsql_common::error::{Error, Result};
+
+use crate::preprocess_range_types;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptStatement {
+    pub sql: String,
+    pub line: u64,
+    pub column: u64,
+    pub is_query: bool,
+}
+
+pub fn split_script(sql: &str) -> Result<Vec<ScriptStatement>> {
+    let preprocessed = preprocess_range_types(sql);
+    let dialect = BigQueryDialect {};
+    let tokens = Tokenizer::new(&dialect, &preprocessed)
+        .tokenize_with_location()
+        .map_err(|e| Error::parse_error(e.to_string()))?;
+    let line_starts = line_start_offsets(&preprocessed);
+
+    let mut statements = Vec::new();
+    let mut parser = Parser::new(&dialect).with_tokens_with_locations(tokens.clone());
+    let mut base = 0;
+
+    loop {
+        while parser.consume_token(&Token::SemiColon) {}
+
+        let start = parser.peek_token();
+        if start.token == Token::EOF {
+            break;
+        }
+        let start_offset = byte_offset(&preprocessed, &line_starts, start.span.start);
+        let start_index = base + parser.index();
+
+        let (is_query, end_index) = match parser.parse_statement() {
+            Ok(stmt) => (matches!(stmt, Statement::Query(_)), base + parser.index()),
+            Err(_) if is_text_planned_statement(&preprocessed[start_offset..]) => {
+                let end = tokens[start_index..]
+                    .iter()
+                    .position(|t| matches!(t.token, Token::SemiColon | Token::EOF))
+                    .map(|p| start_index + p)
+                    .unwrap_or(tokens.len());
+                base = end;
+                parser = Parser::new(&dialect).with_tokens_with_locations(tokens[end..].to_vec());
+                (false, end)
+            }
+            Err(e) => return Err(Error::parse_error(e.to_string())),
+        };
+
+        let end_offset = last_token_end(&tokens[..end_index])
+            .map(|loc| byte_offset(&preprocessed, &line_starts, loc))
+            .unwrap_or(preprocessed.len());
+
+        statements.push(ScriptStatement {
+            sql: preprocessed[start_offset..end_offset.max(start_offset)].to_string(),
+            line: start.span.start.line,
+            column: start.span.start.column,
+            is_query,
+        });
+    }
+
+    Ok(statements)
+}
+
+fn is_text_planned_statement(sql: &str) -> bool {
+    let upper = sql.trim_start().to_uppercase();
+    upper.starts_with("LOAD DATA")
+        || upper.starts_with("CREATE SNAPSHOT TABLE")
+        || upper.starts_with("DROP SNAPSHOT TABLE")
+}
+
+fn last_token_end(tokens: &[TokenWithSpan]) -> Option<Location> {
+    tokens
+        .iter()
+        .rev()
+        .find(|t| {
+            !matches!(
+                t.token,
+                Token::Whitespace(_) | Token::SemiColon | Token::EOF
+            )
+        })
+        .map(|t| t.span.end)
+}
+
+pub(crate) fn line_start_offsets(sql: &str) -> Vec<usize> {
+    std::iter::once(0)
+        .chain(sql.match_indices('\n').map(|(i, _)| i + 1))
+        .collect()
+}
+
+pub(crate) fn byte_offset(sql: &str, line_starts: &[usize], loc: Location) -> usize {
+    let Some(&line_start) = line_starts.get(loc.line.saturating_sub(1) as usize) else {
+        return sql.len();
+    };
+    sql[line_start..]
+        .char_indices()
+        .nth(loc.column.saturating_sub(1) as usize)
+        .map(|(i, _)| line_start + i)
+        .unwrap_or(sql.len())
+}
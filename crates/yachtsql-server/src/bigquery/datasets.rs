@@ -0,0 +1,243 @@
+#![coverage(off)]
+
+use std::sync::Arc;
+
+use axum::Json;
+use axum::body::Bytes;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::{Map, json};
+
+use super::error::ApiError;
+use super::{DEFAULT_LOCATION, DatasetReference, ServerState, check_id, lock, millis, parse_body};
+
+/// Dataset properties the catalog has no place for.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct DatasetInfo {
+    description: Option<String>,
+    friendly_name: Option<String>,
+    labels: Map<String, serde_json::Value>,
+    location: Option<String>,
+    creation_time: Option<DateTime<Utc>>,
+    last_modified_time: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct DatasetRequest {
+    dataset_reference: Option<DatasetReference>,
+    description: Option<String>,
+    friendly_name: Option<String>,
+    labels: Option<Map<String, serde_json::Value>>,
+    location: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct DeleteOptions {
+    #[serde(rename = "deleteContents", default)]
+    delete_contents: bool,
+}
+
+/// `GET /projects/{project}/datasets`
+pub(crate) async fn list(
+    State(state): State<Arc<ServerState>>,
+    Path(project): Path<String>,
+) -> Json<serde_json::Value> {
+    let session = state.session(&project);
+    let mut names: Vec<String> = session
+        .catalog()
+        .get_datasets(&project)
+        .iter()
+        .map(|dataset| state.display_name(dataset, dataset))
+        .collect();
+    names.sort();
+    let datasets: Vec<serde_json::Value> = names
+        .iter()
+        .map(|dataset| resource(&state, &project, dataset))
+        .collect();
+    Json(json!({
+        "kind": "bigquery#datasetList",
+        "datasets": datasets,
+    }))
+}
+
+/// `POST /projects/{project}/datasets`
+pub(crate) async fn insert(
+    State(state): State<Arc<ServerState>>,
+    Path(project): Path<String>,
+    body: Bytes,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let request: DatasetRequest = parse_body(&body)?;
+    let dataset = request
+        .dataset_reference
+        .map(|reference| reference.dataset_id)
+        .unwrap_or_default();
+    check_id("dataset", &dataset)?;
+    let session = state.session(&project);
+    if state.dataset_exists(&session, &project, &dataset) {
+        return Err(ApiError::duplicate(format!(
+            "Already Exists: Dataset {project}:{dataset}"
+        )));
+    }
+    session
+        .execute_sql(&format!("CREATE SCHEMA `{dataset}`"))
+        .await?;
+
+    let now = Utc::now();
+    state.remember_name(&dataset, &dataset);
+    lock(&state.datasets).insert(
+        dataset.to_uppercase(),
+        DatasetInfo {
+            description: request.description,
+            friendly_name: request.friendly_name,
+            labels: request.labels.unwrap_or_default(),
+            location: request.location,
+            creation_time: Some(now),
+            last_modified_time: Some(now),
+        },
+    );
+    Ok(Json(resource(&state, &project, &dataset)))
+}
+
+/// `GET /projects/{project}/datasets/{dataset}`
+pub(crate) async fn get(
+    State(state): State<Arc<ServerState>>,
+    Path((project, dataset)): Path<(String, String)>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    check_exists(&state, &project, &dataset)?;
+    Ok(Json(resource(&state, &project, &dataset)))
+}
+
+/// `PATCH /projects/{project}/datasets/{dataset}`: changes the properties
+/// present in the request. Labels set to `null` are removed.
+pub(crate) async fn patch(
+    State(state): State<Arc<ServerState>>,
+    Path((project, dataset)): Path<(String, String)>,
+    body: Bytes,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    modify(&state, &project, &dataset, &body, false)
+}
+
+/// `PUT /projects/{project}/datasets/{dataset}`: replaces the dataset's
+/// properties with those in the request.
+pub(crate) async fn update(
+    State(state): State<Arc<ServerState>>,
+    Path((project, dataset)): Path<(String, String)>,
+    body: Bytes,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    modify(&state, &project, &dataset, &body, true)
+}
+
+/// `DELETE /projects/{project}/datasets/{dataset}`. Fails for a dataset that
+/// still has tables unless `deleteContents` is set.
+pub(crate) async fn delete(
+    State(state): State<Arc<ServerState>>,
+    Path((project, dataset)): Path<(String, String)>,
+    Query(options): Query<DeleteOptions>,
+) -> Result<StatusCode, ApiError> {
+    check_id("dataset", &dataset)?;
+    check_exists(&state, &project, &dataset)?;
+    let session = state.session(&project);
+    let tables = session.catalog().get_tables_in_dataset(&project, &dataset);
+    if !tables.is_empty() && !options.delete_contents {
+        return Err(ApiError::resource_in_use(format!(
+            "Dataset {project}:{dataset} is still in use"
+        )));
+    }
+    if session.catalog().schema_exists(&dataset) {
+        session
+            .execute_sql(&format!("DROP SCHEMA `{dataset}` CASCADE"))
+            .await?;
+    } else {
+        for table in &tables {
+            session
+                .execute_sql(&format!("DROP TABLE `{dataset}.{table}`"))
+                .await?;
+        }
+    }
+
+    let prefix = format!("{}.", dataset.to_uppercase());
+    lock(&state.names).retain(|key, _| !key.starts_with(&prefix));
+    state.forget_name(&dataset);
+    lock(&state.datasets).remove(&dataset.to_uppercase());
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub(crate) fn check_exists(
+    state: &ServerState,
+    project: &str,
+    dataset: &str,
+) -> Result<(), ApiError> {
+    if state.dataset_exists(&state.session(project), project, dataset) {
+        Ok(())
+    } else {
+        Err(ApiError::not_found(format!(
+            "Not found: Dataset {project}:{dataset}"
+        )))
+    }
+}
+
+fn modify(
+    state: &ServerState,
+    project: &str,
+    dataset: &str,
+    body: &Bytes,
+    replace: bool,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    check_exists(state, project, dataset)?;
+    let request: DatasetRequest = parse_body(body)?;
+    {
+        let mut datasets = lock(&state.datasets);
+        let info = datasets.entry(dataset.to_uppercase()).or_default();
+        if replace {
+            info.description = request.description;
+            info.friendly_name = request.friendly_name;
+            info.labels = request.labels.unwrap_or_default();
+        } else {
+            if request.description.is_some() {
+                info.description = request.description;
+            }
+            if request.friendly_name.is_some() {
+                info.friendly_name = request.friendly_name;
+            }
+            for (key, value) in request.labels.unwrap_or_default() {
+                if value.is_null() {
+                    info.labels.remove(&key);
+                } else {
+                    info.labels.insert(key, value);
+                }
+            }
+        }
+        info.last_modified_time = Some(Utc::now());
+    }
+    Ok(Json(resource(state, project, dataset)))
+}
+
+fn resource(state: &ServerState, project: &str, dataset: &str) -> serde_json::Value {
+    let info = lock(&state.datasets)
+        .get(&dataset.to_uppercase())
+        .cloned()
+        .unwrap_or_default();
+    let mut resource = json!({
+        "kind": "bigquery#dataset",
+        "id": format!("{project}:{dataset}"),
+        "datasetReference": {"projectId": project, "datasetId": dataset},
+        "location": info.location.as_deref().unwrap_or(DEFAULT_LOCATION),
+        "labels": info.labels,
+    });
+    if let Some(description) = info.description {
+        resource["description"] = description.into();
+    }
+    if let Some(friendly_name) = info.friendly_name {
+        resource["friendlyName"] = friendly_name.into();
+    }
+    if let Some(time) = info.creation_time {
+        resource["creationTime"] = millis(&time).into();
+    }
+    if let Some(time) = info.last_modified_time {
+        resource["lastModifiedTime"] = millis(&time).into();
+    }
+    resource
+}
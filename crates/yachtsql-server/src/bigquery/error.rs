@@ -0,0 +1,95 @@
+#![coverage(off)]
+
+use axum::Json;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use serde_json::json;
+use yachtsql::{Error, JobError};
+
+/// A failed request, answered with BigQuery's error body:
+/// `{"error": {"code", "message", "errors": [{"reason", "message"}], "status"}}`.
+#[derive(Debug, Clone)]
+pub(crate) struct ApiError {
+    status: StatusCode,
+    reason: String,
+    message: String,
+}
+
+impl ApiError {
+    fn new(status: StatusCode, reason: &str, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            reason: reason.to_string(),
+            message: message.into(),
+        }
+    }
+
+    pub(crate) fn invalid(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, "invalid", message)
+    }
+
+    pub(crate) fn not_found(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::NOT_FOUND, "notFound", message)
+    }
+
+    pub(crate) fn duplicate(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::CONFLICT, "duplicate", message)
+    }
+
+    pub(crate) fn resource_in_use(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, "resourceInUse", message)
+    }
+
+    /// The `errorResult` of a job that failed with this error.
+    pub(crate) fn error_result(&self) -> serde_json::Value {
+        json!({"reason": self.reason, "message": self.message})
+    }
+
+    fn body(&self) -> serde_json::Value {
+        let status = match self.status {
+            StatusCode::NOT_FOUND => "NOT_FOUND",
+            StatusCode::CONFLICT => "ALREADY_EXISTS",
+            StatusCode::INTERNAL_SERVER_ERROR => "INTERNAL",
+            _ => "INVALID_ARGUMENT",
+        };
+        json!({
+            "error": {
+                "code": self.status.as_u16(),
+                "message": self.message,
+                "errors": [{
+                    "message": self.message,
+                    "domain": "global",
+                    "reason": self.reason,
+                }],
+                "status": status,
+            }
+        })
+    }
+}
+
+impl From<JobError> for ApiError {
+    fn from(error: JobError) -> Self {
+        let status = match error.reason.as_str() {
+            "notFound" => StatusCode::NOT_FOUND,
+            "internalError" => StatusCode::INTERNAL_SERVER_ERROR,
+            _ => StatusCode::BAD_REQUEST,
+        };
+        Self {
+            status,
+            reason: error.reason,
+            message: error.message,
+        }
+    }
+}
+
+impl From<Error> for ApiError {
+    fn from(error: Error) -> Self {
+        JobError::from_error(&error).into()
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(self.body())).into_response()
+    }
+}
@@ -0,0 +1,488 @@
+#![coverage(off)]
+
+use std::collections::hash_map::Entry;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use axum::Json;
+use axum::body::Bytes;
+use axum::extract::{Path, Query, State};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::json;
+use yachtsql::{JobRecord, QueryFinish, QueryObserver, Row, Schema, Table, split_script};
+
+use super::error::ApiError;
+use super::wire::{self, FormatOptions, ListOptions, QueryParameterJson};
+use super::{
+    DEFAULT_LOCATION, DatasetReference, MAX_JOBS, ServerState, check_id, lock, millis, parse_body,
+};
+
+/// Dataset that query results are reported as being written to, as BigQuery
+/// does with its anonymous result tables. `tables.get` and `tabledata.list`
+/// serve `_anonymous.anon_<job id>` from the job's results.
+pub(crate) const ANONYMOUS_DATASET: &str = "_anonymous";
+const ANONYMOUS_TABLE_PREFIX: &str = "anon_";
+
+/// A finished query job.
+pub(crate) struct Job {
+    project: String,
+    job_id: String,
+    location: String,
+    configuration: serde_json::Value,
+    creation_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+    pub(crate) outcome: Result<QueryOutput, ApiError>,
+}
+
+/// The rows and statistics of a successful query.
+pub(crate) struct QueryOutput {
+    pub(crate) schema: Schema,
+    pub(crate) rows: Vec<Row>,
+    statement_type: String,
+    rows_affected: Option<u64>,
+    total_bytes_processed: u64,
+}
+
+/// The query options shared by `jobs.query` requests and the
+/// `configuration.query` of query jobs.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct QueryConfig {
+    query: String,
+    use_legacy_sql: Option<bool>,
+    default_dataset: Option<DatasetReference>,
+    query_parameters: Vec<QueryParameterJson>,
+    destination_table: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct QueryRequest {
+    #[serde(flatten)]
+    config: QueryConfig,
+    dry_run: bool,
+    max_results: Option<usize>,
+    location: Option<String>,
+    format_options: FormatOptions,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct JobRequest {
+    job_reference: JobReference,
+    configuration: serde_json::Value,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct JobReference {
+    job_id: Option<String>,
+    location: Option<String>,
+}
+
+/// Collects the job records of the statements a request runs.
+#[derive(Default)]
+struct JobRecorder(Mutex<Vec<JobRecord>>);
+
+impl QueryObserver for JobRecorder {
+    fn on_query_finish(&self, event: &QueryFinish<'_>) {
+        if let Some(job) = event.job {
+            lock(&self.0).push(job.clone());
+        }
+    }
+}
+
+/// `POST /projects/{project}/queries`
+pub(crate) async fn query(
+    State(state): State<Arc<ServerState>>,
+    Path(project): Path<String>,
+    body: Bytes,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let request: QueryRequest = parse_body(&body)?;
+    let location = request
+        .location
+        .unwrap_or_else(|| DEFAULT_LOCATION.to_string());
+    if request.dry_run {
+        let statistics = dry_run(&state, &project, &request.config)?;
+        return Ok(Json(json!({
+            "kind": "bigquery#queryResponse",
+            "jobReference": {"projectId": project, "location": location},
+            "jobComplete": true,
+            "totalBytesProcessed": statistics["totalBytesProcessed"],
+            "cacheHit": false,
+        })));
+    }
+
+    let configuration = json!({
+        "query": {"query": request.config.query, "useLegacySql": false},
+    });
+    let job = run_job(
+        &state,
+        project,
+        None,
+        location,
+        configuration,
+        &request.config,
+    )
+    .await?;
+    let mut response = job.results(
+        0,
+        request.max_results,
+        request.format_options.use_int64_timestamp,
+    )?;
+    response["kind"] = "bigquery#queryResponse".into();
+    Ok(Json(response))
+}
+
+/// `POST /projects/{project}/jobs`
+pub(crate) async fn insert(
+    State(state): State<Arc<ServerState>>,
+    Path(project): Path<String>,
+    body: Bytes,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let request: JobRequest = parse_body(&body)?;
+    let Some(query) = request.configuration.get("query") else {
+        return Err(ApiError::invalid("Only query jobs are supported"));
+    };
+    let config: QueryConfig = serde_json::from_value(query.clone())
+        .map_err(|e| ApiError::invalid(format!("Invalid query configuration: {e}")))?;
+    let location = request
+        .job_reference
+        .location
+        .unwrap_or_else(|| DEFAULT_LOCATION.to_string());
+
+    if request.configuration["dryRun"] == true {
+        let statistics = dry_run(&state, &project, &config)?;
+        return Ok(Json(json!({
+            "kind": "bigquery#job",
+            "jobReference": {"projectId": project, "location": location},
+            "configuration": request.configuration,
+            "status": {"state": "DONE"},
+            "statistics": {
+                "totalBytesProcessed": statistics["totalBytesProcessed"],
+                "query": statistics,
+            },
+        })));
+    }
+
+    let job = run_job(
+        &state,
+        project,
+        request.job_reference.job_id,
+        location,
+        request.configuration.clone(),
+        &config,
+    )
+    .await?;
+    Ok(Json(job.resource()))
+}
+
+/// `GET /projects/{project}/jobs/{job}`
+pub(crate) async fn get(
+    State(state): State<Arc<ServerState>>,
+    Path((project, job_id)): Path<(String, String)>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    Ok(Json(find_job(&state, &project, &job_id)?.resource()))
+}
+
+/// `POST /projects/{project}/jobs/{job}/cancel`. Jobs finish before they are
+/// reported, so there is never anything left to cancel.
+pub(crate) async fn cancel(
+    State(state): State<Arc<ServerState>>,
+    Path((project, job_id)): Path<(String, String)>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let job = find_job(&state, &project, &job_id)?;
+    Ok(Json(json!({
+        "kind": "bigquery#jobCancelResponse",
+        "job": job.resource(),
+    })))
+}
+
+/// `GET /projects/{project}/queries/{job}`
+pub(crate) async fn get_query_results(
+    State(state): State<Arc<ServerState>>,
+    Path((project, job_id)): Path<(String, String)>,
+    Query(options): Query<ListOptions>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let job = find_job(&state, &project, &job_id)?;
+    let mut response = job.results(
+        options.start()?,
+        options.max_results,
+        options.use_int64_timestamp,
+    )?;
+    response["kind"] = "bigquery#getQueryResultsResponse".into();
+    Ok(Json(response))
+}
+
+fn find_job(state: &ServerState, project: &str, job_id: &str) -> Result<Arc<Job>, ApiError> {
+    let ttl = *lock(&state.job_ttl);
+    lock(&state.jobs)
+        .get(&(project.to_string(), job_id.to_string()))
+        .filter(|(started, _)| started.elapsed() < ttl)
+        .and_then(|(_, job)| job.clone())
+        .ok_or_else(|| ApiError::not_found(format!("Not found: Job {project}:{job_id}")))
+}
+
+/// The job whose results `dataset.table` stands for, if it is an anonymous
+/// result table.
+pub(crate) fn anonymous_result(
+    state: &ServerState,
+    project: &str,
+    dataset: &str,
+    table: &str,
+) -> Option<Arc<Job>> {
+    if dataset != ANONYMOUS_DATASET {
+        return None;
+    }
+    let job_id = table.strip_prefix(ANONYMOUS_TABLE_PREFIX)?;
+    find_job(state, project, job_id)
+        .ok()
+        .filter(|job| job.outcome.is_ok())
+}
+
+/// Runs a query job to completion and records it. Only a duplicate job id
+/// fails the request; query errors are recorded in the job.
+async fn run_job(
+    state: &ServerState,
+    project: String,
+    job_id: Option<String>,
+    location: String,
+    mut configuration: serde_json::Value,
+    config: &QueryConfig,
+) -> Result<Arc<Job>, ApiError> {
+    let job_id = match job_id {
+        Some(job_id) => {
+            check_id("job", &job_id)?;
+            job_id
+        }
+        None => state.next_job_id(),
+    };
+    let key = (project.clone(), job_id.clone());
+    {
+        let ttl = *lock(&state.job_ttl);
+        let now = Instant::now();
+        let mut jobs = lock(&state.jobs);
+        jobs.retain(|_, (started, _)| now.duration_since(*started) < ttl);
+        while jobs.len() >= MAX_JOBS {
+            let Some(oldest) = jobs
+                .iter()
+                .min_by_key(|(_, (started, _))| *started)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            jobs.remove(&oldest);
+        }
+        match jobs.entry(key.clone()) {
+            Entry::Occupied(_) => {
+                return Err(ApiError::duplicate(format!(
+                    "Already Exists: Job {project}:{location}.{job_id}"
+                )));
+            }
+            Entry::Vacant(slot) => {
+                slot.insert((now, None));
+            }
+        }
+    }
+
+    let creation_time = Utc::now();
+    let outcome = execute(state, &project, config).await;
+    configuration["jobType"] = "QUERY".into();
+    if matches!(&outcome, Ok(output) if !output.schema.fields().is_empty()) {
+        configuration["query"]["destinationTable"] = json!({
+            "projectId": project,
+            "datasetId": ANONYMOUS_DATASET,
+            "tableId": format!("{ANONYMOUS_TABLE_PREFIX}{job_id}"),
+        });
+    }
+    let job = Arc::new(Job {
+        project,
+        job_id,
+        location,
+        configuration,
+        creation_time,
+        end_time: Utc::now(),
+        outcome,
+    });
+    if let Some((_, slot)) = lock(&state.jobs).get_mut(&key) {
+        *slot = Some(Arc::clone(&job));
+    }
+    Ok(job)
+}
+
+fn check_config(config: &QueryConfig) -> Result<(), ApiError> {
+    if config.use_legacy_sql == Some(true) {
+        return Err(ApiError::invalid(
+            "Legacy SQL is not supported; set useLegacySql to false",
+        ));
+    }
+    if config.destination_table.is_some() {
+        return Err(ApiError::invalid(
+            "Writing query results to a destinationTable is not supported",
+        ));
+    }
+    Ok(())
+}
+
+fn query_session(
+    state: &ServerState,
+    project: &str,
+    config: &QueryConfig,
+) -> yachtsql::YachtSQLSession {
+    let session = state.session(project);
+    if let Some(dataset) = &config.default_dataset {
+        session
+            .catalog()
+            .set_search_path(vec![dataset.dataset_id.clone()]);
+    }
+    session
+}
+
+async fn execute(
+    state: &ServerState,
+    project: &str,
+    config: &QueryConfig,
+) -> Result<QueryOutput, ApiError> {
+    check_config(config)?;
+    let parameters = config
+        .query_parameters
+        .iter()
+        .map(QueryParameterJson::to_parameter)
+        .collect::<Result<Vec<_>, _>>()?;
+    let session = query_session(state, project, config);
+    let recorder = Arc::new(JobRecorder::default());
+    session.add_observer(Arc::clone(&recorder) as _);
+
+    let is_script = split_script(&config.query).is_ok_and(|statements| statements.len() > 1);
+    let table = if !parameters.is_empty() {
+        session
+            .execute_sql_with_params(&config.query, &parameters)
+            .await?
+    } else if is_script {
+        session
+            .execute_script(&config.query)
+            .await?
+            .into_last_query_result()
+            .unwrap_or_else(|| Table::empty(Schema::new()))
+    } else {
+        session.execute_sql(&config.query).await?
+    };
+
+    let records = std::mem::take(&mut *lock(&recorder.0));
+    let statement_type = match records.as_slice() {
+        [record] => record
+            .statement_type
+            .clone()
+            .unwrap_or_else(|| "SELECT".to_string()),
+        [] => "SELECT".to_string(),
+        _ => "SCRIPT".to_string(),
+    };
+    let rows_affected = records
+        .iter()
+        .filter_map(|record| record.rows_affected)
+        .reduce(|a, b| a + b);
+    Ok(QueryOutput {
+        schema: table.schema().clone(),
+        rows: table.to_query_result()?.rows,
+        statement_type,
+        rows_affected,
+        total_bytes_processed: records
+            .iter()
+            .map(|record| record.total_bytes_processed)
+            .sum(),
+    })
+}
+
+/// The `statistics.query` of a dry run.
+fn dry_run(
+    state: &ServerState,
+    project: &str,
+    config: &QueryConfig,
+) -> Result<serde_json::Value, ApiError> {
+    check_config(config)?;
+    let result = query_session(state, project, config).dry_run(&config.query)?;
+    Ok(json!({
+        "statementType": result.statement_type,
+        "totalBytesProcessed": result.total_bytes_processed.to_string(),
+    }))
+}
+
+impl Job {
+    fn reference(&self) -> serde_json::Value {
+        json!({
+            "projectId": self.project,
+            "jobId": self.job_id,
+            "location": self.location,
+        })
+    }
+
+    /// The job resource returned by `jobs.get` and `jobs.insert`.
+    fn resource(&self) -> serde_json::Value {
+        let mut status = json!({"state": "DONE"});
+        let mut statistics = json!({
+            "creationTime": millis(&self.creation_time),
+            "startTime": millis(&self.creation_time),
+            "endTime": millis(&self.end_time),
+        });
+        match &self.outcome {
+            Ok(output) => {
+                statistics["totalBytesProcessed"] = output.total_bytes_processed.to_string().into();
+                statistics["query"] = output.statistics();
+            }
+            Err(error) => {
+                status["errorResult"] = error.error_result();
+                status["errors"] = json!([error.error_result()]);
+            }
+        }
+        json!({
+            "kind": "bigquery#job",
+            "id": format!("{}:{}.{}", self.project, self.location, self.job_id),
+            "jobReference": self.reference(),
+            "configuration": self.configuration,
+            "status": status,
+            "statistics": statistics,
+        })
+    }
+
+    /// A page of the job's results, in the shape shared by `jobs.query` and
+    /// `jobs.getQueryResults`. A failed job fails the request with its error.
+    fn results(
+        &self,
+        start: usize,
+        max_results: Option<usize>,
+        int64_timestamps: bool,
+    ) -> Result<serde_json::Value, ApiError> {
+        let output = self.outcome.as_ref().map_err(Clone::clone)?;
+        let mut response = wire::page(&output.rows, start, max_results, int64_timestamps);
+        response.insert("jobReference".into(), self.reference());
+        response.insert("jobComplete".into(), true.into());
+        response.insert("cacheHit".into(), false.into());
+        response.insert(
+            "totalBytesProcessed".into(),
+            output.total_bytes_processed.to_string().into(),
+        );
+        if !output.schema.fields().is_empty() {
+            response.insert("schema".into(), wire::schema_json(&output.schema)?);
+        }
+        if let Some(rows) = output.rows_affected {
+            response.insert("numDmlAffectedRows".into(), rows.to_string().into());
+        }
+        Ok(response.into())
+    }
+}
+
+impl QueryOutput {
+    fn statistics(&self) -> serde_json::Value {
+        let mut statistics = json!({
+            "statementType": self.statement_type,
+            "totalBytesProcessed": self.total_bytes_processed.to_string(),
+            "totalBytesBilled": self.total_bytes_processed.to_string(),
+            "cacheHit": false,
+        });
+        if let Some(rows) = self.rows_affected {
+            statistics["numDmlAffectedRows"] = rows.to_string().into();
+        }
+        statistics
+    }
+}
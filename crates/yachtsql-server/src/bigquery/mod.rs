@@ -0,0 +1,228 @@
+#![coverage(off)]
+
+mod datasets;
+mod error;
+mod jobs;
+mod tables;
+mod wire;
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::Router;
+use axum::body::Bytes;
+use axum::routing::{get, post};
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use tokio::net::TcpListener;
+use yachtsql::{YachtSQLEngine, YachtSQLSession};
+
+use self::datasets::DatasetInfo;
+use self::error::ApiError;
+use self::jobs::Job;
+
+/// Location reported for datasets and jobs that were not given one.
+const DEFAULT_LOCATION: &str = "US";
+/// How long finished jobs and their results are kept by default.
+const DEFAULT_JOB_TTL: Duration = Duration::from_secs(60 * 60);
+/// Most jobs kept; the oldest are dropped beyond it.
+const MAX_JOBS: usize = 1024;
+
+/// An emulator of the BigQuery v2 REST API, serving an engine's catalog to the
+/// official client libraries.
+///
+/// Routes live under `/bigquery/v2`, so clients only need their API endpoint
+/// pointed at the server. Supported methods:
+///
+/// - `jobs`: `query`, `insert` (query jobs only), `get`, `getQueryResults`, `cancel`
+/// - `datasets`: `list`, `insert`, `get`, `patch`, `update`, `delete`
+/// - `tables`: `list`, `insert`, `get`, `delete`
+/// - `tabledata`: `insertAll`, `list`
+///
+/// Each request runs in a session of its own whose default project is the one
+/// in the URL, so `dataset.table` and `project.dataset.table` name the same
+/// table; datasets are the engine's schemas. Jobs run to completion before the
+/// response is sent and are kept, with their results, for the
+/// [job TTL](Self::with_job_ttl); beyond 1024 jobs the oldest are dropped.
+///
+/// The engine should share its catalog between sessions, i.e. be created with
+/// [`YachtSQLEngine::with_shared_catalog`] or [`YachtSQLEngine::open`]. With an
+/// engine from [`YachtSQLEngine::new`] nothing outlives a single request.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let server = BigQueryServer::new(YachtSQLEngine::with_shared_catalog());
+/// let listener = tokio::net::TcpListener::bind("127.0.0.1:9050").await?;
+/// server.serve(listener).await?;
+/// ```
+#[derive(Clone)]
+pub struct BigQueryServer {
+    state: Arc<ServerState>,
+}
+
+/// A job's start time and, once it has finished, the job itself.
+type JobSlot = (Instant, Option<Arc<Job>>);
+
+struct ServerState {
+    engine: YachtSQLEngine,
+    /// Jobs by project and job id, with the time they were started. A job that
+    /// is still running has no record yet.
+    jobs: Mutex<HashMap<(String, String), JobSlot>>,
+    job_ttl: Mutex<Duration>,
+    /// Datasets created through the API, by uppercased dataset name.
+    datasets: Mutex<HashMap<String, DatasetInfo>>,
+    /// The spelling datasets (`DATASET`) and tables (`DATASET.TABLE`) were
+    /// created with through the API; the catalog only keeps uppercased names.
+    names: Mutex<HashMap<String, String>>,
+    next_job_id: AtomicU64,
+}
+
+impl BigQueryServer {
+    pub fn new(engine: YachtSQLEngine) -> Self {
+        Self {
+            state: Arc::new(ServerState {
+                engine,
+                jobs: Mutex::new(HashMap::new()),
+                job_ttl: Mutex::new(DEFAULT_JOB_TTL),
+                datasets: Mutex::new(HashMap::new()),
+                names: Mutex::new(HashMap::new()),
+                next_job_id: AtomicU64::new(1),
+            }),
+        }
+    }
+
+    /// Sets how long jobs and their results are kept after they start; an hour
+    /// by default.
+    pub fn with_job_ttl(self, ttl: Duration) -> Self {
+        *lock(&self.state.job_ttl) = ttl;
+        self
+    }
+
+    pub fn engine(&self) -> &YachtSQLEngine {
+        &self.state.engine
+    }
+
+    /// The API as an [`axum::Router`], for embedding in another server or
+    /// calling in-process from tests.
+    pub fn router(&self) -> Router {
+        let dataset = "/projects/{project}/datasets/{dataset}";
+        let table = "/projects/{project}/datasets/{dataset}/tables/{table}";
+        let api = Router::new()
+            .route("/projects/{project}/queries", post(jobs::query))
+            .route(
+                "/projects/{project}/queries/{job}",
+                get(jobs::get_query_results),
+            )
+            .route("/projects/{project}/jobs", post(jobs::insert))
+            .route("/projects/{project}/jobs/{job}", get(jobs::get))
+            .route("/projects/{project}/jobs/{job}/cancel", post(jobs::cancel))
+            .route(
+                "/projects/{project}/datasets",
+                get(datasets::list).post(datasets::insert),
+            )
+            .route(
+                dataset,
+                get(datasets::get)
+                    .patch(datasets::patch)
+                    .put(datasets::update)
+                    .delete(datasets::delete),
+            )
+            .route(
+                &format!("{dataset}/tables"),
+                get(tables::list).post(tables::insert),
+            )
+            .route(table, get(tables::get).delete(tables::delete))
+            .route(&format!("{table}/insertAll"), post(tables::insert_all))
+            .route(&format!("{table}/data"), get(tables::list_data))
+            .with_state(Arc::clone(&self.state));
+        Router::new().nest("/bigquery/v2", api)
+    }
+
+    /// Serves the API on `listener` until the process exits.
+    pub async fn serve(self, listener: TcpListener) -> std::io::Result<()> {
+        axum::serve(listener, self.router()).await
+    }
+}
+
+impl ServerState {
+    /// A fresh session whose default project is `project`.
+    fn session(&self, project: &str) -> YachtSQLSession {
+        let session = self.engine.create_session();
+        session.set_default_project(Some(project.to_string()));
+        session
+    }
+
+    fn next_job_id(&self) -> String {
+        format!(
+            "job_{:012}",
+            self.next_job_id.fetch_add(1, Ordering::Relaxed)
+        )
+    }
+
+    fn remember_name(&self, key: &str, name: &str) {
+        lock(&self.names).insert(key.to_uppercase(), name.to_string());
+    }
+
+    fn forget_name(&self, key: &str) {
+        lock(&self.names).remove(&key.to_uppercase());
+    }
+
+    /// The name the object at `key` was created with, or `fallback` for objects
+    /// created through SQL.
+    fn display_name(&self, key: &str, fallback: &str) -> String {
+        lock(&self.names)
+            .get(&key.to_uppercase())
+            .cloned()
+            .unwrap_or_else(|| fallback.to_string())
+    }
+
+    fn dataset_exists(&self, session: &YachtSQLSession, project: &str, dataset: &str) -> bool {
+        session.catalog().schema_exists(dataset)
+            || session
+                .catalog()
+                .get_datasets(project)
+                .iter()
+                .any(|name| name.eq_ignore_ascii_case(dataset))
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// `datasetReference` in dataset resources and `defaultDataset` in queries.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct DatasetReference {
+    project_id: Option<String>,
+    dataset_id: String,
+}
+
+/// Parses a JSON request body; an empty body is the default request.
+fn parse_body<T: DeserializeOwned + Default>(body: &Bytes) -> Result<T, ApiError> {
+    if body.iter().all(u8::is_ascii_whitespace) {
+        return Ok(T::default());
+    }
+    serde_json::from_slice(body).map_err(|e| ApiError::invalid(format!("Invalid request: {e}")))
+}
+
+/// Rejects identifiers that are not valid BigQuery dataset, table or job ids,
+/// which also keeps them safe to splice into SQL.
+fn check_id(kind: &str, id: &str) -> Result<(), ApiError> {
+    let valid = !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if valid {
+        Ok(())
+    } else {
+        Err(ApiError::invalid(format!("Invalid {kind} ID \"{id}\"")))
+    }
+}
+
+fn millis(time: &chrono::DateTime<chrono::Utc>) -> String {
+    time.timestamp_millis().to_string()
+}
@@ -0,0 +1,301 @@
+#![coverage(off)]
+
+use std::sync::Arc;
+
+use axum::Json;
+use axum::body::Bytes;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use serde::Deserialize;
+use serde_json::{Map, json};
+use yachtsql::{Row, YachtSQLSession};
+
+use super::datasets::check_exists;
+use super::error::ApiError;
+use super::jobs::anonymous_result;
+use super::wire::{self, ListOptions};
+use super::{ServerState, check_id, parse_body};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct TableRequest {
+    table_reference: TableReference,
+    schema: Option<serde_json::Value>,
+    view: Option<ViewDefinition>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct TableReference {
+    table_id: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct ViewDefinition {
+    query: String,
+    use_legacy_sql: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct InsertAllRequest {
+    rows: Vec<InsertAllRow>,
+    skip_invalid_rows: bool,
+    ignore_unknown_values: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct InsertAllRow {
+    json: Map<String, serde_json::Value>,
+}
+
+type TablePath = Path<(String, String, String)>;
+
+/// `GET /projects/{project}/datasets/{dataset}/tables`
+pub(crate) async fn list(
+    State(state): State<Arc<ServerState>>,
+    Path((project, dataset)): Path<(String, String)>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    check_exists(&state, &project, &dataset)?;
+    let session = state.session(&project);
+    let mut names: Vec<String> = session
+        .catalog()
+        .get_tables_in_dataset(&project, &dataset)
+        .iter()
+        .map(|table| state.display_name(&format!("{dataset}.{table}"), table))
+        .collect();
+    names.sort();
+    let tables: Vec<serde_json::Value> = names
+        .iter()
+        .map(|table| {
+            json!({
+                "kind": "bigquery#table",
+                "id": format!("{project}:{dataset}.{table}"),
+                "tableReference": reference(&project, &dataset, table),
+                "type": "TABLE",
+            })
+        })
+        .collect();
+    Ok(Json(json!({
+        "kind": "bigquery#tableList",
+        "tables": tables,
+        "totalItems": names.len(),
+    })))
+}
+
+/// `POST /projects/{project}/datasets/{dataset}/tables`. Creates a table from
+/// the request's `schema`, or a view from its `view.query`.
+pub(crate) async fn insert(
+    State(state): State<Arc<ServerState>>,
+    Path((project, dataset)): Path<(String, String)>,
+    body: Bytes,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let request: TableRequest = parse_body(&body)?;
+    let table = request.table_reference.table_id;
+    check_id("dataset", &dataset)?;
+    check_id("table", &table)?;
+    check_exists(&state, &project, &dataset)?;
+
+    let session = state.session(&project);
+    let name = format!("{dataset}.{table}");
+    if session.catalog().table_exists(&name) || session.catalog().get_view(&name).is_some() {
+        return Err(ApiError::duplicate(format!(
+            "Already Exists: Table {project}:{name}"
+        )));
+    }
+    match request.view {
+        Some(view) => {
+            if view.use_legacy_sql == Some(true) {
+                return Err(ApiError::invalid("Legacy SQL views are not supported"));
+            }
+            session
+                .execute_sql(&format!("CREATE VIEW `{name}` AS {}", view.query))
+                .await?;
+        }
+        None => {
+            let schema = request
+                .schema
+                .map(|schema| schema.to_string())
+                .unwrap_or_else(|| "[]".to_string());
+            session.create_table_from_bigquery_schema(&name, &schema)?;
+        }
+    }
+    state.remember_name(&name, &table);
+    Ok(Json(resource(
+        &state, &session, &project, &dataset, &table,
+    )?))
+}
+
+/// `GET /projects/{project}/datasets/{dataset}/tables/{table}`
+pub(crate) async fn get(
+    State(state): State<Arc<ServerState>>,
+    Path((project, dataset, table)): TablePath,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let session = state.session(&project);
+    Ok(Json(resource(
+        &state, &session, &project, &dataset, &table,
+    )?))
+}
+
+/// `DELETE /projects/{project}/datasets/{dataset}/tables/{table}`
+pub(crate) async fn delete(
+    State(state): State<Arc<ServerState>>,
+    Path((project, dataset, table)): TablePath,
+) -> Result<StatusCode, ApiError> {
+    check_id("dataset", &dataset)?;
+    check_id("table", &table)?;
+    let session = state.session(&project);
+    let name = format!("{dataset}.{table}");
+    let kind = if session.catalog().get_view(&name).is_some() {
+        "VIEW"
+    } else if session.catalog().table_exists(&name) {
+        "TABLE"
+    } else {
+        return Err(not_found(&project, &name));
+    };
+    session
+        .execute_sql(&format!("DROP {kind} `{name}`"))
+        .await?;
+    state.forget_name(&name);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// `POST /projects/{project}/datasets/{dataset}/tables/{table}/insertAll`.
+///
+/// Unless `skipInvalidRows` is set, one invalid row keeps the whole request
+/// from inserting anything; the other rows are reported as `stopped`.
+pub(crate) async fn insert_all(
+    State(state): State<Arc<ServerState>>,
+    Path((project, dataset, table)): TablePath,
+    body: Bytes,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let request: InsertAllRequest = parse_body(&body)?;
+    let session = state.session(&project);
+    let name = format!("{dataset}.{table}");
+    let Some(schema) = session.catalog().get_table_schema(&name) else {
+        return Err(not_found(&project, &name));
+    };
+
+    let mut rows = Vec::with_capacity(request.rows.len());
+    let mut errors = Vec::new();
+    for (index, row) in request.rows.iter().enumerate() {
+        match wire::decode_row(&schema, &row.json, request.ignore_unknown_values) {
+            Ok(value) => rows.push((index, value)),
+            Err((location, message)) => {
+                errors.push((index, insert_error("invalid", &location, &message)));
+            }
+        }
+    }
+    if !errors.is_empty() && !request.skip_invalid_rows {
+        errors.extend(
+            rows.drain(..)
+                .map(|(index, _)| (index, insert_error("stopped", "", ""))),
+        );
+        errors.sort_by_key(|(index, _)| *index);
+    }
+    if !rows.is_empty() {
+        session
+            .insert_values(&name, rows.into_iter().map(|(_, row)| row).collect())
+            .await?;
+    }
+
+    let mut response = json!({"kind": "bigquery#tableDataInsertAllResponse"});
+    if !errors.is_empty() {
+        response["insertErrors"] = errors
+            .into_iter()
+            .map(|(index, error)| json!({"index": index, "errors": [error]}))
+            .collect();
+    }
+    Ok(Json(response))
+}
+
+/// `GET /projects/{project}/datasets/{dataset}/tables/{table}/data`
+pub(crate) async fn list_data(
+    State(state): State<Arc<ServerState>>,
+    Path((project, dataset, table)): TablePath,
+    Query(options): Query<ListOptions>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let start = options.start()?;
+    let rows = match anonymous_result(&state, &project, &dataset, &table) {
+        Some(job) => page_of(
+            job.outcome.as_ref().map(|output| &output.rows[..]),
+            &options,
+            start,
+        ),
+        None => {
+            let session = state.session(&project);
+            let name = format!("{dataset}.{table}");
+            let Some(handle) = session.catalog().get_table_handle(&name) else {
+                return Err(not_found(&project, &name));
+            };
+            let rows = handle.read().to_query_result()?.rows;
+            page_of(Ok(&rows), &options, start)
+        }
+    };
+    let mut response = rows?;
+    response.insert("kind".into(), "bigquery#tableDataList".into());
+    Ok(Json(response.into()))
+}
+
+fn page_of(
+    rows: Result<&[Row], &ApiError>,
+    options: &ListOptions,
+    start: usize,
+) -> Result<Map<String, serde_json::Value>, ApiError> {
+    let rows = rows.map_err(Clone::clone)?;
+    Ok(wire::page(
+        rows,
+        start,
+        options.max_results,
+        options.use_int64_timestamp,
+    ))
+}
+
+/// The table resource returned by `tables.get` and `tables.insert`.
+fn resource(
+    state: &ServerState,
+    session: &YachtSQLSession,
+    project: &str,
+    dataset: &str,
+    table: &str,
+) -> Result<serde_json::Value, ApiError> {
+    let name = format!("{dataset}.{table}");
+    let mut resource = json!({
+        "kind": "bigquery#table",
+        "id": format!("{project}:{name}"),
+        "tableReference": reference(project, dataset, table),
+    });
+
+    if let Some(job) = anonymous_result(state, project, dataset, table) {
+        let output = job.outcome.as_ref().map_err(Clone::clone)?;
+        resource["type"] = "TABLE".into();
+        resource["schema"] = wire::schema_json(&output.schema)?;
+        resource["numRows"] = output.rows.len().to_string().into();
+    } else if let Some(view) = session.catalog().get_view(&name) {
+        resource["type"] = "VIEW".into();
+        resource["view"] = json!({"query": view.query, "useLegacySql": false});
+    } else if let Some(handle) = session.catalog().get_table_handle(&name) {
+        let data = handle.read();
+        resource["type"] = "TABLE".into();
+        resource["schema"] = wire::schema_json(data.schema())?;
+        resource["numRows"] = data.row_count().to_string().into();
+        resource["numBytes"] = data.estimated_bytes().to_string().into();
+    } else {
+        return Err(not_found(project, &name));
+    }
+    Ok(resource)
+}
+
+fn reference(project: &str, dataset: &str, table: &str) -> serde_json::Value {
+    json!({"projectId": project, "datasetId": dataset, "tableId": table})
+}
+
+fn insert_error(reason: &str, location: &str, message: &str) -> serde_json::Value {
+    json!({"reason": reason, "location": location, "message": message})
+}
+
+fn not_found(project: &str, name: &str) -> ApiError {
+    ApiError::not_found(format!("Not found: Table {project}:{name}"))
+}
@@ -0,0 +1,409 @@
+#![coverage(off)]
+
+use std::collections::HashMap;
+
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::{Map, json};
+use yachtsql::{DataType, FieldMode, QueryParameter, Row, Schema, Value};
+use yachtsql_common::types::{RangeValue, StructField};
+use yachtsql_executor::cast_value;
+use yachtsql_executor::scalar_functions::helpers::parse_timestamp_string;
+
+use super::error::ApiError;
+
+/// Query-string options shared by `jobs.getQueryResults` and `tabledata.list`.
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct ListOptions {
+    #[serde(rename = "maxResults")]
+    pub max_results: Option<usize>,
+    #[serde(rename = "pageToken")]
+    pub page_token: Option<String>,
+    #[serde(rename = "startIndex")]
+    pub start_index: Option<usize>,
+    #[serde(rename = "formatOptions.useInt64Timestamp", default)]
+    pub use_int64_timestamp: bool,
+}
+
+/// `formatOptions` in a `jobs.query` request body.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct FormatOptions {
+    pub use_int64_timestamp: bool,
+}
+
+/// One page of rows: the `rows`, `totalRows` and `pageToken` members of a
+/// list response. Page tokens are the index of the next row.
+pub(crate) fn page(
+    rows: &[Row],
+    start: usize,
+    max_results: Option<usize>,
+    int64_timestamps: bool,
+) -> Map<String, serde_json::Value> {
+    let start = start.min(rows.len());
+    let end = match max_results {
+        Some(max) => start.saturating_add(max).min(rows.len()),
+        None => rows.len(),
+    };
+    let mut page = Map::new();
+    page.insert("totalRows".into(), rows.len().to_string().into());
+    page.insert(
+        "rows".into(),
+        rows[start..end]
+            .iter()
+            .map(|row| encode_row(row.values(), int64_timestamps))
+            .collect(),
+    );
+    if end < rows.len() {
+        page.insert("pageToken".into(), end.to_string().into());
+    }
+    page
+}
+
+impl ListOptions {
+    pub(crate) fn start(&self) -> Result<usize, ApiError> {
+        match &self.page_token {
+            Some(token) => token
+                .parse()
+                .map_err(|_| ApiError::invalid(format!("Invalid page token: {token}"))),
+            None => Ok(self.start_index.unwrap_or(0)),
+        }
+    }
+}
+
+/// `{"fields": [...]}` in BigQuery's table schema format.
+pub(crate) fn schema_json(schema: &Schema) -> Result<serde_json::Value, ApiError> {
+    let fields: serde_json::Value = serde_json::from_str(&schema.to_bigquery_json()?)
+        .map_err(|e| ApiError::invalid(e.to_string()))?;
+    Ok(json!({ "fields": fields }))
+}
+
+fn encode_row(values: &[Value], int64_timestamps: bool) -> serde_json::Value {
+    let cells: Vec<serde_json::Value> = values
+        .iter()
+        .map(|value| json!({ "v": encode_value(value, int64_timestamps) }))
+        .collect();
+    json!({ "f": cells })
+}
+
+/// A value in BigQuery's JSON row format: scalars are strings, arrays are
+/// lists of `{"v": ...}` cells and structs are rows.
+fn encode_value(value: &Value, int64_timestamps: bool) -> serde_json::Value {
+    let text = match value {
+        Value::Null | Value::Default => return serde_json::Value::Null,
+        Value::Bool(b) => b.to_string(),
+        Value::Int64(n) => n.to_string(),
+        Value::Float64(f) => encode_float(f.0),
+        Value::Numeric(d) | Value::BigNumeric(d) => d.to_string(),
+        Value::String(s) | Value::Geography(s) => s.clone(),
+        Value::Bytes(b) => BASE64.encode(b),
+        Value::Date(d) => d.to_string(),
+        Value::Time(t) => t.format("%H:%M:%S%.f").to_string(),
+        Value::DateTime(dt) => dt.format("%Y-%m-%dT%H:%M:%S%.f").to_string(),
+        Value::Timestamp(ts) if int64_timestamps => ts.timestamp_micros().to_string(),
+        Value::Timestamp(ts) => encode_seconds(ts.timestamp_micros()),
+        Value::Json(j) => j.to_string(),
+        Value::Array(items) => {
+            return items
+                .iter()
+                .map(|item| json!({ "v": encode_value(item, int64_timestamps) }))
+                .collect();
+        }
+        Value::Struct(fields) => {
+            let values: Vec<Value> = fields.iter().map(|(_, v)| v.clone()).collect();
+            return encode_row(&values, int64_timestamps);
+        }
+        Value::Interval(_) | Value::Range(_) => {
+            match cast_value(value.clone(), &DataType::String, false) {
+                Ok(Value::String(s)) => s,
+                _ => return serde_json::Value::Null,
+            }
+        }
+    };
+    serde_json::Value::String(text)
+}
+
+fn encode_float(f: f64) -> String {
+    if f.is_nan() {
+        "NaN".to_string()
+    } else if f.is_infinite() {
+        if f > 0.0 { "Infinity" } else { "-Infinity" }.to_string()
+    } else {
+        f.to_string()
+    }
+}
+
+/// Seconds since the epoch with exactly six decimals, so clients that parse
+/// timestamps as floats keep microsecond precision.
+fn encode_seconds(micros: i64) -> String {
+    let sign = if micros < 0 { "-" } else { "" };
+    let micros = micros.unsigned_abs();
+    format!("{sign}{}.{:06}", micros / 1_000_000, micros % 1_000_000)
+}
+
+/// Converts a row of an `insertAll` request to a `STRUCT` value for
+/// [`insert_values`](yachtsql::YachtSQLSession::insert_values). Errors name the
+/// offending field.
+pub(crate) fn decode_row(
+    schema: &Schema,
+    row: &Map<String, serde_json::Value>,
+    ignore_unknown_values: bool,
+) -> Result<Value, (String, String)> {
+    let mut fields = Vec::with_capacity(row.len());
+    for (name, json) in row {
+        let Some(field) = schema
+            .fields()
+            .iter()
+            .find(|field| field.name.eq_ignore_ascii_case(name))
+        else {
+            if ignore_unknown_values {
+                continue;
+            }
+            return Err((name.clone(), format!("no such field: {name}.")));
+        };
+        let value = decode_value(json, &field.data_type).map_err(|e| (name.clone(), e))?;
+        fields.push((field.name.clone(), value));
+    }
+    for field in schema.fields() {
+        if field.mode == FieldMode::Required
+            && !fields
+                .iter()
+                .any(|(name, v)| name == &field.name && !v.is_null())
+        {
+            return Err((
+                field.name.clone(),
+                format!("Missing required field: {}.", field.name),
+            ));
+        }
+    }
+    Ok(Value::Struct(fields))
+}
+
+fn decode_value(json: &serde_json::Value, data_type: &DataType) -> Result<Value, String> {
+    use serde_json::Value as Json;
+
+    let mismatch = || format!("Cannot convert value {json} to {data_type}.");
+    Ok(match (json, data_type) {
+        (Json::Null, _) => Value::Null,
+        (Json::Array(items), DataType::Array(inner)) => Value::Array(
+            items
+                .iter()
+                .map(|item| decode_value(item, inner))
+                .collect::<Result<_, _>>()?,
+        ),
+        (Json::Object(object), DataType::Struct(fields)) => {
+            let mut values = Vec::with_capacity(fields.len());
+            for field in fields {
+                let value = object
+                    .iter()
+                    .find(|(name, _)| name.eq_ignore_ascii_case(&field.name))
+                    .map(|(_, v)| decode_value(v, &field.data_type))
+                    .transpose()?
+                    .unwrap_or(Value::Null);
+                values.push((field.name.clone(), value));
+            }
+            Value::Struct(values)
+        }
+        (Json::Bool(b), DataType::Bool) => Value::Bool(*b),
+        (Json::Number(n), DataType::Int64) => Value::Int64(n.as_i64().ok_or_else(mismatch)?),
+        (Json::Number(n), DataType::Float64) => Value::float64(n.as_f64().ok_or_else(mismatch)?),
+        (Json::Number(n), DataType::Timestamp) => {
+            timestamp_from_seconds(n.as_f64().ok_or_else(mismatch)?).ok_or_else(mismatch)?
+        }
+        (Json::Number(n), DataType::Numeric(_) | DataType::BigNumeric) => {
+            cast_value(Value::String(n.to_string()), data_type, false).map_err(|e| e.to_string())?
+        }
+        (Json::Number(n), DataType::String) => Value::String(n.to_string()),
+        (Json::String(s), DataType::String) => Value::String(s.clone()),
+        (Json::String(s), DataType::Geography) => Value::Geography(s.clone()),
+        (Json::String(s), DataType::Bytes) => {
+            Value::Bytes(BASE64.decode(s).map_err(|_| mismatch())?)
+        }
+        (Json::String(s), DataType::Float64) => Value::float64(match s.as_str() {
+            "NaN" => f64::NAN,
+            "Infinity" => f64::INFINITY,
+            "-Infinity" => f64::NEG_INFINITY,
+            _ => s.parse().map_err(|_| mismatch())?,
+        }),
+        (Json::String(s), DataType::Timestamp) if s.parse::<f64>().is_ok() => {
+            timestamp_from_seconds(s.parse().unwrap_or_default()).ok_or_else(mismatch)?
+        }
+        (Json::String(s), DataType::Timestamp) => {
+            Value::Timestamp(parse_timestamp_string(s).map_err(|e| e.to_string())?)
+        }
+        (Json::String(s), DataType::Json) => {
+            Value::Json(serde_json::from_str(s).map_err(|_| mismatch())?)
+        }
+        (json, DataType::Json) => Value::Json(json.clone()),
+        (Json::String(s), _) => {
+            cast_value(Value::String(s.clone()), data_type, false).map_err(|e| e.to_string())?
+        }
+        _ => return Err(mismatch()),
+    })
+}
+
+fn timestamp_from_seconds(seconds: f64) -> Option<Value> {
+    DateTime::<Utc>::from_timestamp_micros((seconds * 1_000_000.0).round() as i64)
+        .map(Value::Timestamp)
+}
+
+/// A member of `queryParameters` in a query request.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct QueryParameterJson {
+    #[serde(default)]
+    name: Option<String>,
+    parameter_type: ParameterType,
+    #[serde(default)]
+    parameter_value: ParameterValue,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ParameterType {
+    #[serde(rename = "type")]
+    type_name: String,
+    #[serde(default)]
+    array_type: Option<Box<ParameterType>>,
+    #[serde(default)]
+    struct_types: Vec<StructType>,
+    #[serde(default)]
+    range_element_type: Option<Box<ParameterType>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct StructType {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(rename = "type")]
+    field_type: ParameterType,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct ParameterValue {
+    value: Option<serde_json::Value>,
+    array_values: Option<Vec<ParameterValue>>,
+    struct_values: Option<HashMap<String, ParameterValue>>,
+    range_value: Option<RangeParameterValue>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+struct RangeParameterValue {
+    start: Option<Box<ParameterValue>>,
+    end: Option<Box<ParameterValue>>,
+}
+
+impl QueryParameterJson {
+    pub(crate) fn to_parameter(&self) -> Result<QueryParameter, ApiError> {
+        let data_type = self.parameter_type.data_type()?;
+        let value = self
+            .parameter_value
+            .to_value(&data_type)
+            .map_err(|e| ApiError::invalid(format!("Invalid query parameter value: {e}")))?;
+        let parameter = match self.name.as_deref() {
+            Some(name) if !name.is_empty() => QueryParameter::named(name, value),
+            _ => QueryParameter::positional(value),
+        };
+        Ok(parameter.with_type(data_type))
+    }
+}
+
+impl ParameterType {
+    fn data_type(&self) -> Result<DataType, ApiError> {
+        let element = |inner: &Option<Box<ParameterType>>| match inner {
+            Some(inner) => inner.data_type(),
+            None => Err(ApiError::invalid(format!(
+                "Query parameter type {} requires an element type",
+                self.type_name
+            ))),
+        };
+        Ok(match self.type_name.to_uppercase().as_str() {
+            "BOOL" | "BOOLEAN" => DataType::Bool,
+            "INT64" | "INTEGER" => DataType::Int64,
+            "FLOAT64" | "FLOAT" => DataType::Float64,
+            "NUMERIC" => DataType::Numeric(None),
+            "BIGNUMERIC" => DataType::BigNumeric,
+            "STRING" => DataType::String,
+            "BYTES" => DataType::Bytes,
+            "DATE" => DataType::Date,
+            "DATETIME" => DataType::DateTime,
+            "TIME" => DataType::Time,
+            "TIMESTAMP" => DataType::Timestamp,
+            "GEOGRAPHY" => DataType::Geography,
+            "JSON" => DataType::Json,
+            "INTERVAL" => DataType::Interval,
+            "ARRAY" => DataType::Array(Box::new(element(&self.array_type)?)),
+            "RANGE" => DataType::Range(Box::new(element(&self.range_element_type)?)),
+            "STRUCT" | "RECORD" => DataType::Struct(
+                self.struct_types
+                    .iter()
+                    .map(|field| {
+                        Ok(StructField {
+                            name: field.name.clone().unwrap_or_default(),
+                            data_type: field.field_type.data_type()?,
+                        })
+                    })
+                    .collect::<Result<_, ApiError>>()?,
+            ),
+            other => {
+                return Err(ApiError::invalid(format!(
+                    "Unsupported query parameter type: {other}"
+                )));
+            }
+        })
+    }
+}
+
+impl ParameterValue {
+    fn to_value(&self, data_type: &DataType) -> Result<Value, String> {
+        match data_type {
+            DataType::Array(inner) => match &self.array_values {
+                Some(items) => Ok(Value::Array(
+                    items
+                        .iter()
+                        .map(|item| item.to_value(inner))
+                        .collect::<Result<_, _>>()?,
+                )),
+                None => Ok(Value::Null),
+            },
+            DataType::Struct(fields) => match &self.struct_values {
+                Some(values) => Ok(Value::Struct(
+                    fields
+                        .iter()
+                        .map(|field| {
+                            let value = match values.get(&field.name) {
+                                Some(value) => value.to_value(&field.data_type)?,
+                                None => Value::Null,
+                            };
+                            Ok((field.name.clone(), value))
+                        })
+                        .collect::<Result<_, String>>()?,
+                )),
+                None => Ok(Value::Null),
+            },
+            DataType::Range(inner) => match &self.range_value {
+                Some(range) => {
+                    let bound = |bound: &Option<Box<ParameterValue>>| match bound {
+                        Some(bound) => bound
+                            .to_value(inner)
+                            .map(|v| Some(v).filter(|v| !v.is_null())),
+                        None => Ok(None),
+                    };
+                    Ok(Value::Range(RangeValue::new(
+                        bound(&range.start)?,
+                        bound(&range.end)?,
+                    )))
+                }
+                None => Ok(Value::Null),
+            },
+            _ => match &self.value {
+                Some(value) => decode_value(value, data_type),
+                None => Ok(Value::Null),
+            },
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use tokio::net::TcpListener;
+use yachtsql::YachtSQLEngine;
+use yachtsql_server::FlightSqlServer;
+
+#[derive(Parser)]
+#[command(name = "yachtsql-flight-sql-server")]
+#[command(about = "Serve Arrow Flight SQL from an in-memory YachtSQL engine", long_about = None)]
+struct Cli {
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1")]
+    host: String,
+    #[arg(long, default_value_t = 50051)]
+    port: u16,
+    /// Project that tables named `dataset.table` belong to
+    #[arg(long)]
+    project: Option<String>,
+    /// Persist tables in this directory instead of discarding them on exit
+    #[arg(long)]
+    data_dir: Option<PathBuf>,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    let engine = match &cli.data_dir {
+        Some(dir) => YachtSQLEngine::open(dir)
+            .await
+            .with_context(|| format!("Failed to open database at {}", dir.display()))?,
+        None => YachtSQLEngine::with_shared_catalog(),
+    };
+    let mut server = FlightSqlServer::new(engine);
+    if let Some(project) = cli.project {
+        server = server.with_default_project(project);
+    }
+    let address = format!("{}:{}", cli.host, cli.port);
+    let listener = TcpListener::bind(&address)
+        .await
+        .with_context(|| format!("Failed to listen on {address}"))?;
+    println!("Flight SQL listening on grpc://{address}");
+    server.serve(listener).await.context("Server failed")
+}
@@ -0,0 +1,534 @@
+#![coverage(off)]
+// `tonic::Status` is the error type of every Flight method, large as it is.
+#![allow(clippy::result_large_err)]
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use arrow_flight::decode::FlightRecordBatchStream;
+use arrow_flight::encode::FlightDataEncoderBuilder;
+use arrow_flight::error::FlightError;
+use arrow_flight::flight_service_server::{FlightService, FlightServiceServer};
+use arrow_flight::sql::server::{FlightSqlService, PeekableFlightDataStream};
+use arrow_flight::sql::{
+    ActionClosePreparedStatementRequest, ActionCreatePreparedStatementRequest,
+    ActionCreatePreparedStatementResult, CommandGetCatalogs, CommandGetDbSchemas, CommandGetTables,
+    CommandPreparedStatementQuery, CommandPreparedStatementUpdate, CommandStatementQuery,
+    CommandStatementUpdate, DoPutPreparedStatementResult, ProstMessageExt, SqlInfo,
+    TicketStatementQuery,
+};
+use arrow_flight::{Action, FlightDescriptor, FlightEndpoint, FlightInfo, Ticket};
+use futures::stream::{self, BoxStream};
+use futures::{StreamExt, TryStreamExt};
+use prost::Message;
+use tokio::net::TcpListener;
+use tonic::transport::Server;
+use tonic::transport::server::TcpIncoming;
+use tonic::{Request, Response, Status};
+use yachtsql::arrow::datatypes::Schema as ArrowSchema;
+use yachtsql::arrow::record_batch::RecordBatch;
+use yachtsql::{
+    Error, JobError, PreparedStatement, QueryParameter, QueryStream, Schema, Table, YachtSQLEngine,
+    YachtSQLSession,
+};
+
+type DoGetStream = <FlightSqlServer as FlightService>::DoGetStream;
+
+/// How long a query's results wait for their `DoGet` by default.
+const DEFAULT_RESULT_TTL: Duration = Duration::from_secs(10 * 60);
+/// Most results kept waiting for a `DoGet`; the oldest are dropped beyond it.
+const MAX_PENDING_RESULTS: usize = 256;
+
+/// An Arrow Flight SQL front-end for an engine, for clients such as ADBC and
+/// JDBC drivers, DBeaver or `pyarrow.flight`.
+///
+/// Supported commands:
+///
+/// - statements: `CommandStatementQuery`, `CommandStatementUpdate`
+/// - prepared statements: `CreatePreparedStatement`, `ClosePreparedStatement`,
+///   `CommandPreparedStatementQuery` (with parameters bound through `DoPut`),
+///   `CommandPreparedStatementUpdate`
+/// - metadata: `CommandGetCatalogs`, `CommandGetDbSchemas`, `CommandGetTables`
+///
+/// Catalogs are the catalog's projects and database schemas their datasets, so
+/// only tables in a project are listed; tables named without one belong to the
+/// server's [default project](Self::with_default_project).
+///
+/// A query runs when its `GetFlightInfo` is answered, and its batches are
+/// produced as the client reads them with the single `DoGet` ticket in the
+/// returned endpoint. Results that are not fetched within the
+/// [result TTL](Self::with_result_ttl), or that are the oldest of more than
+/// 256 waiting, are dropped and their queries stopped. Each request runs in a
+/// session of its own, so the engine should share its catalog between sessions
+/// (see [`YachtSQLEngine::with_shared_catalog`]).
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let server = FlightSqlServer::new(YachtSQLEngine::with_shared_catalog())
+///     .with_default_project("my-project");
+/// let listener = tokio::net::TcpListener::bind("127.0.0.1:50051").await?;
+/// server.serve(listener).await?;
+/// ```
+#[derive(Clone)]
+pub struct FlightSqlServer {
+    state: Arc<FlightState>,
+    default_project: Option<String>,
+    result_ttl: Duration,
+}
+
+struct FlightState {
+    engine: YachtSQLEngine,
+    /// Queries whose results have not been fetched yet, by ticket handle, with
+    /// the time they were started.
+    results: Mutex<HashMap<String, (Instant, PendingResult)>>,
+    prepared: Mutex<HashMap<String, PreparedQuery>>,
+    next_handle: AtomicU64,
+}
+
+/// The result of a query, waiting for its `DoGet`.
+enum PendingResult {
+    Stream(QueryStream),
+    Table(Table),
+}
+
+/// A prepared statement and the parameter rows last bound to it.
+struct PreparedQuery {
+    statement: Arc<PreparedStatement>,
+    parameters: Vec<Vec<QueryParameter>>,
+}
+
+impl FlightSqlServer {
+    pub fn new(engine: YachtSQLEngine) -> Self {
+        Self {
+            state: Arc::new(FlightState {
+                engine,
+                results: Mutex::new(HashMap::new()),
+                prepared: Mutex::new(HashMap::new()),
+                next_handle: AtomicU64::new(1),
+            }),
+            default_project: None,
+            result_ttl: DEFAULT_RESULT_TTL,
+        }
+    }
+
+    /// Sets the project that tables named `dataset.table` belong to.
+    pub fn with_default_project(mut self, project: impl Into<String>) -> Self {
+        self.default_project = Some(project.into());
+        self
+    }
+
+    /// Sets how long a query's results wait for their `DoGet` before they are
+    /// dropped; ten minutes by default.
+    pub fn with_result_ttl(mut self, ttl: Duration) -> Self {
+        self.result_ttl = ttl;
+        self
+    }
+
+    pub fn engine(&self) -> &YachtSQLEngine {
+        &self.state.engine
+    }
+
+    /// The Flight service, for adding to a [`tonic`] server of the caller's own.
+    pub fn service(&self) -> FlightServiceServer<Self> {
+        FlightServiceServer::new(self.clone())
+    }
+
+    /// Serves Flight SQL on `listener` until the process exits.
+    pub async fn serve(self, listener: TcpListener) -> std::io::Result<()> {
+        let incoming =
+            TcpIncoming::from_listener(listener, true, None).map_err(std::io::Error::other)?;
+        Server::builder()
+            .add_service(self.service())
+            .serve_with_incoming(incoming)
+            .await
+            .map_err(std::io::Error::other)
+    }
+
+    fn session(&self) -> YachtSQLSession {
+        let session = self.state.engine.create_session();
+        if let Some(project) = &self.default_project {
+            session.set_default_project(Some(project.clone()));
+        }
+        session
+    }
+
+    fn next_handle(&self) -> String {
+        self.state
+            .next_handle
+            .fetch_add(1, Ordering::Relaxed)
+            .to_string()
+    }
+
+    /// Keeps `results` for the `DoGet` of the returned flight, dropping expired
+    /// results and, past the limit, the oldest.
+    fn result_flight(
+        &self,
+        results: PendingResult,
+        descriptor: FlightDescriptor,
+    ) -> Result<Response<FlightInfo>, Status> {
+        let schema = results.schema().to_arrow_schema();
+        let handle = self.next_handle();
+        let now = Instant::now();
+        let mut pending = lock(&self.state.results);
+        pending.retain(|_, (started, _)| now.duration_since(*started) < self.result_ttl);
+        while pending.len() >= MAX_PENDING_RESULTS {
+            let Some(oldest) = pending
+                .iter()
+                .min_by_key(|(_, (started, _))| *started)
+                .map(|(handle, _)| handle.clone())
+            else {
+                break;
+            };
+            pending.remove(&oldest);
+        }
+        pending.insert(handle.clone(), (now, results));
+        drop(pending);
+        let ticket = TicketStatementQuery {
+            statement_handle: handle.into(),
+        };
+        flight_info(&ticket, &schema, descriptor)
+    }
+
+    fn prepared_statement(&self, handle: &[u8]) -> Result<Arc<PreparedStatement>, Status> {
+        lock(&self.state.prepared)
+            .get(&handle_str(handle)?)
+            .map(|prepared| Arc::clone(&prepared.statement))
+            .ok_or_else(|| Status::not_found("Unknown prepared statement"))
+    }
+}
+
+#[tonic::async_trait]
+impl FlightSqlService for FlightSqlServer {
+    type FlightService = Self;
+
+    async fn get_flight_info_statement(
+        &self,
+        query: CommandStatementQuery,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        let results = self
+            .session()
+            .query_stream(&query.query)
+            .await
+            .map_err(status)?;
+        self.result_flight(PendingResult::Stream(results), request.into_inner())
+    }
+
+    async fn get_flight_info_prepared_statement(
+        &self,
+        query: CommandPreparedStatementQuery,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        let handle = handle_str(&query.prepared_statement_handle)?;
+        let (statement, parameters) = {
+            let prepared = lock(&self.state.prepared);
+            let prepared = prepared
+                .get(&handle)
+                .ok_or_else(|| Status::not_found("Unknown prepared statement"))?;
+            let parameters = match prepared.parameters.as_slice() {
+                [] => Vec::new(),
+                [parameters] => parameters.clone(),
+                _ => {
+                    return Err(Status::invalid_argument(
+                        "A prepared query can only be bound to one row of parameters",
+                    ));
+                }
+            };
+            (Arc::clone(&prepared.statement), parameters)
+        };
+        let table = statement.execute(&parameters).await.map_err(status)?;
+        self.result_flight(PendingResult::Table(table), request.into_inner())
+    }
+
+    async fn get_flight_info_catalogs(
+        &self,
+        query: CommandGetCatalogs,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        let schema = query.into_builder().schema();
+        flight_info(&query, &schema, request.into_inner())
+    }
+
+    async fn get_flight_info_schemas(
+        &self,
+        query: CommandGetDbSchemas,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        let schema = query.clone().into_builder().schema();
+        flight_info(&query, &schema, request.into_inner())
+    }
+
+    async fn get_flight_info_tables(
+        &self,
+        query: CommandGetTables,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        let schema = query.clone().into_builder().schema();
+        flight_info(&query, &schema, request.into_inner())
+    }
+
+    async fn do_get_statement(
+        &self,
+        ticket: TicketStatementQuery,
+        _request: Request<Ticket>,
+    ) -> Result<Response<DoGetStream>, Status> {
+        let handle = handle_str(&ticket.statement_handle)?;
+        let (_, results) = lock(&self.state.results)
+            .remove(&handle)
+            .filter(|(started, _)| started.elapsed() < self.result_ttl)
+            .ok_or_else(|| Status::not_found("Unknown, expired or already fetched ticket"))?;
+        let schema = Arc::new(results.schema().to_arrow_schema());
+        let batches = results.into_stream().map(|batch| {
+            batch
+                .and_then(|table| table.to_record_batch())
+                .map_err(|e| FlightError::from(status(e)))
+        });
+        let stream = FlightDataEncoderBuilder::new()
+            .with_schema(schema)
+            .build(batches)
+            .map_err(Status::from);
+        Ok(Response::new(Box::pin(stream)))
+    }
+
+    async fn do_get_catalogs(
+        &self,
+        query: CommandGetCatalogs,
+        _request: Request<Ticket>,
+    ) -> Result<Response<DoGetStream>, Status> {
+        let mut builder = query.into_builder();
+        for project in sorted(self.session().get_projects()) {
+            builder.append(project);
+        }
+        let schema = builder.schema();
+        batch_response(builder.build(), schema)
+    }
+
+    async fn do_get_schemas(
+        &self,
+        query: CommandGetDbSchemas,
+        _request: Request<Ticket>,
+    ) -> Result<Response<DoGetStream>, Status> {
+        let session = self.session();
+        let mut builder = query.into_builder();
+        for project in sorted(session.get_projects()) {
+            for dataset in sorted(session.get_datasets(&project)) {
+                builder.append(&project, dataset);
+            }
+        }
+        let schema = builder.schema();
+        batch_response(builder.build(), schema)
+    }
+
+    async fn do_get_tables(
+        &self,
+        query: CommandGetTables,
+        _request: Request<Ticket>,
+    ) -> Result<Response<DoGetStream>, Status> {
+        let session = self.session();
+        let mut builder = query.into_builder();
+        for project in sorted(session.get_projects()) {
+            for dataset in sorted(session.get_datasets(&project)) {
+                for table in sorted(session.get_tables_in_dataset(&project, &dataset)) {
+                    let schema = session
+                        .catalog()
+                        .get_table_schema(&format!("{project}.{dataset}.{table}"))
+                        .map(|schema| schema.to_arrow_schema())
+                        .unwrap_or_else(ArrowSchema::empty);
+                    builder
+                        .append(&project, &dataset, table, "TABLE", &schema)
+                        .map_err(|e| Status::internal(e.to_string()))?;
+                }
+            }
+        }
+        let schema = builder.schema();
+        batch_response(builder.build(), schema)
+    }
+
+    async fn do_put_statement_update(
+        &self,
+        ticket: CommandStatementUpdate,
+        _request: Request<PeekableFlightDataStream>,
+    ) -> Result<i64, Status> {
+        let rows = self.session().run(&ticket.query).await.map_err(status)?;
+        Ok(rows as i64)
+    }
+
+    async fn do_put_prepared_statement_query(
+        &self,
+        query: CommandPreparedStatementQuery,
+        request: Request<PeekableFlightDataStream>,
+    ) -> Result<DoPutPreparedStatementResult, Status> {
+        let statement = self.prepared_statement(&query.prepared_statement_handle)?;
+        let parameters = parameter_rows(&statement, request.into_inner()).await?;
+        let handle = handle_str(&query.prepared_statement_handle)?;
+        if let Some(prepared) = lock(&self.state.prepared).get_mut(&handle) {
+            prepared.parameters = parameters;
+        }
+        Ok(DoPutPreparedStatementResult {
+            prepared_statement_handle: Some(query.prepared_statement_handle),
+        })
+    }
+
+    /// Runs the statement once per bound parameter row and returns the total
+    /// row count.
+    async fn do_put_prepared_statement_update(
+        &self,
+        query: CommandPreparedStatementUpdate,
+        request: Request<PeekableFlightDataStream>,
+    ) -> Result<i64, Status> {
+        let statement = self.prepared_statement(&query.prepared_statement_handle)?;
+        let mut parameters = parameter_rows(&statement, request.into_inner()).await?;
+        if parameters.is_empty() {
+            parameters.push(Vec::new());
+        }
+        let mut rows = 0;
+        for parameters in &parameters {
+            let table = statement.execute(parameters).await.map_err(status)?;
+            rows += table.row_count() as i64;
+        }
+        Ok(rows)
+    }
+
+    async fn do_action_create_prepared_statement(
+        &self,
+        query: ActionCreatePreparedStatementRequest,
+        _request: Request<Action>,
+    ) -> Result<ActionCreatePreparedStatementResult, Status> {
+        let statement = self.session().prepare(&query.query).map_err(status)?;
+        let handle = self.next_handle();
+        lock(&self.state.prepared).insert(
+            handle.clone(),
+            PreparedQuery {
+                statement: Arc::new(statement),
+                parameters: Vec::new(),
+            },
+        );
+        Ok(ActionCreatePreparedStatementResult {
+            prepared_statement_handle: handle.into(),
+            ..Default::default()
+        })
+    }
+
+    async fn do_action_close_prepared_statement(
+        &self,
+        query: ActionClosePreparedStatementRequest,
+        _request: Request<Action>,
+    ) -> Result<(), Status> {
+        let handle = handle_str(&query.prepared_statement_handle)?;
+        lock(&self.state.prepared).remove(&handle);
+        Ok(())
+    }
+
+    async fn register_sql_info(&self, _id: i32, _result: &SqlInfo) {}
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn sorted(mut names: Vec<String>) -> Vec<String> {
+    names.sort();
+    names
+}
+
+fn handle_str(handle: &[u8]) -> Result<String, Status> {
+    String::from_utf8(handle.to_vec()).map_err(|_| Status::invalid_argument("Invalid handle"))
+}
+
+/// Maps an engine error to the gRPC status a client sees.
+fn status(error: Error) -> Status {
+    let error = JobError::from_error(&error);
+    match error.reason.as_str() {
+        "notFound" => Status::not_found(error.message),
+        "stopped" => Status::cancelled(error.message),
+        "resourcesExceeded" => Status::resource_exhausted(error.message),
+        "internalError" => Status::internal(error.message),
+        _ => Status::invalid_argument(error.message),
+    }
+}
+
+/// A flight with one endpoint whose ticket is `command`.
+fn flight_info(
+    command: &impl ProstMessageExt,
+    schema: &ArrowSchema,
+    descriptor: FlightDescriptor,
+) -> Result<Response<FlightInfo>, Status> {
+    let ticket = Ticket::new(command.as_any().encode_to_vec());
+    let info = FlightInfo::new()
+        .try_with_schema(schema)
+        .map_err(|e| Status::internal(e.to_string()))?
+        .with_endpoint(FlightEndpoint::new().with_ticket(ticket))
+        .with_descriptor(descriptor);
+    Ok(Response::new(info))
+}
+
+fn batch_response(
+    batch: Result<RecordBatch, FlightError>,
+    schema: Arc<ArrowSchema>,
+) -> Result<Response<DoGetStream>, Status> {
+    let batch = batch.map_err(Status::from)?;
+    let stream = FlightDataEncoderBuilder::new()
+        .with_schema(schema)
+        .build(stream::once(async move { Ok(batch) }))
+        .map_err(Status::from);
+    Ok(Response::new(Box::pin(stream)))
+}
+
+impl PendingResult {
+    fn schema(&self) -> &Schema {
+        match self {
+            PendingResult::Stream(stream) => stream.schema(),
+            PendingResult::Table(table) => table.schema(),
+        }
+    }
+
+    fn into_stream(self) -> BoxStream<'static, yachtsql::Result<Table>> {
+        match self {
+            PendingResult::Stream(stream) => stream.boxed(),
+            PendingResult::Table(table) => stream::once(async move { Ok(table) }).boxed(),
+        }
+    }
+}
+
+/// The parameter rows sent with a `DoPut` on a prepared statement. Columns
+/// named like one of the statement's `@name` parameters bind to it; the others
+/// bind to its `?` markers in order.
+async fn parameter_rows(
+    statement: &PreparedStatement,
+    data: PeekableFlightDataStream,
+) -> Result<Vec<Vec<QueryParameter>>, Status> {
+    let batches: Vec<RecordBatch> =
+        FlightRecordBatchStream::new_from_flight_data(data.map_err(FlightError::from))
+            .try_collect()
+            .await
+            .map_err(Status::from)?;
+
+    let mut rows = Vec::new();
+    for batch in &batches {
+        let table = Table::from_record_batch(batch).map_err(status)?;
+        let fields = table.schema().fields().to_vec();
+        for row in table.to_query_result().map_err(status)?.rows {
+            let parameters = fields
+                .iter()
+                .zip(row.values())
+                .map(|(field, value)| {
+                    let name = field.name.trim_start_matches('@');
+                    let parameter = match statement
+                        .parameter_names()
+                        .iter()
+                        .find(|named| named.eq_ignore_ascii_case(name))
+                    {
+                        Some(named) => QueryParameter::named(named, value.clone()),
+                        None => QueryParameter::positional(value.clone()),
+                    };
+                    parameter.with_type(field.data_type.clone())
+                })
+                .collect();
+            rows.push(parameters);
+        }
+    }
+    Ok(rows)
+}
@@ -0,0 +1,17 @@
+#![feature(coverage_attribute)]
+#![coverage(off)]
+
+//! Network front-ends for YachtSQL.
+//!
+//! [`BigQueryServer`] emulates the BigQuery v2 REST API, so applications using
+//! the official BigQuery client libraries can run against an in-memory engine.
+//! With the `flight-sql` feature, [`FlightSqlServer`] serves the engine to
+//! Arrow Flight SQL clients.
+
+mod bigquery;
+#[cfg(feature = "flight-sql")]
+mod flight_sql;
+
+pub use bigquery::BigQueryServer;
+#[cfg(feature = "flight-sql")]
+pub use flight_sql::FlightSqlServer;
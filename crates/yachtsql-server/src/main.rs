@@ -0,0 +1,42 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use tokio::net::TcpListener;
+use yachtsql::YachtSQLEngine;
+use yachtsql_server::BigQueryServer;
+
+#[derive(Parser)]
+#[command(name = "yachtsql-bigquery-server")]
+#[command(about = "Serve the BigQuery v2 REST API from an in-memory YachtSQL engine", long_about = None)]
+struct Cli {
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1")]
+    host: String,
+    #[arg(long, default_value_t = 9050)]
+    port: u16,
+    /// Persist tables in this directory instead of discarding them on exit
+    #[arg(long)]
+    data_dir: Option<PathBuf>,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    let engine = match &cli.data_dir {
+        Some(dir) => YachtSQLEngine::open(dir)
+            .await
+            .with_context(|| format!("Failed to open database at {}", dir.display()))?,
+        None => YachtSQLEngine::with_shared_catalog(),
+    };
+    let address = format!("{}:{}", cli.host, cli.port);
+    let listener = TcpListener::bind(&address)
+        .await
+        .with_context(|| format!("Failed to listen on {address}"))?;
+    println!("BigQuery API listening on http://{address}/bigquery/v2");
+    BigQueryServer::new(engine)
+        .serve(listener)
+        .await
+        .context("Server failed")
+}
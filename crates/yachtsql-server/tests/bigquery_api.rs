@@ -0,0 +1,555 @@
+use std::time::Duration;
+
+use axum::Router;
+use axum::body::Body;
+use axum::http::{Method, Request, StatusCode};
+use http_body_util::BodyExt;
+use serde_json::{Value, json};
+use tower::ServiceExt;
+use yachtsql::YachtSQLEngine;
+use yachtsql_server::BigQueryServer;
+
+const PROJECT: &str = "/bigquery/v2/projects/test-project";
+
+struct Api {
+    router: Router,
+}
+
+impl Api {
+    fn new() -> Self {
+        Self::serving(BigQueryServer::new(YachtSQLEngine::with_shared_catalog()))
+    }
+
+    fn serving(server: BigQueryServer) -> Self {
+        Self {
+            router: server.router(),
+        }
+    }
+
+    async fn call(&self, method: Method, path: &str, body: Option<Value>) -> (StatusCode, Value) {
+        let request = Request::builder()
+            .method(method)
+            .uri(format!("{PROJECT}{path}"))
+            .header("content-type", "application/json")
+            .body(match body {
+                Some(body) => Body::from(body.to_string()),
+                None => Body::empty(),
+            })
+            .unwrap();
+        let response = self.router.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        let body = if bytes.is_empty() {
+            Value::Null
+        } else {
+            serde_json::from_slice(&bytes).unwrap()
+        };
+        (status, body)
+    }
+
+    async fn get(&self, path: &str) -> (StatusCode, Value) {
+        self.call(Method::GET, path, None).await
+    }
+
+    async fn post(&self, path: &str, body: Value) -> (StatusCode, Value) {
+        self.call(Method::POST, path, Some(body)).await
+    }
+
+    async fn query(&self, sql: &str) -> Value {
+        let (status, body) = self
+            .post("/queries", json!({"query": sql, "useLegacySql": false}))
+            .await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+        body
+    }
+
+    async fn create_orders(&self) {
+        self.post(
+            "/datasets",
+            json!({"datasetReference": {"projectId": "test-project", "datasetId": "sales"}}),
+        )
+        .await;
+        let (status, body) = self
+            .post(
+                "/datasets/sales/tables",
+                json!({
+                    "tableReference": {"tableId": "orders"},
+                    "schema": {"fields": [
+                        {"name": "id", "type": "INTEGER", "mode": "REQUIRED"},
+                        {"name": "item", "type": "STRING"},
+                        {"name": "price", "type": "NUMERIC"},
+                        {"name": "tags", "type": "STRING", "mode": "REPEATED"},
+                        {"name": "placed_at", "type": "TIMESTAMP"},
+                    ]},
+                }),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+    }
+}
+
+fn cells(row: &Value) -> Vec<Value> {
+    row["f"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|cell| cell["v"].clone())
+        .collect()
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_query_returns_schema_and_rows_in_wire_format() {
+    let api = Api::new();
+    let body = api
+        .query(
+            "SELECT 1 AS n, 2.5 AS f, TRUE AS b, b'hi' AS bytes,
+                    TIMESTAMP '2024-01-02 03:04:05.5 UTC' AS ts,
+                    [1, 2] AS arr, STRUCT('x' AS a, 3 AS b) AS s, CAST(NULL AS STRING) AS nothing",
+        )
+        .await;
+
+    assert_eq!(body["kind"], "bigquery#queryResponse");
+    assert_eq!(body["jobComplete"], true);
+    assert_eq!(body["totalRows"], "1");
+    let types: Vec<&str> = body["schema"]["fields"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|field| field["type"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        types,
+        [
+            "INTEGER",
+            "FLOAT",
+            "BOOLEAN",
+            "BYTES",
+            "TIMESTAMP",
+            "INTEGER",
+            "RECORD",
+            "STRING"
+        ]
+    );
+    assert_eq!(body["schema"]["fields"][5]["mode"], "REPEATED");
+    assert_eq!(
+        cells(&body["rows"][0]),
+        vec![
+            json!("1"),
+            json!("2.5"),
+            json!("true"),
+            json!("aGk="),
+            json!("1704164645.500000"),
+            json!([{"v": "1"}, {"v": "2"}]),
+            json!({"f": [{"v": "x"}, {"v": "3"}]}),
+            Value::Null,
+        ]
+    );
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_query_pages_through_results() {
+    let api = Api::new();
+    let (_, body) = api
+        .post(
+            "/queries",
+            json!({"query": "SELECT x FROM UNNEST(GENERATE_ARRAY(1, 5)) AS x ORDER BY x", "maxResults": 2}),
+        )
+        .await;
+    assert_eq!(body["totalRows"], "5");
+    assert_eq!(body["rows"].as_array().unwrap().len(), 2);
+    assert_eq!(body["pageToken"], "2");
+    let job_id = body["jobReference"]["jobId"].as_str().unwrap().to_string();
+
+    let (status, page) = api
+        .get(&format!("/queries/{job_id}?maxResults=2&pageToken=2"))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(page["kind"], "bigquery#getQueryResultsResponse");
+    let values: Vec<Value> = page["rows"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| cells(r)[0].clone())
+        .collect();
+    assert_eq!(values, vec![json!("3"), json!("4")]);
+    assert_eq!(page["pageToken"], "4");
+
+    let (_, job) = api.get(&format!("/jobs/{job_id}")).await;
+    assert_eq!(job["status"]["state"], "DONE");
+    assert_eq!(job["statistics"]["query"]["statementType"], "SELECT");
+    let destination = &job["configuration"]["query"]["destinationTable"];
+    let (status, data) = api
+        .get(&format!(
+            "/datasets/{}/tables/{}/data?startIndex=4",
+            destination["datasetId"].as_str().unwrap(),
+            destination["tableId"].as_str().unwrap()
+        ))
+        .await;
+    assert_eq!(status, StatusCode::OK, "{data}");
+    assert_eq!(data["totalRows"], "5");
+    assert_eq!(cells(&data["rows"][0]), vec![json!("5")]);
+    assert!(data.get("pageToken").is_none());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_query_parameters_and_statistics() {
+    let api = Api::new();
+    api.create_orders().await;
+    let body = api
+        .query(
+            "INSERT INTO sales.orders (id, item, price, tags) VALUES
+               (1, 'pen', 1.50, ['office']), (2, 'ink', 12.25, []), (3, 'pad', 4.00, ['office', 'paper'])",
+        )
+        .await;
+    assert_eq!(body["numDmlAffectedRows"], "3");
+    assert!(body.get("schema").is_none());
+
+    let (status, body) = api
+        .post(
+            "/queries",
+            json!({
+                "query": "SELECT item FROM orders WHERE id IN UNNEST(@ids) AND price < @limit.max ORDER BY id",
+                "defaultDataset": {"datasetId": "sales"},
+                "queryParameters": [
+                    {
+                        "name": "ids",
+                        "parameterType": {"type": "ARRAY", "arrayType": {"type": "INT64"}},
+                        "parameterValue": {"arrayValues": [{"value": "1"}, {"value": "2"}, {"value": "3"}]},
+                    },
+                    {
+                        "name": "limit",
+                        "parameterType": {"type": "STRUCT", "structTypes": [
+                            {"name": "max", "type": {"type": "NUMERIC"}},
+                        ]},
+                        "parameterValue": {"structValues": {"max": {"value": "10"}}},
+                    },
+                ],
+            }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let items: Vec<Value> = body["rows"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| cells(r)[0].clone())
+        .collect();
+    assert_eq!(items, vec![json!("pen"), json!("pad")]);
+
+    let (_, body) = api
+        .post(
+            "/queries",
+            json!({
+                "query": "SELECT COUNT(*) FROM `test-project.sales.orders` WHERE id > ?",
+                "queryParameters": [
+                    {"parameterType": {"type": "INT64"}, "parameterValue": {"value": "1"}},
+                ],
+            }),
+        )
+        .await;
+    assert_eq!(cells(&body["rows"][0]), vec![json!("2")]);
+    assert_ne!(body["totalBytesProcessed"], "0");
+
+    let (status, body) = api
+        .post(
+            "/queries",
+            json!({"query": "SELECT item FROM sales.orders", "dryRun": true}),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_ne!(body["totalBytesProcessed"], "0");
+    assert!(body["jobReference"].get("jobId").is_none());
+    assert!(body.get("rows").is_none());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_query_errors() {
+    let api = Api::new();
+    let (status, body) = api
+        .post("/queries", json!({"query": "SELECT * FROM missing_table"}))
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["error"]["code"], 404);
+    assert_eq!(body["error"]["errors"][0]["reason"], "notFound");
+    assert!(
+        body["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("missing_table")
+    );
+
+    let (status, body) = api.post("/queries", json!({"query": "SELEC 1"})).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"]["errors"][0]["reason"], "invalidQuery");
+
+    let (status, _) = api
+        .post(
+            "/queries",
+            json!({"query": "SELECT 1", "useLegacySql": true}),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_jobs_insert_and_get() {
+    let api = Api::new();
+    let (status, job) = api
+        .post(
+            "/jobs",
+            json!({
+                "jobReference": {"projectId": "test-project", "jobId": "my_job"},
+                "configuration": {"query": {"query": "SELECT 'a' AS letter", "useLegacySql": false}},
+            }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{job}");
+    assert_eq!(job["jobReference"]["jobId"], "my_job");
+    assert_eq!(job["status"]["state"], "DONE");
+    assert!(job["status"].get("errorResult").is_none());
+
+    let (_, results) = api.get("/queries/my_job").await;
+    assert_eq!(cells(&results["rows"][0]), vec![json!("a")]);
+
+    let (status, body) = api
+        .post(
+            "/jobs",
+            json!({
+                "jobReference": {"jobId": "my_job"},
+                "configuration": {"query": {"query": "SELECT 1"}},
+            }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["error"]["errors"][0]["reason"], "duplicate");
+
+    let (status, job) = api
+        .post(
+            "/jobs",
+            json!({"configuration": {"query": {"query": "SELECT * FROM nowhere"}}}),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(job["status"]["errorResult"]["reason"], "notFound");
+    let job_id = job["jobReference"]["jobId"].as_str().unwrap();
+    let (status, _) = api.get(&format!("/queries/{job_id}")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, body) = api.get("/jobs/unknown").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["error"]["status"], "NOT_FOUND");
+
+    let (status, body) = api
+        .post(
+            "/jobs",
+            json!({"configuration": {"load": {"sourceUris": ["gs://bucket/file.csv"]}}}),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{body}");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_concurrent_jobs_with_the_same_id_conflict() {
+    let api = Api::new();
+    let job = json!({
+        "jobReference": {"jobId": "shared_id"},
+        "configuration": {"query": {"query": "SELECT 1", "useLegacySql": false}},
+    });
+
+    let ((first, _), (second, _)) =
+        tokio::join!(api.post("/jobs", job.clone()), api.post("/jobs", job));
+
+    let mut statuses = [first, second];
+    statuses.sort();
+    assert_eq!(statuses, [StatusCode::OK, StatusCode::CONFLICT]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_jobs_expire_after_their_ttl() {
+    let server =
+        BigQueryServer::new(YachtSQLEngine::with_shared_catalog()).with_job_ttl(Duration::ZERO);
+    let api = Api::serving(server);
+    let (status, _) = api
+        .post(
+            "/jobs",
+            json!({
+                "jobReference": {"jobId": "short_lived"},
+                "configuration": {"query": {"query": "SELECT 1", "useLegacySql": false}},
+            }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = api.get("/jobs/short_lived").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = api.get("/queries/short_lived").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_dataset_lifecycle() {
+    let api = Api::new();
+    let (status, dataset) = api
+        .post(
+            "/datasets",
+            json!({
+                "datasetReference": {"projectId": "test-project", "datasetId": "analytics"},
+                "description": "Reporting tables",
+                "labels": {"team": "data"},
+            }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{dataset}");
+    assert_eq!(dataset["id"], "test-project:analytics");
+    assert_eq!(dataset["description"], "Reporting tables");
+
+    let (status, _) = api
+        .post(
+            "/datasets",
+            json!({"datasetReference": {"datasetId": "analytics"}}),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    api.query("CREATE SCHEMA staging").await;
+    let (_, list) = api.get("/datasets").await;
+    let names: Vec<&str> = list["datasets"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|d| d["datasetReference"]["datasetId"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["STAGING", "analytics"]);
+
+    let (status, dataset) = api
+        .call(
+            Method::PATCH,
+            "/datasets/analytics",
+            Some(json!({"description": "Dashboards", "labels": {"team": null, "tier": "gold"}})),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(dataset["description"], "Dashboards");
+    assert_eq!(dataset["labels"], json!({"tier": "gold"}));
+
+    api.query("CREATE TABLE analytics.visits (id INT64)").await;
+    let (status, body) = api.call(Method::DELETE, "/datasets/analytics", None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"]["errors"][0]["reason"], "resourceInUse");
+
+    let (status, _) = api
+        .call(
+            Method::DELETE,
+            "/datasets/analytics?deleteContents=true",
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, _) = api.get("/datasets/analytics").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = api
+        .post(
+            "/queries",
+            json!({"query": "SELECT * FROM analytics.visits"}),
+        )
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_tables_and_tabledata() {
+    let api = Api::new();
+    api.create_orders().await;
+
+    let (status, body) = api
+        .post(
+            "/datasets/sales/tables",
+            json!({"tableReference": {"tableId": "orders"}, "schema": {"fields": []}}),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CONFLICT, "{body}");
+    let (status, _) = api
+        .post(
+            "/datasets/nowhere/tables",
+            json!({"tableReference": {"tableId": "t"}}),
+        )
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let rows = json!({"rows": [
+        {"insertId": "a", "json": {"id": 1, "item": "pen", "price": "1.50", "tags": ["office"], "placed_at": "2024-01-02 03:04:05 UTC"}},
+        {"json": {"item": "nameless"}},
+        {"json": {"id": "3", "item": "pad", "placed_at": 1704164645.25}},
+    ]});
+    let (status, body) = api
+        .post("/datasets/sales/tables/orders/insertAll", rows.clone())
+        .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let errors = body["insertErrors"].as_array().unwrap();
+    assert_eq!(errors.len(), 3);
+    assert_eq!(errors[1]["index"], 1);
+    assert_eq!(errors[1]["errors"][0]["reason"], "invalid");
+    assert_eq!(errors[1]["errors"][0]["location"], "id");
+    assert_eq!(errors[0]["errors"][0]["reason"], "stopped");
+
+    let mut skipping = rows;
+    skipping["skipInvalidRows"] = true.into();
+    let (_, body) = api
+        .post("/datasets/sales/tables/orders/insertAll", skipping)
+        .await;
+    assert_eq!(body["insertErrors"].as_array().unwrap().len(), 1);
+
+    let (status, body) = api
+        .post(
+            "/datasets/sales/tables/orders/insertAll",
+            json!({"rows": [{"json": {"id": 4, "colour": "red"}}], "ignoreUnknownValues": true}),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.get("insertErrors").is_none());
+
+    let (_, table) = api.get("/datasets/sales/tables/orders").await;
+    assert_eq!(table["type"], "TABLE");
+    assert_eq!(table["numRows"], "3");
+    assert_eq!(table["schema"]["fields"][0]["name"], "id");
+    assert_eq!(table["schema"]["fields"][0]["mode"], "REQUIRED");
+
+    let (_, data) = api
+        .get("/datasets/sales/tables/orders/data?maxResults=2&formatOptions.useInt64Timestamp=true")
+        .await;
+    assert_eq!(data["kind"], "bigquery#tableDataList");
+    assert_eq!(data["totalRows"], "3");
+    assert_eq!(data["pageToken"], "2");
+    assert_eq!(
+        cells(&data["rows"][0]),
+        vec![
+            json!("1"),
+            json!("pen"),
+            json!("1.50"),
+            json!([{"v": "office"}]),
+            json!("1704164645000000"),
+        ]
+    );
+    assert_eq!(cells(&data["rows"][1])[4], json!("1704164645250000"));
+
+    api.post(
+        "/datasets/sales/tables",
+        json!({"tableReference": {"tableId": "cheap"}, "view": {"query": "SELECT * FROM sales.orders WHERE price < 2"}}),
+    )
+    .await;
+    let (_, view) = api.get("/datasets/sales/tables/cheap").await;
+    assert_eq!(view["type"], "VIEW");
+    let (_, list) = api.get("/datasets/sales/tables").await;
+    assert_eq!(list["tables"][0]["tableReference"]["tableId"], "orders");
+
+    let (status, _) = api
+        .call(Method::DELETE, "/datasets/sales/tables/orders", None)
+        .await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, body) = api.get("/datasets/sales/tables/orders").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(
+        body["error"]["message"],
+        "Not found: Table test-project:sales.orders"
+    );
+}
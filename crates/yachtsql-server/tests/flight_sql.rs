@@ -0,0 +1,199 @@
+#![cfg(feature = "flight-sql")]
+
+use std::time::Duration;
+
+use arrow_flight::decode::FlightRecordBatchStream;
+use arrow_flight::error::FlightError;
+use arrow_flight::flight_service_server::FlightService;
+use arrow_flight::sql::server::FlightSqlService;
+use arrow_flight::sql::{
+    ActionClosePreparedStatementRequest, ActionCreatePreparedStatementRequest, CommandGetCatalogs,
+    CommandGetDbSchemas, CommandGetTables, CommandPreparedStatementQuery, CommandStatementQuery,
+    ProstMessageExt,
+};
+use arrow_flight::{Action, FlightDescriptor};
+use futures::TryStreamExt;
+use prost::Message;
+use tonic::Request;
+use yachtsql::arrow::array::{AsArray, RecordBatch};
+use yachtsql::arrow::datatypes::Int64Type;
+use yachtsql::{Table, YachtSQLEngine};
+use yachtsql_server::FlightSqlServer;
+
+fn create_server() -> FlightSqlServer {
+    FlightSqlServer::new(YachtSQLEngine::with_shared_catalog()).with_default_project("test-project")
+}
+
+/// Runs `command` the way a client does: `GetFlightInfo`, then `DoGet` on the
+/// ticket of its endpoint.
+async fn fetch(server: &FlightSqlServer, command: &impl ProstMessageExt) -> Vec<RecordBatch> {
+    let descriptor = FlightDescriptor::new_cmd(command.as_any().encode_to_vec());
+    let info = server
+        .get_flight_info(Request::new(descriptor))
+        .await
+        .unwrap()
+        .into_inner();
+    let ticket = info.endpoint[0].ticket.clone().unwrap();
+    let data = server
+        .do_get(Request::new(ticket))
+        .await
+        .unwrap()
+        .into_inner();
+    FlightRecordBatchStream::new_from_flight_data(data.map_err(FlightError::from))
+        .try_collect()
+        .await
+        .unwrap()
+}
+
+async fn sql(server: &FlightSqlServer, query: &str) -> Vec<RecordBatch> {
+    let command = CommandStatementQuery {
+        query: query.to_string(),
+        transaction_id: None,
+    };
+    fetch(server, &command).await
+}
+
+fn strings(batches: &[RecordBatch], column: &str) -> Vec<String> {
+    batches
+        .iter()
+        .flat_map(|batch| {
+            batch
+                .column_by_name(column)
+                .unwrap()
+                .as_string::<i32>()
+                .iter()
+                .map(|value| value.unwrap().to_string())
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_statement_query_streams_record_batches() {
+    let server = create_server();
+    sql(&server, "CREATE TABLE sales.orders (id INT64, item STRING)").await;
+    sql(
+        &server,
+        "INSERT INTO sales.orders VALUES (1, 'apple'), (2, 'pear'), (3, 'plum')",
+    )
+    .await;
+
+    let batches = sql(&server, "SELECT id, item FROM sales.orders ORDER BY id").await;
+    let table = Table::from_record_batch(&batches[0]).unwrap();
+    assert_eq!(table.row_count(), 3);
+    assert_eq!(strings(&batches, "item"), ["apple", "pear", "plum"]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_statement_query_error_is_reported_as_status() {
+    let server = create_server();
+    let command = CommandStatementQuery {
+        query: "SELECT * FROM missing.table_name".to_string(),
+        transaction_id: None,
+    };
+    let descriptor = FlightDescriptor::new_cmd(command.as_any().encode_to_vec());
+    let error = server
+        .get_flight_info(Request::new(descriptor))
+        .await
+        .unwrap_err();
+    assert_eq!(error.code(), tonic::Code::NotFound);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_unfetched_results_expire() {
+    let server = create_server().with_result_ttl(Duration::ZERO);
+    let command = CommandStatementQuery {
+        query: "SELECT 1 AS n".to_string(),
+        transaction_id: None,
+    };
+    let descriptor = FlightDescriptor::new_cmd(command.as_any().encode_to_vec());
+    let info = server
+        .get_flight_info(Request::new(descriptor))
+        .await
+        .unwrap()
+        .into_inner();
+    let ticket = info.endpoint[0].ticket.clone().unwrap();
+    let error = server.do_get(Request::new(ticket)).await.err().unwrap();
+    assert_eq!(error.code(), tonic::Code::NotFound);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_prepared_statement_query() {
+    let server = create_server();
+    sql(&server, "CREATE TABLE sales.orders (id INT64)").await;
+    sql(&server, "INSERT INTO sales.orders VALUES (1), (2)").await;
+
+    let prepared = server
+        .do_action_create_prepared_statement(
+            ActionCreatePreparedStatementRequest {
+                query: "SELECT COUNT(*) AS n FROM sales.orders".to_string(),
+                transaction_id: None,
+            },
+            Request::new(Action::default()),
+        )
+        .await
+        .unwrap();
+    let command = CommandPreparedStatementQuery {
+        prepared_statement_handle: prepared.prepared_statement_handle.clone(),
+    };
+
+    let batches = fetch(&server, &command).await;
+    assert_eq!(batches[0].column(0).as_primitive::<Int64Type>().value(0), 2);
+
+    sql(&server, "INSERT INTO sales.orders VALUES (3)").await;
+    let batches = fetch(&server, &command).await;
+    assert_eq!(batches[0].column(0).as_primitive::<Int64Type>().value(0), 3);
+
+    server
+        .do_action_close_prepared_statement(
+            ActionClosePreparedStatementRequest {
+                prepared_statement_handle: prepared.prepared_statement_handle,
+            },
+            Request::new(Action::default()),
+        )
+        .await
+        .unwrap();
+    let descriptor = FlightDescriptor::new_cmd(command.as_any().encode_to_vec());
+    assert!(
+        server
+            .get_flight_info(Request::new(descriptor))
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_catalog_metadata() {
+    let server = create_server();
+    sql(&server, "CREATE SCHEMA sales").await;
+    sql(&server, "CREATE SCHEMA hr").await;
+    sql(&server, "CREATE TABLE sales.orders (id INT64)").await;
+    sql(&server, "CREATE TABLE sales.refunds (id INT64)").await;
+
+    let catalogs = fetch(&server, &CommandGetCatalogs {}).await;
+    assert_eq!(strings(&catalogs, "catalog_name"), ["TEST-PROJECT"]);
+
+    let schemas = fetch(
+        &server,
+        &CommandGetDbSchemas {
+            catalog: None,
+            db_schema_filter_pattern: None,
+        },
+    )
+    .await;
+    assert_eq!(strings(&schemas, "db_schema_name"), ["HR", "SALES"]);
+
+    let tables = fetch(
+        &server,
+        &CommandGetTables {
+            catalog: None,
+            db_schema_filter_pattern: Some("SALES".to_string()),
+            table_name_filter_pattern: Some("ORD%".to_string()),
+            table_types: Vec::new(),
+            include_schema: true,
+        },
+    )
+    .await;
+    assert_eq!(strings(&tables, "table_name"), ["ORDERS"]);
+    assert_eq!(strings(&tables, "table_type"), ["TABLE"]);
+}
@@ -0,0 +1,372 @@
+#![coverage(off)]
+
+use serde::{Deserialize, Serialize};
+use yachtsql_common::error::{Error, Result};
+use yachtsql_common::types::{DataType, StructField};
+
+use crate::schema::{Field, FieldMode, Schema};
+
+/// One column in the JSON format of `bq show --schema` and the BigQuery REST API.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BigQueryField {
+    name: String,
+    #[serde(rename = "type")]
+    field_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    mode: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    fields: Vec<BigQueryField>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    precision: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    scale: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    range_element_type: Option<RangeElementType>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RangeElementType {
+    #[serde(rename = "type")]
+    element_type: String,
+}
+
+/// The shapes a schema file comes in: the bare field list written by
+/// `bq show --schema`, `{"fields": [...]}`, or a whole table resource.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SchemaDocument {
+    Fields(Vec<BigQueryField>),
+    Schema { fields: Vec<BigQueryField> },
+    Table { schema: FieldList },
+}
+
+#[derive(Deserialize)]
+struct FieldList {
+    fields: Vec<BigQueryField>,
+}
+
+impl Schema {
+    /// Parses a BigQuery JSON schema, as written by `bq show --schema`.
+    ///
+    /// `RECORD` columns become [`DataType::Struct`] and `REPEATED` columns become
+    /// [`DataType::Array`] fields with [`FieldMode::Repeated`]. Column descriptions
+    /// are kept in [`Field::description`]; descriptions of nested fields are dropped.
+    pub fn from_bigquery_json(json: &str) -> Result<Self> {
+        let document: SchemaDocument = serde_json::from_str(json)
+            .map_err(|e| Error::parse_error(format!("Invalid BigQuery schema: {}", e)))?;
+        let fields = match document {
+            SchemaDocument::Fields(fields) | SchemaDocument::Schema { fields } => fields,
+            SchemaDocument::Table { schema } => schema.fields,
+        };
+        let schema = Schema::from_fields(
+            fields
+                .iter()
+                .map(|field| {
+                    let (data_type, mode) = field_type(field)?;
+                    let mut imported = Field::new(&field.name, data_type, mode);
+                    imported.description = field.description.clone();
+                    Ok(imported)
+                })
+                .collect::<Result<_>>()?,
+        );
+        schema.validate()?;
+        Ok(schema)
+    }
+
+    /// Renders the schema in the JSON format of `bq show --schema`.
+    ///
+    /// Fails for types BigQuery cannot store in a table column, such as an array
+    /// of arrays.
+    pub fn to_bigquery_json(&self) -> Result<String> {
+        let fields = self
+            .fields()
+            .iter()
+            .map(|field| {
+                let mode = match (&field.data_type, field.mode) {
+                    (DataType::Array(_), _) | (_, FieldMode::Repeated) => FieldMode::Repeated,
+                    (_, mode) => mode,
+                };
+                let mut json = bigquery_field(&field.name, &field.data_type, mode)?;
+                json.description = field.description.clone();
+                Ok(json)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        serde_json::to_string_pretty(&fields)
+            .map_err(|e| Error::internal(format!("Failed to serialize schema: {}", e)))
+    }
+}
+
+fn field_type(field: &BigQueryField) -> Result<(DataType, FieldMode)> {
+    let data_type = element_type(field)?;
+    let mode = match field.mode.as_deref().map(str::to_uppercase).as_deref() {
+        None | Some("NULLABLE") => FieldMode::Nullable,
+        Some("REQUIRED") => FieldMode::Required,
+        Some("REPEATED") => FieldMode::Repeated,
+        Some(other) => {
+            return Err(Error::parse_error(format!(
+                "Unknown mode {} for field {}",
+                other, field.name
+            )));
+        }
+    };
+    match mode {
+        FieldMode::Repeated => Ok((DataType::Array(Box::new(data_type)), mode)),
+        _ => Ok((data_type, mode)),
+    }
+}
+
+fn element_type(field: &BigQueryField) -> Result<DataType> {
+    let data_type = match field.field_type.to_uppercase().as_str() {
+        "STRING" => DataType::String,
+        "BYTES" => DataType::Bytes,
+        "INTEGER" | "INT64" => DataType::Int64,
+        "FLOAT" | "FLOAT64" => DataType::Float64,
+        "NUMERIC" => match (&field.precision, &field.scale) {
+            (Some(precision), scale) => DataType::Numeric(Some((
+                type_parameter(field, precision)?,
+                scale.as_ref().map_or(Ok(0), |s| type_parameter(field, s))?,
+            ))),
+            (None, _) => DataType::Numeric(None),
+        },
+        "BIGNUMERIC" => DataType::BigNumeric,
+        "BOOLEAN" | "BOOL" => DataType::Bool,
+        "TIMESTAMP" => DataType::Timestamp,
+        "DATE" => DataType::Date,
+        "TIME" => DataType::Time,
+        "DATETIME" => DataType::DateTime,
+        "GEOGRAPHY" => DataType::Geography,
+        "JSON" => DataType::Json,
+        "INTERVAL" => DataType::Interval,
+        "RECORD" | "STRUCT" => DataType::Struct(
+            field
+                .fields
+                .iter()
+                .map(|nested| {
+                    Ok(StructField {
+                        name: nested.name.clone(),
+                        data_type: field_type(nested)?.0,
+                    })
+                })
+                .collect::<Result<_>>()?,
+        ),
+        "RANGE" => {
+            let element = field.range_element_type.as_ref().ok_or_else(|| {
+                Error::parse_error(format!(
+                    "RANGE field {} is missing rangeElementType",
+                    field.name
+                ))
+            })?;
+            let element = match element.element_type.to_uppercase().as_str() {
+                "DATE" => DataType::Date,
+                "DATETIME" => DataType::DateTime,
+                "TIMESTAMP" => DataType::Timestamp,
+                other => {
+                    return Err(Error::parse_error(format!(
+                        "Unsupported RANGE element type {} for field {}",
+                        other, field.name
+                    )));
+                }
+            };
+            DataType::Range(Box::new(element))
+        }
+        other => {
+            return Err(Error::parse_error(format!(
+                "Unknown type {} for field {}",
+                other, field.name
+            )));
+        }
+    };
+    Ok(data_type)
+}
+
+fn type_parameter(field: &BigQueryField, value: &serde_json::Value) -> Result<u8> {
+    let parsed = match value {
+        serde_json::Value::String(s) => s.parse().ok(),
+        serde_json::Value::Number(n) => n.as_u64().and_then(|n| u8::try_from(n).ok()),
+        _ => None,
+    };
+    parsed.ok_or_else(|| {
+        Error::parse_error(format!(
+            "Invalid precision or scale {} for field {}",
+            value, field.name
+        ))
+    })
+}
+
+fn bigquery_field(name: &str, data_type: &DataType, mode: FieldMode) -> Result<BigQueryField> {
+    let mut field = BigQueryField {
+        name: name.to_string(),
+        field_type: String::new(),
+        mode: Some(
+            match mode {
+                FieldMode::Nullable => "NULLABLE",
+                FieldMode::Required => "REQUIRED",
+                FieldMode::Repeated => "REPEATED",
+            }
+            .to_string(),
+        ),
+        fields: Vec::new(),
+        description: None,
+        precision: None,
+        scale: None,
+        range_element_type: None,
+    };
+    let data_type = match (data_type, mode) {
+        (DataType::Array(inner), FieldMode::Repeated) => inner.as_ref(),
+        (data_type, _) => data_type,
+    };
+    field.field_type = match data_type {
+        DataType::Unknown | DataType::String => "STRING",
+        DataType::Bytes => "BYTES",
+        DataType::Int64 => "INTEGER",
+        DataType::Float64 => "FLOAT",
+        DataType::Numeric(parameters) => {
+            if let Some((precision, scale)) = parameters {
+                field.precision = Some(serde_json::Value::String(precision.to_string()));
+                field.scale = Some(serde_json::Value::String(scale.to_string()));
+            }
+            "NUMERIC"
+        }
+        DataType::BigNumeric => "BIGNUMERIC",
+        DataType::Bool => "BOOLEAN",
+        DataType::Timestamp => "TIMESTAMP",
+        DataType::Date => "DATE",
+        DataType::Time => "TIME",
+        DataType::DateTime => "DATETIME",
+        DataType::Geography => "GEOGRAPHY",
+        DataType::Json => "JSON",
+        DataType::Interval => "INTERVAL",
+        DataType::Struct(fields) => {
+            field.fields = fields
+                .iter()
+                .map(|nested| {
+                    let mode = match nested.data_type {
+                        DataType::Array(_) => FieldMode::Repeated,
+                        _ => FieldMode::Nullable,
+                    };
+                    bigquery_field(&nested.name, &nested.data_type, mode)
+                })
+                .collect::<Result<_>>()?;
+            "RECORD"
+        }
+        DataType::Range(element) => {
+            field.range_element_type = Some(RangeElementType {
+                element_type: element.to_bq_type(),
+            });
+            "RANGE"
+        }
+        DataType::Array(_) => {
+            return Err(Error::unsupported(format!(
+                "Field {} is an array of arrays, which BigQuery cannot store",
+                name
+            )));
+        }
+    }
+    .to_string();
+    Ok(field)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_bigquery_json() {
+        let schema = Schema::from_bigquery_json(
+            r#"[
+                {"name": "id", "type": "INTEGER", "mode": "REQUIRED", "description": "Primary key"},
+                {"name": "price", "type": "NUMERIC", "precision": "10", "scale": "2"},
+                {"name": "tags", "type": "STRING", "mode": "REPEATED"},
+                {"name": "address", "type": "RECORD", "mode": "NULLABLE", "fields": [
+                    {"name": "city", "type": "STRING"},
+                    {"name": "lines", "type": "STRING", "mode": "REPEATED"}
+                ]}
+            ]"#,
+        )
+        .unwrap();
+
+        let id = schema.field("id").unwrap();
+        assert_eq!(id.data_type, DataType::Int64);
+        assert_eq!(id.mode, FieldMode::Required);
+        assert_eq!(id.description, Some("Primary key".to_string()));
+        assert_eq!(
+            schema.field("price").unwrap().data_type,
+            DataType::Numeric(Some((10, 2)))
+        );
+        let tags = schema.field("tags").unwrap();
+        assert_eq!(tags.data_type, DataType::Array(Box::new(DataType::String)));
+        assert!(tags.is_repeated());
+        assert_eq!(
+            schema.field("address").unwrap().data_type,
+            DataType::Struct(vec![
+                StructField {
+                    name: "city".to_string(),
+                    data_type: DataType::String,
+                },
+                StructField {
+                    name: "lines".to_string(),
+                    data_type: DataType::Array(Box::new(DataType::String)),
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_from_bigquery_json_table_resource() {
+        let schema = Schema::from_bigquery_json(
+            r#"{"schema": {"fields": [{"name": "day", "type": "DATE"}]}}"#,
+        )
+        .unwrap();
+        assert_eq!(schema.field("day").unwrap().data_type, DataType::Date);
+        assert!(schema.field("day").unwrap().is_nullable());
+    }
+
+    #[test]
+    fn test_from_bigquery_json_errors() {
+        assert!(Schema::from_bigquery_json("not json").is_err());
+        assert!(Schema::from_bigquery_json(r#"[{"name": "a", "type": "WIDGET"}]"#).is_err());
+        assert!(
+            Schema::from_bigquery_json(r#"[{"name": "a", "type": "STRING", "mode": "SOMETIMES"}]"#)
+                .is_err()
+        );
+        assert!(
+            Schema::from_bigquery_json(
+                r#"[{"name": "a", "type": "STRING"}, {"name": "a", "type": "INTEGER"}]"#
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_bigquery_json_round_trip() {
+        let json = r#"[
+                {"name": "id", "type": "INTEGER", "mode": "REQUIRED", "description": "Primary key"},
+                {"name": "price", "type": "NUMERIC", "mode": "NULLABLE", "precision": "10", "scale": "2"},
+                {"name": "tags", "type": "STRING", "mode": "REPEATED"},
+                {"name": "during", "type": "RANGE", "mode": "NULLABLE", "rangeElementType": {"type": "DATE"}},
+                {"name": "address", "type": "RECORD", "mode": "NULLABLE", "fields": [
+                    {"name": "city", "type": "STRING", "mode": "NULLABLE"},
+                    {"name": "lines", "type": "STRING", "mode": "REPEATED"}
+                ]}
+            ]"#;
+        let schema = Schema::from_bigquery_json(json).unwrap();
+        let exported = schema.to_bigquery_json().unwrap();
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&exported).unwrap(),
+            serde_json::from_str::<serde_json::Value>(json).unwrap()
+        );
+        assert_eq!(Schema::from_bigquery_json(&exported).unwrap(), schema);
+    }
+
+    #[test]
+    fn test_to_bigquery_json_rejects_nested_arrays() {
+        let schema = Schema::from_fields(vec![Field::nullable(
+            "matrix",
+            DataType::Array(Box::new(DataType::Array(Box::new(DataType::Int64)))),
+        )]);
+        assert!(schema.to_bigquery_json().is_err());
+    }
+}
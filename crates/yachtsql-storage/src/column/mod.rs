@@ -288,6 +288,64 @@ impl Column {
     pub fn count_valid(&self) -> usize {
         self.len() - self.count_null()
     }
+
+    pub fn estimated_bytes(&self) -> usize {
+        fn fixed<T>(data: &[T]) -> usize {
+            std::mem::size_of_val(data)
+        }
+        let (data_bytes, nulls) = match self {
+            Column::Bool { data, nulls } => (fixed(data), nulls),
+            Column::Int64 { data, nulls } => (fixed(data), nulls),
+            Column::Float64 { data, nulls } => (fixed(data), nulls),
+            Column::Numeric { data, nulls } => (fixed(data), nulls),
+            Column::Date { data, nulls } => (fixed(data), nulls),
+            Column::Time { data, nulls } => (fixed(data), nulls),
+            Column::DateTime { data, nulls } => (fixed(data), nulls),
+            Column::Timestamp { data, nulls } => (fixed(data), nulls),
+            Column::Interval { data, nulls } => (fixed(data), nulls),
+            Column::String { data, nulls } | Column::Geography { data, nulls } => (
+                fixed(data) + data.iter().map(String::len).sum::<usize>(),
+                nulls,
+            ),
+            Column::Bytes { data, nulls } => (
+                fixed(data) + data.iter().map(Vec::len).sum::<usize>(),
+                nulls,
+            ),
+            Column::Json { data, nulls } => (
+                fixed(data) + data.iter().map(|j| j.to_string().len()).sum::<usize>(),
+                nulls,
+            ),
+            Column::Array { data, nulls, .. } => (
+                fixed(data)
+                    + data
+                        .iter()
+                        .flatten()
+                        .map(|v| std::mem::size_of::<Value>() + v.heap_size())
+                        .sum::<usize>(),
+                nulls,
+            ),
+            Column::Struct { data, nulls, .. } => (
+                fixed(data)
+                    + data
+                        .iter()
+                        .flatten()
+                        .map(|(name, v)| {
+                            std::mem::size_of::<(String, Value)>() + name.len() + v.heap_size()
+                        })
+                        .sum::<usize>(),
+                nulls,
+            ),
+            Column::Range { data, nulls, .. } => (
+                fixed(data)
+                    + data
+                        .iter()
+                        .map(|r| Value::Range(r.clone()).heap_size())
+                        .sum::<usize>(),
+                nulls,
+            ),
+        };
+        data_bytes + nulls.len().div_ceil(8)
+    }
 }
 
 #[cfg(test)]
@@ -1149,4 +1207,21 @@ mod tests {
             0
         );
     }
+
+    #[test]
+    fn test_estimated_bytes() {
+        let mut ints = Column::new(&DataType::Int64);
+        ints.push(Value::int64(1)).unwrap();
+        ints.push(Value::int64(2)).unwrap();
+        assert_eq!(ints.estimated_bytes(), 16 + 1);
+
+        let mut strings = Column::new(&DataType::String);
+        strings.push(Value::string("abc")).unwrap();
+        assert_eq!(
+            strings.estimated_bytes(),
+            std::mem::size_of::<String>() + 3 + 1
+        );
+
+        assert_eq!(Column::new(&DataType::Bool).estimated_bytes(), 0);
+    }
 }
@@ -1,12 +1,14 @@
 #![feature(coverage_attribute)]
 #![coverage(off)]
 
+mod bigquery_schema;
 mod bitmap;
 mod column;
 mod record;
 mod schema;
 mod table;
 
+pub use arrow;
 pub use bitmap::NullBitmap;
 pub use column::{A64, Column};
 pub use record::Record;
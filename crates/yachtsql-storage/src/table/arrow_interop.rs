@@ -0,0 +1,1081 @@
+#![coverage(off)]
+
+//! Conversion between [`Table`] and Arrow [`RecordBatch`]es.
+//!
+//! Every [`DataType`] has an Arrow counterpart, so a table survives a round trip
+//! through a record batch unchanged:
+//!
+//! | YachtSQL | Arrow |
+//! |----------|-------|
+//! | `BOOL`, `INT64`, `FLOAT64` | `Boolean`, `Int64`, `Float64` |
+//! | `NUMERIC` | `Decimal128(38, 9)` (`Decimal128(p, s)` for `NUMERIC(p, s)`) |
+//! | `BIGNUMERIC` | `Decimal256(76, 38)` |
+//! | `STRING`, `BYTES` | `Utf8`, `Binary` |
+//! | `DATE`, `TIME` | `Date32`, `Time64(µs)` |
+//! | `DATETIME`, `TIMESTAMP` | `Timestamp(µs)`, `Timestamp(µs, "UTC")` |
+//! | `INTERVAL` | `Interval(MonthDayNano)` |
+//! | `ARRAY<T>`, `STRUCT<...>` | `List<T>`, `Struct<...>` |
+//! | `JSON` | `Utf8` with the `arrow.json` extension |
+//! | `GEOGRAPHY` | `Utf8` (WKT) with the `geoarrow.wkt` extension |
+//! | `RANGE<T>` | `Struct<start: T, end: T>` with the `yachtsql.range` extension |
+//!
+//! Reading accepts a wider set of Arrow types (narrower integers and floats,
+//! large and view variants of strings, binaries and lists, other time units,
+//! dictionaries) and converts them to the closest type above.
+
+use std::sync::Arc;
+
+use arrow::array::{
+    Array, ArrayRef, AsArray, BinaryArray, BooleanArray, Date32Array, Decimal128Array,
+    Decimal256Array, Float64Array, Int64Array, IntervalMonthDayNanoArray, ListArray, StringArray,
+    StructArray, Time64MicrosecondArray, TimestampMicrosecondArray,
+};
+use arrow::buffer::{NullBuffer, OffsetBuffer};
+use arrow::datatypes::{
+    DataType as ArrowDataType, Field as ArrowField, Fields, IntervalMonthDayNano, IntervalUnit,
+    Schema as ArrowSchema, TimeUnit, i256,
+};
+use arrow::record_batch::{RecordBatch, RecordBatchOptions};
+use chrono::{DateTime, Datelike, NaiveDate, NaiveTime, Timelike};
+use indexmap::IndexMap;
+use rust_decimal::Decimal;
+use yachtsql_common::error::{Error, Result};
+use yachtsql_common::types::{DataType, IntervalValue, RangeValue, StructField, Value};
+
+use super::Table;
+use crate::{Column, Field, FieldMode, Schema};
+
+const EXTENSION_NAME_KEY: &str = "ARROW:extension:name";
+const JSON_EXTENSION: &str = "arrow.json";
+const GEOGRAPHY_EXTENSION: &str = "geoarrow.wkt";
+const RANGE_EXTENSION: &str = "yachtsql.range";
+
+const NUMERIC_PRECISION: u8 = 38;
+const NUMERIC_SCALE: i8 = 9;
+const BIGNUMERIC_PRECISION: u8 = 76;
+const BIGNUMERIC_SCALE: i8 = 38;
+const MAX_DECIMAL_SCALE: i32 = 28;
+
+const UNIX_EPOCH_DAYS_FROM_CE: i32 = 719_163;
+const NANOS_PER_MICRO: i64 = 1_000;
+
+impl Table {
+    /// Converts the table into an Arrow [`RecordBatch`] with one column per field.
+    ///
+    /// `REQUIRED` fields become non-nullable Arrow fields; see the module
+    /// documentation for how each type is represented.
+    pub fn to_record_batch(&self) -> Result<RecordBatch> {
+        let schema = Arc::new(self.schema().to_arrow_schema());
+        let arrays = self
+            .schema()
+            .fields()
+            .iter()
+            .enumerate()
+            .map(|(index, field)| {
+                let values: Vec<Value> = match self.column(index) {
+                    Some(column) => (0..self.row_count())
+                        .map(|row| column.get_value(row))
+                        .collect(),
+                    None => vec![Value::Null; self.row_count()],
+                };
+                values_to_array(&values, &field.data_type)
+                    .map_err(|e| Error::internal(format!("column {}: {}", field.name, e)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let options = RecordBatchOptions::new().with_row_count(Some(self.row_count()));
+        RecordBatch::try_new_with_options(schema, arrays, &options)
+            .map_err(|e| Error::internal(format!("Failed to create RecordBatch: {}", e)))
+    }
+
+    /// Builds a table from an Arrow [`RecordBatch`].
+    ///
+    /// The schema is derived from the batch's Arrow schema, so a batch produced by
+    /// [`to_record_batch`](Self::to_record_batch) converts back to an identical table.
+    pub fn from_record_batch(batch: &RecordBatch) -> Result<Table> {
+        let schema = Schema::from_arrow_schema(batch.schema_ref())?;
+        let mut columns = IndexMap::with_capacity(schema.field_count());
+        for (field, array) in schema.fields().iter().zip(batch.columns()) {
+            let mut column = Column::new(&field.data_type);
+            for value in array_to_values(array.as_ref(), &field.data_type)? {
+                column.push(value)?;
+            }
+            columns.insert(field.name.clone(), column);
+        }
+        Ok(Table::from_columns(schema, columns))
+    }
+}
+
+impl Schema {
+    /// Returns the Arrow schema [`Table::to_record_batch`] produces for this schema.
+    pub fn to_arrow_schema(&self) -> ArrowSchema {
+        ArrowSchema::new(
+            self.fields()
+                .iter()
+                .map(|field| {
+                    arrow_field(
+                        &field.name,
+                        &field.data_type,
+                        field.mode != FieldMode::Required,
+                    )
+                })
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    /// Derives a schema from an Arrow schema; non-nullable fields become `REQUIRED`.
+    pub fn from_arrow_schema(schema: &ArrowSchema) -> Result<Schema> {
+        let fields = schema
+            .fields()
+            .iter()
+            .map(|field| {
+                let mode = if field.is_nullable() {
+                    FieldMode::Nullable
+                } else {
+                    FieldMode::Required
+                };
+                Ok(Field::new(
+                    field.name(),
+                    arrow_field_to_data_type(field)?,
+                    mode,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Schema::from_fields(fields))
+    }
+}
+
+fn arrow_field(name: &str, data_type: &DataType, nullable: bool) -> ArrowField {
+    let field = ArrowField::new(name, data_type_to_arrow(data_type), nullable);
+    let extension = match data_type {
+        DataType::Json => JSON_EXTENSION,
+        DataType::Geography => GEOGRAPHY_EXTENSION,
+        DataType::Range(_) => RANGE_EXTENSION,
+        _ => return field,
+    };
+    field.with_metadata(
+        [(EXTENSION_NAME_KEY.to_string(), extension.to_string())]
+            .into_iter()
+            .collect(),
+    )
+}
+
+fn struct_fields(fields: &[StructField]) -> Fields {
+    fields
+        .iter()
+        .map(|f| arrow_field(&f.name, &f.data_type, true))
+        .collect()
+}
+
+fn range_fields(element_type: &DataType) -> Fields {
+    Fields::from(vec![
+        arrow_field("start", element_type, true),
+        arrow_field("end", element_type, true),
+    ])
+}
+
+fn data_type_to_arrow(data_type: &DataType) -> ArrowDataType {
+    match data_type {
+        DataType::Bool => ArrowDataType::Boolean,
+        DataType::Int64 => ArrowDataType::Int64,
+        DataType::Float64 => ArrowDataType::Float64,
+        DataType::Numeric(None) => ArrowDataType::Decimal128(NUMERIC_PRECISION, NUMERIC_SCALE),
+        DataType::Numeric(Some((precision, scale))) => {
+            ArrowDataType::Decimal128(*precision, *scale as i8)
+        }
+        DataType::BigNumeric => ArrowDataType::Decimal256(BIGNUMERIC_PRECISION, BIGNUMERIC_SCALE),
+        DataType::Unknown | DataType::String | DataType::Json | DataType::Geography => {
+            ArrowDataType::Utf8
+        }
+        DataType::Bytes => ArrowDataType::Binary,
+        DataType::Date => ArrowDataType::Date32,
+        DataType::Time => ArrowDataType::Time64(TimeUnit::Microsecond),
+        DataType::DateTime => ArrowDataType::Timestamp(TimeUnit::Microsecond, None),
+        DataType::Timestamp => ArrowDataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())),
+        DataType::Interval => ArrowDataType::Interval(IntervalUnit::MonthDayNano),
+        DataType::Array(element_type) => {
+            ArrowDataType::List(Arc::new(arrow_field("item", element_type, true)))
+        }
+        DataType::Struct(fields) => ArrowDataType::Struct(struct_fields(fields)),
+        DataType::Range(element_type) => ArrowDataType::Struct(range_fields(element_type)),
+    }
+}
+
+fn arrow_field_to_data_type(field: &ArrowField) -> Result<DataType> {
+    let extension = field.metadata().get(EXTENSION_NAME_KEY).map(String::as_str);
+    match (extension, field.data_type()) {
+        (
+            Some(JSON_EXTENSION),
+            ArrowDataType::Utf8 | ArrowDataType::LargeUtf8 | ArrowDataType::Utf8View,
+        ) => Ok(DataType::Json),
+        (
+            Some(GEOGRAPHY_EXTENSION),
+            ArrowDataType::Utf8 | ArrowDataType::LargeUtf8 | ArrowDataType::Utf8View,
+        ) => Ok(DataType::Geography),
+        (Some(RANGE_EXTENSION), ArrowDataType::Struct(fields)) => {
+            let start = fields
+                .iter()
+                .find(|f| f.name() == "start")
+                .ok_or_else(|| Error::schema_mismatch("RANGE struct has no `start` field"))?;
+            Ok(DataType::Range(Box::new(arrow_field_to_data_type(start)?)))
+        }
+        (_, data_type) => arrow_to_data_type(data_type),
+    }
+}
+
+fn arrow_to_data_type(data_type: &ArrowDataType) -> Result<DataType> {
+    match data_type {
+        ArrowDataType::Null => Ok(DataType::Unknown),
+        ArrowDataType::Boolean => Ok(DataType::Bool),
+        ArrowDataType::Int8
+        | ArrowDataType::Int16
+        | ArrowDataType::Int32
+        | ArrowDataType::Int64
+        | ArrowDataType::UInt8
+        | ArrowDataType::UInt16
+        | ArrowDataType::UInt32
+        | ArrowDataType::UInt64 => Ok(DataType::Int64),
+        ArrowDataType::Float16 | ArrowDataType::Float32 | ArrowDataType::Float64 => {
+            Ok(DataType::Float64)
+        }
+        ArrowDataType::Decimal128(NUMERIC_PRECISION, NUMERIC_SCALE) => Ok(DataType::Numeric(None)),
+        ArrowDataType::Decimal128(precision, scale)
+            if (0..=NUMERIC_SCALE).contains(scale)
+                && *precision as i32 - *scale as i32 <= (NUMERIC_PRECISION - 9) as i32 =>
+        {
+            Ok(DataType::Numeric(Some((*precision, *scale as u8))))
+        }
+        ArrowDataType::Decimal128(..) | ArrowDataType::Decimal256(..) => Ok(DataType::BigNumeric),
+        ArrowDataType::Utf8 | ArrowDataType::LargeUtf8 | ArrowDataType::Utf8View => {
+            Ok(DataType::String)
+        }
+        ArrowDataType::Binary
+        | ArrowDataType::LargeBinary
+        | ArrowDataType::BinaryView
+        | ArrowDataType::FixedSizeBinary(_) => Ok(DataType::Bytes),
+        ArrowDataType::Date32 | ArrowDataType::Date64 => Ok(DataType::Date),
+        ArrowDataType::Time32(_) | ArrowDataType::Time64(_) => Ok(DataType::Time),
+        ArrowDataType::Timestamp(_, None) => Ok(DataType::DateTime),
+        ArrowDataType::Timestamp(_, Some(_)) => Ok(DataType::Timestamp),
+        ArrowDataType::Interval(_) | ArrowDataType::Duration(_) => Ok(DataType::Interval),
+        ArrowDataType::List(element)
+        | ArrowDataType::LargeList(element)
+        | ArrowDataType::ListView(element)
+        | ArrowDataType::LargeListView(element)
+        | ArrowDataType::FixedSizeList(element, _) => Ok(DataType::Array(Box::new(
+            arrow_field_to_data_type(element)?,
+        ))),
+        ArrowDataType::Struct(fields) => Ok(DataType::Struct(
+            fields
+                .iter()
+                .map(|f| {
+                    Ok(StructField {
+                        name: f.name().clone(),
+                        data_type: arrow_field_to_data_type(f)?,
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?,
+        )),
+        ArrowDataType::Dictionary(_, value_type) => arrow_to_data_type(value_type),
+        other => Err(Error::unsupported(format!(
+            "Arrow type {} has no YachtSQL equivalent",
+            other
+        ))),
+    }
+}
+
+fn values_to_array(values: &[Value], data_type: &DataType) -> Result<ArrayRef> {
+    let array: ArrayRef = match data_type {
+        DataType::Bool => Arc::new(values.iter().map(|v| v.as_bool()).collect::<BooleanArray>()),
+        DataType::Int64 => Arc::new(values.iter().map(|v| v.as_i64()).collect::<Int64Array>()),
+        DataType::Float64 => Arc::new(values.iter().map(|v| v.as_f64()).collect::<Float64Array>()),
+        DataType::Numeric(_) | DataType::BigNumeric => {
+            decimal_array(values, &data_type_to_arrow(data_type))?
+        }
+        DataType::Unknown | DataType::String => Arc::new(
+            values
+                .iter()
+                .map(|v| match v {
+                    Value::Null => None,
+                    Value::String(s) => Some(s.clone()),
+                    other => Some(other.to_string()),
+                })
+                .collect::<StringArray>(),
+        ),
+        DataType::Json => Arc::new(
+            values
+                .iter()
+                .map(|v| match v {
+                    Value::Null => None,
+                    Value::Json(json) => Some(json.to_string()),
+                    other => Some(other.to_json().to_string()),
+                })
+                .collect::<StringArray>(),
+        ),
+        DataType::Geography => Arc::new(
+            values
+                .iter()
+                .map(|v| match v {
+                    Value::Geography(wkt) | Value::String(wkt) => Some(wkt.as_str()),
+                    _ => None,
+                })
+                .collect::<StringArray>(),
+        ),
+        DataType::Bytes => Arc::new(values.iter().map(|v| v.as_bytes()).collect::<BinaryArray>()),
+        DataType::Date => Arc::new(
+            values
+                .iter()
+                .map(|v| v.as_date().map(date_to_days))
+                .collect::<Date32Array>(),
+        ),
+        DataType::Time => Arc::new(
+            values
+                .iter()
+                .map(|v| v.as_time().map(time_to_micros))
+                .collect::<Time64MicrosecondArray>(),
+        ),
+        DataType::DateTime => Arc::new(
+            values
+                .iter()
+                .map(|v| v.as_datetime().map(|dt| dt.and_utc().timestamp_micros()))
+                .collect::<TimestampMicrosecondArray>(),
+        ),
+        DataType::Timestamp => Arc::new(
+            values
+                .iter()
+                .map(|v| v.as_timestamp().map(|ts| ts.timestamp_micros()))
+                .collect::<TimestampMicrosecondArray>()
+                .with_timezone("UTC"),
+        ),
+        DataType::Interval => Arc::new(
+            values
+                .iter()
+                .map(|v| {
+                    v.as_interval()
+                        .map(|i| IntervalMonthDayNano::new(i.months, i.days, i.nanos))
+                })
+                .collect::<IntervalMonthDayNanoArray>(),
+        ),
+        DataType::Array(element_type) => {
+            let mut offsets = Vec::with_capacity(values.len() + 1);
+            let mut elements = Vec::new();
+            offsets.push(0i32);
+            for value in values {
+                if let Some(items) = value.as_array() {
+                    elements.extend_from_slice(items);
+                }
+                let offset = i32::try_from(elements.len())
+                    .map_err(|_| Error::internal("ARRAY column is too large for Arrow"))?;
+                offsets.push(offset);
+            }
+            Arc::new(
+                ListArray::try_new(
+                    Arc::new(arrow_field("item", element_type, true)),
+                    OffsetBuffer::new(offsets.into()),
+                    values_to_array(&elements, element_type)?,
+                    null_buffer(values),
+                )
+                .map_err(arrow_error)?,
+            )
+        }
+        DataType::Struct(fields) => {
+            let children = fields
+                .iter()
+                .enumerate()
+                .map(|(index, field)| {
+                    let child: Vec<Value> = values
+                        .iter()
+                        .map(|v| match v {
+                            Value::Struct(entries) => entries
+                                .get(index)
+                                .map(|(_, value)| value.clone())
+                                .unwrap_or(Value::Null),
+                            _ => Value::Null,
+                        })
+                        .collect();
+                    values_to_array(&child, &field.data_type)
+                })
+                .collect::<Result<Vec<_>>>()?;
+            struct_array(struct_fields(fields), children, values)?
+        }
+        DataType::Range(element_type) => {
+            let bound = |pick: fn(&RangeValue) -> Option<&Value>| -> Vec<Value> {
+                values
+                    .iter()
+                    .map(|v| v.as_range().and_then(pick).cloned().unwrap_or(Value::Null))
+                    .collect()
+            };
+            let children = vec![
+                values_to_array(&bound(RangeValue::start), element_type)?,
+                values_to_array(&bound(RangeValue::end), element_type)?,
+            ];
+            struct_array(range_fields(element_type), children, values)?
+        }
+    };
+    Ok(array)
+}
+
+fn struct_array(fields: Fields, children: Vec<ArrayRef>, values: &[Value]) -> Result<ArrayRef> {
+    let nulls = null_buffer(values);
+    if fields.is_empty() {
+        return Ok(Arc::new(StructArray::new_empty_fields(values.len(), nulls)));
+    }
+    Ok(Arc::new(
+        StructArray::try_new(fields, children, nulls).map_err(arrow_error)?,
+    ))
+}
+
+fn null_buffer(values: &[Value]) -> Option<NullBuffer> {
+    values
+        .iter()
+        .any(Value::is_null)
+        .then(|| NullBuffer::from_iter(values.iter().map(|v| !v.is_null())))
+}
+
+fn decimal_array(values: &[Value], arrow_type: &ArrowDataType) -> Result<ArrayRef> {
+    let decimals = values.iter().map(|v| match v {
+        Value::Numeric(d) | Value::BigNumeric(d) => Some(*d),
+        _ => None,
+    });
+    match arrow_type {
+        ArrowDataType::Decimal128(precision, scale) => {
+            let array = decimals
+                .map(|d| d.map(|d| decimal_to_i128(d, *scale)).transpose())
+                .collect::<Result<Decimal128Array>>()?
+                .with_precision_and_scale(*precision, *scale)
+                .map_err(arrow_error)?;
+            Ok(Arc::new(array))
+        }
+        ArrowDataType::Decimal256(precision, scale) => {
+            let array = decimals
+                .map(|d| d.map(|d| decimal_to_i256(d, *scale)).transpose())
+                .collect::<Result<Decimal256Array>>()?
+                .with_precision_and_scale(*precision, *scale)
+                .map_err(arrow_error)?;
+            Ok(Arc::new(array))
+        }
+        other => Err(Error::internal(format!("{} is not a decimal type", other))),
+    }
+}
+
+fn decimal_to_i128(value: Decimal, scale: i8) -> Result<i128> {
+    let rounded = value.round_dp(scale.max(0) as u32);
+    10i128
+        .checked_pow((scale as i32 - rounded.scale() as i32).max(0) as u32)
+        .and_then(|factor| rounded.mantissa().checked_mul(factor))
+        .ok_or_else(|| Error::out_of_range(value.to_string(), "Decimal128"))
+}
+
+fn decimal_to_i256(value: Decimal, scale: i8) -> Result<i256> {
+    let rounded = value.round_dp(scale.max(0) as u32);
+    i256::from_i128(10)
+        .checked_pow((scale as i32 - rounded.scale() as i32).max(0) as u32)
+        .and_then(|factor| i256::from_i128(rounded.mantissa()).checked_mul(factor))
+        .ok_or_else(|| Error::out_of_range(value.to_string(), "Decimal256"))
+}
+
+/// Converts an Arrow decimal to a `Decimal`, dropping trailing fractional zeros
+/// and, where `Decimal` has fewer digits than the Arrow value, rounding.
+fn decimal_from_i256(mut value: i256, mut scale: i32) -> Result<Decimal> {
+    let original = format!("{}e-{}", value, scale);
+    let out_of_range = || Error::out_of_range(original.clone(), "NUMERIC");
+    let ten = i256::from_i128(10);
+    while scale < 0 {
+        value = value.checked_mul(ten).ok_or_else(out_of_range)?;
+        scale += 1;
+    }
+    loop {
+        if scale <= MAX_DECIMAL_SCALE
+            && let Some(mantissa) = value.to_i128()
+            && let Ok(decimal) = Decimal::try_from_i128_with_scale(mantissa, scale as u32)
+        {
+            return Ok(decimal.normalize());
+        }
+        if scale == 0 {
+            return Err(out_of_range());
+        }
+        let remainder = value.checked_rem(ten).ok_or_else(out_of_range)?;
+        value = value.checked_div(ten).ok_or_else(out_of_range)?;
+        scale -= 1;
+        let five = i256::from_i128(5);
+        if remainder >= five {
+            value = value.checked_add(i256::ONE).ok_or_else(out_of_range)?;
+        } else if remainder <= i256::ZERO.wrapping_sub(five) {
+            value = value.checked_sub(i256::ONE).ok_or_else(out_of_range)?;
+        }
+    }
+}
+
+fn date_to_days(date: NaiveDate) -> i32 {
+    date.num_days_from_ce() - UNIX_EPOCH_DAYS_FROM_CE
+}
+
+fn time_to_micros(time: NaiveTime) -> i64 {
+    time.num_seconds_from_midnight() as i64 * 1_000_000 + (time.nanosecond() / 1_000) as i64
+}
+
+fn arrow_error(e: arrow::error::ArrowError) -> Error {
+    Error::internal(format!("Arrow error: {}", e))
+}
+
+fn array_to_values(array: &dyn Array, data_type: &DataType) -> Result<Vec<Value>> {
+    use arrow::datatypes::{
+        Date32Type, Date64Type, Decimal128Type, Decimal256Type, DurationMicrosecondType,
+        DurationMillisecondType, DurationNanosecondType, DurationSecondType, Float16Type,
+        Float32Type, Float64Type, Int8Type, Int16Type, Int32Type, Int64Type, IntervalDayTimeType,
+        IntervalMonthDayNanoType, IntervalYearMonthType, Time32MillisecondType, Time32SecondType,
+        Time64MicrosecondType, Time64NanosecondType, TimestampMicrosecondType,
+        TimestampMillisecondType, TimestampNanosecondType, TimestampSecondType, UInt8Type,
+        UInt16Type, UInt32Type, UInt64Type,
+    };
+
+    macro_rules! map_values {
+        ($array:expr, | $value:ident | $convert:expr) => {{
+            let array = $array;
+            (0..array.len())
+                .map(|i| {
+                    if array.is_null(i) {
+                        Ok(Value::Null)
+                    } else {
+                        let $value = array.value(i);
+                        $convert
+                    }
+                })
+                .collect::<Result<Vec<Value>>>()
+        }};
+    }
+
+    let nested = |array: ArrayRef| array_to_values(array.as_ref(), element_type(data_type));
+
+    match array.data_type() {
+        ArrowDataType::Null => Ok(vec![Value::Null; array.len()]),
+        ArrowDataType::Boolean => map_values!(array.as_boolean(), |v| Ok(Value::Bool(v))),
+        ArrowDataType::Int8 => map_values!(array.as_primitive::<Int8Type>(), |v| Ok(Value::Int64(
+            v.into()
+        ))),
+        ArrowDataType::Int16 => map_values!(array.as_primitive::<Int16Type>(), |v| Ok(
+            Value::Int64(v.into())
+        )),
+        ArrowDataType::Int32 => map_values!(array.as_primitive::<Int32Type>(), |v| Ok(
+            Value::Int64(v.into())
+        )),
+        ArrowDataType::Int64 => {
+            map_values!(array.as_primitive::<Int64Type>(), |v| Ok(Value::Int64(v)))
+        }
+        ArrowDataType::UInt8 => map_values!(array.as_primitive::<UInt8Type>(), |v| Ok(
+            Value::Int64(v.into())
+        )),
+        ArrowDataType::UInt16 => map_values!(array.as_primitive::<UInt16Type>(), |v| Ok(
+            Value::Int64(v.into())
+        )),
+        ArrowDataType::UInt32 => map_values!(array.as_primitive::<UInt32Type>(), |v| Ok(
+            Value::Int64(v.into())
+        )),
+        ArrowDataType::UInt64 => map_values!(array.as_primitive::<UInt64Type>(), |v| {
+            i64::try_from(v)
+                .map(Value::Int64)
+                .map_err(|_| Error::out_of_range(v.to_string(), "INT64"))
+        }),
+        ArrowDataType::Float16 => map_values!(array.as_primitive::<Float16Type>(), |v| Ok(
+            Value::float64(v.to_f64())
+        )),
+        ArrowDataType::Float32 => map_values!(array.as_primitive::<Float32Type>(), |v| Ok(
+            Value::float64(v.into())
+        )),
+        ArrowDataType::Float64 => map_values!(array.as_primitive::<Float64Type>(), |v| Ok(
+            Value::float64(v)
+        )),
+        ArrowDataType::Decimal128(_, scale) => {
+            let numeric = !matches!(data_type, DataType::BigNumeric);
+            map_values!(array.as_primitive::<Decimal128Type>(), |v| {
+                decimal_from_i256(i256::from_i128(v), *scale as i32)
+                    .map(|d| decimal_value(d, numeric))
+            })
+        }
+        ArrowDataType::Decimal256(_, scale) => {
+            let numeric = !matches!(data_type, DataType::BigNumeric);
+            map_values!(array.as_primitive::<Decimal256Type>(), |v| {
+                decimal_from_i256(v, *scale as i32).map(|d| decimal_value(d, numeric))
+            })
+        }
+        ArrowDataType::Utf8 => {
+            map_values!(array.as_string::<i32>(), |v| string_value(v, data_type))
+        }
+        ArrowDataType::LargeUtf8 => {
+            map_values!(array.as_string::<i64>(), |v| string_value(v, data_type))
+        }
+        ArrowDataType::Utf8View => {
+            map_values!(array.as_string_view(), |v| string_value(v, data_type))
+        }
+        ArrowDataType::Binary => {
+            map_values!(array.as_binary::<i32>(), |v| Ok(Value::Bytes(v.to_vec())))
+        }
+        ArrowDataType::LargeBinary => {
+            map_values!(array.as_binary::<i64>(), |v| Ok(Value::Bytes(v.to_vec())))
+        }
+        ArrowDataType::BinaryView => {
+            map_values!(array.as_binary_view(), |v| Ok(Value::Bytes(v.to_vec())))
+        }
+        ArrowDataType::FixedSizeBinary(_) => map_values!(array.as_fixed_size_binary(), |v| Ok(
+            Value::Bytes(v.to_vec())
+        )),
+        ArrowDataType::Date32 => map_values!(array.as_primitive::<Date32Type>(), |v| {
+            NaiveDate::from_num_days_from_ce_opt(v + UNIX_EPOCH_DAYS_FROM_CE)
+                .map(Value::Date)
+                .ok_or_else(|| Error::out_of_range(v.to_string(), "DATE"))
+        }),
+        ArrowDataType::Date64 => map_values!(array.as_primitive::<Date64Type>(), |v| {
+            DateTime::from_timestamp_millis(v)
+                .map(|dt| Value::Date(dt.date_naive()))
+                .ok_or_else(|| Error::out_of_range(v.to_string(), "DATE"))
+        }),
+        ArrowDataType::Time32(TimeUnit::Second) => {
+            map_values!(array.as_primitive::<Time32SecondType>(), |v| time_value(
+                v as i64 * 1_000_000_000
+            ))
+        }
+        ArrowDataType::Time32(_) => {
+            map_values!(array.as_primitive::<Time32MillisecondType>(), |v| {
+                time_value(v as i64 * 1_000_000)
+            })
+        }
+        ArrowDataType::Time64(TimeUnit::Nanosecond) => {
+            map_values!(
+                array.as_primitive::<Time64NanosecondType>(),
+                |v| time_value(v)
+            )
+        }
+        ArrowDataType::Time64(_) => {
+            map_values!(array.as_primitive::<Time64MicrosecondType>(), |v| {
+                time_value(v * NANOS_PER_MICRO)
+            })
+        }
+        ArrowDataType::Timestamp(unit, _) => {
+            let nanos_per_unit = match unit {
+                TimeUnit::Second => 1_000_000_000,
+                TimeUnit::Millisecond => 1_000_000,
+                TimeUnit::Microsecond => NANOS_PER_MICRO,
+                TimeUnit::Nanosecond => 1,
+            };
+            let raw: Vec<Option<i64>> = match unit {
+                TimeUnit::Second => array.as_primitive::<TimestampSecondType>().iter().collect(),
+                TimeUnit::Millisecond => array
+                    .as_primitive::<TimestampMillisecondType>()
+                    .iter()
+                    .collect(),
+                TimeUnit::Microsecond => array
+                    .as_primitive::<TimestampMicrosecondType>()
+                    .iter()
+                    .collect(),
+                TimeUnit::Nanosecond => array
+                    .as_primitive::<TimestampNanosecondType>()
+                    .iter()
+                    .collect(),
+            };
+            raw.into_iter()
+                .map(|v| match v {
+                    None => Ok(Value::Null),
+                    Some(v) => timestamp_value(v as i128 * nanos_per_unit as i128, data_type),
+                })
+                .collect()
+        }
+        ArrowDataType::Interval(IntervalUnit::MonthDayNano) => {
+            map_values!(array.as_primitive::<IntervalMonthDayNanoType>(), |v| Ok(
+                Value::Interval(IntervalValue {
+                    months: v.months,
+                    days: v.days,
+                    nanos: v.nanoseconds,
+                })
+            ))
+        }
+        ArrowDataType::Interval(IntervalUnit::DayTime) => {
+            map_values!(array.as_primitive::<IntervalDayTimeType>(), |v| Ok(
+                Value::Interval(IntervalValue {
+                    months: 0,
+                    days: v.days,
+                    nanos: v.milliseconds as i64 * 1_000_000,
+                })
+            ))
+        }
+        ArrowDataType::Interval(IntervalUnit::YearMonth) => {
+            map_values!(array.as_primitive::<IntervalYearMonthType>(), |v| Ok(
+                Value::Interval(IntervalValue::from_months(v))
+            ))
+        }
+        ArrowDataType::Duration(unit) => {
+            let raw: Vec<Option<i64>> = match unit {
+                TimeUnit::Second => array
+                    .as_primitive::<DurationSecondType>()
+                    .iter()
+                    .map(|v| v.and_then(|s| s.checked_mul(1_000_000_000)))
+                    .collect(),
+                TimeUnit::Millisecond => array
+                    .as_primitive::<DurationMillisecondType>()
+                    .iter()
+                    .map(|v| v.and_then(|ms| ms.checked_mul(1_000_000)))
+                    .collect(),
+                TimeUnit::Microsecond => array
+                    .as_primitive::<DurationMicrosecondType>()
+                    .iter()
+                    .map(|v| v.and_then(|us| us.checked_mul(NANOS_PER_MICRO)))
+                    .collect(),
+                TimeUnit::Nanosecond => array
+                    .as_primitive::<DurationNanosecondType>()
+                    .iter()
+                    .collect(),
+            };
+            Ok(raw
+                .into_iter()
+                .map(|v| match v {
+                    None => Value::Null,
+                    Some(nanos) => Value::Interval(IntervalValue {
+                        months: 0,
+                        days: 0,
+                        nanos,
+                    }),
+                })
+                .collect())
+        }
+        ArrowDataType::List(_) => {
+            let list = array.as_list::<i32>();
+            (0..list.len())
+                .map(|i| list_value(list.is_null(i), || nested(list.value(i))))
+                .collect()
+        }
+        ArrowDataType::LargeList(_) => {
+            let list = array.as_list::<i64>();
+            (0..list.len())
+                .map(|i| list_value(list.is_null(i), || nested(list.value(i))))
+                .collect()
+        }
+        ArrowDataType::FixedSizeList(..) => {
+            let list = array.as_fixed_size_list();
+            (0..list.len())
+                .map(|i| list_value(list.is_null(i), || nested(list.value(i))))
+                .collect()
+        }
+        ArrowDataType::Struct(_) => struct_values(array.as_struct(), data_type),
+        ArrowDataType::Dictionary(_, value_type) => {
+            let unpacked = arrow::compute::cast(array, value_type).map_err(arrow_error)?;
+            array_to_values(unpacked.as_ref(), data_type)
+        }
+        other => Err(Error::unsupported(format!(
+            "Arrow type {} has no YachtSQL equivalent",
+            other
+        ))),
+    }
+}
+
+fn element_type(data_type: &DataType) -> &DataType {
+    match data_type {
+        DataType::Array(element_type) => element_type,
+        _ => &DataType::Unknown,
+    }
+}
+
+fn list_value(is_null: bool, items: impl FnOnce() -> Result<Vec<Value>>) -> Result<Value> {
+    if is_null {
+        Ok(Value::Null)
+    } else {
+        items().map(Value::Array)
+    }
+}
+
+fn struct_values(array: &StructArray, data_type: &DataType) -> Result<Vec<Value>> {
+    if let DataType::Range(element_type) = data_type {
+        let bound = |name: &str| -> Result<Vec<Value>> {
+            match array.column_by_name(name) {
+                Some(child) => array_to_values(child.as_ref(), element_type),
+                None => Ok(vec![Value::Null; array.len()]),
+            }
+        };
+        let (starts, ends) = (bound("start")?, bound("end")?);
+        return Ok(starts
+            .into_iter()
+            .zip(ends)
+            .enumerate()
+            .map(|(i, (start, end))| {
+                if array.is_null(i) {
+                    Value::Null
+                } else {
+                    let bound = |v: Value| (!v.is_null()).then_some(v);
+                    Value::Range(RangeValue::new(bound(start), bound(end)))
+                }
+            })
+            .collect());
+    }
+
+    let field_types: Vec<&DataType> = match data_type {
+        DataType::Struct(fields) => fields.iter().map(|f| &f.data_type).collect(),
+        _ => vec![&DataType::Unknown; array.num_columns()],
+    };
+    let children = array
+        .columns()
+        .iter()
+        .zip(field_types)
+        .map(|(child, child_type)| array_to_values(child.as_ref(), child_type))
+        .collect::<Result<Vec<_>>>()?;
+    let names = array.column_names();
+    Ok((0..array.len())
+        .map(|i| {
+            if array.is_null(i) {
+                Value::Null
+            } else {
+                Value::Struct(
+                    names
+                        .iter()
+                        .zip(&children)
+                        .map(|(name, values)| (name.to_string(), values[i].clone()))
+                        .collect(),
+                )
+            }
+        })
+        .collect())
+}
+
+fn decimal_value(decimal: Decimal, numeric: bool) -> Value {
+    if numeric {
+        Value::Numeric(decimal)
+    } else {
+        Value::BigNumeric(decimal)
+    }
+}
+
+fn string_value(s: &str, data_type: &DataType) -> Result<Value> {
+    match data_type {
+        DataType::Json => serde_json::from_str(s)
+            .map(Value::Json)
+            .map_err(|e| Error::invalid_literal(format!("invalid JSON '{}': {}", s, e))),
+        DataType::Geography => Ok(Value::Geography(s.to_string())),
+        _ => Ok(Value::String(s.to_string())),
+    }
+}
+
+fn time_value(nanos: i64) -> Result<Value> {
+    let seconds = nanos.div_euclid(1_000_000_000);
+    let subsec = nanos.rem_euclid(1_000_000_000);
+    u32::try_from(seconds)
+        .ok()
+        .and_then(|s| NaiveTime::from_num_seconds_from_midnight_opt(s, subsec as u32))
+        .map(Value::Time)
+        .ok_or_else(|| Error::out_of_range(nanos.to_string(), "TIME"))
+}
+
+fn timestamp_value(nanos: i128, data_type: &DataType) -> Result<Value> {
+    let seconds = nanos.div_euclid(1_000_000_000);
+    let subsec = nanos.rem_euclid(1_000_000_000) as u32;
+    let timestamp = i64::try_from(seconds)
+        .ok()
+        .and_then(|s| DateTime::from_timestamp(s, subsec))
+        .ok_or_else(|| Error::out_of_range(nanos.to_string(), "TIMESTAMP"))?;
+    Ok(match data_type {
+        DataType::DateTime => Value::DateTime(timestamp.naive_utc()),
+        _ => Value::Timestamp(timestamp),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::{Int32Array, LargeStringArray, UInt64Array};
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn assert_round_trip(table: &Table) {
+        let batch = table.to_record_batch().unwrap();
+        let restored = Table::from_record_batch(&batch).unwrap();
+        assert_eq!(restored.schema(), table.schema());
+        assert_eq!(restored.to_records().unwrap(), table.to_records().unwrap());
+    }
+
+    #[test]
+    fn test_scalar_types_round_trip() {
+        let schema = Schema::from_fields(vec![
+            Field::required("id", DataType::Int64),
+            Field::nullable("flag", DataType::Bool),
+            Field::nullable("score", DataType::Float64),
+            Field::nullable("amount", DataType::Numeric(None)),
+            Field::nullable("price", DataType::Numeric(Some((10, 2)))),
+            Field::nullable("big", DataType::BigNumeric),
+            Field::nullable("name", DataType::String),
+            Field::nullable("blob", DataType::Bytes),
+            Field::nullable("day", DataType::Date),
+            Field::nullable("at", DataType::Time),
+            Field::nullable("local", DataType::DateTime),
+            Field::nullable("ts", DataType::Timestamp),
+            Field::nullable("doc", DataType::Json),
+            Field::nullable("place", DataType::Geography),
+            Field::nullable("span", DataType::Interval),
+        ]);
+        let day = NaiveDate::from_ymd_opt(1969, 7, 20).unwrap();
+        let time = NaiveTime::from_hms_micro_opt(20, 17, 40, 123_456).unwrap();
+        let table = Table::from_values(
+            schema,
+            vec![
+                vec![
+                    Value::Int64(1),
+                    Value::Bool(true),
+                    Value::float64(1.5),
+                    Value::Numeric(Decimal::new(-12_345_678_901, 9)),
+                    Value::Numeric(Decimal::new(1999, 2)),
+                    Value::BigNumeric(Decimal::new(123_456_789, 20)),
+                    Value::String("Alice".to_string()),
+                    Value::Bytes(vec![0, 1, 255]),
+                    Value::Date(day),
+                    Value::Time(time),
+                    Value::DateTime(day.and_time(time)),
+                    Value::Timestamp(chrono::Utc.from_utc_datetime(&day.and_time(time))),
+                    Value::Json(serde_json::json!({"a": [1, 2]})),
+                    Value::Geography("POINT(1 2)".to_string()),
+                    Value::Interval(IntervalValue {
+                        months: 14,
+                        days: -3,
+                        nanos: 5_000,
+                    }),
+                ],
+                std::iter::once(Value::Int64(2))
+                    .chain(std::iter::repeat_n(Value::Null, 14))
+                    .collect(),
+            ],
+        )
+        .unwrap();
+
+        assert_round_trip(&table);
+    }
+
+    #[test]
+    fn test_nested_types_round_trip() {
+        let address = DataType::Struct(vec![
+            StructField {
+                name: "city".to_string(),
+                data_type: DataType::String,
+            },
+            StructField {
+                name: "zips".to_string(),
+                data_type: DataType::Array(Box::new(DataType::Int64)),
+            },
+        ]);
+        let schema = Schema::from_fields(vec![
+            Field::nullable("tags", DataType::Array(Box::new(DataType::String))),
+            Field::nullable("address", address),
+            Field::nullable("period", DataType::Range(Box::new(DataType::Date))),
+            Field::nullable(
+                "history",
+                DataType::Array(Box::new(DataType::Array(Box::new(DataType::Json)))),
+            ),
+        ]);
+        let date = |d| Value::Date(NaiveDate::from_ymd_opt(2024, 1, d).unwrap());
+        let table = Table::from_values(
+            schema,
+            vec![
+                vec![
+                    Value::Array(vec![Value::String("a".to_string()), Value::Null]),
+                    Value::Struct(vec![
+                        ("city".to_string(), Value::String("Oslo".to_string())),
+                        (
+                            "zips".to_string(),
+                            Value::Array(vec![Value::Int64(150), Value::Int64(151)]),
+                        ),
+                    ]),
+                    Value::Range(RangeValue::new(Some(date(1)), Some(date(31)))),
+                    Value::Array(vec![
+                        Value::Array(vec![Value::Json(serde_json::json!(1))]),
+                        Value::Array(vec![]),
+                    ]),
+                ],
+                vec![
+                    Value::Array(vec![]),
+                    Value::Struct(vec![
+                        ("city".to_string(), Value::Null),
+                        ("zips".to_string(), Value::Null),
+                    ]),
+                    Value::Range(RangeValue::new(None, Some(date(2)))),
+                    Value::Null,
+                ],
+                vec![Value::Null, Value::Null, Value::Null, Value::Null],
+            ],
+        )
+        .unwrap();
+
+        assert_round_trip(&table);
+    }
+
+    #[test]
+    fn test_extension_metadata_and_nullability() {
+        let schema = Schema::from_fields(vec![
+            Field::required("id", DataType::Int64),
+            Field::nullable("doc", DataType::Json),
+            Field::nullable("place", DataType::Geography),
+            Field::nullable("period", DataType::Range(Box::new(DataType::Timestamp))),
+        ]);
+        let arrow_schema = schema.to_arrow_schema();
+
+        assert!(!arrow_schema.field(0).is_nullable());
+        assert!(arrow_schema.field(1).is_nullable());
+        let extension = |i: usize| {
+            arrow_schema
+                .field(i)
+                .metadata()
+                .get(EXTENSION_NAME_KEY)
+                .cloned()
+        };
+        assert_eq!(extension(0), None);
+        assert_eq!(extension(1).as_deref(), Some(JSON_EXTENSION));
+        assert_eq!(extension(2).as_deref(), Some(GEOGRAPHY_EXTENSION));
+        assert_eq!(extension(3).as_deref(), Some(RANGE_EXTENSION));
+        assert_eq!(Schema::from_arrow_schema(&arrow_schema).unwrap(), schema);
+    }
+
+    #[test]
+    fn test_from_record_batch_widens_foreign_types() {
+        let batch = RecordBatch::try_from_iter(vec![
+            (
+                "small",
+                Arc::new(Int32Array::from(vec![Some(7), None])) as ArrayRef,
+            ),
+            (
+                "text",
+                Arc::new(LargeStringArray::from(vec!["x", "y"])) as ArrayRef,
+            ),
+            (
+                "ts",
+                Arc::new(
+                    arrow::array::TimestampNanosecondArray::from(vec![1_500, 0])
+                        .with_timezone("UTC"),
+                ) as ArrayRef,
+            ),
+        ])
+        .unwrap();
+
+        let table = Table::from_record_batch(&batch).unwrap();
+
+        let types: Vec<_> = table
+            .schema()
+            .fields()
+            .iter()
+            .map(|f| f.data_type.clone())
+            .collect();
+        assert_eq!(
+            types,
+            vec![DataType::Int64, DataType::String, DataType::Timestamp]
+        );
+        let first = table.get_row(0).unwrap();
+        assert_eq!(first.values()[0], Value::Int64(7));
+        assert_eq!(first.values()[1], Value::String("x".to_string()));
+        assert_eq!(
+            first.values()[2],
+            Value::Timestamp(DateTime::from_timestamp(0, 1_500).unwrap())
+        );
+        assert_eq!(table.get_row(1).unwrap().values()[0], Value::Null);
+    }
+
+    #[test]
+    fn test_from_record_batch_rejects_out_of_range_values() {
+        let batch = RecordBatch::try_from_iter(vec![(
+            "n",
+            Arc::new(UInt64Array::from(vec![u64::MAX])) as ArrayRef,
+        )])
+        .unwrap();
+
+        assert!(Table::from_record_batch(&batch).is_err());
+    }
+}
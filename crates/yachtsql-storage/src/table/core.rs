@@ -69,6 +69,10 @@ impl Table {
         self.row_count == 0
     }
 
+    pub fn estimated_bytes(&self) -> usize {
+        self.columns.values().map(|c| c.estimated_bytes()).sum()
+    }
+
     pub fn column(&self, idx: usize) -> Option<&Column> {
         self.columns.get_index(idx).map(|(_, arc)| arc.as_ref())
     }
@@ -1940,4 +1944,13 @@ mod tests {
         assert_eq!(row1.values()[0], Value::Numeric(Decimal::from(2)));
         assert_eq!(row1.values()[1], Value::Int64(200));
     }
+
+    #[test]
+    fn test_estimated_bytes() {
+        let table = create_test_table();
+        let expected: usize = table.columns().values().map(|c| c.estimated_bytes()).sum();
+        assert!(expected > 0);
+        assert_eq!(table.estimated_bytes(), expected);
+        assert_eq!(Table::empty(create_test_schema()).estimated_bytes(), 0);
+    }
 }
@@ -1,3 +1,4 @@
+mod arrow_interop;
 mod core;
 
 pub use core::{Table, TableSchemaOps};
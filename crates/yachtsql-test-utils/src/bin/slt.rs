@@ -0,0 +1,74 @@
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::Parser;
+use yachtsql::YachtSQLEngine;
+use yachtsql_test_utils::slt::SltRunner;
+
+#[derive(Parser)]
+#[command(name = "yachtsql-slt")]
+#[command(about = "Run sqllogictest files against YachtSQL", long_about = None)]
+struct Cli {
+    /// Rewrite the expected results in place instead of checking them
+    #[arg(long)]
+    complete: bool,
+    /// .slt files, or directories to search for them
+    #[arg(required = true)]
+    paths: Vec<PathBuf>,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let mut files = Vec::new();
+    for path in &cli.paths {
+        if let Err(e) = collect_files(path, &mut files) {
+            eprintln!("{}: {e}", path.display());
+            return ExitCode::FAILURE;
+        }
+    }
+
+    let mut failed = 0;
+    for file in &files {
+        let mut runner = SltRunner::new(YachtSQLEngine::new().create_session());
+        let result = if cli.complete {
+            runner.complete_file(file).await
+        } else {
+            runner.run_file(file).await
+        };
+        match result {
+            Ok(()) => println!("ok {}", file.display()),
+            Err(e) => {
+                failed += 1;
+                println!("FAILED {e}");
+            }
+        }
+    }
+
+    println!("{} files, {} failed", files.len(), failed);
+    if failed == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Adds `path` if it is a file, or the `.slt` files below it if it is a
+/// directory, in name order.
+fn collect_files(path: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    if !path.is_dir() {
+        files.push(path.to_path_buf());
+        return Ok(());
+    }
+    let mut entries: Vec<PathBuf> = std::fs::read_dir(path)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<_, _>>()?;
+    entries.sort();
+    for entry in entries {
+        if entry.is_dir() || entry.extension().is_some_and(|e| e == "slt") {
+            collect_files(&entry, files)?;
+        }
+    }
+    Ok(())
+}
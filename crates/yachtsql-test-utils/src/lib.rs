@@ -8,6 +8,8 @@
 #![warn(rustdoc::broken_intra_doc_links)]
 #![allow(missing_docs)]
 
+pub mod slt;
+
 use yachtsql::{Record, Result, Schema, Table, Value, YachtSQLEngine, YachtSQLSession};
 
 pub fn setup_executor() -> YachtSQLSession {
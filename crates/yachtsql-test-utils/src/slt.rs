@@ -0,0 +1,571 @@
+//! A runner for [sqllogictest] files, so compatibility cases can be written as
+//! plain SQL text instead of Rust.
+//!
+//! A file is a sequence of records separated by blank lines. Lines starting with
+//! `#` are comments.
+//!
+//! ```text
+//! statement ok
+//! CREATE TABLE t (a INT64, b STRING)
+//!
+//! statement error Table not found
+//! SELECT * FROM missing
+//!
+//! query IT rowsort
+//! SELECT a, b FROM t
+//! ----
+//! 1 one
+//! 2 NULL
+//!
+//! query error division by zero
+//! SELECT 1 / 0
+//!
+//! hash-threshold 8
+//! ```
+//!
+//! - `statement ok` expects the statement to succeed; `statement error` expects
+//!   it to fail, with a message containing the rest of the line if there is one.
+//! - `query <types> [nosort|rowsort|valuesort] [label]` runs a query and compares
+//!   its rows, one line per row with values separated by spaces. Each character of
+//!   `<types>` describes a column and must match its type: `I` for `INT64`, `R`
+//!   for `FLOAT64`, `NUMERIC` and `BIGNUMERIC` (printed with three decimals), `T`
+//!   for any other type. `NULL` is printed as `NULL`, a string that is empty or
+//!   only whitespace as `(empty)`, and control characters such as newlines as
+//!   `@`.
+//!   Runs of whitespace in expected and actual rows compare as a single space.
+//!   `rowsort` sorts the rows and `valuesort` the individual values before
+//!   comparing, for queries without a defined order.
+//! - `hash-threshold N` compares results of more than `N` values by their MD5 hash,
+//!   written as `<count> values hashing to <hash>`. `0` turns hashing off.
+//! - `skipif yachtsql` and `onlyif <engine>` before a record skip it; `halt`
+//!   stops the file.
+//!
+//! Records run in order against one session and the first failing record stops
+//! the file. [`SltRunner::complete`] instead rewrites every expected result with
+//! what the engine returns.
+//!
+//! [sqllogictest]: https://www.sqlite.org/sqllogictest/doc/trunk/about.wiki
+
+use std::fmt;
+use std::ops::Range;
+use std::path::Path;
+
+use yachtsql::{QueryResult, Value, YachtSQLSession};
+
+/// The engine name matched by `skipif` and `onlyif`.
+pub const ENGINE_NAME: &str = "yachtsql";
+
+/// A record that did not parse, or whose result did not match.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SltError {
+    pub file: String,
+    /// 1-based line of the record header.
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for SltError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.file, self.line, self.message)
+    }
+}
+
+impl std::error::Error for SltError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortMode {
+    NoSort,
+    RowSort,
+    ValueSort,
+}
+
+impl SortMode {
+    fn name(self) -> &'static str {
+        match self {
+            SortMode::NoSort => "nosort",
+            SortMode::RowSort => "rowsort",
+            SortMode::ValueSort => "valuesort",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expect {
+    Ok,
+    /// An error whose message contains the pattern; an empty pattern matches any
+    /// error.
+    Error(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum RecordKind {
+    Statement {
+        sql: String,
+        expect: Expect,
+    },
+    Query {
+        sql: String,
+        types: String,
+        sort: SortMode,
+        label: Option<String>,
+        expect: Expect,
+        expected: Vec<String>,
+    },
+    HashThreshold(usize),
+    Halt,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Record {
+    kind: RecordKind,
+    skip: bool,
+    /// 1-based line of the header.
+    line: usize,
+    /// 0-based lines the record occupies, from its header to its last line.
+    span: Range<usize>,
+}
+
+/// Runs sqllogictest files against a [`YachtSQLSession`].
+pub struct SltRunner {
+    session: YachtSQLSession,
+    hash_threshold: usize,
+}
+
+impl SltRunner {
+    pub fn new(session: YachtSQLSession) -> Self {
+        Self {
+            session,
+            hash_threshold: 0,
+        }
+    }
+
+    /// Runs every record of `script`, stopping at the first one that fails.
+    /// `file` names the script in errors.
+    pub async fn run(&mut self, file: &str, script: &str) -> Result<(), SltError> {
+        for record in parse(file, script)? {
+            if record.skip {
+                continue;
+            }
+            let error = |message: String| SltError {
+                file: file.to_string(),
+                line: record.line,
+                message,
+            };
+            match &record.kind {
+                RecordKind::HashThreshold(n) => self.hash_threshold = *n,
+                RecordKind::Halt => break,
+                RecordKind::Statement { sql, expect } => {
+                    let result = self.session.execute_sql(sql).await;
+                    match (expect, result) {
+                        (Expect::Ok, Ok(_)) => {}
+                        (Expect::Ok, Err(e)) => {
+                            return Err(error(format!("statement failed: {e}\n{sql}")));
+                        }
+                        (Expect::Error(_), Ok(_)) => {
+                            return Err(error(format!(
+                                "statement succeeded, expected an error\n{sql}"
+                            )));
+                        }
+                        (Expect::Error(pattern), Err(e)) => {
+                            check_error(pattern, &e).map_err(error)?
+                        }
+                    }
+                }
+                RecordKind::Query {
+                    sql,
+                    types,
+                    sort,
+                    expect,
+                    expected,
+                    ..
+                } => {
+                    let result = self.query(sql).await;
+                    match (expect, result) {
+                        (Expect::Ok, Ok(result)) => {
+                            check_types(&result, types)
+                                .map_err(|message| error(format!("{message}\n{sql}")))?;
+                            let actual = self.format_result(&result, types, *sort);
+                            let normalized: Vec<String> =
+                                expected.iter().map(|line| normalize(line)).collect();
+                            if actual != normalized {
+                                return Err(error(format!(
+                                    "query result mismatch\n{sql}\n-- expected:\n{}\n-- actual:\n{}",
+                                    normalized.join("\n"),
+                                    actual.join("\n")
+                                )));
+                            }
+                        }
+                        (Expect::Ok, Err(e)) => {
+                            return Err(error(format!("query failed: {e}\n{sql}")));
+                        }
+                        (Expect::Error(_), Ok(_)) => {
+                            return Err(error(format!(
+                                "query succeeded, expected an error\n{sql}"
+                            )));
+                        }
+                        (Expect::Error(pattern), Err(e)) => {
+                            check_error(pattern, &e).map_err(error)?
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs every record of `script` and returns the script with each expected
+    /// result replaced by the actual one. Comments, conditions and skipped
+    /// records are kept as they are.
+    pub async fn complete(&mut self, file: &str, script: &str) -> Result<String, SltError> {
+        let lines: Vec<&str> = script.lines().collect();
+        let mut out = String::new();
+        let mut next_line = 0;
+        for record in parse(file, script)? {
+            for line in &lines[next_line..record.span.start] {
+                out.push_str(line);
+                out.push('\n');
+            }
+            next_line = record.span.end;
+            if record.skip {
+                for line in &lines[record.span.clone()] {
+                    out.push_str(line);
+                    out.push('\n');
+                }
+                continue;
+            }
+            match &record.kind {
+                RecordKind::HashThreshold(n) => {
+                    self.hash_threshold = *n;
+                    out.push_str(&format!("hash-threshold {n}\n"));
+                }
+                RecordKind::Halt => {
+                    for line in &lines[record.span.start..] {
+                        out.push_str(line);
+                        out.push('\n');
+                    }
+                    return Ok(out);
+                }
+                RecordKind::Statement { sql, expect } => {
+                    let header = match (expect, self.session.execute_sql(sql).await) {
+                        (_, Ok(_)) => "statement ok".to_string(),
+                        (Expect::Error(pattern), Err(e)) if e.to_string().contains(pattern) => {
+                            lines[record.span.start].trim_end().to_string()
+                        }
+                        (_, Err(e)) => format!("statement error {}", one_line(&e.to_string())),
+                    };
+                    out.push_str(&format!("{header}\n{sql}\n"));
+                }
+                RecordKind::Query {
+                    sql,
+                    types,
+                    sort,
+                    label,
+                    expect,
+                    ..
+                } => match (expect, self.query(sql).await) {
+                    (_, Ok(result)) => {
+                        let types = match check_types(&result, types) {
+                            Ok(()) => types.clone(),
+                            Err(_) => infer_types(&result),
+                        };
+                        let mut header = format!("query {types}");
+                        if *sort != SortMode::NoSort || label.is_some() {
+                            header.push(' ');
+                            header.push_str(sort.name());
+                        }
+                        if let Some(label) = label {
+                            header.push(' ');
+                            header.push_str(label);
+                        }
+                        out.push_str(&format!("{header}\n{sql}\n----\n"));
+                        for line in self.format_result(&result, &types, *sort) {
+                            out.push_str(&line);
+                            out.push('\n');
+                        }
+                    }
+                    (Expect::Error(pattern), Err(e)) if e.to_string().contains(pattern) => {
+                        out.push_str(&format!("{}\n{sql}\n", lines[record.span.start].trim_end()));
+                    }
+                    (_, Err(e)) => {
+                        out.push_str(&format!(
+                            "query error {}\n{sql}\n",
+                            one_line(&e.to_string())
+                        ));
+                    }
+                },
+            }
+        }
+        for line in &lines[next_line..] {
+            out.push_str(line);
+            out.push('\n');
+        }
+        Ok(out)
+    }
+
+    /// Runs the `.slt` file at `path`.
+    pub async fn run_file(&mut self, path: impl AsRef<Path>) -> Result<(), SltError> {
+        let path = path.as_ref();
+        let script = read_file(path)?;
+        self.run(&path.display().to_string(), &script).await
+    }
+
+    /// Rewrites the expected results of the `.slt` file at `path`, see
+    /// [`complete`](Self::complete).
+    pub async fn complete_file(&mut self, path: impl AsRef<Path>) -> Result<(), SltError> {
+        let path = path.as_ref();
+        let script = read_file(path)?;
+        let completed = self.complete(&path.display().to_string(), &script).await?;
+        std::fs::write(path, completed).map_err(|e| SltError {
+            file: path.display().to_string(),
+            line: 0,
+            message: format!("failed to write file: {e}"),
+        })
+    }
+
+    async fn query(&self, sql: &str) -> yachtsql::Result<QueryResult> {
+        self.session.execute_sql(sql).await?.to_query_result()
+    }
+
+    /// The lines a query result is compared by: one per row, or a single hash line
+    /// when the result has more values than the hash threshold.
+    fn format_result(&self, result: &QueryResult, types: &str, sort: SortMode) -> Vec<String> {
+        let types: Vec<char> = types.chars().collect();
+        let mut rows: Vec<Vec<String>> = result
+            .rows
+            .iter()
+            .map(|row| {
+                row.values()
+                    .iter()
+                    .enumerate()
+                    .map(|(i, value)| format_value(value, types.get(i).copied().unwrap_or('T')))
+                    .collect()
+            })
+            .collect();
+
+        match sort {
+            SortMode::NoSort => {}
+            SortMode::RowSort => rows.sort(),
+            SortMode::ValueSort => {
+                let width = result.schema.len().max(1);
+                let mut values: Vec<String> = rows.into_iter().flatten().collect();
+                values.sort();
+                rows = values.chunks(width).map(<[String]>::to_vec).collect();
+            }
+        }
+
+        let count: usize = rows.iter().map(Vec::len).sum();
+        if self.hash_threshold > 0 && count > self.hash_threshold {
+            let mut context = md5::Context::new();
+            for value in rows.iter().flatten() {
+                context.consume(value.as_bytes());
+                context.consume(b"\n");
+            }
+            return vec![format!("{count} values hashing to {:x}", context.compute())];
+        }
+        rows.into_iter()
+            .map(|row| normalize(&row.join(" ")))
+            .collect()
+    }
+}
+
+fn format_value(value: &Value, column_type: char) -> String {
+    match value {
+        Value::Null => "NULL".to_string(),
+        Value::String(s) if s.trim().is_empty() => "(empty)".to_string(),
+        value if column_type == 'R' => match value.as_f64() {
+            Some(f) => format!("{f:.3}"),
+            None => value.to_string(),
+        },
+        value => value
+            .to_string()
+            .chars()
+            .map(|c| if c.is_control() { '@' } else { c })
+            .collect(),
+    }
+}
+
+/// The type letter that describes a column of `data_type`.
+fn type_letter(data_type: &str) -> char {
+    match data_type {
+        "INT64" => 'I',
+        "FLOAT64" | "NUMERIC" | "BIGNUMERIC" => 'R',
+        _ => 'T',
+    }
+}
+
+fn infer_types(result: &QueryResult) -> String {
+    result
+        .schema
+        .iter()
+        .map(|column| type_letter(&column.data_type))
+        .collect()
+}
+
+fn check_types(result: &QueryResult, types: &str) -> Result<(), String> {
+    if result.schema.len() != types.chars().count() {
+        return Err(format!(
+            "query returned {} columns, expected {}",
+            result.schema.len(),
+            types.chars().count()
+        ));
+    }
+    for (i, (column, letter)) in result.schema.iter().zip(types.chars()).enumerate() {
+        let expected = type_letter(&column.data_type);
+        if letter != expected {
+            return Err(format!(
+                "column {} is {}, which has type {expected}, not {letter}",
+                i + 1,
+                column.data_type
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn check_error(pattern: &str, error: &yachtsql::Error) -> Result<(), String> {
+    let message = error.to_string();
+    if message.contains(pattern) {
+        Ok(())
+    } else {
+        Err(format!(
+            "expected an error containing {pattern:?}, got: {message}"
+        ))
+    }
+}
+
+/// Collapses runs of whitespace, so expected rows may be aligned by hand.
+fn normalize(line: &str) -> String {
+    line.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn one_line(message: &str) -> String {
+    normalize(message)
+}
+
+fn read_file(path: &Path) -> Result<String, SltError> {
+    std::fs::read_to_string(path).map_err(|e| SltError {
+        file: path.display().to_string(),
+        line: 0,
+        message: format!("failed to read file: {e}"),
+    })
+}
+
+fn parse(file: &str, script: &str) -> Result<Vec<Record>, SltError> {
+    let lines: Vec<&str> = script.lines().collect();
+    let mut records = Vec::new();
+    let mut skip = false;
+    let mut i = 0;
+    while i < lines.len() {
+        let start = i;
+        let line = lines[i].trim();
+        i += 1;
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let error = |message: String| SltError {
+            file: file.to_string(),
+            line: start + 1,
+            message,
+        };
+
+        let mut tokens = line.split_whitespace();
+        let keyword = tokens.next().unwrap_or_default();
+        let rest = line[keyword.len()..].trim();
+        let kind = match keyword {
+            "skipif" | "onlyif" => {
+                let engine = tokens
+                    .next()
+                    .ok_or_else(|| error(format!("{keyword} needs an engine name")))?;
+                skip |= (keyword == "skipif") == engine.eq_ignore_ascii_case(ENGINE_NAME);
+                continue;
+            }
+            "hash-threshold" => RecordKind::HashThreshold(
+                rest.parse()
+                    .map_err(|_| error(format!("invalid hash-threshold: {rest}")))?,
+            ),
+            "halt" => RecordKind::Halt,
+            "statement" => {
+                let expect = match tokens.next() {
+                    Some("ok") => Expect::Ok,
+                    Some("error") => Expect::Error(rest["error".len()..].trim().to_string()),
+                    _ => return Err(error(format!("expected statement ok or error: {line}"))),
+                };
+                let sql = take_sql(&lines, &mut i);
+                if sql.is_empty() {
+                    return Err(error("statement has no SQL".to_string()));
+                }
+                RecordKind::Statement { sql, expect }
+            }
+            "query" => {
+                let (types, sort, label, expect) = match tokens.next() {
+                    Some("error") => (
+                        String::new(),
+                        SortMode::NoSort,
+                        None,
+                        Expect::Error(rest["error".len()..].trim().to_string()),
+                    ),
+                    Some(types) => {
+                        let sort = match tokens.next() {
+                            None | Some("nosort") => SortMode::NoSort,
+                            Some("rowsort") => SortMode::RowSort,
+                            Some("valuesort") => SortMode::ValueSort,
+                            Some(other) => {
+                                return Err(error(format!("unknown sort mode: {other}")));
+                            }
+                        };
+                        if let Some(c) = types.chars().find(|c| !matches!(c, 'I' | 'R' | 'T')) {
+                            return Err(error(format!("unknown column type {c:?} in {types}")));
+                        }
+                        let label = tokens.next().map(str::to_string);
+                        (types.to_string(), sort, label, Expect::Ok)
+                    }
+                    None => return Err(error("query needs a type string".to_string())),
+                };
+                let sql = take_sql(&lines, &mut i);
+                if sql.is_empty() {
+                    return Err(error("query has no SQL".to_string()));
+                }
+                let mut expected = Vec::new();
+                if lines.get(i).is_some_and(|line| line.trim() == "----") {
+                    i += 1;
+                    while let Some(line) = lines.get(i).filter(|line| !line.trim().is_empty()) {
+                        expected.push(line.to_string());
+                        i += 1;
+                    }
+                }
+                RecordKind::Query {
+                    sql,
+                    types,
+                    sort,
+                    label,
+                    expect,
+                    expected,
+                }
+            }
+            _ => return Err(error(format!("unknown record: {line}"))),
+        };
+
+        records.push(Record {
+            kind,
+            skip,
+            line: start + 1,
+            span: start..i,
+        });
+        skip = false;
+    }
+    Ok(records)
+}
+
+/// Takes the SQL lines of a record, up to a blank line, a `----` separator or
+/// the end of the file.
+fn take_sql(lines: &[&str], i: &mut usize) -> String {
+    let mut sql = Vec::new();
+    while let Some(line) = lines
+        .get(*i)
+        .filter(|line| !line.trim().is_empty() && line.trim() != "----")
+    {
+        sql.push(*line);
+        *i += 1;
+    }
+    sql.join("\n")
+}
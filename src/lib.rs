@@ -13,7 +13,9 @@
 //! SQL String → Parser → LogicalPlan → Optimizer → PhysicalPlan → Executor → Result
 //! ```
 //!
-//! The `YachtSQLEngine` creates isolated sessions with their own catalog and state.
+//! The `YachtSQLEngine` creates isolated sessions with their own catalog and state,
+//! or, with [`YachtSQLEngine::with_shared_catalog`], sessions that share one catalog.
+//! [`YachtSQLEngine::open`] additionally persists the shared catalog to a directory.
 //!
 //! # Example
 //!
@@ -45,22 +47,35 @@
 //! }
 //! ```
 
+use std::sync::Arc;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
 pub use yachtsql_common::error::{Error, Result};
 pub use yachtsql_common::result::{ColumnInfo, QueryResult, Row};
-pub use yachtsql_common::types::{DataType, Value};
+pub use yachtsql_common::types::{DataType, FunctionSignature, Value};
 pub use yachtsql_executor::{
-    AsyncQueryExecutor, ConcurrentCatalog, ConcurrentSession, Record, Table,
+    Accumulator, AsyncQueryExecutor, CancellationHandle, ConcurrentCatalog, ConcurrentSession,
+    DEFAULT_JOB_HISTORY_LIMIT, DEFAULT_STREAM_BATCH_SIZE, DryRunResult, DurableStore, JobError,
+    JobHistory, JobRecord, NativeAggregateFunction, NativeScalarFunction, NativeTableFunction,
+    PlanReady, PreparedStatement, QueryFinish, QueryJob, QueryObserver, QueryParameter, QueryStart,
+    QueryStream, Record, ReferencedTable, ScriptResult, StatementResult, Table, TableProvider,
 };
-pub use yachtsql_ir::LogicalPlan;
+pub use yachtsql_ir::{Expr, LogicalPlan};
 pub use yachtsql_optimizer::PhysicalPlan;
-pub use yachtsql_parser::{CatalogProvider, Planner, parse_and_plan, parse_sql};
-pub use yachtsql_storage::{Field, FieldMode, Schema};
+pub use yachtsql_parser::{
+    CatalogProvider, Planner, ScriptStatement, fingerprint_sql, parse_and_plan, parse_sql,
+    split_script,
+};
+pub use yachtsql_storage::{Column, Field, FieldMode, Schema, arrow};
 
-/// Factory for creating isolated SQL sessions.
+/// Factory for creating SQL sessions.
 ///
-/// `YachtSQLEngine` is lightweight and can be shared across threads. Each session
-/// created from an engine has its own isolated catalog (tables, views, functions)
-/// but shares the global query plan cache for better performance.
+/// `YachtSQLEngine` is lightweight and can be shared across threads. By default each
+/// session created from an engine has its own isolated catalog (tables, views,
+/// functions). An engine created with [`with_shared_catalog`](Self::with_shared_catalog)
+/// instead hands every session the same permanent datasets, like connections to
+/// one BigQuery project.
 ///
 /// # Example
 ///
@@ -68,22 +83,184 @@ pub use yachtsql_storage::{Field, FieldMode, Schema};
 /// let engine = YachtSQLEngine::new();
 /// let session = engine.create_session();
 /// ```
-pub struct YachtSQLEngine;
+pub struct YachtSQLEngine {
+    shared_catalog: Option<ConcurrentCatalog>,
+    durable: Option<Arc<DurableStore>>,
+    observers: Vec<Arc<dyn QueryObserver>>,
+    jobs: JobHistory,
+}
 
 impl YachtSQLEngine {
-    /// Creates a new engine instance.
+    /// Creates a new engine instance whose sessions are isolated from each other.
     pub fn new() -> Self {
-        Self
+        Self {
+            shared_catalog: None,
+            durable: None,
+            observers: Vec::new(),
+            jobs: JobHistory::default(),
+        }
+    }
+
+    /// Creates an engine whose sessions share one catalog.
+    ///
+    /// Permanent tables, views, functions, procedures and datasets created in any
+    /// session are visible to all of them. Each session still follows BigQuery
+    /// session semantics for its own state: variables, `CREATE TEMP TABLE` and
+    /// `CREATE TEMP FUNCTION` objects are private to the session. Temporary tables
+    /// can be referenced unqualified or through the `_SESSION` dataset
+    /// (`_SESSION.my_table`) and are discarded when the session is dropped.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let engine = YachtSQLEngine::with_shared_catalog();
+    /// let writer = engine.create_session();
+    /// let reader = engine.create_session();
+    ///
+    /// writer.execute_sql("CREATE TABLE ds.users (id INT64)").await?;
+    /// writer.execute_sql("CREATE TEMP TABLE scratch (id INT64)").await?;
+    ///
+    /// reader.execute_sql("SELECT * FROM ds.users").await?; // visible
+    /// assert!(reader.execute_sql("SELECT * FROM scratch").await.is_err());
+    /// ```
+    pub fn with_shared_catalog() -> Self {
+        Self {
+            shared_catalog: Some(ConcurrentCatalog::new()),
+            durable: None,
+            observers: Vec::new(),
+            jobs: JobHistory::default(),
+        }
+    }
+
+    /// Opens a durable engine backed by the directory at `path`.
+    ///
+    /// Sessions share one catalog, as with [`with_shared_catalog`](Self::with_shared_catalog),
+    /// and every statement that changes it is appended to a write-ahead log in `path`
+    /// before the statement returns. The log is replayed on top of the most recent
+    /// checkpoint when the directory is opened again, and is folded into a new
+    /// checkpoint once it grows large or when [`checkpoint`](Self::checkpoint) is called.
+    ///
+    /// # Crash consistency
+    ///
+    /// - A statement that returned `Ok` is on disk (the log is synced after every
+    ///   commit) and survives the process being killed at any later point.
+    /// - A statement interrupted by a crash is either fully recovered or not at all;
+    ///   a partly written log record is detected by its checksum and discarded.
+    /// - Statements inside `BEGIN TRANSACTION ... COMMIT` are logged when the
+    ///   transaction ends, so a crash before `COMMIT` recovers none of them.
+    ///   Checkpoints wait until no session has a transaction open, and a script or
+    ///   `CALL`, which is made durable by a checkpoint, fails while another session
+    ///   has one open.
+    /// - A statement whose log record cannot be written returns an error. Table
+    ///   writes are logged before they are applied and are dropped, and a `COMMIT`
+    ///   is rolled back; after any other change, such as DDL, the engine refuses
+    ///   further writes until the directory is opened again.
+    /// - `UPDATE`, `DELETE`, `MERGE`, `TRUNCATE` and `LOAD DATA` log the whole
+    ///   table they change, so frequent updates of a large table grow the log
+    ///   quickly; it is checkpointed once it is larger than the last checkpoint.
+    /// - Temporary tables and functions, session variables, and changes made
+    ///   directly through [`YachtSQLSession::catalog`] are not logged. Catalog API
+    ///   changes become durable at the next checkpoint.
+    ///
+    /// Writing statements are serialized across the engine's sessions while the
+    /// log is in use; queries run concurrently as usual. The directory stays locked
+    /// until the engine and all of its sessions are dropped.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let engine = YachtSQLEngine::open("./data").await?;
+    /// let session = engine.create_session();
+    /// session.execute_sql("CREATE TABLE IF NOT EXISTS events (id INT64)").await?;
+    /// session.execute_sql("INSERT INTO events VALUES (1)").await?;
+    /// // the row is still there the next time ./data is opened
+    /// ```
+    pub async fn open(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        let store = DurableStore::open(path).await?;
+        Ok(Self {
+            shared_catalog: Some(store.catalog().session_scope()),
+            durable: Some(Arc::new(store)),
+            observers: Vec::new(),
+            jobs: JobHistory::default(),
+        })
+    }
+
+    /// Registers `observer` on every session this engine creates from now on.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let recorder = Arc::new(QueryRecorder::default());
+    /// let engine = YachtSQLEngine::new().with_observer(recorder.clone());
+    /// ```
+    pub fn with_observer(mut self, observer: Arc<dyn QueryObserver>) -> Self {
+        self.observers.push(observer);
+        self
+    }
+
+    /// Keeps at most `limit` jobs in the engine's history instead of
+    /// [`DEFAULT_JOB_HISTORY_LIMIT`]; see [`job_history`](Self::job_history).
+    pub fn with_job_history_limit(mut self, limit: usize) -> Self {
+        self.jobs = JobHistory::new(limit);
+        self
+    }
+
+    /// The statements run by this engine's sessions, most recent last.
+    ///
+    /// Sessions can query the same history through `INFORMATION_SCHEMA.JOBS`,
+    /// which lists one row per statement with its type, timing, referenced and
+    /// destination tables, affected rows, estimated bytes processed and error.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// session.execute_sql("DELETE FROM users WHERE id = 1").await?;
+    /// let jobs = session
+    ///     .execute_sql(
+    ///         "SELECT statement_type, num_dml_affected_rows
+    ///          FROM INFORMATION_SCHEMA.JOBS
+    ///          ORDER BY job_id DESC
+    ///          LIMIT 1",
+    ///     )
+    ///     .await?;
+    /// ```
+    pub fn job_history(&self) -> &JobHistory {
+        &self.jobs
     }
 
-    /// Creates a new isolated session.
+    /// Writes the whole catalog of a durable engine to a new checkpoint and
+    /// truncates its write-ahead log, so the next [`open`](Self::open) has nothing
+    /// to replay. While a session has a transaction open, the checkpoint is
+    /// deferred until after it ends. Does nothing for in-memory engines.
+    pub async fn checkpoint(&self) -> Result<()> {
+        match &self.durable {
+            Some(store) => store.checkpoint().await,
+            None => Ok(()),
+        }
+    }
+
+    /// Creates a new session.
     ///
-    /// Each session has its own catalog, meaning tables created in one session
-    /// are not visible to other sessions. This is useful for parallel test execution.
+    /// For an engine created with [`new`](Self::new), each session has its own
+    /// catalog, meaning tables created in one session are not visible to other
+    /// sessions. This is useful for parallel test execution.
     pub fn create_session(&self) -> YachtSQLSession {
-        YachtSQLSession {
-            executor: AsyncQueryExecutor::new(),
+        let executor = match &self.shared_catalog {
+            Some(catalog) => AsyncQueryExecutor::from_catalog_and_session(
+                catalog.session_scope(),
+                ConcurrentSession::new(),
+            ),
+            None => AsyncQueryExecutor::new(),
+        };
+        let executor = match &self.durable {
+            Some(store) => executor.with_durable_store(Arc::clone(store)),
+            None => executor,
         }
+        .with_job_history(self.jobs.clone());
+        for observer in &self.observers {
+            executor.add_observer(Arc::clone(observer));
+        }
+        YachtSQLSession { executor }
     }
 }
 
@@ -103,13 +280,18 @@ impl Default for YachtSQLEngine {
 ///
 /// # Query Methods
 ///
-/// The session provides three methods for executing SQL, each returning different result types:
+/// The session provides eight methods for executing SQL, each returning different result types:
 ///
 /// | Method | Returns | Use Case |
 /// |--------|---------|----------|
 /// | [`execute_sql`](Self::execute_sql) | `Table` | Low-level access to columnar data |
 /// | [`query`](Self::query) | `QueryResult` | Row-based results, easy to serialize |
+/// | [`query_as`](Self::query_as) | `Vec<T>` | Rows decoded into your own serde types |
 /// | [`run`](Self::run) | `u64` | DDL/DML statements where you only need the row count |
+/// | [`execute_script`](Self::execute_script) | `ScriptResult` | Multi-statement scripts separated by `;` |
+/// | [`query_with_params`](Self::query_with_params) | `QueryResult` | Queries with typed `@name` or `?` parameters |
+/// | [`query_stream`](Self::query_stream) | `QueryStream` | Large results consumed batch by batch |
+/// | [`start_query`](Self::start_query) | `QueryJob` | Background queries that can be cancelled |
 ///
 /// # Example
 ///
@@ -131,10 +313,31 @@ pub struct YachtSQLSession {
 }
 
 impl YachtSQLSession {
-    /// Creates a new session with an empty catalog.
+    /// Creates a new session with an empty catalog and its own job history.
     pub fn new() -> Self {
         Self {
-            executor: AsyncQueryExecutor::new(),
+            executor: AsyncQueryExecutor::new().with_job_history(JobHistory::default()),
+        }
+    }
+
+    /// Creates a session over an existing catalog, such as one restored with
+    /// [`ConcurrentCatalog::load_from`].
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// fixture.catalog().save_to("fixture.catalog")?;
+    ///
+    /// let catalog = ConcurrentCatalog::load_from("fixture.catalog")?;
+    /// let session = YachtSQLSession::with_catalog(catalog);
+    /// ```
+    pub fn with_catalog(catalog: ConcurrentCatalog) -> Self {
+        Self {
+            executor: AsyncQueryExecutor::from_catalog_and_session(
+                catalog,
+                ConcurrentSession::new(),
+            )
+            .with_job_history(JobHistory::default()),
         }
     }
 
@@ -167,6 +370,462 @@ impl YachtSQLSession {
         table.to_query_result()
     }
 
+    /// Executes a query and deserializes each row into `T`.
+    ///
+    /// Struct fields are matched to result columns by name, so their order does not
+    /// matter; tuples are filled by position, and a single-column result can be
+    /// read straight into a scalar type. `STRUCT` columns map to nested structs,
+    /// `ARRAY` columns to `Vec`, nullable columns to `Option`, `NUMERIC` and
+    /// `BIGNUMERIC` to `rust_decimal::Decimal`, and `DATE`, `TIME`, `DATETIME` and
+    /// `TIMESTAMP` to the corresponding `chrono` types. A row that does not fit `T`
+    /// fails with [`Error::SchemaMismatch`] naming the offending column.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// #[derive(Deserialize)]
+    /// struct User {
+    ///     id: i64,
+    ///     name: String,
+    ///     signed_up: chrono::NaiveDate,
+    ///     tags: Vec<String>,
+    /// }
+    ///
+    /// let users: Vec<User> = session.query_as("SELECT * FROM users").await?;
+    /// let counts: Vec<i64> = session.query_as("SELECT COUNT(*) FROM users").await?;
+    /// ```
+    pub async fn query_as<T: DeserializeOwned>(&self, sql: &str) -> Result<Vec<T>> {
+        self.query(sql).await?.rows_as()
+    }
+
+    /// Appends `rows` to the table `table_name` and returns the number of rows added.
+    ///
+    /// Each row is serialized to a `STRUCT` whose fields are matched to the table's
+    /// columns by name, converted to the column types, and appended as a block
+    /// without building any SQL text. Columns missing from `T` (or `None` fields)
+    /// are `NULL`; a field with no matching column, or a value that cannot be
+    /// stored in its column, fails with [`Error::SchemaMismatch`] and nothing is
+    /// inserted.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// #[derive(Serialize)]
+    /// struct Event {
+    ///     id: i64,
+    ///     amount: rust_decimal::Decimal,
+    ///     at: chrono::DateTime<chrono::Utc>,
+    /// }
+    ///
+    /// session.insert_rows("events", &events).await?;
+    /// ```
+    pub async fn insert_rows<T: Serialize>(&self, table_name: &str, rows: &[T]) -> Result<u64> {
+        let rows = rows
+            .iter()
+            .map(yachtsql_common::mapping::to_value)
+            .collect::<Result<Vec<_>>>()?;
+        self.executor.insert_rows(table_name, rows).await
+    }
+
+    /// Like [`insert_rows`](Self::insert_rows), for rows that are already
+    /// `STRUCT` [`Value`]s.
+    pub async fn insert_values(&self, table_name: &str, rows: Vec<Value>) -> Result<u64> {
+        self.executor.insert_rows(table_name, rows).await
+    }
+
+    /// Executes a parameterized query and returns the result as a [`QueryResult`].
+    ///
+    /// Parameters are referenced either by name (`@user_id`) or by position (`?`),
+    /// but not both in the same query. Each [`QueryParameter`] carries a BigQuery
+    /// type, which defaults to the type of its value and can be set explicitly with
+    /// [`QueryParameter::with_type`] (for example to bind a typed `NULL`). Values are
+    /// bound at execution time rather than spliced into the SQL text, so arrays and
+    /// structs can be passed directly.
+    ///
+    /// Fails if the query references a parameter that was not provided (session
+    /// variables are not consulted), if a named parameter is not used by the query,
+    /// if the number of positional values does not match, or if a parameter's type
+    /// does not fit the expression it is used in.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let result = session
+    ///     .query_with_params(
+    ///         "SELECT name FROM users WHERE id = @user_id AND name IN UNNEST(@names)",
+    ///         &[
+    ///             QueryParameter::named("user_id", Value::int64(1)),
+    ///             QueryParameter::named(
+    ///                 "names",
+    ///                 Value::array(vec![Value::string("Alice"), Value::string("Bob")]),
+    ///             ),
+    ///         ],
+    ///     )
+    ///     .await?;
+    ///
+    /// let result = session
+    ///     .query_with_params("SELECT ? + ?", &[
+    ///         QueryParameter::positional(Value::int64(1)),
+    ///         QueryParameter::positional(Value::int64(2)),
+    ///     ])
+    ///     .await?;
+    /// ```
+    pub async fn query_with_params(
+        &self,
+        sql: &str,
+        params: &[QueryParameter],
+    ) -> Result<QueryResult> {
+        let table = self.executor.execute_sql_with_params(sql, params).await?;
+        table.to_query_result()
+    }
+
+    /// Executes a parameterized statement and returns the result as a [`Table`].
+    ///
+    /// Parameters work the same way as in [`query_with_params`](Self::query_with_params).
+    pub async fn execute_sql_with_params(
+        &self,
+        sql: &str,
+        params: &[QueryParameter],
+    ) -> Result<Table> {
+        self.executor.execute_sql_with_params(sql, params).await
+    }
+
+    /// Executes a query and returns its result as an async stream of [`Table`] batches.
+    ///
+    /// Each batch holds at most [`DEFAULT_STREAM_BATCH_SIZE`] rows. Queries made of
+    /// filters and projections over a single table (optionally with `LIMIT`/`OFFSET`)
+    /// are evaluated batch by batch as the caller consumes them, so memory stays
+    /// bounded and dropping the stream stops execution. Every other query,
+    /// including joins, aggregations, `ORDER BY` and set operations such as
+    /// `UNION ALL`, is executed in full first and its result then handed out in
+    /// batches, so its memory use is that of [`execute_sql`](Self::execute_sql).
+    ///
+    /// Streams are subject to `@@job_timeout_ms`, and [`QueryStream::cancel`] stops
+    /// the query at the next batch, failing the stream with [`Error::QueryCancelled`].
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use futures::StreamExt;
+    ///
+    /// let mut stream = session.query_stream("SELECT * FROM events").await?;
+    /// while let Some(batch) = stream.next().await {
+    ///     let batch = batch?;
+    ///     println!("{} rows", batch.row_count());
+    /// }
+    /// ```
+    pub async fn query_stream(&self, sql: &str) -> Result<QueryStream> {
+        self.executor
+            .execute_sql_stream(sql, DEFAULT_STREAM_BATCH_SIZE)
+            .await
+    }
+
+    /// Like [`query_stream`](Self::query_stream), with a caller-chosen batch size.
+    pub async fn query_stream_with_batch_size(
+        &self,
+        sql: &str,
+        batch_size: usize,
+    ) -> Result<QueryStream> {
+        self.executor.execute_sql_stream(sql, batch_size).await
+    }
+
+    /// Parses, plans, and optimizes a statement once for repeated execution.
+    ///
+    /// The returned [`PreparedStatement`] keeps the optimized plan, its execution
+    /// hints and the tables it locks, so each call to [`PreparedStatement::execute`]
+    /// or [`PreparedStatement::query`] only binds parameters and runs the plan. Parameters use the same `@name` / `?` syntax
+    /// as [`query_with_params`](Self::query_with_params).
+    ///
+    /// DDL on a table, view, or function the statement depends on invalidates the
+    /// stored plan; the next execution transparently re-plans against the new
+    /// catalog state.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let lookup = session.prepare("SELECT name FROM users WHERE id = ?")?;
+    /// for id in 1..=1000 {
+    ///     let result = lookup
+    ///         .query(&[QueryParameter::positional(Value::int64(id))])
+    ///         .await?;
+    /// }
+    /// ```
+    pub fn prepare(&self, sql: &str) -> Result<PreparedStatement> {
+        self.executor.prepare(sql)
+    }
+
+    /// Validates a statement without running it, like a BigQuery dry run.
+    ///
+    /// The statement is parsed, planned and optimized against the current catalog,
+    /// so syntax errors and unknown tables are reported as they would be by
+    /// [`execute_sql`](Self::execute_sql). Nothing is executed and
+    /// the catalog is left unchanged. The result lists the output columns, the
+    /// statement type, the tables and columns the statement refers to, and the
+    /// estimated size of those columns.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let dry_run = session.dry_run("SELECT name FROM users WHERE id > 10")?;
+    /// assert_eq!(dry_run.statement_type, "SELECT");
+    /// assert_eq!(dry_run.referenced_tables[0].columns, vec!["id", "name"]);
+    /// println!("would scan {} bytes", dry_run.total_bytes_processed);
+    /// ```
+    pub fn dry_run(&self, sql: &str) -> Result<DryRunResult> {
+        self.executor.dry_run(sql)
+    }
+
+    /// Starts executing SQL in the background and returns a [`QueryJob`] for it.
+    ///
+    /// The job can be cancelled at any time with [`QueryJob::cancel`] (or through a
+    /// [`CancellationHandle`] obtained from [`QueryJob::cancellation_handle`] and
+    /// shared with another task). Cancellation is cooperative: long-running joins,
+    /// aggregations, recursive CTEs, and scripting loops check for it as they run,
+    /// and the query then fails with [`Error::QueryCancelled`]. Must be called from
+    /// within a Tokio runtime.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let job = session.start_query("SELECT COUNT(*) FROM a CROSS JOIN b");
+    /// job.cancel();
+    /// assert!(matches!(job.wait().await, Err(Error::QueryCancelled)));
+    /// ```
+    pub fn start_query(&self, sql: &str) -> QueryJob {
+        self.executor.start_query(sql)
+    }
+
+    /// Executes SQL, stopping with [`Error::QueryCancelled`] once `cancel` is cancelled.
+    pub async fn execute_sql_with_cancellation(
+        &self,
+        sql: &str,
+        cancel: &CancellationHandle,
+    ) -> Result<Table> {
+        self.executor
+            .execute_sql_with_cancellation(sql, cancel)
+            .await
+    }
+
+    /// Sets the maximum time a single statement may run, like BigQuery's `job_timeout_ms`.
+    ///
+    /// Statements running longer fail with [`Error::QueryTimeout`]. `None` removes the
+    /// limit. The same setting is available from SQL as `SET @@job_timeout_ms = 5000`.
+    pub fn set_job_timeout(&self, timeout: Option<std::time::Duration>) {
+        let value = match timeout {
+            Some(timeout) => Value::int64(timeout.as_millis().min(i64::MAX as u128) as i64),
+            None => Value::null(),
+        };
+        self.executor
+            .session()
+            .set_system_variable(yachtsql_executor::JOB_TIMEOUT_VARIABLE, value);
+    }
+
+    /// Limits the memory a single statement may use for intermediate results.
+    ///
+    /// Hash joins, aggregations, sorts, window functions, and materialized CTEs
+    /// account for the rows they hold; a statement that needs more than `limit_bytes`
+    /// fails with [`Error::ResourcesExceeded`] instead of exhausting process memory.
+    /// The accounting is an estimate of the in-memory size of the values involved.
+    /// `None` removes the limit. The same setting is available from SQL as
+    /// `SET @@memory_limit_bytes = 104857600`.
+    pub fn set_memory_limit(&self, limit_bytes: Option<usize>) {
+        let value = match limit_bytes {
+            Some(limit) => Value::int64(limit.min(i64::MAX as usize) as i64),
+            None => Value::null(),
+        };
+        self.executor
+            .session()
+            .set_system_variable(yachtsql_executor::MEMORY_LIMIT_VARIABLE, value);
+    }
+
+    /// Registers a Rust function that queries in this session can call as `name(...)`.
+    ///
+    /// Calls are checked against `signature` when a query is planned, and the
+    /// function receives its arguments converted to the declared types (`NULL`s are
+    /// passed through). To add a columnar fast path, build a [`NativeScalarFunction`]
+    /// with [`NativeScalarFunction::with_columnar`] and register it through
+    /// [`ConcurrentCatalog::register_scalar_function`].
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// session.register_scalar_function(
+    ///     "risk_score",
+    ///     FunctionSignature::new(vec![DataType::Int64, DataType::String], DataType::Float64),
+    ///     |args| Ok(Value::float64(score(&args[0], &args[1]))),
+    /// )?;
+    /// let result = session.execute_sql("SELECT risk_score(id, region) FROM accounts").await?;
+    /// ```
+    pub fn register_scalar_function(
+        &self,
+        name: &str,
+        signature: FunctionSignature,
+        function: impl Fn(&[Value]) -> Result<Value> + Send + Sync + 'static,
+    ) -> Result<()> {
+        self.executor
+            .catalog()
+            .register_scalar_function(name, NativeScalarFunction::new(signature, function))
+    }
+
+    /// Registers a Rust aggregate function that queries in this session can call as
+    /// `name(...)`, including with `DISTINCT` and as a window function.
+    ///
+    /// `factory` creates an empty [`Accumulator`] for each group or window frame.
+    /// Calls are checked against `signature` when a query is planned.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// session.register_aggregate_function(
+    ///     "geo_mean",
+    ///     FunctionSignature::new(vec![DataType::Float64], DataType::Float64),
+    ///     || Box::new(GeoMean::default()),
+    /// )?;
+    /// let result = session
+    ///     .execute_sql("SELECT region, geo_mean(price) FROM sales GROUP BY region")
+    ///     .await?;
+    /// ```
+    pub fn register_aggregate_function(
+        &self,
+        name: &str,
+        signature: FunctionSignature,
+        factory: impl Fn() -> Box<dyn Accumulator> + Send + Sync + 'static,
+    ) -> Result<()> {
+        self.executor
+            .catalog()
+            .register_aggregate_function(name, NativeAggregateFunction::new(signature, factory))
+    }
+
+    /// Registers a Rust table-valued function that queries in this session can
+    /// call in a `FROM` clause as `name(...)`.
+    ///
+    /// `function` receives the arguments converted to `arguments` and returns a
+    /// [`Table`] whose columns match `schema` by position. Arguments may refer to
+    /// columns of earlier `FROM` items, as in `FROM t, name(t.x)`, in which case
+    /// `function` is called once per row.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let mut schema = Schema::new();
+    /// schema.add_field(Field::nullable("n", DataType::Int64));
+    /// session.register_table_function("numbers", vec![DataType::Int64], schema.clone(), move |args| {
+    ///     let records = (1..=args[0].as_i64().unwrap_or(0))
+    ///         .map(|n| Record::from_values(vec![Value::Int64(n)]))
+    ///         .collect();
+    ///     Table::from_records(schema.clone(), records)
+    /// })?;
+    /// let result = session.execute_sql("SELECT n FROM numbers(3)").await?;
+    /// ```
+    pub fn register_table_function(
+        &self,
+        name: &str,
+        arguments: Vec<DataType>,
+        schema: Schema,
+        function: impl Fn(&[Value]) -> Result<Table> + Send + Sync + 'static,
+    ) -> Result<()> {
+        self.executor
+            .catalog()
+            .register_table_function(name, NativeTableFunction::new(arguments, schema, function))
+    }
+
+    /// Creates the empty table `table_name` from a BigQuery JSON schema, as written
+    /// by `bq show --schema`, creating its dataset if needed.
+    ///
+    /// See [`Schema::from_bigquery_json`] for how BigQuery types and modes map to
+    /// column types. Fails if the table already exists.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let json = std::fs::read_to_string("schemas/warehouse.orders.json")?;
+    /// session.create_table_from_bigquery_schema("warehouse.orders", &json)?;
+    /// ```
+    pub fn create_table_from_bigquery_schema(&self, table_name: &str, json: &str) -> Result<()> {
+        let schema = Schema::from_bigquery_json(json)?;
+        let catalog = self.executor.catalog();
+        if let Some((dataset, _)) = table_name.rsplit_once('.') {
+            catalog.create_schema(dataset, true)?;
+        }
+        catalog.create_table(table_name, schema)
+    }
+
+    /// Creates an empty table for every BigQuery JSON schema file in `dir` and
+    /// returns the names of the tables created, sorted.
+    ///
+    /// A file `<dataset>.<table>.json` in `dir` creates `<dataset>.<table>`, and a
+    /// file `<table>.json` in a subdirectory `<dataset>` of `dir` does the same.
+    /// Files without a `.json` extension are ignored. Loading stops at the first
+    /// file that fails, naming it in the error; tables created before it are kept.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// // schemas/warehouse/orders.json, schemas/warehouse/customers.json, ...
+    /// session.load_bigquery_schemas("schemas")?;
+    /// let result = session.execute_sql("SELECT COUNT(*) FROM warehouse.orders").await?;
+    /// ```
+    pub fn load_bigquery_schemas(&self, dir: impl AsRef<std::path::Path>) -> Result<Vec<String>> {
+        let mut files = Vec::new();
+        for entry in read_dir(dir.as_ref())? {
+            if entry.is_dir() {
+                let Some(dataset) = entry.file_name().and_then(|name| name.to_str()) else {
+                    continue;
+                };
+                for file in read_dir(&entry)? {
+                    if let Some(table) = json_file_stem(&file) {
+                        files.push((format!("{}.{}", dataset, table), file));
+                    }
+                }
+            } else if let Some(table_name) = json_file_stem(&entry) {
+                files.push((table_name, entry));
+            }
+        }
+        files.sort();
+
+        let mut created = Vec::with_capacity(files.len());
+        for (table_name, path) in files {
+            let json = std::fs::read_to_string(&path)
+                .map_err(|e| Error::io_error(format!("read {}", path.display()), e.to_string()))?;
+            self.create_table_from_bigquery_schema(&table_name, &json)
+                .map_err(|e| Error::invalid_query(format!("{}: {}", path.display(), e)))?;
+            created.push(table_name);
+        }
+        Ok(created)
+    }
+
+    /// Returns the schema of `table_name` as BigQuery JSON, in the format written
+    /// by `bq show --schema`; see [`Schema::to_bigquery_json`].
+    pub fn bigquery_schema(&self, table_name: &str) -> Result<String> {
+        self.executor
+            .catalog()
+            .get_table_schema(table_name)
+            .ok_or_else(|| Error::table_not_found(table_name))?
+            .to_bigquery_json()
+    }
+
+    /// Registers `observer` to receive an event when each statement of this
+    /// session starts, is planned and finishes; see [`QueryObserver`].
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// struct SlowQueries;
+    ///
+    /// impl QueryObserver for SlowQueries {
+    ///     fn on_query_finish(&self, event: &QueryFinish<'_>) {
+    ///         if event.duration > Duration::from_millis(100) {
+    ///             eprintln!("slow: {}", event.fingerprint);
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// session.add_observer(Arc::new(SlowQueries));
+    /// ```
+    pub fn add_observer(&self, observer: Arc<dyn QueryObserver>) {
+        self.executor.add_observer(observer);
+    }
+
     /// Executes SQL and returns the number of affected rows.
     ///
     /// Use this for DDL statements (`CREATE`, `DROP`) or DML statements
@@ -183,6 +842,61 @@ impl YachtSQLSession {
         Ok(table.row_count() as u64)
     }
 
+    /// Executes a multi-statement script and returns the result of every statement.
+    ///
+    /// Statements are separated by semicolons and run in order against this session,
+    /// so variables declared with `DECLARE` and temporary tables stay visible to
+    /// later statements. Execution stops at the first failing statement.
+    ///
+    /// Like a BigQuery script job, the script's own result is the result of the last
+    /// `SELECT`, available through [`ScriptResult::last_query_result`].
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let script = session
+    ///     .execute_script(
+    ///         "DECLARE min_id INT64 DEFAULT 1;
+    ///          CREATE TEMP TABLE users (id INT64, name STRING);
+    ///          INSERT INTO users VALUES (1, 'Alice'), (2, 'Bob');
+    ///          SELECT name FROM users WHERE id > min_id;",
+    ///     )
+    ///     .await?;
+    /// assert_eq!(script.statements.len(), 4);
+    /// let names = script.last_query_result().unwrap();
+    /// ```
+    pub async fn execute_script(&self, sql: &str) -> Result<ScriptResult> {
+        self.executor.execute_script(sql).await
+    }
+
+    /// Creates an independent copy of this session.
+    ///
+    /// The fork starts with the same tables, views, functions, procedures, schema
+    /// metadata, temporary objects and variables, after which the two sessions
+    /// diverge: nothing written through one is visible to the other. Table data is
+    /// shared copy-on-write, so forking is cheap even for large fixtures and only the
+    /// columns a session actually modifies are copied.
+    ///
+    /// Forking a session from a shared-catalog engine detaches the fork from that
+    /// catalog.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let fixture = engine.create_session();
+    /// fixture.execute_sql("CREATE TABLE users (id INT64, name STRING)").await?;
+    /// fixture.execute_sql("INSERT INTO users VALUES (1, 'Alice')").await?;
+    ///
+    /// let session = fixture.fork();
+    /// session.execute_sql("DELETE FROM users WHERE TRUE").await?;
+    /// // `fixture` still contains Alice
+    /// ```
+    pub fn fork(&self) -> YachtSQLSession {
+        YachtSQLSession {
+            executor: self.executor.fork(),
+        }
+    }
+
     /// Returns a reference to the session state.
     ///
     /// Use this to access or modify session variables and settings.
@@ -234,3 +948,24 @@ impl Default for YachtSQLSession {
         Self::new()
     }
 }
+
+fn read_dir(dir: &std::path::Path) -> Result<Vec<std::path::PathBuf>> {
+    let entries = std::fs::read_dir(dir)
+        .map_err(|e| Error::io_error(format!("read {}", dir.display()), e.to_string()))?;
+    entries
+        .map(|entry| {
+            entry
+                .map(|entry| entry.path())
+                .map_err(|e| Error::io_error(format!("read {}", dir.display()), e.to_string()))
+        })
+        .collect()
+}
+
+fn json_file_stem(path: &std::path::Path) -> Option<String> {
+    if !path.is_file() || path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+        return None;
+    }
+    path.file_stem()
+        .and_then(|stem| stem.to_str())
+        .map(str::to_string)
+}
@@ -1451,3 +1451,79 @@ async fn test_catalog_rollback_no_transaction() {
         .unwrap();
     assert_table_eq!(result, [[1]]);
 }
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_catalog_default_project_qualified_names() {
+    let session = create_session();
+    session.set_default_project(Some("my-project".to_string()));
+
+    session
+        .execute_sql("CREATE TABLE sales.orders (id INT64)")
+        .await
+        .unwrap();
+    session
+        .execute_sql("INSERT INTO `my-project.sales.orders` VALUES (1), (2)")
+        .await
+        .unwrap();
+    session
+        .execute_sql("UPDATE `my-project`.sales.orders SET id = id * 10 WHERE id = 2")
+        .await
+        .unwrap();
+
+    let result = session
+        .execute_sql("SELECT id FROM sales.orders ORDER BY id")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[1], [20]]);
+
+    let result = session
+        .execute_sql("SELECT COUNT(*) FROM `my-project.sales.orders`")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[2]]);
+
+    assert!(
+        session
+            .execute_sql("SELECT * FROM `other-project.sales.orders`")
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_catalog_lists_datasets_of_default_project() {
+    let session = create_session();
+    session.set_default_project(Some("my-project".to_string()));
+
+    session.execute_sql("CREATE SCHEMA empty_ds").await.unwrap();
+    session.execute_sql("CREATE SCHEMA sales").await.unwrap();
+    session
+        .execute_sql("CREATE TABLE sales.orders (id INT64)")
+        .await
+        .unwrap();
+
+    let mut datasets = session.get_datasets("my-project");
+    datasets.sort();
+    assert_eq!(datasets, vec!["EMPTY_DS", "SALES"]);
+    assert_eq!(
+        session.get_tables_in_dataset("my-project", "sales"),
+        vec!["ORDERS"]
+    );
+
+    session
+        .execute_sql("DROP SCHEMA sales CASCADE")
+        .await
+        .unwrap();
+    assert_eq!(session.get_datasets("my-project"), vec!["EMPTY_DS"]);
+    assert!(
+        session
+            .get_tables_in_dataset("my-project", "sales")
+            .is_empty()
+    );
+
+    session.execute_sql("UNDROP SCHEMA sales").await.unwrap();
+    assert_eq!(
+        session.get_tables_in_dataset("my-project", "sales"),
+        vec!["ORDERS"]
+    );
+}
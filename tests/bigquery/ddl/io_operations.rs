@@ -214,6 +214,62 @@ async fn test_export_data_to_local_parquet() {
     assert!(output_path.exists());
 }
 
+#[tokio::test(flavor = "current_thread")]
+async fn test_parquet_export_round_trips_through_load() {
+    let session = create_session();
+    let temp_dir = TempDir::new().unwrap();
+    let output_path = temp_dir.path().join("round_trip.parquet");
+
+    session
+        .execute_sql(
+            "CREATE TABLE round_trip (id INT64, price NUMERIC, tags ARRAY<STRING>, at TIMESTAMP, local DATETIME)",
+        )
+        .await
+        .unwrap();
+    session
+        .execute_sql(
+            "INSERT INTO round_trip VALUES (1, NUMERIC '12.50', ['a', 'b'], TIMESTAMP '2024-01-02 03:04:05 UTC', DATETIME '2024-01-02 03:04:05')",
+        )
+        .await
+        .unwrap();
+    session
+        .execute_sql(&format!(
+            "EXPORT DATA OPTIONS(uri='file://{}', format='PARQUET') AS SELECT * FROM round_trip",
+            output_path.display()
+        ))
+        .await
+        .unwrap();
+
+    session
+        .execute_sql("CREATE TABLE reloaded (id INT64, price NUMERIC, tags ARRAY<STRING>, at DATETIME, local TIMESTAMP)")
+        .await
+        .unwrap();
+    session
+        .execute_sql(&format!(
+            "LOAD DATA INTO reloaded FROM FILES (FORMAT='PARQUET', URIS=['file://{}'])",
+            output_path.display()
+        ))
+        .await
+        .unwrap();
+
+    let result = session
+        .execute_sql(
+            "SELECT id, CAST(price AS STRING), ARRAY_TO_STRING(tags, ','), CAST(at AS STRING), CAST(local AS STRING) FROM reloaded",
+        )
+        .await
+        .unwrap();
+    assert_table_eq!(
+        result,
+        [[
+            1,
+            "12.5",
+            "a,b",
+            "2024-01-02 03:04:05",
+            "2024-01-02 03:04:05.000000 UTC"
+        ]]
+    );
+}
+
 #[tokio::test(flavor = "current_thread")]
 async fn test_export_data_to_local_json() {
     let session = create_session();
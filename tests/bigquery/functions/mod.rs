@@ -13,6 +13,9 @@ mod hash;
 mod json;
 mod map_functions;
 mod math;
+mod native_tvf;
+mod native_udaf;
+mod native_udf;
 mod net;
 mod regex;
 mod safe;
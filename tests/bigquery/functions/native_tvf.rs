@@ -0,0 +1,179 @@
+use yachtsql::{DataType, Error, Field, Record, Schema, Table, Value};
+
+use crate::assert_table_eq;
+use crate::common::create_session;
+
+fn sequence_schema() -> Schema {
+    Schema::from_fields(vec![
+        Field::nullable("n", DataType::Int64),
+        Field::nullable("half", DataType::Float64),
+    ])
+}
+
+fn sequence(args: &[Value]) -> yachtsql::Result<Table> {
+    let count = match &args[0] {
+        Value::Int64(n) if *n < 0 => {
+            return Err(Error::invalid_query("count must not be negative"));
+        }
+        Value::Int64(n) => *n,
+        _ => 0,
+    };
+    let records = (1..=count)
+        .map(|n| Record::from_values(vec![Value::Int64(n), Value::Int64(n / 2)]))
+        .collect();
+    Table::from_records(
+        Schema::from_fields(vec![
+            Field::nullable("n", DataType::Int64),
+            Field::nullable("half", DataType::Int64),
+        ]),
+        records,
+    )
+}
+
+fn register_sequence(session: &yachtsql::YachtSQLSession) {
+    session
+        .register_table_function(
+            "sequence",
+            vec![DataType::Int64],
+            sequence_schema(),
+            sequence,
+        )
+        .unwrap();
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_native_table_function_in_from() {
+    let session = create_session();
+    register_sequence(&session);
+
+    let result = session
+        .execute_sql("SELECT n, half FROM sequence(3) ORDER BY n")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[1, 0.0], [2, 1.0], [3, 1.0]]);
+    assert_eq!(result.schema().fields()[1].data_type, DataType::Float64);
+
+    let result = session
+        .execute_sql("SELECT s.n FROM SEQUENCE(2 + 3) AS s WHERE s.n > 3 ORDER BY s.n")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[4], [5]]);
+
+    let result = session
+        .execute_sql("SELECT COUNT(*) FROM sequence(0)")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[0]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_native_table_function_lateral_call() {
+    let session = create_session();
+    register_sequence(&session);
+    session
+        .execute_sql("CREATE TABLE counts (id INT64, k INT64)")
+        .await
+        .unwrap();
+    session
+        .execute_sql("INSERT INTO counts VALUES (1, 2), (2, 0), (3, 1), (4, NULL)")
+        .await
+        .unwrap();
+
+    let result = session
+        .execute_sql("SELECT c.id, s.n FROM counts AS c, sequence(c.k) AS s ORDER BY c.id, s.n")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[1, 1], [1, 2], [3, 1]]);
+
+    let result = session
+        .execute_sql(
+            "SELECT id, SUM(n) AS total FROM counts CROSS JOIN sequence(k) \
+             GROUP BY id ORDER BY id",
+        )
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[1, 3], [3, 1]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_native_table_function_arguments_are_checked_at_plan_time() {
+    let session = create_session();
+    register_sequence(&session);
+
+    let err = session
+        .execute_sql("SELECT * FROM sequence('three')")
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("No matching signature for function SEQUENCE"),
+        "{err}"
+    );
+
+    let err = session.execute_sql("SELECT sequence(3)").await.unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("can only be called in a FROM clause"),
+        "{err}"
+    );
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_native_table_function_errors_propagate() {
+    let session = create_session();
+    register_sequence(&session);
+    session
+        .register_table_function("wrong_shape", vec![], sequence_schema(), |_| {
+            Table::from_records(
+                Schema::from_fields(vec![Field::nullable("n", DataType::Int64)]),
+                vec![Record::from_values(vec![Value::Int64(1)])],
+            )
+        })
+        .unwrap();
+
+    let err = session
+        .execute_sql("SELECT * FROM sequence(-1)")
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("count must not be negative"),
+        "{err}"
+    );
+
+    let err = session
+        .execute_sql("SELECT * FROM wrong_shape()")
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Table function WRONG_SHAPE returned 1 columns, expected 2"),
+        "{err}"
+    );
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_native_table_function_registration_lifecycle() {
+    let session = create_session();
+    register_sequence(&session);
+    assert!(
+        session
+            .register_table_function("Sequence", vec![], sequence_schema(), sequence)
+            .is_err()
+    );
+
+    session.execute_sql("DROP FUNCTION sequence").await.unwrap();
+    assert!(
+        session
+            .execute_sql("SELECT * FROM sequence(1)")
+            .await
+            .is_err()
+    );
+
+    register_sequence(&session);
+    assert!(
+        session
+            .catalog()
+            .deregister_table_function("SEQUENCE")
+            .is_some()
+    );
+}
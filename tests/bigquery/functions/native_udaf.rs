@@ -0,0 +1,307 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use yachtsql::{Accumulator, DataType, Error, FunctionSignature, Value};
+
+use crate::assert_table_eq;
+use crate::common::create_session;
+
+#[derive(Default)]
+struct SumSquares {
+    sum: i64,
+    count: i64,
+    retracts: Option<Arc<AtomicUsize>>,
+}
+
+impl Accumulator for SumSquares {
+    fn update(&mut self, args: &[Value]) -> yachtsql::Result<()> {
+        if let Value::Int64(n) = &args[0] {
+            self.sum += n * n;
+            self.count += 1;
+        }
+        Ok(())
+    }
+
+    fn retract(&mut self, args: &[Value]) -> yachtsql::Result<()> {
+        if let Some(retracts) = &self.retracts {
+            retracts.fetch_add(1, Ordering::SeqCst);
+        }
+        if let Value::Int64(n) = &args[0] {
+            self.sum -= n * n;
+            self.count -= 1;
+        }
+        Ok(())
+    }
+
+    fn supports_retract(&self) -> bool {
+        true
+    }
+
+    fn state(&self) -> yachtsql::Result<Vec<Value>> {
+        Ok(vec![Value::Int64(self.sum), Value::Int64(self.count)])
+    }
+
+    fn merge(&mut self, state: &[Value]) -> yachtsql::Result<()> {
+        if let [Value::Int64(sum), Value::Int64(count)] = state {
+            self.sum += sum;
+            self.count += count;
+        }
+        Ok(())
+    }
+
+    fn finalize(&self) -> yachtsql::Result<Value> {
+        Ok(if self.count == 0 {
+            Value::Null
+        } else {
+            Value::Int64(self.sum)
+        })
+    }
+}
+
+#[derive(Default)]
+struct Concat {
+    parts: Vec<String>,
+}
+
+impl Accumulator for Concat {
+    fn update(&mut self, args: &[Value]) -> yachtsql::Result<()> {
+        match &args[0] {
+            Value::String(s) if s == "boom" => Err(Error::invalid_query("cannot concat boom")),
+            Value::String(s) => {
+                self.parts.push(s.clone());
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn state(&self) -> yachtsql::Result<Vec<Value>> {
+        Ok(vec![Value::String(self.parts.join("|"))])
+    }
+
+    fn merge(&mut self, state: &[Value]) -> yachtsql::Result<()> {
+        if let [Value::String(s)] = state
+            && !s.is_empty()
+        {
+            self.parts.push(s.clone());
+        }
+        Ok(())
+    }
+
+    fn finalize(&self) -> yachtsql::Result<Value> {
+        Ok(Value::String(self.parts.join("|")))
+    }
+}
+
+fn register_sum_squares(session: &yachtsql::YachtSQLSession) {
+    session
+        .register_aggregate_function(
+            "sum_squares",
+            FunctionSignature::new(vec![DataType::Int64], DataType::Int64),
+            || Box::new(SumSquares::default()),
+        )
+        .unwrap();
+}
+
+fn register_concat(session: &yachtsql::YachtSQLSession) {
+    session
+        .register_aggregate_function(
+            "concat_agg",
+            FunctionSignature::new(vec![DataType::String], DataType::String),
+            || Box::new(Concat::default()),
+        )
+        .unwrap();
+}
+
+async fn create_readings(session: &yachtsql::YachtSQLSession) {
+    session
+        .execute_sql("CREATE TABLE readings (id INT64, sensor STRING, value INT64)")
+        .await
+        .unwrap();
+    session
+        .execute_sql(
+            "INSERT INTO readings VALUES \
+             (1, 'a', 1), (2, 'a', 2), (3, 'a', 2), (4, 'b', 3), (5, 'b', NULL), (6, 'c', NULL)",
+        )
+        .await
+        .unwrap();
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_native_aggregate_with_group_by() {
+    let session = create_session();
+    create_readings(&session).await;
+    register_sum_squares(&session);
+
+    let result = session
+        .execute_sql(
+            "SELECT sensor, SUM_SQUARES(value) AS squares, sum_squares(value) + COUNT(*) AS mixed \
+             FROM readings GROUP BY sensor ORDER BY sensor",
+        )
+        .await
+        .unwrap();
+    assert_table_eq!(result, [["a", 9, 12], ["b", 9, 11], ["c", null, null]]);
+    assert_eq!(result.schema().fields()[1].data_type, DataType::Int64);
+
+    let result = session
+        .execute_sql("SELECT sum_squares(value) FROM readings")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[18]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_native_aggregate_arguments_are_checked_at_plan_time() {
+    let session = create_session();
+    create_readings(&session).await;
+    register_sum_squares(&session);
+
+    let err = session
+        .execute_sql("SELECT sum_squares(sensor) FROM readings")
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string().contains(
+            "No matching signature for function SUM_SQUARES for argument types: STRING. \
+             Supported signature: SUM_SQUARES(INT64)"
+        ),
+        "{err}"
+    );
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_native_aggregate_with_distinct() {
+    let session = create_session();
+    create_readings(&session).await;
+    register_sum_squares(&session);
+    register_concat(&session);
+
+    let result = session
+        .execute_sql(
+            "SELECT sensor, sum_squares(DISTINCT value), concat_agg(DISTINCT sensor) \
+             FROM readings GROUP BY sensor ORDER BY sensor",
+        )
+        .await
+        .unwrap();
+    assert_table_eq!(result, [["a", 5, "a"], ["b", 9, "b"], ["c", null, "c"]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_native_aggregate_over_many_rows() {
+    let session = create_session();
+    register_sum_squares(&session);
+    session
+        .execute_sql("CREATE TABLE big (n INT64)")
+        .await
+        .unwrap();
+    session
+        .execute_sql("INSERT INTO big SELECT n FROM UNNEST(GENERATE_ARRAY(1, 6000)) AS n")
+        .await
+        .unwrap();
+
+    let result = session
+        .execute_sql(
+            "SELECT MOD(n, 3) AS bucket, sum_squares(n) = CAST(SUM(n * n) AS INT64) \
+             FROM big GROUP BY bucket ORDER BY bucket",
+        )
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[0, true], [1, true], [2, true]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_native_aggregate_as_window_function() {
+    let session = create_session();
+    create_readings(&session).await;
+    let retracts = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&retracts);
+    session
+        .register_aggregate_function(
+            "sum_squares",
+            FunctionSignature::new(vec![DataType::Int64], DataType::Int64),
+            move || {
+                Box::new(SumSquares {
+                    retracts: Some(Arc::clone(&counter)),
+                    ..SumSquares::default()
+                })
+            },
+        )
+        .unwrap();
+    register_concat(&session);
+
+    let result = session
+        .execute_sql(
+            "SELECT id, \
+                    sum_squares(value) OVER (PARTITION BY sensor), \
+                    sum_squares(value) OVER (ORDER BY sensor), \
+                    sum_squares(value) OVER (ORDER BY id ROWS BETWEEN 1 PRECEDING AND CURRENT ROW), \
+                    concat_agg(sensor) OVER (ORDER BY id ROWS BETWEEN 1 PRECEDING AND 1 FOLLOWING) \
+             FROM readings ORDER BY id",
+        )
+        .await
+        .unwrap();
+    assert_table_eq!(
+        result,
+        [
+            [1, 9, 9, 1, "a|a"],
+            [2, 9, 9, 5, "a|a|a"],
+            [3, 9, 9, 8, "a|a|b"],
+            [4, 9, 18, 13, "a|b|b"],
+            [5, 9, 18, 9, "b|b|c"],
+            [6, null, 18, null, "b|c"],
+        ]
+    );
+    assert!(retracts.load(Ordering::SeqCst) > 0);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_native_aggregate_errors_propagate() {
+    let session = create_session();
+    register_concat(&session);
+
+    let err = session
+        .execute_sql("SELECT concat_agg(s) FROM UNNEST(['x', 'boom']) AS s")
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("cannot concat boom"), "{err}");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_native_aggregate_registration_lifecycle() {
+    let session = create_session();
+    register_sum_squares(&session);
+    assert!(
+        session
+            .register_scalar_function(
+                "SUM_SQUARES",
+                FunctionSignature::new(vec![DataType::Int64], DataType::Int64),
+                |args| Ok(args[0].clone()),
+            )
+            .is_err()
+    );
+
+    let result = session
+        .execute_sql("SELECT sum_squares(n) FROM UNNEST([1, 2, 3]) AS n")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[14]]);
+
+    session
+        .execute_sql("DROP FUNCTION sum_squares")
+        .await
+        .unwrap();
+    assert!(
+        session
+            .execute_sql("SELECT sum_squares(n) FROM UNNEST([1]) AS n")
+            .await
+            .is_err()
+    );
+
+    register_sum_squares(&session);
+    assert!(
+        session
+            .catalog()
+            .deregister_aggregate_function("sum_squares")
+            .is_some()
+    );
+}
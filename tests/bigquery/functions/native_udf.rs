@@ -0,0 +1,234 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use yachtsql::{
+    Column, DataType, Error, FunctionSignature, NativeScalarFunction, Value, YachtSQLEngine,
+};
+
+use crate::assert_table_eq;
+use crate::common::create_session;
+
+fn double_int64(args: &[Value]) -> yachtsql::Result<Value> {
+    Ok(match &args[0] {
+        Value::Int64(n) => Value::Int64(n * 2),
+        _ => Value::Null,
+    })
+}
+
+async fn create_numbers(session: &yachtsql::YachtSQLSession) {
+    session
+        .execute_sql("CREATE TABLE numbers (n INT64, label STRING)")
+        .await
+        .unwrap();
+    session
+        .execute_sql(
+            "INSERT INTO numbers VALUES (1, 'one'), (2, 'two'), (3, 'three'), (NULL, 'none')",
+        )
+        .await
+        .unwrap();
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_native_function_in_select_and_where() {
+    let session = create_session();
+    create_numbers(&session).await;
+    session
+        .register_scalar_function(
+            "double_it",
+            FunctionSignature::new(vec![DataType::Int64], DataType::Int64),
+            double_int64,
+        )
+        .unwrap();
+
+    let result = session
+        .execute_sql(
+            "SELECT label, DOUBLE_IT(n) AS doubled FROM numbers WHERE double_it(n) > 2 ORDER BY n",
+        )
+        .await
+        .unwrap();
+    assert_table_eq!(result, [["two", 4], ["three", 6]]);
+
+    let result = session
+        .execute_sql("SELECT double_it(n) FROM numbers WHERE n IS NULL")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[null]]);
+    assert_eq!(result.schema().fields()[0].data_type, DataType::Int64);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_native_function_arguments_are_checked_at_plan_time() {
+    let session = create_session();
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&calls);
+    session
+        .register_scalar_function(
+            "double_it",
+            FunctionSignature::new(vec![DataType::Int64], DataType::Int64),
+            move |args| {
+                counter.fetch_add(1, Ordering::SeqCst);
+                double_int64(args)
+            },
+        )
+        .unwrap();
+
+    let err = session
+        .execute_sql("SELECT double_it('x')")
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string().contains(
+            "No matching signature for function DOUBLE_IT for argument types: STRING. \
+             Supported signature: DOUBLE_IT(INT64)"
+        ),
+        "{err}"
+    );
+
+    let err = session
+        .execute_sql("SELECT 1 WHERE double_it(1, 2) > 0")
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("No matching signature"), "{err}");
+    assert_eq!(calls.load(Ordering::SeqCst), 0);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_native_function_arguments_are_widened() {
+    let session = create_session();
+    session
+        .register_scalar_function(
+            "halve",
+            FunctionSignature::new(vec![DataType::Float64], DataType::Float64),
+            |args| match &args[0] {
+                Value::Float64(f) => Ok(Value::float64(f.0 / 2.0)),
+                Value::Null => Ok(Value::Null),
+                other => Err(Error::internal(format!("unexpected argument {:?}", other))),
+            },
+        )
+        .unwrap();
+
+    let result = session
+        .execute_sql("SELECT halve(5), halve(1.5), halve(NULL)")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[2.5, 0.75, null]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_native_function_errors_propagate() {
+    let session = create_session();
+    session
+        .register_scalar_function(
+            "fail_on_negative",
+            FunctionSignature::new(vec![DataType::Int64], DataType::Int64),
+            |args| match &args[0] {
+                Value::Int64(n) if *n < 0 => Err(Error::invalid_query("negative input")),
+                other => Ok(other.clone()),
+            },
+        )
+        .unwrap();
+
+    let result = session
+        .execute_sql("SELECT fail_on_negative(3)")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[3]]);
+
+    let err = session
+        .execute_sql("SELECT fail_on_negative(-3)")
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("negative input"), "{err}");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_native_function_columnar_fast_path() {
+    let session = create_session();
+    create_numbers(&session).await;
+    let columnar_calls = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&columnar_calls);
+    let function = NativeScalarFunction::new(
+        FunctionSignature::new(vec![DataType::Int64], DataType::Int64),
+        double_int64,
+    )
+    .with_columnar(move |columns: &[&Column]| {
+        counter.fetch_add(1, Ordering::SeqCst);
+        let values: Vec<Value> = (0..columns[0].len())
+            .map(|i| double_int64(&[columns[0].get_value(i)]))
+            .collect::<yachtsql::Result<_>>()?;
+        Ok(Column::from_values(&values))
+    });
+    session
+        .catalog()
+        .register_scalar_function("double_it", function)
+        .unwrap();
+
+    let result = session
+        .execute_sql("SELECT n FROM numbers WHERE double_it(n) > 2 ORDER BY n")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[2], [3]]);
+    assert!(columnar_calls.load(Ordering::SeqCst) > 0);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_native_function_registration_lifecycle() {
+    let session = create_session();
+    let signature = FunctionSignature::new(vec![DataType::Int64], DataType::Int64);
+    session
+        .register_scalar_function("double_it", signature.clone(), double_int64)
+        .unwrap();
+    assert!(
+        session
+            .register_scalar_function("DOUBLE_IT", signature.clone(), double_int64)
+            .is_err()
+    );
+    assert!(
+        session
+            .execute_sql("CREATE TEMP FUNCTION double_it(x INT64) AS (x * 2)")
+            .await
+            .is_err()
+    );
+
+    let result = session.execute_sql("SELECT double_it(21)").await.unwrap();
+    assert_table_eq!(result, [[42]]);
+
+    session
+        .execute_sql("DROP FUNCTION double_it")
+        .await
+        .unwrap();
+    assert!(session.execute_sql("SELECT double_it(21)").await.is_err());
+
+    session
+        .register_scalar_function("double_it", signature, double_int64)
+        .unwrap();
+    assert!(
+        session
+            .catalog()
+            .deregister_scalar_function("double_it")
+            .is_some()
+    );
+    assert!(session.execute_sql("SELECT double_it(21)").await.is_err());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_native_function_is_private_to_its_session() {
+    let engine = YachtSQLEngine::with_shared_catalog();
+    let session1 = engine.create_session();
+    let session2 = engine.create_session();
+    session1
+        .register_scalar_function(
+            "double_it",
+            FunctionSignature::new(vec![DataType::Int64], DataType::Int64),
+            double_int64,
+        )
+        .unwrap();
+
+    let result = session1.execute_sql("SELECT double_it(2)").await.unwrap();
+    assert_table_eq!(result, [[4]]);
+    assert!(session2.execute_sql("SELECT double_it(2)").await.is_err());
+
+    let fork = session1.fork();
+    let result = fork.execute_sql("SELECT double_it(3)").await.unwrap();
+    assert_table_eq!(result, [[6]]);
+}
@@ -0,0 +1,78 @@
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, AsArray, Int64Array, ListArray, RecordBatch, StringArray};
+use arrow::datatypes::{DataType as ArrowDataType, Int64Type};
+use yachtsql::Table;
+
+use crate::assert_table_eq;
+use crate::common::create_session;
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_record_batch_can_be_registered_and_queried() {
+    let session = create_session();
+    let batch = RecordBatch::try_from_iter(vec![
+        ("id", Arc::new(Int64Array::from(vec![1, 2, 3])) as ArrayRef),
+        (
+            "name",
+            Arc::new(StringArray::from(vec![Some("a"), None, Some("c")])) as ArrayRef,
+        ),
+        (
+            "tags",
+            Arc::new(ListArray::from_iter_primitive::<Int64Type, _, _>(vec![
+                Some(vec![Some(1), Some(2)]),
+                Some(vec![]),
+                None,
+            ])) as ArrayRef,
+        ),
+    ])
+    .unwrap();
+
+    session
+        .catalog()
+        .insert_table("events", Table::from_record_batch(&batch).unwrap())
+        .unwrap();
+
+    let result = session
+        .execute_sql("SELECT id, name, ARRAY_LENGTH(tags) FROM events WHERE id < 3 ORDER BY id")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[1, "a", 2], [2, null, 0]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_query_result_converts_to_record_batch() {
+    let session = create_session();
+
+    let result = session
+        .execute_sql(
+            "SELECT 1 AS id,
+                    STRUCT('Oslo' AS city, [1, 2] AS zips) AS address,
+                    JSON '{\"a\": 1}' AS doc,
+                    RANGE(DATE '2024-01-01', DATE '2024-02-01') AS period,
+                    INTERVAL 3 DAY AS wait",
+        )
+        .await
+        .unwrap();
+    let batch = result.to_record_batch().unwrap();
+
+    assert_eq!(batch.num_rows(), 1);
+    assert_eq!(batch.column(0).as_primitive::<Int64Type>().value(0), 1);
+    let address = batch.column(1).as_struct();
+    assert_eq!(address.column(0).as_string::<i32>().value(0), "Oslo");
+    assert!(matches!(
+        batch.schema().field(3).data_type(),
+        ArrowDataType::Struct(fields) if fields.len() == 2
+    ));
+    assert_eq!(
+        batch
+            .schema()
+            .field(2)
+            .metadata()
+            .get("ARROW:extension:name"),
+        Some(&"arrow.json".to_string())
+    );
+    assert!(!batch.column(4).is_null(0));
+
+    let restored = Table::from_record_batch(&batch).unwrap();
+    assert_eq!(restored.to_records().unwrap(), result.to_records().unwrap());
+}
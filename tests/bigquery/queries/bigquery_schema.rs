@@ -0,0 +1,128 @@
+use yachtsql::{DataType, FieldMode};
+
+use crate::assert_table_eq;
+use crate::common::create_session;
+
+const ORDERS_SCHEMA: &str = r#"[
+  {"name": "id", "type": "INTEGER", "mode": "REQUIRED", "description": "Order id"},
+  {"name": "placed_at", "type": "TIMESTAMP", "mode": "NULLABLE"},
+  {"name": "total", "type": "NUMERIC", "mode": "NULLABLE", "precision": "10", "scale": "2"},
+  {"name": "tags", "type": "STRING", "mode": "REPEATED"},
+  {"name": "shipping", "type": "RECORD", "mode": "NULLABLE", "fields": [
+    {"name": "city", "type": "STRING", "mode": "NULLABLE"},
+    {"name": "lines", "type": "STRING", "mode": "REPEATED"}
+  ]}
+]"#;
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_create_table_from_bigquery_schema() {
+    let session = create_session();
+    session
+        .create_table_from_bigquery_schema("warehouse.orders", ORDERS_SCHEMA)
+        .unwrap();
+    assert!(session.catalog().schema_exists("warehouse"));
+
+    let schema = session
+        .catalog()
+        .get_table_schema("warehouse.orders")
+        .unwrap();
+    let id = schema.field("id").unwrap();
+    assert_eq!(id.mode, FieldMode::Required);
+    assert_eq!(id.description.as_deref(), Some("Order id"));
+    let tags = schema.field("tags").unwrap();
+    assert_eq!(tags.mode, FieldMode::Repeated);
+    assert_eq!(tags.data_type, DataType::Array(Box::new(DataType::String)));
+
+    session
+        .execute_sql(
+            "INSERT INTO warehouse.orders (id, total, tags, shipping)
+             VALUES (1, 9.99, ['gift', 'rush'], STRUCT('Oslo', ['Main St 1']))",
+        )
+        .await
+        .unwrap();
+    let result = session
+        .execute_sql(
+            "SELECT id, ARRAY_LENGTH(tags), shipping.city, shipping.lines[OFFSET(0)]
+             FROM warehouse.orders",
+        )
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[1, 2, "Oslo", "Main St 1"]]);
+
+    let err = session
+        .create_table_from_bigquery_schema("warehouse.orders", ORDERS_SCHEMA)
+        .unwrap_err();
+    assert!(err.to_string().contains("already exists"), "{err}");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_load_bigquery_schemas_from_directory() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir(dir.path().join("sales")).unwrap();
+    std::fs::write(dir.path().join("sales").join("orders.json"), ORDERS_SCHEMA).unwrap();
+    std::fs::write(
+        dir.path().join("crm.customers.json"),
+        r#"[{"name": "id", "type": "INTEGER"}, {"name": "name", "type": "STRING"}]"#,
+    )
+    .unwrap();
+    std::fs::write(dir.path().join("README.md"), "not a schema").unwrap();
+
+    let session = create_session();
+    let created = session.load_bigquery_schemas(dir.path()).unwrap();
+    assert_eq!(created, vec!["crm.customers", "sales.orders"]);
+
+    let result = session
+        .execute_sql("SELECT COUNT(*) FROM sales.orders o JOIN crm.customers c ON o.id = c.id")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[0]]);
+
+    std::fs::write(dir.path().join("crm.broken.json"), r#"[{"name": "x"}]"#).unwrap();
+    let err = create_session()
+        .load_bigquery_schemas(dir.path())
+        .unwrap_err();
+    assert!(err.to_string().contains("crm.broken.json"), "{err}");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_bigquery_schema_export() {
+    let session = create_session();
+    session
+        .execute_sql(
+            "CREATE TABLE events (
+               id INT64 NOT NULL,
+               payload JSON,
+               labels ARRAY<STRING>,
+               source STRUCT<host STRING, ports ARRAY<INT64>>
+             )",
+        )
+        .await
+        .unwrap();
+
+    let json: serde_json::Value =
+        serde_json::from_str(&session.bigquery_schema("events").unwrap()).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!([
+            {"name": "id", "type": "INTEGER", "mode": "REQUIRED"},
+            {"name": "payload", "type": "JSON", "mode": "NULLABLE"},
+            {"name": "labels", "type": "STRING", "mode": "REPEATED"},
+            {"name": "source", "type": "RECORD", "mode": "NULLABLE", "fields": [
+                {"name": "host", "type": "STRING", "mode": "NULLABLE"},
+                {"name": "ports", "type": "INTEGER", "mode": "REPEATED"}
+            ]}
+        ])
+    );
+
+    session
+        .create_table_from_bigquery_schema("ds.orders", ORDERS_SCHEMA)
+        .unwrap();
+    let exported: serde_json::Value =
+        serde_json::from_str(&session.bigquery_schema("ds.orders").unwrap()).unwrap();
+    assert_eq!(
+        exported,
+        serde_json::from_str::<serde_json::Value>(ORDERS_SCHEMA).unwrap()
+    );
+
+    assert!(session.bigquery_schema("missing").is_err());
+}
@@ -0,0 +1,284 @@
+use std::io::Write;
+
+use tempfile::{NamedTempFile, TempDir};
+use yachtsql::{ConcurrentCatalog, YachtSQLEngine, YachtSQLSession};
+
+use crate::assert_table_eq;
+use crate::common::{create_session, d};
+
+fn save_and_reload(session: &YachtSQLSession) -> YachtSQLSession {
+    let file = NamedTempFile::new().unwrap();
+    session.catalog().save_to(file.path()).unwrap();
+    let catalog = ConcurrentCatalog::load_from(file.path()).unwrap();
+    YachtSQLSession::with_catalog(catalog)
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_save_and_load_tables_with_data() {
+    let session = create_session();
+    session
+        .execute_sql(
+            "CREATE TABLE users (id INT64, name STRING, tags ARRAY<STRING>, address STRUCT<city STRING, zip INT64>)",
+        )
+        .await
+        .unwrap();
+    session
+        .execute_sql(
+            "INSERT INTO users VALUES (1, 'Alice', ['a', 'b'], STRUCT('Paris', 75001)), (2, NULL, [], NULL)",
+        )
+        .await
+        .unwrap();
+
+    let restored = save_and_reload(&session);
+
+    let result = restored
+        .execute_sql("SELECT id, name, ARRAY_LENGTH(tags), address.city FROM users ORDER BY id")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[1, "Alice", 2, "Paris"], [2, null, 0, null]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_save_and_load_column_defaults() {
+    let session = create_session();
+    session
+        .execute_sql("CREATE TABLE counters (id INT64, hits INT64 DEFAULT 7)")
+        .await
+        .unwrap();
+
+    let restored = save_and_reload(&session);
+    restored
+        .execute_sql("INSERT INTO counters (id) VALUES (1)")
+        .await
+        .unwrap();
+
+    let result = restored
+        .execute_sql("SELECT id, hits FROM counters")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[1, 7]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_save_and_load_views_functions_and_procedures() {
+    let session = create_session();
+    session
+        .execute_sql("CREATE TABLE items (price INT64)")
+        .await
+        .unwrap();
+    session
+        .execute_sql("INSERT INTO items VALUES (10), (20)")
+        .await
+        .unwrap();
+    session
+        .execute_sql("CREATE VIEW expensive AS SELECT price FROM items WHERE price > 15")
+        .await
+        .unwrap();
+    session
+        .execute_sql("CREATE FUNCTION with_tax(x INT64) AS (x * 2)")
+        .await
+        .unwrap();
+    session
+        .execute_sql("CREATE PROCEDURE add_item(p INT64) BEGIN INSERT INTO items VALUES (p); END")
+        .await
+        .unwrap();
+
+    let restored = save_and_reload(&session);
+
+    let result = restored
+        .execute_sql("SELECT price FROM expensive")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[20]]);
+    let result = restored.execute_sql("SELECT with_tax(21)").await.unwrap();
+    assert_table_eq!(result, [[42]]);
+    restored.execute_sql("CALL add_item(30)").await.unwrap();
+    let result = restored
+        .execute_sql("SELECT COUNT(*) FROM items")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[3]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_save_and_load_schemas_and_search_path() {
+    let session = create_session();
+    session.execute_sql("CREATE SCHEMA sales").await.unwrap();
+    session
+        .execute_sql("ALTER SCHEMA sales SET DEFAULT COLLATE 'und:ci'")
+        .await
+        .unwrap();
+    session
+        .execute_sql("CREATE TABLE sales.orders (id INT64)")
+        .await
+        .unwrap();
+    session.catalog().set_search_path(vec!["sales".to_string()]);
+
+    let restored = save_and_reload(&session);
+
+    assert!(restored.catalog().schema_exists("sales"));
+    assert!(restored.catalog().table_exists("sales.orders"));
+    assert_eq!(
+        restored.catalog().get_search_path(),
+        vec!["SALES".to_string()]
+    );
+
+    restored
+        .execute_sql("CREATE TABLE sales.customers (name STRING)")
+        .await
+        .unwrap();
+    restored
+        .execute_sql("INSERT INTO sales.customers VALUES ('Hello')")
+        .await
+        .unwrap();
+    let result = restored
+        .execute_sql("SELECT name FROM sales.customers WHERE name = 'hello'")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [["Hello"]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_temp_tables_are_not_saved() {
+    let session = YachtSQLEngine::with_shared_catalog().create_session();
+    session
+        .execute_sql("CREATE TEMP TABLE scratch (id INT64)")
+        .await
+        .unwrap();
+
+    let restored = save_and_reload(&session);
+
+    assert!(!restored.catalog().table_exists("scratch"));
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_restored_catalog_is_independent_of_file() {
+    let session = create_session();
+    session
+        .execute_sql("CREATE TABLE t (id INT64)")
+        .await
+        .unwrap();
+    let file = NamedTempFile::new().unwrap();
+    session.catalog().save_to(file.path()).unwrap();
+
+    let first = YachtSQLSession::with_catalog(ConcurrentCatalog::load_from(file.path()).unwrap());
+    first.execute_sql("INSERT INTO t VALUES (1)").await.unwrap();
+    let second = YachtSQLSession::with_catalog(ConcurrentCatalog::load_from(file.path()).unwrap());
+
+    let result = second.execute_sql("SELECT COUNT(*) FROM t").await.unwrap();
+    assert_table_eq!(result, [[0]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_save_replaces_existing_file() {
+    let session = create_session();
+    session
+        .execute_sql("CREATE TABLE t (id INT64)")
+        .await
+        .unwrap();
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("db.catalog");
+    session.catalog().save_to(&path).unwrap();
+    session
+        .execute_sql("INSERT INTO t VALUES (1)")
+        .await
+        .unwrap();
+    session.catalog().save_to(&path).unwrap();
+
+    let files: Vec<_> = std::fs::read_dir(dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .collect();
+    assert_eq!(files, ["db.catalog"]);
+    let restored = YachtSQLSession::with_catalog(ConcurrentCatalog::load_from(&path).unwrap());
+    let result = restored.execute_sql("SELECT id FROM t").await.unwrap();
+    assert_table_eq!(result, [[1]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_failed_save_keeps_previous_file() {
+    let session = create_session();
+    session
+        .execute_sql("CREATE TABLE t (id INT64)")
+        .await
+        .unwrap();
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("db.catalog");
+    session.catalog().save_to(&path).unwrap();
+    session
+        .execute_sql("INSERT INTO t VALUES (1)")
+        .await
+        .unwrap();
+    std::fs::create_dir(dir.path().join("db.catalog.tmp")).unwrap();
+
+    assert!(session.catalog().save_to(&path).is_err());
+    let restored = YachtSQLSession::with_catalog(ConcurrentCatalog::load_from(&path).unwrap());
+    let result = restored
+        .execute_sql("SELECT COUNT(*) FROM t")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[0]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_load_version_1_file() {
+    let catalog = ConcurrentCatalog::load_from(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/catalog_v1.yachtcat"
+    ))
+    .unwrap();
+    let session = YachtSQLSession::with_catalog(catalog);
+
+    let result = session
+        .execute_sql("SELECT id, customer, amount, placed, status FROM sales.orders ORDER BY id")
+        .await
+        .unwrap();
+    assert_table_eq!(
+        result,
+        [
+            [1, "Alice", 12.5, d(2024, 1, 2), "open"],
+            [2, "Bob", 30.0, d(2024, 1, 3), "open"],
+            [3, null, 7.25, null, "open"],
+        ]
+    );
+    let result = session
+        .execute_sql("SELECT id FROM sales.big_orders ORDER BY id")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[1], [2]]);
+    let result = session.execute_sql("SELECT with_tax(1.5)").await.unwrap();
+    assert_table_eq!(result, [[3.0]]);
+    session
+        .execute_sql("INSERT INTO sales.orders (id) VALUES (4)")
+        .await
+        .unwrap();
+    let result = session
+        .execute_sql("SELECT status FROM sales.orders WHERE id = 4")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [["open"]]);
+}
+
+#[test]
+fn test_load_rejects_non_catalog_file() {
+    let mut file = NamedTempFile::new().unwrap();
+    file.write_all(b"id,name\n1,Alice\n").unwrap();
+
+    let err = ConcurrentCatalog::load_from(file.path()).unwrap_err();
+    assert!(err.to_string().contains("not a YachtSQL catalog file"));
+}
+
+#[test]
+fn test_load_rejects_newer_format_version() {
+    let mut file = NamedTempFile::new().unwrap();
+    file.write_all(b"YACHTCAT").unwrap();
+    file.write_all(&99u32.to_le_bytes()).unwrap();
+
+    let err = ConcurrentCatalog::load_from(file.path()).unwrap_err();
+    assert!(err.to_string().contains("version 99"));
+}
+
+#[test]
+fn test_load_missing_file_fails() {
+    assert!(ConcurrentCatalog::load_from("/nonexistent/yachtsql.catalog").is_err());
+}
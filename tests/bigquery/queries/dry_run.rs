@@ -0,0 +1,134 @@
+use yachtsql::{ReferencedTable, YachtSQLSession};
+
+use crate::assert_table_eq;
+use crate::common::create_session;
+
+async fn create_schema(session: &YachtSQLSession) {
+    session
+        .execute_sql("CREATE TABLE users (id INT64, name STRING, bio STRING)")
+        .await
+        .unwrap();
+    session
+        .execute_sql("CREATE TABLE orders (id INT64, user_id INT64, total FLOAT64)")
+        .await
+        .unwrap();
+}
+
+fn table(name: &str, columns: &[&str], written: bool) -> ReferencedTable {
+    ReferencedTable {
+        name: name.to_string(),
+        columns: columns.iter().map(|c| c.to_string()).collect(),
+        written,
+    }
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_dry_run_reports_schema_and_referenced_columns() {
+    let session = create_session();
+    create_schema(&session).await;
+
+    let dry_run = session
+        .dry_run(
+            "SELECT u.name, SUM(o.total) AS spent
+             FROM users u JOIN orders o ON o.user_id = u.id
+             GROUP BY u.name
+             ORDER BY spent DESC",
+        )
+        .unwrap();
+    assert_eq!(dry_run.statement_type, "SELECT");
+    let schema: Vec<(String, String)> = dry_run
+        .schema
+        .iter()
+        .map(|c| (c.name.clone(), c.data_type.clone()))
+        .collect();
+    assert_eq!(
+        schema,
+        vec![
+            ("name".to_string(), "STRING".to_string()),
+            ("spent".to_string(), "FLOAT64".to_string()),
+        ]
+    );
+    assert_eq!(
+        dry_run.referenced_tables,
+        vec![
+            table("orders", &["user_id", "total"], false),
+            table("users", &["id", "name"], false),
+        ]
+    );
+    assert_eq!(dry_run.total_bytes_processed, 0);
+
+    let dry_run = session.dry_run("SELECT * FROM users").unwrap();
+    assert_eq!(
+        dry_run.referenced_tables,
+        vec![table("users", &["id", "name", "bio"], false)]
+    );
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_dry_run_estimates_bytes_from_referenced_columns() {
+    let session = create_session();
+    create_schema(&session).await;
+    session
+        .execute_sql(
+            "INSERT INTO users VALUES
+               (1, 'a', REPEAT('x', 1000)),
+               (2, 'b', REPEAT('y', 1000))",
+        )
+        .await
+        .unwrap();
+
+    let ids = session.dry_run("SELECT id FROM users").unwrap();
+    let bios = session.dry_run("SELECT bio FROM users").unwrap();
+    let all = session.dry_run("SELECT * FROM users").unwrap();
+    let count = session.dry_run("SELECT COUNT(*) FROM users").unwrap();
+    assert!(ids.total_bytes_processed > 0);
+    assert!(bios.total_bytes_processed >= 2000);
+    assert!(all.total_bytes_processed > bios.total_bytes_processed);
+    assert_eq!(count.total_bytes_processed, 0);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_dry_run_does_not_execute() {
+    let session = create_session();
+    create_schema(&session).await;
+
+    let dry_run = session
+        .dry_run("INSERT INTO orders SELECT id, id, 1.0 FROM users WHERE name = 'a'")
+        .unwrap();
+    assert_eq!(dry_run.statement_type, "INSERT");
+    assert!(dry_run.schema.is_empty());
+    assert_eq!(
+        dry_run.referenced_tables,
+        vec![
+            table("orders", &["id"], true),
+            table("users", &["id", "name"], false),
+        ]
+    );
+
+    let dry_run = session
+        .dry_run("CREATE TABLE archive AS SELECT * FROM orders")
+        .unwrap();
+    assert_eq!(dry_run.statement_type, "CREATE_TABLE_AS_SELECT");
+    assert!(session.dry_run("DROP TABLE users").is_ok());
+
+    let result = session
+        .execute_sql(
+            "SELECT (SELECT COUNT(*) FROM orders),
+                    (SELECT COUNT(*) FROM users),
+                    (SELECT COUNT(*) FROM INFORMATION_SCHEMA.JOBS WHERE query LIKE 'DROP%')",
+        )
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[0, 0, 0]]);
+    assert!(session.execute_sql("SELECT * FROM archive").await.is_err());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_dry_run_reports_planning_errors() {
+    let session = create_session();
+    create_schema(&session).await;
+
+    let err = session.dry_run("SELECT * FROM nowhere").unwrap_err();
+    assert!(err.to_string().contains("nowhere"), "{err}");
+    assert!(session.dry_run("SELEC 1").is_err());
+}
@@ -0,0 +1,541 @@
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use yachtsql::YachtSQLEngine;
+
+use crate::assert_table_eq;
+
+const CRASH_CHILD_DIR: &str = "YACHTSQL_DURABLE_CRASH_CHILD_DIR";
+
+fn wal_file(dir: &Path) -> PathBuf {
+    std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .find(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with("wal-"))
+        })
+        .unwrap()
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_tables_and_objects_survive_reopen() {
+    let dir = tempfile::tempdir().unwrap();
+    {
+        let engine = YachtSQLEngine::open(dir.path()).await.unwrap();
+        let session = engine.create_session();
+        session.execute_sql("CREATE SCHEMA app").await.unwrap();
+        session
+            .execute_sql("CREATE TABLE app.users (id INT64, name STRING, score INT64 DEFAULT 5)")
+            .await
+            .unwrap();
+        session
+            .execute_sql("INSERT INTO app.users (id, name) VALUES (1, 'Alice'), (2, 'Bob')")
+            .await
+            .unwrap();
+        session
+            .execute_sql("CREATE VIEW app.user_names AS SELECT name FROM app.users")
+            .await
+            .unwrap();
+        session
+            .execute_sql("CREATE FUNCTION app.double_it(x INT64) AS (x * 2)")
+            .await
+            .unwrap();
+    }
+
+    let engine = YachtSQLEngine::open(dir.path()).await.unwrap();
+    let session = engine.create_session();
+    let result = session
+        .execute_sql("SELECT id, name, app.double_it(score) FROM app.users ORDER BY id")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[1, "Alice", 10], [2, "Bob", 10]]);
+    let result = session
+        .execute_sql("SELECT name FROM app.user_names ORDER BY name")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [["Alice"], ["Bob"]]);
+    session
+        .execute_sql("INSERT INTO app.users (id, name) VALUES (3, 'Carol')")
+        .await
+        .unwrap();
+    let result = session
+        .execute_sql("SELECT score FROM app.users WHERE id = 3")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[5]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_updates_deletes_and_alters_survive_reopen() {
+    let dir = tempfile::tempdir().unwrap();
+    {
+        let engine = YachtSQLEngine::open(dir.path()).await.unwrap();
+        let session = engine.create_session();
+        session
+            .execute_sql("CREATE TABLE items (id INT64, price INT64)")
+            .await
+            .unwrap();
+        session
+            .execute_sql("INSERT INTO items VALUES (1, 10), (2, 20), (3, 30)")
+            .await
+            .unwrap();
+        session
+            .execute_sql("UPDATE items SET price = price + 1 WHERE id = 2")
+            .await
+            .unwrap();
+        session
+            .execute_sql("DELETE FROM items WHERE id = 3")
+            .await
+            .unwrap();
+        session
+            .execute_sql("ALTER TABLE items ADD COLUMN label STRING")
+            .await
+            .unwrap();
+        session
+            .execute_sql("CREATE TABLE expensive AS SELECT id FROM items WHERE price > 15")
+            .await
+            .unwrap();
+        session
+            .execute_sql("CREATE TABLE dropped (id INT64)")
+            .await
+            .unwrap();
+        session.execute_sql("DROP TABLE dropped").await.unwrap();
+    }
+
+    let engine = YachtSQLEngine::open(dir.path()).await.unwrap();
+    let session = engine.create_session();
+    let result = session
+        .execute_sql("SELECT * FROM items ORDER BY id")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[1, 10, null], [2, 21, null]]);
+    let result = session
+        .execute_sql("SELECT id FROM expensive")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[2]]);
+    assert!(!session.catalog().table_exists("dropped"));
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_non_deterministic_values_replay_exactly() {
+    let dir = tempfile::tempdir().unwrap();
+    let original = {
+        let engine = YachtSQLEngine::open(dir.path()).await.unwrap();
+        let session = engine.create_session();
+        session
+            .execute_sql("CREATE TABLE samples (id STRING, x FLOAT64)")
+            .await
+            .unwrap();
+        session
+            .execute_sql(
+                "INSERT INTO samples VALUES (GENERATE_UUID(), RAND()), (GENERATE_UUID(), RAND())",
+            )
+            .await
+            .unwrap();
+        session
+            .execute_sql("UPDATE samples SET x = x + RAND() WHERE TRUE")
+            .await
+            .unwrap();
+        session
+            .query("SELECT id, x FROM samples ORDER BY id")
+            .await
+            .unwrap()
+    };
+
+    let engine = YachtSQLEngine::open(dir.path()).await.unwrap();
+    let session = engine.create_session();
+    let restored = session
+        .query("SELECT id, x FROM samples ORDER BY id")
+        .await
+        .unwrap();
+    let values = |result: &yachtsql::QueryResult| -> Vec<Vec<yachtsql::Value>> {
+        result
+            .rows
+            .iter()
+            .map(|row| row.values().to_vec())
+            .collect()
+    };
+    assert_eq!(values(&original), values(&restored));
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_checkpoint_then_more_writes() {
+    let dir = tempfile::tempdir().unwrap();
+    {
+        let engine = YachtSQLEngine::open(dir.path()).await.unwrap();
+        let session = engine.create_session();
+        session
+            .execute_sql("CREATE TABLE log (n INT64)")
+            .await
+            .unwrap();
+        session
+            .execute_sql("INSERT INTO log VALUES (1)")
+            .await
+            .unwrap();
+        engine.checkpoint().await.unwrap();
+        session
+            .execute_sql("INSERT INTO log VALUES (2)")
+            .await
+            .unwrap();
+        engine.checkpoint().await.unwrap();
+        session
+            .execute_sql("INSERT INTO log VALUES (3)")
+            .await
+            .unwrap();
+    }
+
+    let names: Vec<String> = std::fs::read_dir(dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .filter(|name| name != "LOCK")
+        .collect();
+    assert_eq!(names.len(), 2, "unexpected files: {:?}", names);
+
+    let engine = YachtSQLEngine::open(dir.path()).await.unwrap();
+    let session = engine.create_session();
+    let result = session
+        .execute_sql("SELECT n FROM log ORDER BY n")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[1], [2], [3]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_only_committed_transactions_survive_reopen() {
+    let dir = tempfile::tempdir().unwrap();
+    {
+        let engine = YachtSQLEngine::open(dir.path()).await.unwrap();
+        let session = engine.create_session();
+        session
+            .execute_sql("CREATE TABLE accounts (id INT64, balance INT64)")
+            .await
+            .unwrap();
+        session
+            .execute_sql("INSERT INTO accounts VALUES (1, 100)")
+            .await
+            .unwrap();
+
+        session.execute_sql("BEGIN TRANSACTION").await.unwrap();
+        session
+            .execute_sql("UPDATE accounts SET balance = 0 WHERE id = 1")
+            .await
+            .unwrap();
+        session.execute_sql("ROLLBACK").await.unwrap();
+
+        session.execute_sql("BEGIN TRANSACTION").await.unwrap();
+        session
+            .execute_sql("INSERT INTO accounts VALUES (2, 50)")
+            .await
+            .unwrap();
+        session.execute_sql("COMMIT").await.unwrap();
+
+        session.execute_sql("BEGIN TRANSACTION").await.unwrap();
+        session
+            .execute_sql("INSERT INTO accounts VALUES (3, 75)")
+            .await
+            .unwrap();
+    }
+
+    let engine = YachtSQLEngine::open(dir.path()).await.unwrap();
+    let session = engine.create_session();
+    let result = session
+        .execute_sql("SELECT id, balance FROM accounts ORDER BY id")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[1, 100], [2, 50]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_checkpoint_waits_for_open_transactions() {
+    let dir = tempfile::tempdir().unwrap();
+    {
+        let engine = YachtSQLEngine::open(dir.path()).await.unwrap();
+        let writer = engine.create_session();
+        let other = engine.create_session();
+        writer
+            .execute_sql("CREATE TABLE accounts (id INT64)")
+            .await
+            .unwrap();
+        writer
+            .execute_sql("INSERT INTO accounts VALUES (1)")
+            .await
+            .unwrap();
+
+        writer.execute_sql("BEGIN TRANSACTION").await.unwrap();
+        writer
+            .execute_sql("INSERT INTO accounts VALUES (2)")
+            .await
+            .unwrap();
+        engine.checkpoint().await.unwrap();
+        other
+            .execute_sql("CREATE TABLE audit (id INT64)")
+            .await
+            .unwrap();
+        assert!(dir.path().join("wal-0.log").exists());
+    }
+
+    let engine = YachtSQLEngine::open(dir.path()).await.unwrap();
+    let session = engine.create_session();
+    let result = session
+        .execute_sql("SELECT id FROM accounts ORDER BY id")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[1]]);
+    assert!(session.catalog().table_exists("audit"));
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_scripts_fail_while_another_transaction_is_open() {
+    let dir = tempfile::tempdir().unwrap();
+    let engine = YachtSQLEngine::open(dir.path()).await.unwrap();
+    let writer = engine.create_session();
+    let caller = engine.create_session();
+    writer
+        .execute_sql("CREATE TABLE events (id INT64)")
+        .await
+        .unwrap();
+    writer
+        .execute_sql("CREATE PROCEDURE add_event(p INT64) BEGIN INSERT INTO events VALUES (p); END")
+        .await
+        .unwrap();
+
+    writer.execute_sql("BEGIN TRANSACTION").await.unwrap();
+    writer
+        .execute_sql("INSERT INTO events VALUES (1)")
+        .await
+        .unwrap();
+    let error = caller
+        .execute_sql("CALL add_event(2)")
+        .await
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("transaction open"), "{}", error);
+
+    writer.execute_sql("COMMIT").await.unwrap();
+    caller.execute_sql("CALL add_event(2)").await.unwrap();
+    let result = caller
+        .execute_sql("SELECT id FROM events ORDER BY id")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[1], [2]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_procedure_calls_survive_reopen() {
+    let dir = tempfile::tempdir().unwrap();
+    {
+        let engine = YachtSQLEngine::open(dir.path()).await.unwrap();
+        let session = engine.create_session();
+        session
+            .execute_sql("CREATE TABLE events (id INT64)")
+            .await
+            .unwrap();
+        session
+            .execute_sql(
+                "CREATE PROCEDURE add_event(p INT64) BEGIN INSERT INTO events VALUES (p); END",
+            )
+            .await
+            .unwrap();
+        session.execute_sql("CALL add_event(7)").await.unwrap();
+        session
+            .execute_sql("INSERT INTO events VALUES (8)")
+            .await
+            .unwrap();
+    }
+
+    let engine = YachtSQLEngine::open(dir.path()).await.unwrap();
+    let session = engine.create_session();
+    session.execute_sql("CALL add_event(9)").await.unwrap();
+    let result = session
+        .execute_sql("SELECT id FROM events ORDER BY id")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[7], [8], [9]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_temp_objects_are_not_persisted() {
+    let dir = tempfile::tempdir().unwrap();
+    {
+        let engine = YachtSQLEngine::open(dir.path()).await.unwrap();
+        let session = engine.create_session();
+        session
+            .execute_sql("CREATE TEMP TABLE scratch (id INT64)")
+            .await
+            .unwrap();
+        session
+            .execute_sql("INSERT INTO scratch VALUES (1)")
+            .await
+            .unwrap();
+        session
+            .execute_sql("CREATE TABLE kept AS SELECT id FROM scratch")
+            .await
+            .unwrap();
+        session
+            .execute_sql("CREATE TEMP FUNCTION f(x INT64) AS (x)")
+            .await
+            .unwrap();
+        session.execute_sql("DROP FUNCTION f").await.unwrap();
+    }
+
+    let engine = YachtSQLEngine::open(dir.path()).await.unwrap();
+    let session = engine.create_session();
+    assert!(!session.catalog().table_exists("scratch"));
+    let result = session.execute_sql("SELECT id FROM kept").await.unwrap();
+    assert_table_eq!(result, [[1]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_sessions_share_the_durable_catalog() {
+    let dir = tempfile::tempdir().unwrap();
+    let engine = YachtSQLEngine::open(dir.path()).await.unwrap();
+    let writer = engine.create_session();
+    let reader = engine.create_session();
+
+    writer
+        .execute_sql("CREATE TABLE shared (id INT64)")
+        .await
+        .unwrap();
+    reader
+        .execute_sql("INSERT INTO shared VALUES (1)")
+        .await
+        .unwrap();
+
+    let result = writer.execute_sql("SELECT id FROM shared").await.unwrap();
+    assert_table_eq!(result, [[1]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_torn_log_tail_is_discarded() {
+    let dir = tempfile::tempdir().unwrap();
+    {
+        let engine = YachtSQLEngine::open(dir.path()).await.unwrap();
+        let session = engine.create_session();
+        session
+            .execute_sql("CREATE TABLE t (id INT64)")
+            .await
+            .unwrap();
+        session
+            .execute_sql("INSERT INTO t VALUES (1)")
+            .await
+            .unwrap();
+    }
+    let mut wal = OpenOptions::new()
+        .append(true)
+        .open(wal_file(dir.path()))
+        .unwrap();
+    wal.write_all(&200u32.to_le_bytes()).unwrap();
+    wal.write_all(&[0xAB; 37]).unwrap();
+    drop(wal);
+
+    {
+        let engine = YachtSQLEngine::open(dir.path()).await.unwrap();
+        let session = engine.create_session();
+        session
+            .execute_sql("INSERT INTO t VALUES (2)")
+            .await
+            .unwrap();
+    }
+
+    let engine = YachtSQLEngine::open(dir.path()).await.unwrap();
+    let session = engine.create_session();
+    let result = session
+        .execute_sql("SELECT id FROM t ORDER BY id")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[1], [2]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_directory_is_locked_while_open() {
+    let dir = tempfile::tempdir().unwrap();
+    let engine = YachtSQLEngine::open(dir.path()).await.unwrap();
+    let session = engine.create_session();
+
+    let err = YachtSQLEngine::open(dir.path()).await.err().unwrap();
+    assert!(err.to_string().contains("already open"));
+
+    drop(session);
+    drop(engine);
+    assert!(YachtSQLEngine::open(dir.path()).await.is_ok());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_recovers_after_process_is_killed() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut child = Command::new(std::env::current_exe().unwrap())
+        .args([
+            "--exact",
+            "queries::durable_storage::crash_child_writer",
+            "--nocapture",
+            "--test-threads=1",
+        ])
+        .env(CRASH_CHILD_DIR, dir.path())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+
+    let mut committed = None;
+    for line in BufReader::new(child.stdout.take().unwrap()).lines() {
+        let line = line.unwrap();
+        if let Some(n) = line.strip_prefix("committed ") {
+            let n: i64 = n.trim().parse().unwrap();
+            committed = Some(n);
+            if n >= 40 {
+                break;
+            }
+        }
+    }
+    child.kill().unwrap();
+    child.wait().unwrap();
+    let committed = committed.expect("child never committed a row");
+
+    let engine = YachtSQLEngine::open(dir.path()).await.unwrap();
+    let session = engine.create_session();
+    let result = session
+        .query("SELECT COUNT(*), MIN(n), MAX(n), COUNT(DISTINCT n) FROM counter")
+        .await
+        .unwrap();
+    let row = result.rows[0].values();
+    let count = row[0].as_i64().unwrap();
+    assert!(
+        count > committed,
+        "lost committed rows: {} <= {}",
+        count,
+        committed
+    );
+    assert_eq!(row[1].as_i64(), Some(0));
+    assert_eq!(row[2].as_i64(), Some(count - 1));
+    assert_eq!(row[3].as_i64(), Some(count));
+}
+
+/// Runs as the child of `test_recovers_after_process_is_killed`: inserts
+/// increasing numbers, checkpointing now and then, until it is killed.
+#[tokio::test(flavor = "current_thread")]
+async fn crash_child_writer() {
+    let Some(dir) = std::env::var_os(CRASH_CHILD_DIR) else {
+        return;
+    };
+    let engine = YachtSQLEngine::open(&dir).await.unwrap();
+    let session = engine.create_session();
+    session
+        .execute_sql("CREATE TABLE counter (n INT64)")
+        .await
+        .unwrap();
+    for n in 0.. {
+        session
+            .execute_sql(&format!("INSERT INTO counter VALUES ({})", n))
+            .await
+            .unwrap();
+        println!("committed {}", n);
+        if n % 7 == 6 {
+            engine.checkpoint().await.unwrap();
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use yachtsql::YachtSQLEngine;
+
+use crate::assert_table_eq;
+use crate::common::create_session;
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_jobs_records_statements_and_affected_rows() {
+    let session = create_session();
+    session
+        .execute_sql("CREATE TABLE users (id INT64, name STRING)")
+        .await
+        .unwrap();
+    session
+        .execute_sql("INSERT INTO users VALUES (1, 'a'), (2, 'b'), (3, 'c')")
+        .await
+        .unwrap();
+    session
+        .execute_sql("UPDATE users SET name = 'z' WHERE id >= 2")
+        .await
+        .unwrap();
+    session
+        .execute_sql("DELETE FROM users WHERE id = 1")
+        .await
+        .unwrap();
+    session
+        .execute_sql("SELECT * FROM users WHERE id = 2")
+        .await
+        .unwrap();
+
+    let result = session
+        .execute_sql(
+            "SELECT statement_type, num_dml_affected_rows, destination_table.table_id,
+                    job_type, state, end_time >= start_time
+             FROM INFORMATION_SCHEMA.JOBS
+             ORDER BY job_id",
+        )
+        .await
+        .unwrap();
+    assert_table_eq!(
+        result,
+        [
+            ["CREATE_TABLE", null, "users", "QUERY", "DONE", true],
+            ["INSERT", 3, "users", "QUERY", "DONE", true],
+            ["UPDATE", 2, "users", "QUERY", "DONE", true],
+            ["DELETE", 1, "users", "QUERY", "DONE", true],
+            ["SELECT", null, null, "QUERY", "DONE", true],
+        ]
+    );
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_jobs_lists_referenced_tables_and_bytes() {
+    let session = create_session();
+    session
+        .execute_sql("CREATE TABLE orders (id INT64, customer INT64)")
+        .await
+        .unwrap();
+    session
+        .execute_sql("CREATE TABLE customers (id INT64, name STRING)")
+        .await
+        .unwrap();
+    session
+        .execute_sql("INSERT INTO orders VALUES (1, 1), (2, 1)")
+        .await
+        .unwrap();
+    session
+        .execute_sql("INSERT INTO customers VALUES (1, 'a')")
+        .await
+        .unwrap();
+    session
+        .execute_sql(
+            "SELECT c.name, COUNT(*) FROM orders o JOIN customers c ON o.customer = c.id \
+             GROUP BY c.name",
+        )
+        .await
+        .unwrap();
+
+    let result = session
+        .execute_sql(
+            "SELECT t.table_id, t.dataset_id
+             FROM INFORMATION_SCHEMA.JOBS AS j, UNNEST(j.referenced_tables) AS t
+             WHERE j.statement_type = 'SELECT'
+             ORDER BY t.table_id",
+        )
+        .await
+        .unwrap();
+    assert_table_eq!(result, [["customers", null], ["orders", null]]);
+
+    let result = session
+        .execute_sql(
+            "SELECT statement_type, total_bytes_processed > 0
+             FROM INFORMATION_SCHEMA.JOBS
+             WHERE statement_type IN ('CREATE_TABLE', 'SELECT')
+             ORDER BY job_id",
+        )
+        .await
+        .unwrap();
+    assert_table_eq!(
+        result,
+        [
+            ["CREATE_TABLE", false],
+            ["CREATE_TABLE", false],
+            ["SELECT", true],
+            ["SELECT", false],
+        ]
+    );
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_jobs_records_errors() {
+    let session = create_session();
+    assert!(session.execute_sql("SELEC 1").await.is_err());
+    assert!(session.execute_sql("SELECT * FROM missing").await.is_err());
+    session.execute_sql("SELECT 1").await.unwrap();
+
+    let result = session
+        .execute_sql(
+            "SELECT statement_type, error_result.reason, error_result.message IS NOT NULL
+             FROM `region-us`.INFORMATION_SCHEMA.JOBS
+             ORDER BY job_id",
+        )
+        .await
+        .unwrap();
+    assert_table_eq!(
+        result,
+        [
+            [null, "invalidQuery", true],
+            [null, "notFound", true],
+            ["SELECT", null, false],
+        ]
+    );
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_jobs_history_is_per_engine_and_bounded() {
+    let engine = YachtSQLEngine::new().with_job_history_limit(3);
+    let first = engine.create_session();
+    let second = engine.create_session();
+
+    first.execute_sql("SELECT 1").await.unwrap();
+    second.execute_sql("SELECT 2").await.unwrap();
+    first.execute_sql("SELECT 3").await.unwrap();
+    second.execute_sql("SELECT 4").await.unwrap();
+
+    let queries: Vec<String> = engine
+        .job_history()
+        .jobs()
+        .into_iter()
+        .map(|job| job.query)
+        .collect();
+    assert_eq!(queries, vec!["SELECT 2", "SELECT 3", "SELECT 4"]);
+
+    let result = first
+        .execute_sql("SELECT query FROM INFORMATION_SCHEMA.JOBS_BY_USER ORDER BY job_id")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [["SELECT 2"], ["SELECT 3"], ["SELECT 4"]]);
+
+    let err = first
+        .execute_sql("DELETE FROM INFORMATION_SCHEMA.JOBS WHERE TRUE")
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("read-only"), "{err}");
+
+    engine.job_history().clear();
+    assert!(engine.job_history().jobs().is_empty());
+}
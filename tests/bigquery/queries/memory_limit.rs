@@ -0,0 +1,150 @@
+use yachtsql::{Error, YachtSQLSession};
+
+use crate::assert_table_eq;
+use crate::common::create_session;
+
+const LIMIT: usize = 1024 * 1024;
+
+async fn create_numbers(session: &YachtSQLSession, count: i64) {
+    session
+        .execute_sql("CREATE TABLE numbers (n INT64, label STRING)")
+        .await
+        .unwrap();
+    session
+        .execute_sql(&format!(
+            "INSERT INTO numbers SELECT n, CONCAT('label-', CAST(n AS STRING)) FROM UNNEST(GENERATE_ARRAY(1, {})) AS n",
+            count
+        ))
+        .await
+        .unwrap();
+}
+
+async fn assert_resources_exceeded(session: &YachtSQLSession, sql: &str) {
+    let err = session.execute_sql(sql).await.unwrap_err();
+    assert_eq!(
+        err,
+        Error::ResourcesExceeded {
+            limit_bytes: LIMIT as u64
+        }
+    );
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_cross_join_exceeds_memory_limit() {
+    let session = create_session();
+    create_numbers(&session, 2000).await;
+    session.set_memory_limit(Some(LIMIT));
+
+    assert_resources_exceeded(&session, "SELECT * FROM numbers a CROSS JOIN numbers b").await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_hash_join_exceeds_memory_limit() {
+    let session = create_session();
+    create_numbers(&session, 2000).await;
+    session.set_memory_limit(Some(LIMIT));
+
+    assert_resources_exceeded(
+        &session,
+        "SELECT * FROM numbers a JOIN numbers b ON MOD(a.n, 2) = MOD(b.n, 2)",
+    )
+    .await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_aggregate_exceeds_memory_limit() {
+    let session = create_session();
+    create_numbers(&session, 50000).await;
+    session.set_memory_limit(Some(LIMIT));
+
+    assert_resources_exceeded(
+        &session,
+        "SELECT label, STRING_AGG(label) FROM numbers GROUP BY label",
+    )
+    .await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_sort_exceeds_memory_limit() {
+    let session = create_session();
+    create_numbers(&session, 50000).await;
+    session.set_memory_limit(Some(LIMIT));
+
+    assert_resources_exceeded(&session, "SELECT * FROM numbers ORDER BY label DESC").await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_window_exceeds_memory_limit() {
+    let session = create_session();
+    create_numbers(&session, 50000).await;
+    session.set_memory_limit(Some(LIMIT));
+
+    assert_resources_exceeded(
+        &session,
+        "SELECT label, ROW_NUMBER() OVER (ORDER BY n DESC) FROM numbers",
+    )
+    .await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_recursive_cte_exceeds_memory_limit() {
+    let session = create_session();
+    session.set_memory_limit(Some(LIMIT));
+
+    assert_resources_exceeded(
+        &session,
+        "WITH RECURSIVE grow AS (
+            SELECT 1 AS depth, REPEAT('x', 10000) AS payload
+            UNION ALL
+            SELECT depth + 1, payload FROM grow WHERE depth < 400
+        )
+        SELECT COUNT(*) FROM grow",
+    )
+    .await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_queries_within_limit_succeed() {
+    let session = create_session();
+    create_numbers(&session, 100).await;
+    session.set_memory_limit(Some(LIMIT));
+
+    let result = session
+        .execute_sql("SELECT COUNT(*) FROM numbers a JOIN numbers b ON a.n = b.n WHERE a.n <= 10")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[10]]);
+
+    let result = session
+        .execute_sql("SELECT MOD(n, 3) AS k, COUNT(*) FROM numbers GROUP BY k ORDER BY k")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[0, 33], [1, 34], [2, 33]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_memory_limit_set_from_sql() {
+    let session = create_session();
+    create_numbers(&session, 2000).await;
+    session
+        .execute_sql(&format!("SET @@memory_limit_bytes = {}", LIMIT))
+        .await
+        .unwrap();
+
+    assert_resources_exceeded(&session, "SELECT * FROM numbers a CROSS JOIN numbers b").await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_memory_limit_can_be_removed() {
+    let session = create_session();
+    create_numbers(&session, 300).await;
+    session.set_memory_limit(Some(LIMIT));
+    assert_resources_exceeded(&session, "SELECT * FROM numbers a CROSS JOIN numbers b").await;
+
+    session.set_memory_limit(None);
+    let result = session
+        .execute_sql("SELECT COUNT(*) FROM (SELECT * FROM numbers a CROSS JOIN numbers b)")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[90000]]);
+}
@@ -1,6 +1,9 @@
 mod aggregate_planning;
 mod aliases;
 mod analytical;
+mod arrow_interop;
+mod bigquery_schema;
+mod catalog_persistence;
 mod column_reference;
 mod columnar_alias;
 mod columnar_array;
@@ -17,6 +20,8 @@ mod columnar_variable;
 mod complex_correctness;
 mod cte;
 mod distinct;
+mod dry_run;
+mod durable_storage;
 mod expr_planner_coverage;
 mod expression_dedup_tests;
 mod expressions;
@@ -26,24 +31,36 @@ mod gap_fill;
 mod group_by;
 mod grouping;
 // mod information_schema; // TODO: INFORMATION_SCHEMA not implemented yet
+mod information_schema_jobs;
 mod ir_plan;
 mod joins;
+mod memory_limit;
 mod optimizer_equivalence;
 mod order_by_edge_cases;
 mod order_limit;
 mod params;
 mod parser_lib_coverage;
 mod pivot;
+mod prepared_statements;
 mod projection;
 mod qualify;
+mod query_cancellation;
+mod query_observer;
+mod query_parameters;
+mod query_stream;
+mod script_execution;
 mod scripting;
+mod session_fork;
 mod set_operations;
+mod shared_sessions;
 mod sort_coverage;
 mod subqueries;
+mod table_provider;
 mod tablesample;
 mod time_travel;
 mod type_edge_cases;
 mod type_inference;
+mod typed_rows;
 mod unnest;
 mod value_evaluator_edge_cases;
 mod window_functions;
@@ -0,0 +1,199 @@
+use yachtsql::{DataType, QueryParameter, Value};
+
+use crate::assert_table_eq;
+use crate::common::create_session;
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_prepared_statement_runs_with_different_parameters() {
+    let session = create_session();
+    session
+        .execute_sql("CREATE TABLE items (id INT64, name STRING)")
+        .await
+        .unwrap();
+    session
+        .execute_sql("INSERT INTO items VALUES (1, 'apple'), (2, 'banana'), (3, 'cherry')")
+        .await
+        .unwrap();
+
+    let stmt = session
+        .prepare("SELECT name FROM items WHERE id = ?")
+        .unwrap();
+    assert_eq!(stmt.positional_parameter_count(), 1);
+
+    let result = stmt
+        .execute(&[QueryParameter::positional(Value::int64(1))])
+        .await
+        .unwrap();
+    assert_table_eq!(result, [["apple"]]);
+
+    let result = stmt
+        .execute(&[QueryParameter::positional(Value::int64(3))])
+        .await
+        .unwrap();
+    assert_table_eq!(result, [["cherry"]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_prepared_statement_with_named_parameters() {
+    let session = create_session();
+
+    let stmt = session.prepare("SELECT @a * @b").unwrap();
+    assert_eq!(stmt.parameter_names(), ["a".to_string(), "b".to_string()]);
+
+    let result = stmt
+        .query(&[
+            QueryParameter::named("a", Value::int64(6)),
+            QueryParameter::named("b", Value::int64(7)),
+        ])
+        .await
+        .unwrap();
+    assert_eq!(result.rows[0].get(0), Some(&Value::int64(42)));
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_prepared_statement_sees_new_rows() {
+    let session = create_session();
+    session
+        .execute_sql("CREATE TABLE events (id INT64)")
+        .await
+        .unwrap();
+
+    let count = session.prepare("SELECT COUNT(*) FROM events").unwrap();
+    let insert = session.prepare("INSERT INTO events VALUES (?)").unwrap();
+
+    for id in 0..5 {
+        insert
+            .execute(&[QueryParameter::positional(Value::int64(id))])
+            .await
+            .unwrap();
+    }
+
+    let result = count.execute(&[]).await.unwrap();
+    assert_table_eq!(result, [[5]]);
+    assert!(!count.is_stale());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_prepared_statement_replans_after_ddl_on_referenced_table() {
+    let session = create_session();
+    session
+        .execute_sql("CREATE TABLE t (a INT64)")
+        .await
+        .unwrap();
+    session
+        .execute_sql("INSERT INTO t VALUES (1)")
+        .await
+        .unwrap();
+
+    let stmt = session.prepare("SELECT * FROM t").unwrap();
+    assert_table_eq!(stmt.execute(&[]).await.unwrap(), [[1]]);
+
+    session.execute_sql("DROP TABLE t").await.unwrap();
+    session
+        .execute_sql("CREATE TABLE t (a INT64, b STRING)")
+        .await
+        .unwrap();
+    session
+        .execute_sql("INSERT INTO t VALUES (2, 'two')")
+        .await
+        .unwrap();
+
+    assert!(stmt.is_stale());
+    assert_table_eq!(stmt.execute(&[]).await.unwrap(), [[2, "two"]]);
+    assert!(!stmt.is_stale());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_prepared_statement_errors_after_referenced_table_dropped() {
+    let session = create_session();
+    session
+        .execute_sql("CREATE TABLE t (a INT64)")
+        .await
+        .unwrap();
+
+    let stmt = session.prepare("SELECT a FROM t").unwrap();
+    session.execute_sql("DROP TABLE t").await.unwrap();
+
+    assert!(stmt.is_stale());
+    assert!(stmt.execute(&[]).await.is_err());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_prepared_statement_not_invalidated_by_unrelated_ddl() {
+    let session = create_session();
+    session
+        .execute_sql("CREATE TABLE t (a INT64)")
+        .await
+        .unwrap();
+
+    let stmt = session.prepare("SELECT a FROM t").unwrap();
+    session
+        .execute_sql("CREATE TABLE other (x INT64)")
+        .await
+        .unwrap();
+    assert!(!stmt.is_stale());
+
+    session
+        .execute_sql("CREATE VIEW v AS SELECT x FROM other")
+        .await
+        .unwrap();
+    assert!(stmt.is_stale());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_prepared_statement_checks_parameters_per_execution() {
+    let session = create_session();
+    session
+        .execute_sql("CREATE TABLE t (a INT64)")
+        .await
+        .unwrap();
+
+    let stmt = session.prepare("SELECT a FROM t WHERE a = ?").unwrap();
+
+    let err = stmt.execute(&[]).await.unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Query has 1 positional parameters but 0 values were provided")
+    );
+
+    let err = stmt
+        .execute(&[QueryParameter::positional(Value::string("x"))])
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("No matching signature for operator =")
+    );
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_prepare_reports_parse_errors() {
+    let session = create_session();
+
+    assert!(session.prepare("SELEC 1").is_err());
+    assert!(session.prepare("SELECT * FROM missing_table").is_err());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_prepared_parameter_columns_take_the_bound_types() {
+    let session = create_session();
+    let stmt = session
+        .prepare("SELECT @n + 1 AS next, @s AS label")
+        .unwrap();
+
+    let result = stmt
+        .execute(&[
+            QueryParameter::named("n", Value::int64(1)),
+            QueryParameter::named("s", Value::string("one")),
+        ])
+        .await
+        .unwrap();
+    let types: Vec<_> = result
+        .schema()
+        .fields()
+        .iter()
+        .map(|field| field.data_type.clone())
+        .collect();
+    assert_eq!(types, [DataType::Int64, DataType::String]);
+    assert_table_eq!(result, [[2, "one"]]);
+}
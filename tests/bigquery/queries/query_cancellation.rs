@@ -0,0 +1,224 @@
+use std::time::{Duration, Instant};
+
+use yachtsql::{CancellationHandle, Error, YachtSQLSession};
+
+use crate::assert_table_eq;
+use crate::common::create_session;
+
+const SLOW_JOIN: &str = "SELECT COUNT(*) FROM numbers a JOIN numbers b ON MOD(a.n + b.n, 7) = 99";
+
+async fn create_numbers(session: &YachtSQLSession, count: i64) {
+    session
+        .execute_sql("CREATE TABLE numbers (n INT64)")
+        .await
+        .unwrap();
+    session
+        .execute_sql(&format!(
+            "INSERT INTO numbers SELECT n FROM UNNEST(GENERATE_ARRAY(1, {})) AS n",
+            count
+        ))
+        .await
+        .unwrap();
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_cancel_running_join() {
+    let session = create_session();
+    create_numbers(&session, 20000).await;
+
+    let job = session.start_query(SLOW_JOIN);
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let started = Instant::now();
+    job.cancel();
+
+    let err = job.wait().await.unwrap_err();
+    assert_eq!(err, Error::QueryCancelled);
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_cancel_through_shared_handle() {
+    let session = create_session();
+    create_numbers(&session, 20000).await;
+
+    let cancel = CancellationHandle::new();
+    let canceller = cancel.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        canceller.cancel();
+    });
+
+    let err = session
+        .execute_sql_with_cancellation(SLOW_JOIN, &cancel)
+        .await
+        .unwrap_err();
+    assert_eq!(err, Error::QueryCancelled);
+    assert!(cancel.is_cancelled());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_cancelled_handle_stops_query_before_it_runs() {
+    let session = create_session();
+    create_numbers(&session, 10).await;
+
+    let cancel = CancellationHandle::new();
+    cancel.cancel();
+    let err = session
+        .execute_sql_with_cancellation("SELECT COUNT(*) FROM numbers", &cancel)
+        .await
+        .unwrap_err();
+    assert_eq!(err, Error::QueryCancelled);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_completed_job_returns_result() {
+    let session = create_session();
+    create_numbers(&session, 10).await;
+
+    let job = session.start_query("SELECT SUM(n) FROM numbers");
+    let handle = job.cancellation_handle();
+    let result = job.wait().await.unwrap();
+    assert_table_eq!(result, [[55]]);
+    assert!(!handle.is_cancelled());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_job_timeout_on_join() {
+    let session = create_session();
+    create_numbers(&session, 20000).await;
+    session.set_job_timeout(Some(Duration::from_millis(100)));
+
+    let err = session.execute_sql(SLOW_JOIN).await.unwrap_err();
+    assert_eq!(err, Error::QueryTimeout { timeout_ms: 100 });
+    assert_eq!(err.to_string(), "Query exceeded timeout of 100 ms");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_job_timeout_set_from_sql() {
+    let session = create_session();
+    create_numbers(&session, 20000).await;
+    session
+        .execute_sql("SET @@job_timeout_ms = 100")
+        .await
+        .unwrap();
+
+    let err = session.execute_sql(SLOW_JOIN).await.unwrap_err();
+    assert_eq!(err, Error::QueryTimeout { timeout_ms: 100 });
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_job_timeout_on_aggregation() {
+    let session = create_session();
+    create_numbers(&session, 1000000).await;
+    session.set_job_timeout(Some(Duration::from_millis(20)));
+
+    let err = session
+        .execute_sql(
+            "SELECT MOD(n, 10) AS k, STRING_AGG(CAST(n AS STRING)) FROM numbers GROUP BY k",
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err, Error::QueryTimeout { timeout_ms: 20 });
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_job_timeout_on_while_loop() {
+    let session = create_session();
+    session
+        .execute_sql("DECLARE total INT64 DEFAULT 0")
+        .await
+        .unwrap();
+    session.set_job_timeout(Some(Duration::from_millis(100)));
+
+    let err = session
+        .execute_sql(
+            "WHILE TRUE DO SET total = total + 1; SELECT COUNT(*) FROM UNNEST(GENERATE_ARRAY(1, 20000)); END WHILE",
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err, Error::QueryTimeout { timeout_ms: 100 });
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_job_timeout_on_loop() {
+    let session = create_session();
+    session
+        .execute_sql("DECLARE total INT64 DEFAULT 0")
+        .await
+        .unwrap();
+    session.set_job_timeout(Some(Duration::from_millis(100)));
+
+    let err = session
+        .execute_sql(
+            "LOOP SET total = total + 1; SELECT COUNT(*) FROM UNNEST(GENERATE_ARRAY(1, 20000)); END LOOP",
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err, Error::QueryTimeout { timeout_ms: 100 });
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_job_timeout_on_recursive_cte() {
+    let session = create_session();
+    session.set_job_timeout(Some(Duration::from_millis(100)));
+
+    let err = session
+        .execute_sql(
+            "WITH RECURSIVE grow AS (
+                SELECT 1 AS depth, GENERATE_ARRAY(1, 50000) AS items
+                UNION ALL
+                SELECT depth + 1, ARRAY(SELECT x + 1 FROM UNNEST(items) AS x) FROM grow WHERE depth < 400
+            )
+            SELECT MAX(depth) FROM grow",
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err, Error::QueryTimeout { timeout_ms: 100 });
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_exception_handler_does_not_catch_timeout() {
+    let session = create_session();
+    session
+        .execute_sql("DECLARE handled BOOL DEFAULT FALSE")
+        .await
+        .unwrap();
+    session.set_job_timeout(Some(Duration::from_millis(100)));
+
+    let err = session
+        .execute_sql(
+            "BEGIN
+                LOOP SELECT COUNT(*) FROM UNNEST(GENERATE_ARRAY(1, 20000)); END LOOP;
+            EXCEPTION WHEN ERROR THEN
+                SET handled = TRUE;
+            END",
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err, Error::QueryTimeout { timeout_ms: 100 });
+
+    session.set_job_timeout(None);
+    let result = session.execute_sql("SELECT handled").await.unwrap();
+    assert_table_eq!(result, [[false]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_session_usable_after_timeout() {
+    let session = create_session();
+    create_numbers(&session, 20000).await;
+    session.set_job_timeout(Some(Duration::from_millis(50)));
+    assert!(session.execute_sql(SLOW_JOIN).await.is_err());
+
+    let result = session
+        .execute_sql("SELECT COUNT(*) FROM numbers")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[20000]]);
+
+    session.set_job_timeout(None);
+    let result = session
+        .execute_sql("SELECT COUNT(*) FROM numbers a JOIN numbers b ON a.n = b.n")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[20000]]);
+}
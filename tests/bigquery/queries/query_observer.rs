@@ -0,0 +1,295 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use yachtsql::{PlanReady, QueryFinish, QueryObserver, QueryParameter, QueryStart, Value};
+
+use crate::common::create_session;
+
+#[derive(Debug, Clone, PartialEq)]
+struct Finished {
+    query_id: u64,
+    fingerprint: String,
+    rows: usize,
+    tables_read: Vec<String>,
+    tables_written: Vec<String>,
+    error: Option<String>,
+    duration: Duration,
+}
+
+#[derive(Default)]
+struct Recorder {
+    started: Mutex<Vec<(u64, String, String)>>,
+    planned: Mutex<Vec<u64>>,
+    finished: Mutex<Vec<Finished>>,
+}
+
+impl QueryObserver for Recorder {
+    fn on_query_start(&self, event: &QueryStart<'_>) {
+        self.started.lock().unwrap().push((
+            event.query_id,
+            event.sql.to_string(),
+            event.fingerprint.to_string(),
+        ));
+    }
+
+    fn on_plan_ready(&self, event: &PlanReady<'_>) {
+        assert_eq!(
+            event.logical_plan.schema().fields.len(),
+            event.physical_plan.schema().fields.len()
+        );
+        self.planned.lock().unwrap().push(event.query_id);
+    }
+
+    fn on_query_finish(&self, event: &QueryFinish<'_>) {
+        self.finished.lock().unwrap().push(Finished {
+            query_id: event.query_id,
+            fingerprint: event.fingerprint.to_string(),
+            rows: event.rows,
+            tables_read: event.tables_read.to_vec(),
+            tables_written: event.tables_written.to_vec(),
+            error: event.error.map(|e| e.to_string()),
+            duration: event.duration,
+        });
+    }
+}
+
+impl Recorder {
+    fn finished(&self) -> Vec<Finished> {
+        self.finished.lock().unwrap().clone()
+    }
+
+    fn fingerprints(&self) -> Vec<String> {
+        self.finished()
+            .into_iter()
+            .map(|finished| finished.fingerprint)
+            .collect()
+    }
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_observer_receives_lifecycle_events() {
+    let session = create_session();
+    let recorder = Arc::new(Recorder::default());
+    session.add_observer(recorder.clone());
+
+    session
+        .execute_sql("CREATE TABLE users (id INT64, name STRING)")
+        .await
+        .unwrap();
+    session
+        .execute_sql("INSERT INTO users VALUES (1, 'a'), (2, 'b'), (3, 'c')")
+        .await
+        .unwrap();
+    session
+        .execute_sql("SELECT name FROM users WHERE id = 1")
+        .await
+        .unwrap();
+    session
+        .execute_sql("select name from users where id = 2")
+        .await
+        .unwrap();
+
+    let started = recorder.started.lock().unwrap().clone();
+    let planned = recorder.planned.lock().unwrap().clone();
+    let finished = recorder.finished();
+    assert_eq!(started.len(), 4);
+    assert_eq!(
+        started.iter().map(|s| s.0).collect::<Vec<_>>(),
+        planned,
+        "every statement is planned once, in order"
+    );
+    assert_eq!(
+        started.iter().map(|s| s.0).collect::<Vec<_>>(),
+        finished.iter().map(|f| f.query_id).collect::<Vec<_>>()
+    );
+    assert_eq!(started[2].1, "SELECT name FROM users WHERE id = 1");
+
+    assert_eq!(finished[1].tables_written, vec!["users".to_string()]);
+    assert!(finished[1].tables_read.is_empty());
+    assert_eq!(finished[2].tables_read, vec!["users".to_string()]);
+    assert!(finished[2].tables_written.is_empty());
+    assert_eq!(finished[2].rows, 1);
+    assert!(finished.iter().all(|f| f.error.is_none()));
+
+    assert_eq!(
+        finished[2].fingerprint,
+        "SELECT NAME FROM USERS WHERE ID = ?"
+    );
+    assert_eq!(finished[2].fingerprint, finished[3].fingerprint);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_observer_sees_failed_queries() {
+    let session = create_session();
+    let recorder = Arc::new(Recorder::default());
+    session.add_observer(recorder.clone());
+
+    assert!(session.execute_sql("SELEC 1").await.is_err());
+    assert!(session.execute_sql("SELECT * FROM missing").await.is_err());
+    assert!(
+        session
+            .execute_sql("SELECT ERROR('boom') FROM UNNEST([1])")
+            .await
+            .is_err()
+    );
+
+    let finished = recorder.finished();
+    assert_eq!(finished.len(), 3);
+    assert!(finished.iter().all(|f| f.error.is_some() && f.rows == 0));
+    assert!(finished[2].error.as_deref().unwrap().contains("boom"));
+
+    let planned = recorder.planned.lock().unwrap().clone();
+    assert_eq!(planned, vec![finished[2].query_id]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_observer_covers_prepared_streamed_and_scripted_queries() {
+    let session = create_session();
+    session
+        .execute_sql("CREATE TABLE items (id INT64)")
+        .await
+        .unwrap();
+    session
+        .execute_sql("INSERT INTO items SELECT n FROM UNNEST(GENERATE_ARRAY(1, 10)) AS n")
+        .await
+        .unwrap();
+    let recorder = Arc::new(Recorder::default());
+    session.add_observer(recorder.clone());
+
+    let statement = session
+        .prepare("SELECT id FROM items WHERE id <= @max")
+        .unwrap();
+    for max in [2, 5] {
+        statement
+            .execute(&[QueryParameter::named("max", Value::Int64(max))])
+            .await
+            .unwrap();
+    }
+
+    let mut stream = session
+        .query_stream_with_batch_size("SELECT id FROM items", 3)
+        .await
+        .unwrap();
+    while let Some(batch) = stream.next_batch().await {
+        batch.unwrap();
+    }
+
+    session
+        .execute_script("SELECT 1; SELECT id FROM items WHERE id = 4")
+        .await
+        .unwrap();
+
+    let finished = recorder.finished();
+    assert_eq!(
+        finished.iter().map(|f| f.rows).collect::<Vec<_>>(),
+        vec![2, 5, 10, 1, 1]
+    );
+    assert_eq!(finished[0].fingerprint, finished[1].fingerprint);
+    assert_eq!(finished[2].tables_read, vec!["items".to_string()]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_observer_detects_repeated_queries() {
+    let session = create_session();
+    session
+        .execute_sql("CREATE TABLE orders (id INT64, customer INT64)")
+        .await
+        .unwrap();
+    session
+        .execute_sql("INSERT INTO orders VALUES (1, 1), (2, 3)")
+        .await
+        .unwrap();
+    let recorder = Arc::new(Recorder::default());
+    session.add_observer(recorder.clone());
+
+    for customer in 1..=5 {
+        session
+            .execute_sql(&format!(
+                "SELECT id FROM orders WHERE customer = {}",
+                customer
+            ))
+            .await
+            .unwrap();
+    }
+    session
+        .execute_sql("SELECT id FROM orders WHERE customer IN (1, 2, 3, 4, 5)")
+        .await
+        .unwrap();
+
+    let fingerprints = recorder.fingerprints();
+    let repeated = fingerprints
+        .iter()
+        .filter(|f| *f == "SELECT ID FROM ORDERS WHERE CUSTOMER = ?")
+        .count();
+    assert_eq!(repeated, 5);
+    assert_eq!(
+        fingerprints[5],
+        "SELECT ID FROM ORDERS WHERE CUSTOMER IN (?)"
+    );
+    assert!(
+        recorder
+            .finished()
+            .iter()
+            .all(|f| f.duration < Duration::from_secs(10))
+    );
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_engine_observers_apply_to_new_sessions_and_forks() {
+    let recorder = Arc::new(Recorder::default());
+    let engine = yachtsql::YachtSQLEngine::new().with_observer(recorder.clone());
+
+    let session = engine.create_session();
+    session.execute_sql("SELECT 1").await.unwrap();
+    let fork = session.fork();
+    fork.execute_sql("SELECT 2").await.unwrap();
+
+    let other = Arc::new(Recorder::default());
+    fork.add_observer(other.clone());
+    session.execute_sql("SELECT 3").await.unwrap();
+
+    assert_eq!(recorder.finished().len(), 3);
+    assert!(other.finished().is_empty());
+}
+
+#[derive(Default)]
+struct JobRecorder {
+    jobs: Mutex<Vec<(Option<String>, Option<u64>)>>,
+}
+
+impl QueryObserver for JobRecorder {
+    fn on_query_finish(&self, event: &QueryFinish<'_>) {
+        let job = event.job.expect("engine sessions keep a job history");
+        self.jobs
+            .lock()
+            .unwrap()
+            .push((job.statement_type.clone(), job.rows_affected));
+    }
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_observer_sees_the_job_record_of_each_statement() {
+    let engine = yachtsql::YachtSQLEngine::new();
+    let session = engine.create_session();
+    let recorder = Arc::new(JobRecorder::default());
+    session.add_observer(recorder.clone());
+
+    session
+        .execute_sql("CREATE TABLE t (id INT64)")
+        .await
+        .unwrap();
+    session
+        .execute_sql("INSERT INTO t VALUES (1), (2)")
+        .await
+        .unwrap();
+    session.execute_sql("SELECT id FROM t").await.unwrap();
+
+    assert_eq!(
+        *recorder.jobs.lock().unwrap(),
+        vec![
+            (Some("CREATE_TABLE".to_string()), None),
+            (Some("INSERT".to_string()), Some(2)),
+            (Some("SELECT".to_string()), None),
+        ]
+    );
+}
@@ -0,0 +1,443 @@
+use yachtsql::{DataType, QueryParameter, Value};
+
+use crate::assert_table_eq;
+use crate::common::create_session;
+
+async fn setup_users(session: &yachtsql::YachtSQLSession) {
+    session
+        .execute_sql("CREATE TABLE users (id INT64, name STRING, score FLOAT64, joined DATE)")
+        .await
+        .unwrap();
+    session
+        .execute_sql(
+            "INSERT INTO users VALUES
+            (1, 'Alice', 9.5, DATE '2024-01-10'),
+            (2, 'Bob', 7.0, DATE '2024-02-20'),
+            (3, 'Carol', 8.25, DATE '2024-03-30')",
+        )
+        .await
+        .unwrap();
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_named_parameters() {
+    let session = create_session();
+    setup_users(&session).await;
+
+    let result = session
+        .execute_sql_with_params(
+            "SELECT name FROM users WHERE id >= @min_id AND name != @excluded ORDER BY id",
+            &[
+                QueryParameter::named("min_id", Value::int64(2)),
+                QueryParameter::named("excluded", Value::string("Carol")),
+            ],
+        )
+        .await
+        .unwrap();
+
+    assert_table_eq!(result, [["Bob"]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_named_parameter_used_twice_and_case_insensitive() {
+    let session = create_session();
+
+    let result = session
+        .execute_sql_with_params(
+            "SELECT @Value + @value",
+            &[QueryParameter::named("VALUE", Value::int64(21))],
+        )
+        .await
+        .unwrap();
+
+    assert_table_eq!(result, [[42]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_mixed_case_script_variables_with_parameters() {
+    let session = create_session();
+    setup_users(&session).await;
+
+    session
+        .execute_sql("DECLARE minScore FLOAT64 DEFAULT 7.5")
+        .await
+        .unwrap();
+    session.execute_sql("SET MinScore = 8.0").await.unwrap();
+    let result = session
+        .execute_sql_with_params(
+            "SELECT name, MINSCORE FROM users WHERE score > minscore AND id >= @Min_Id ORDER BY id",
+            &[QueryParameter::named("min_id", Value::int64(1))],
+        )
+        .await
+        .unwrap();
+
+    assert_table_eq!(result, [["Alice", 8.0], ["Carol", 8.0]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_positional_parameters() {
+    let session = create_session();
+    setup_users(&session).await;
+
+    let result = session
+        .execute_sql_with_params(
+            "SELECT id, name FROM users WHERE score > ? AND joined < ? ORDER BY id",
+            &[
+                QueryParameter::positional(Value::float64(8.0)),
+                QueryParameter::positional(Value::date(
+                    chrono::NaiveDate::from_ymd_opt(2024, 12, 31).unwrap(),
+                )),
+            ],
+        )
+        .await
+        .unwrap();
+
+    assert_table_eq!(result, [[1, "Alice"], [3, "Carol"]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_positional_parameters_keep_order() {
+    let session = create_session();
+
+    let result = session
+        .execute_sql_with_params(
+            "SELECT ? - ?, '?'",
+            &[
+                QueryParameter::positional(Value::int64(10)),
+                QueryParameter::positional(Value::int64(3)),
+            ],
+        )
+        .await
+        .unwrap();
+
+    assert_table_eq!(result, [[7, "?"]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_array_parameter_in_unnest() {
+    let session = create_session();
+    setup_users(&session).await;
+
+    let result = session
+        .execute_sql_with_params(
+            "SELECT id FROM users WHERE name IN UNNEST(@names) ORDER BY id",
+            &[QueryParameter::named(
+                "names",
+                Value::array(vec![Value::string("Alice"), Value::string("Carol")]),
+            )],
+        )
+        .await
+        .unwrap();
+
+    assert_table_eq!(result, [[1], [3]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_struct_parameter_field_access() {
+    let session = create_session();
+
+    let result = session
+        .execute_sql_with_params(
+            "SELECT @point.x + @point.y",
+            &[QueryParameter::named(
+                "point",
+                Value::struct_val(vec![
+                    ("x".to_string(), Value::int64(3)),
+                    ("y".to_string(), Value::int64(4)),
+                ]),
+            )],
+        )
+        .await
+        .unwrap();
+
+    assert_table_eq!(result, [[7]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_parameters_in_dml() {
+    let session = create_session();
+    setup_users(&session).await;
+
+    session
+        .execute_sql_with_params(
+            "INSERT INTO users (id, name) VALUES (@id, @name)",
+            &[
+                QueryParameter::named("id", Value::int64(4)),
+                QueryParameter::named("name", Value::string("Dave")),
+            ],
+        )
+        .await
+        .unwrap();
+    session
+        .execute_sql_with_params(
+            "UPDATE users SET score = ? WHERE id = ?",
+            &[
+                QueryParameter::positional(Value::float64(5.5)),
+                QueryParameter::positional(Value::int64(4)),
+            ],
+        )
+        .await
+        .unwrap();
+    session
+        .execute_sql_with_params(
+            "DELETE FROM users WHERE id < @id",
+            &[QueryParameter::named("id", Value::int64(3))],
+        )
+        .await
+        .unwrap();
+
+    let result = session
+        .execute_sql("SELECT id, name, score FROM users ORDER BY id")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[3, "Carol", 8.25], [4, "Dave", 5.5]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_typed_null_parameter() {
+    let session = create_session();
+    setup_users(&session).await;
+
+    let result = session
+        .execute_sql_with_params(
+            "SELECT COUNT(*) FROM users WHERE id = @id OR @id IS NULL",
+            &[QueryParameter::named("id", Value::null()).with_type(DataType::Int64)],
+        )
+        .await
+        .unwrap();
+
+    assert_table_eq!(result, [[3]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_declared_type_converts_value() {
+    let session = create_session();
+
+    let result = session
+        .execute_sql_with_params(
+            "SELECT @amount * 2",
+            &[QueryParameter::named("amount", Value::int64(2)).with_type(DataType::Float64)],
+        )
+        .await
+        .unwrap();
+
+    assert_table_eq!(result, [[4.0]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_parameters_do_not_leak_into_session() {
+    let session = create_session();
+
+    session
+        .execute_sql_with_params("SELECT @x", &[QueryParameter::named("x", Value::int64(1))])
+        .await
+        .unwrap();
+
+    assert!(session.session().get_variable("@x").is_none());
+    let err = session
+        .execute_sql_with_params("SELECT @x", &[])
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Unbound query parameter '@x'"));
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_query_with_params_returns_query_result() {
+    let session = create_session();
+    setup_users(&session).await;
+
+    let result = session
+        .query_with_params(
+            "SELECT name FROM users WHERE id = @id",
+            &[QueryParameter::named("id", Value::int64(2))],
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(result.row_count(), 1);
+    assert_eq!(result.rows[0].get(0), Some(&Value::string("Bob")));
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_unbound_named_parameter_errors() {
+    let session = create_session();
+    setup_users(&session).await;
+
+    let err = session
+        .execute_sql_with_params(
+            "SELECT name FROM users WHERE id = @id AND name = @name",
+            &[QueryParameter::named("id", Value::int64(1))],
+        )
+        .await
+        .unwrap_err();
+
+    assert!(err.to_string().contains("Unbound query parameter '@name'"));
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_session_variable_does_not_bind_parameter() {
+    let session = create_session();
+    setup_users(&session).await;
+    session.session().set_variable("@id", Value::int64(1));
+
+    let err = session
+        .execute_sql_with_params("SELECT name FROM users WHERE id = @id", &[])
+        .await
+        .unwrap_err();
+
+    assert!(err.to_string().contains("Unbound query parameter '@id'"));
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_unused_parameters_error() {
+    let session = create_session();
+    setup_users(&session).await;
+
+    let err = session
+        .execute_sql_with_params(
+            "SELECT name FROM users WHERE id = @id",
+            &[
+                QueryParameter::named("id", Value::int64(1)),
+                QueryParameter::named("limit", Value::int64(10)),
+            ],
+        )
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Query parameter '@limit' is not used in the query")
+    );
+
+    let err = session
+        .execute_sql_with_params("SELECT 1", &[QueryParameter::positional(Value::int64(1))])
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Query has 0 positional parameters but 1 values were provided")
+    );
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_positional_count_mismatch_errors() {
+    let session = create_session();
+
+    let err = session
+        .execute_sql_with_params(
+            "SELECT ?, ?",
+            &[QueryParameter::positional(Value::int64(1))],
+        )
+        .await
+        .unwrap_err();
+
+    assert!(
+        err.to_string()
+            .contains("Query has 2 positional parameters but 1 values were provided")
+    );
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_mixed_parameter_styles_error() {
+    let session = create_session();
+
+    let err = session
+        .execute_sql_with_params(
+            "SELECT @a, ?",
+            &[
+                QueryParameter::named("a", Value::int64(1)),
+                QueryParameter::named("b", Value::int64(2)),
+            ],
+        )
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("cannot mix named (@name) and positional (?) parameters")
+    );
+
+    let err = session
+        .execute_sql_with_params(
+            "SELECT @a",
+            &[
+                QueryParameter::named("a", Value::int64(1)),
+                QueryParameter::positional(Value::int64(2)),
+            ],
+        )
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("all named or all positional"));
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_mistyped_comparison_parameter_errors() {
+    let session = create_session();
+    setup_users(&session).await;
+
+    let err = session
+        .execute_sql_with_params(
+            "SELECT name FROM users WHERE id = @id",
+            &[QueryParameter::named("id", Value::string("1"))],
+        )
+        .await
+        .unwrap_err();
+
+    assert!(
+        err.to_string()
+            .contains("No matching signature for operator = for argument types: INT64, STRING")
+    );
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_mistyped_array_parameter_errors() {
+    let session = create_session();
+    setup_users(&session).await;
+
+    let err = session
+        .execute_sql_with_params(
+            "SELECT name FROM users WHERE id IN UNNEST(@ids)",
+            &[QueryParameter::named(
+                "ids",
+                Value::array(vec![Value::string("1")]),
+            )],
+        )
+        .await
+        .unwrap_err();
+
+    assert!(err.to_string().contains("IN UNNEST"));
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_mistyped_insert_parameter_errors() {
+    let session = create_session();
+    setup_users(&session).await;
+
+    let err = session
+        .execute_sql_with_params(
+            "INSERT INTO users (id, name) VALUES (@id, 'Eve')",
+            &[QueryParameter::named("id", Value::bool_val(true))],
+        )
+        .await
+        .unwrap_err();
+
+    assert!(err.to_string().contains(
+        "Value has type BOOL which cannot be inserted into column id, which has type INT64"
+    ));
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_declared_type_mismatch_errors() {
+    let session = create_session();
+
+    let err = session
+        .execute_sql_with_params(
+            "SELECT @flag",
+            &[QueryParameter::named("flag", Value::string("yes")).with_type(DataType::Bool)],
+        )
+        .await
+        .unwrap_err();
+
+    assert!(
+        err.to_string()
+            .contains("Query parameter '@flag' is declared as BOOL but its value has type STRING")
+    );
+}
@@ -0,0 +1,290 @@
+use std::time::Duration;
+
+use futures::StreamExt;
+use yachtsql::{Error, Value, YachtSQLSession};
+
+use crate::assert_table_eq;
+use crate::common::create_session;
+
+async fn create_numbers(session: &YachtSQLSession, count: i64) {
+    session
+        .execute_sql("CREATE TABLE numbers (id INT64, label STRING)")
+        .await
+        .unwrap();
+    session
+        .execute_sql(&format!(
+            "INSERT INTO numbers SELECT n, CONCAT('n', CAST(n AS STRING)) FROM UNNEST(GENERATE_ARRAY(1, {})) AS n",
+            count
+        ))
+        .await
+        .unwrap();
+}
+
+async fn collect_ids(
+    session: &YachtSQLSession,
+    sql: &str,
+    batch_size: usize,
+) -> Vec<(usize, Vec<i64>)> {
+    let mut stream = session
+        .query_stream_with_batch_size(sql, batch_size)
+        .await
+        .unwrap();
+    let mut batches = Vec::new();
+    while let Some(batch) = stream.next().await {
+        let batch = batch.unwrap();
+        let ids = batch
+            .to_records()
+            .unwrap()
+            .into_iter()
+            .map(|r| r.values()[0].as_i64().unwrap())
+            .collect();
+        batches.push((batch.row_count(), ids));
+    }
+    batches
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_stream_yields_bounded_batches() {
+    let session = create_session();
+    create_numbers(&session, 2500).await;
+
+    let batches = collect_ids(&session, "SELECT id FROM numbers", 1000).await;
+
+    let sizes: Vec<usize> = batches.iter().map(|(n, _)| *n).collect();
+    assert_eq!(sizes, vec![1000, 1000, 500]);
+    let ids: Vec<i64> = batches.into_iter().flat_map(|(_, ids)| ids).collect();
+    assert_eq!(ids, (1..=2500).collect::<Vec<_>>());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_stream_applies_filter_and_projection() {
+    let session = create_session();
+    create_numbers(&session, 100).await;
+
+    let mut stream = session
+        .query_stream_with_batch_size(
+            "SELECT id * 10 AS tens, label FROM numbers WHERE MOD(id, 25) = 0",
+            30,
+        )
+        .await
+        .unwrap();
+
+    let mut rows = Vec::new();
+    while let Some(batch) = stream.next().await {
+        rows.extend(batch.unwrap().to_records().unwrap());
+    }
+    let values: Vec<Vec<Value>> = rows.into_iter().map(|r| r.values().to_vec()).collect();
+    assert_eq!(
+        values,
+        vec![
+            vec![Value::int64(250), Value::string("n25")],
+            vec![Value::int64(500), Value::string("n50")],
+            vec![Value::int64(750), Value::string("n75")],
+            vec![Value::int64(1000), Value::string("n100")],
+        ]
+    );
+    assert_eq!(stream.schema().field_count(), 2);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_stream_subqueries_read_the_whole_table() {
+    let session = create_session();
+    create_numbers(&session, 1000).await;
+
+    let batches = collect_ids(
+        &session,
+        "SELECT id FROM numbers
+         WHERE id > (SELECT AVG(id) FROM numbers)
+           AND EXISTS (SELECT 1 FROM numbers AS n WHERE n.id = 1000)
+           AND id IN (SELECT id FROM numbers WHERE MOD(id, 2) = 0)",
+        100,
+    )
+    .await;
+
+    let ids: Vec<i64> = batches.into_iter().flat_map(|(_, ids)| ids).collect();
+    assert_eq!(
+        ids,
+        (501..=1000).filter(|id| id % 2 == 0).collect::<Vec<_>>()
+    );
+
+    let batches = collect_ids(
+        &session,
+        "SELECT id - (SELECT MIN(id) FROM numbers) AS offset_from_min FROM numbers",
+        300,
+    )
+    .await;
+    let sizes: Vec<usize> = batches.iter().map(|(n, _)| *n).collect();
+    assert_eq!(sizes, vec![300, 300, 300, 100]);
+    let offsets: Vec<i64> = batches.into_iter().flat_map(|(_, ids)| ids).collect();
+    assert_eq!(offsets, (0..1000).collect::<Vec<_>>());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_stream_limit_and_offset_span_batches() {
+    let session = create_session();
+    create_numbers(&session, 500).await;
+
+    let batches = collect_ids(
+        &session,
+        "SELECT id FROM numbers WHERE id > 10 LIMIT 150 OFFSET 95",
+        100,
+    )
+    .await;
+
+    let ids: Vec<i64> = batches.into_iter().flat_map(|(_, ids)| ids).collect();
+    assert_eq!(ids, (106..=255).collect::<Vec<_>>());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_stream_can_stop_early() {
+    let session = create_session();
+    create_numbers(&session, 5000).await;
+
+    let mut stream = session
+        .query_stream_with_batch_size("SELECT id FROM numbers", 100)
+        .await
+        .unwrap();
+    let first = stream.next().await.unwrap().unwrap();
+    assert_eq!(first.row_count(), 100);
+    drop(stream);
+
+    session
+        .execute_sql("DELETE FROM numbers WHERE id > 10")
+        .await
+        .unwrap();
+    let result = session
+        .execute_sql("SELECT COUNT(*) FROM numbers")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[10]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_stream_non_pipelined_query_is_batched() {
+    let session = create_session();
+    create_numbers(&session, 250).await;
+
+    let batches = collect_ids(&session, "SELECT id FROM numbers ORDER BY id DESC", 100).await;
+
+    let sizes: Vec<usize> = batches.iter().map(|(n, _)| *n).collect();
+    assert_eq!(sizes, vec![100, 100, 50]);
+    assert_eq!(batches[0].1[0], 250);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_stream_empty_result_keeps_schema() {
+    let session = create_session();
+    create_numbers(&session, 10).await;
+
+    let mut stream = session
+        .query_stream("SELECT id, label FROM numbers WHERE id > 100")
+        .await
+        .unwrap();
+
+    assert!(stream.next().await.is_none());
+    let names: Vec<&str> = stream
+        .schema()
+        .fields()
+        .iter()
+        .map(|f| f.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["id", "label"]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_stream_dml_statement() {
+    let session = create_session();
+    create_numbers(&session, 10).await;
+
+    let mut stream = session
+        .query_stream("DELETE FROM numbers WHERE id <= 4")
+        .await
+        .unwrap();
+    while stream.next().await.is_some() {}
+
+    let result = session
+        .execute_sql("SELECT COUNT(*) FROM numbers")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[6]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_stream_reports_errors() {
+    let session = create_session();
+    create_numbers(&session, 300).await;
+
+    assert!(session.query_stream("SELEC id FROM numbers").await.is_err());
+    assert!(
+        session
+            .query_stream("SELECT id FROM missing")
+            .await
+            .is_err()
+    );
+
+    let mut stream = session
+        .query_stream_with_batch_size("SELECT DIV(id, id - 150) FROM numbers", 100)
+        .await
+        .unwrap();
+    let first = stream.next().await.unwrap();
+    assert!(first.is_ok());
+    let second = stream.next().await.unwrap();
+    assert!(
+        second
+            .unwrap_err()
+            .to_string()
+            .to_lowercase()
+            .contains("division by zero")
+    );
+    assert!(stream.next().await.is_none());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_cancelled_stream_stops_at_next_batch() {
+    let session = create_session();
+    create_numbers(&session, 10000).await;
+
+    let mut stream = session
+        .query_stream_with_batch_size("SELECT id FROM numbers", 100)
+        .await
+        .unwrap();
+    assert!(stream.next().await.unwrap().is_ok());
+    stream.cancel();
+
+    let mut rows = 100;
+    let mut last = None;
+    while let Some(batch) = stream.next().await {
+        match batch {
+            Ok(batch) => rows += batch.row_count(),
+            Err(e) => last = Some(e),
+        }
+    }
+    assert_eq!(last, Some(Error::QueryCancelled));
+    assert!(rows < 10000);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_stream_honours_job_timeout() {
+    let session = create_session();
+    create_numbers(&session, 20000).await;
+    session.set_job_timeout(Some(Duration::from_millis(100)));
+
+    let mut stream = session
+        .query_stream("SELECT COUNT(*) FROM numbers a JOIN numbers b ON MOD(a.id + b.id, 7) = 99")
+        .await
+        .unwrap();
+    let err = stream.next().await.unwrap().unwrap_err();
+    assert_eq!(err, Error::QueryTimeout { timeout_ms: 100 });
+    assert!(stream.next().await.is_none());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_stream_statement_result_is_batched() {
+    let session = create_session();
+    create_numbers(&session, 250).await;
+
+    let batches = collect_ids(&session, "EXECUTE IMMEDIATE 'SELECT id FROM numbers'", 100).await;
+
+    let sizes: Vec<usize> = batches.iter().map(|(n, _)| *n).collect();
+    assert_eq!(sizes, vec![100, 100, 50]);
+}
@@ -0,0 +1,173 @@
+use crate::assert_table_eq;
+use crate::common::create_session;
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_script_runs_statements_in_order() {
+    let session = create_session();
+
+    let script = session
+        .execute_script(
+            "CREATE TABLE users (id INT64, name STRING);
+            INSERT INTO users VALUES (1, 'Alice'), (2, 'Bob');
+            SELECT name FROM users ORDER BY id;",
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(script.statements.len(), 3);
+    assert!(!script.statements[0].is_query);
+    assert!(!script.statements[1].is_query);
+    assert!(script.statements[2].is_query);
+    assert_table_eq!(script.statements[2].table, [["Alice"], ["Bob"]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_script_keeps_variables_between_statements() {
+    let session = create_session();
+
+    let script = session
+        .execute_script(
+            "DECLARE total INT64 DEFAULT 0;
+            SET total = total + 5;
+            SET total = total * 2;
+            SELECT total;",
+        )
+        .await
+        .unwrap();
+
+    assert_table_eq!(*script.last_query_result().unwrap(), [[10]]);
+
+    let result = session.execute_sql("SELECT total").await.unwrap();
+    assert_table_eq!(result, [[10]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_script_keeps_temp_tables_between_statements() {
+    let session = create_session();
+
+    let script = session
+        .execute_script(
+            "CREATE TEMP TABLE staging (id INT64);
+            INSERT INTO staging VALUES (1), (2), (3);
+            SELECT COUNT(*) FROM staging;",
+        )
+        .await
+        .unwrap();
+
+    assert_table_eq!(*script.last_query_result().unwrap(), [[3]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_script_last_query_result_skips_trailing_dml() {
+    let session = create_session();
+
+    let script = session
+        .execute_script(
+            "CREATE TABLE items (id INT64);
+            INSERT INTO items VALUES (1);
+            SELECT id FROM items;
+            INSERT INTO items VALUES (2);",
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(script.statements.len(), 4);
+    assert_table_eq!(*script.last_query_result().unwrap(), [[1]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_script_with_block_containing_semicolons() {
+    let session = create_session();
+
+    let script = session
+        .execute_script(
+            "DECLARE i INT64 DEFAULT 0;
+            WHILE i < 3 DO
+                SET i = i + 1;
+            END WHILE;
+            BEGIN
+                SET i = i * 10;
+            END;
+            SELECT i",
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(script.statements.len(), 4);
+    assert_table_eq!(*script.last_query_result().unwrap(), [[30]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_script_ignores_comments_and_empty_statements() {
+    let session = create_session();
+
+    let script = session
+        .execute_script(
+            "-- leading comment
+            SELECT 'a;b' AS s;;
+            /* block; comment */
+            SELECT 2;",
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(script.statements.len(), 2);
+    assert_eq!(script.statements[0].sql, "SELECT 'a;b' AS s");
+    assert_table_eq!(script.statements[0].table, [["a;b"]]);
+    assert_table_eq!(*script.last_query_result().unwrap(), [[2]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_script_stops_at_first_error() {
+    let session = create_session();
+
+    let result = session
+        .execute_script(
+            "CREATE TABLE t (id INT64);
+            INSERT INTO missing_table VALUES (1);
+            INSERT INTO t VALUES (1);",
+        )
+        .await;
+    assert!(result.is_err());
+
+    let count = session.execute_sql("SELECT COUNT(*) FROM t").await.unwrap();
+    assert_table_eq!(count, [[0]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_script_without_query_has_no_last_result() {
+    let session = create_session();
+
+    let script = session
+        .execute_script("CREATE TABLE t (id INT64); INSERT INTO t VALUES (1)")
+        .await
+        .unwrap();
+
+    assert!(script.last_query_result().is_none());
+}
+
+#[test]
+fn test_split_script_reports_positions() {
+    let statements = yachtsql::split_script("SELECT 1;\n  SELECT 2;").unwrap();
+
+    assert_eq!(statements.len(), 2);
+    assert_eq!(statements[1].sql, "SELECT 2");
+    assert_eq!(statements[1].line, 2);
+    assert_eq!(statements[1].column, 3);
+}
+
+#[test]
+fn test_split_script_load_data() {
+    let statements = yachtsql::split_script(
+        "LOAD DATA INTO t FROM FILES (format = 'CSV', uris = ['/tmp/a.csv']); SELECT 1",
+    )
+    .unwrap();
+
+    assert_eq!(statements.len(), 2);
+    assert_eq!(
+        statements[0].sql,
+        "LOAD DATA INTO t FROM FILES (format = 'CSV', uris = ['/tmp/a.csv'])"
+    );
+    assert!(!statements[0].is_query);
+    assert!(statements[1].is_query);
+}
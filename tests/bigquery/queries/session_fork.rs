@@ -0,0 +1,200 @@
+use yachtsql::{Value, YachtSQLEngine};
+
+use crate::assert_table_eq;
+use crate::common::create_session;
+
+async fn create_fixture() -> yachtsql::YachtSQLSession {
+    let session = create_session();
+    session
+        .execute_sql("CREATE TABLE users (id INT64, name STRING)")
+        .await
+        .unwrap();
+    session
+        .execute_sql("INSERT INTO users VALUES (1, 'Alice'), (2, 'Bob')")
+        .await
+        .unwrap();
+    session
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_fork_starts_with_parent_data() {
+    let fixture = create_fixture().await;
+    let fork = fixture.fork();
+
+    let result = fork
+        .execute_sql("SELECT name FROM users ORDER BY id")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [["Alice"], ["Bob"]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_fork_writes_are_not_visible_to_parent() {
+    let fixture = create_fixture().await;
+    let fork = fixture.fork();
+
+    fork.execute_sql("INSERT INTO users VALUES (3, 'Carol')")
+        .await
+        .unwrap();
+    fork.execute_sql("UPDATE users SET name = 'Alicia' WHERE id = 1")
+        .await
+        .unwrap();
+    fork.execute_sql("CREATE TABLE orders (id INT64)")
+        .await
+        .unwrap();
+
+    let result = fixture
+        .execute_sql("SELECT name FROM users ORDER BY id")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [["Alice"], ["Bob"]]);
+    assert!(!fixture.catalog().table_exists("orders"));
+
+    let result = fork
+        .execute_sql("SELECT name FROM users ORDER BY id")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [["Alicia"], ["Bob"], ["Carol"]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_parent_writes_are_not_visible_to_fork() {
+    let fixture = create_fixture().await;
+    let fork = fixture.fork();
+
+    fixture
+        .execute_sql("DELETE FROM users WHERE id = 1")
+        .await
+        .unwrap();
+    fixture
+        .execute_sql("ALTER TABLE users ADD COLUMN email STRING")
+        .await
+        .unwrap();
+
+    let result = fork
+        .execute_sql("SELECT * FROM users ORDER BY id")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[1, "Alice"], [2, "Bob"]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_fork_copies_views_functions_procedures_and_schemas() {
+    let fixture = create_fixture().await;
+    fixture.execute_sql("CREATE SCHEMA app").await.unwrap();
+    fixture
+        .execute_sql("CREATE TABLE app.events (id INT64)")
+        .await
+        .unwrap();
+    fixture
+        .execute_sql("CREATE VIEW user_names AS SELECT name FROM users")
+        .await
+        .unwrap();
+    fixture
+        .execute_sql("CREATE FUNCTION double_it(x INT64) AS (x * 2)")
+        .await
+        .unwrap();
+    fixture
+        .execute_sql("CREATE PROCEDURE noop() BEGIN SELECT 1; END")
+        .await
+        .unwrap();
+
+    let fork = fixture.fork();
+
+    let result = fork
+        .execute_sql("SELECT name FROM user_names ORDER BY name")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [["Alice"], ["Bob"]]);
+    let result = fork.execute_sql("SELECT double_it(21)").await.unwrap();
+    assert_table_eq!(result, [[42]]);
+    assert!(fork.catalog().procedure_exists("noop"));
+    assert!(fork.catalog().table_exists("app.events"));
+
+    fork.execute_sql("DROP VIEW user_names").await.unwrap();
+    fork.execute_sql("DROP FUNCTION double_it").await.unwrap();
+    assert!(fixture.catalog().view_exists("user_names"));
+    let result = fixture.execute_sql("SELECT double_it(21)").await.unwrap();
+    assert_table_eq!(result, [[42]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_fork_copies_temp_tables_and_variables() {
+    let fixture = create_session();
+    fixture
+        .execute_sql("CREATE TEMP TABLE scratch (x INT64)")
+        .await
+        .unwrap();
+    fixture
+        .execute_sql("INSERT INTO scratch VALUES (1)")
+        .await
+        .unwrap();
+    fixture
+        .session()
+        .set_variable("threshold", Value::int64(10));
+
+    let fork = fixture.fork();
+    fork.execute_sql("INSERT INTO scratch VALUES (2)")
+        .await
+        .unwrap();
+    fork.session().set_variable("threshold", Value::int64(20));
+
+    let result = fork
+        .execute_sql("SELECT x FROM scratch ORDER BY x")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[1], [2]]);
+    let result = fixture.execute_sql("SELECT x FROM scratch").await.unwrap();
+    assert_table_eq!(result, [[1]]);
+    assert_eq!(
+        fixture.session().get_variable("threshold"),
+        Some(Value::int64(10))
+    );
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_fork_of_shared_catalog_session_is_detached() {
+    let engine = YachtSQLEngine::with_shared_catalog();
+    let session = engine.create_session();
+    session
+        .execute_sql("CREATE TABLE items (id INT64)")
+        .await
+        .unwrap();
+
+    let fork = session.fork();
+    fork.execute_sql("INSERT INTO items VALUES (1)")
+        .await
+        .unwrap();
+
+    let other = engine.create_session();
+    let result = other
+        .execute_sql("SELECT COUNT(*) FROM items")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[0]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_many_forks_diverge_independently() {
+    let fixture = create_fixture().await;
+    let forks: Vec<_> = (0..16).map(|_| fixture.fork()).collect();
+
+    for (i, fork) in forks.iter().enumerate() {
+        fork.execute_sql(&format!("INSERT INTO users VALUES ({}, 'user')", i + 10))
+            .await
+            .unwrap();
+    }
+
+    for fork in &forks {
+        let result = fork
+            .execute_sql("SELECT COUNT(*) FROM users")
+            .await
+            .unwrap();
+        assert_table_eq!(result, [[3]]);
+    }
+    let result = fixture
+        .execute_sql("SELECT COUNT(*) FROM users")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[2]]);
+}
@@ -0,0 +1,319 @@
+use yachtsql::{Value, YachtSQLEngine};
+
+use crate::assert_table_eq;
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_permanent_tables_are_shared_between_sessions() {
+    let engine = YachtSQLEngine::with_shared_catalog();
+    let writer = engine.create_session();
+    let reader = engine.create_session();
+
+    writer.execute_sql("CREATE SCHEMA ds").await.unwrap();
+    writer
+        .execute_sql("CREATE TABLE ds.users (id INT64, name STRING)")
+        .await
+        .unwrap();
+    reader
+        .execute_sql("INSERT INTO ds.users VALUES (1, 'Alice'), (2, 'Bob')")
+        .await
+        .unwrap();
+
+    let result = writer
+        .execute_sql("SELECT name FROM ds.users ORDER BY id")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [["Alice"], ["Bob"]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_isolated_engine_does_not_share_tables() {
+    let engine = YachtSQLEngine::new();
+    let first = engine.create_session();
+    let second = engine.create_session();
+
+    first
+        .execute_sql("CREATE TABLE users (id INT64)")
+        .await
+        .unwrap();
+
+    assert!(second.execute_sql("SELECT * FROM users").await.is_err());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_isolated_engine_keeps_temp_table_names() {
+    let engine = YachtSQLEngine::new();
+    let session = engine.create_session();
+
+    session
+        .execute_sql("CREATE TEMP TABLE scratch (id INT64)")
+        .await
+        .unwrap();
+
+    let names = session.catalog().get_table_names();
+    assert!(names.contains(&"SCRATCH".to_string()));
+    assert!(!names.iter().any(|name| name.starts_with("_SESSION.")));
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_temp_tables_stay_out_of_the_shared_dataset_index() {
+    let engine = YachtSQLEngine::with_shared_catalog();
+    let first = engine.create_session();
+    let second = engine.create_session();
+    for session in [&first, &second] {
+        session
+            .catalog()
+            .set_default_project(Some("proj".to_string()));
+    }
+
+    first.execute_sql("CREATE SCHEMA ds").await.unwrap();
+    first
+        .execute_sql("CREATE TABLE ds.users (id INT64)")
+        .await
+        .unwrap();
+    first
+        .execute_sql("CREATE TEMP TABLE scratch (id INT64)")
+        .await
+        .unwrap();
+    first
+        .execute_sql("CREATE TABLE _SESSION.notes AS SELECT 1 AS id")
+        .await
+        .unwrap();
+
+    assert_eq!(second.catalog().get_datasets("proj"), vec!["DS"]);
+    assert_eq!(
+        second.catalog().get_tables_in_dataset("proj", "ds"),
+        vec!["USERS"]
+    );
+    assert!(
+        second
+            .catalog()
+            .get_tables_in_dataset("proj", "_SESSION")
+            .is_empty()
+    );
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_temp_tables_are_private_to_their_session() {
+    let engine = YachtSQLEngine::with_shared_catalog();
+    let first = engine.create_session();
+    let second = engine.create_session();
+
+    first
+        .execute_sql("CREATE TEMP TABLE scratch (x INT64)")
+        .await
+        .unwrap();
+    first
+        .execute_sql("INSERT INTO scratch VALUES (1)")
+        .await
+        .unwrap();
+    assert!(second.execute_sql("SELECT * FROM scratch").await.is_err());
+    assert!(first.catalog().table_exists("scratch"));
+    assert!(!second.catalog().table_exists("scratch"));
+
+    second
+        .execute_sql("CREATE TEMP TABLE scratch (x INT64)")
+        .await
+        .unwrap();
+    second
+        .execute_sql("INSERT INTO scratch VALUES (2), (3)")
+        .await
+        .unwrap();
+
+    let result = first.execute_sql("SELECT x FROM scratch").await.unwrap();
+    assert_table_eq!(result, [[1]]);
+    let result = second
+        .execute_sql("SELECT x FROM scratch ORDER BY x")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[2], [3]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_session_qualified_temp_table_names() {
+    let engine = YachtSQLEngine::with_shared_catalog();
+    let session = engine.create_session();
+
+    session
+        .execute_sql("CREATE TEMP TABLE events (id INT64)")
+        .await
+        .unwrap();
+    session
+        .execute_sql("INSERT INTO _SESSION.events VALUES (1), (2)")
+        .await
+        .unwrap();
+    session
+        .execute_sql("CREATE TABLE _SESSION.more_events (id INT64)")
+        .await
+        .unwrap();
+    session
+        .execute_sql("INSERT INTO more_events SELECT id + 10 FROM _SESSION.events")
+        .await
+        .unwrap();
+
+    let result = session
+        .execute_sql("SELECT id FROM _SESSION.more_events ORDER BY id")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[11], [12]]);
+
+    session
+        .execute_sql("DROP TABLE _SESSION.events")
+        .await
+        .unwrap();
+    assert!(session.execute_sql("SELECT * FROM events").await.is_err());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_temp_table_shadows_permanent_table_in_its_session() {
+    let engine = YachtSQLEngine::with_shared_catalog();
+    let first = engine.create_session();
+    let second = engine.create_session();
+
+    first
+        .execute_sql("CREATE TABLE items (name STRING)")
+        .await
+        .unwrap();
+    first
+        .execute_sql("INSERT INTO items VALUES ('permanent')")
+        .await
+        .unwrap();
+    second
+        .execute_sql("CREATE TEMP TABLE items (name STRING)")
+        .await
+        .unwrap();
+    second
+        .execute_sql("INSERT INTO items VALUES ('temporary')")
+        .await
+        .unwrap();
+
+    let result = first.execute_sql("SELECT name FROM items").await.unwrap();
+    assert_table_eq!(result, [["permanent"]]);
+    let result = second.execute_sql("SELECT name FROM items").await.unwrap();
+    assert_table_eq!(result, [["temporary"]]);
+
+    second.execute_sql("DROP TABLE items").await.unwrap();
+    let result = second.execute_sql("SELECT name FROM items").await.unwrap();
+    assert_table_eq!(result, [["permanent"]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_temp_tables_are_dropped_with_their_session() {
+    let engine = YachtSQLEngine::with_shared_catalog();
+    let session = engine.create_session();
+    session
+        .execute_sql("CREATE TABLE kept (id INT64)")
+        .await
+        .unwrap();
+    session
+        .execute_sql("CREATE TEMP TABLE discarded (id INT64)")
+        .await
+        .unwrap();
+    drop(session);
+
+    let session = engine.create_session();
+    assert!(session.catalog().table_exists("kept"));
+    assert!(!session.catalog().table_exists("discarded"));
+    assert!(!session.catalog().table_exists("_SESSION.discarded"));
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_temp_functions_are_private_to_their_session() {
+    let engine = YachtSQLEngine::with_shared_catalog();
+    let first = engine.create_session();
+    let second = engine.create_session();
+
+    first
+        .execute_sql("CREATE TEMP FUNCTION double_it(x INT64) AS (x * 2)")
+        .await
+        .unwrap();
+    first
+        .execute_sql("CREATE FUNCTION triple_it(x INT64) AS (x * 3)")
+        .await
+        .unwrap();
+
+    let result = first.execute_sql("SELECT double_it(4)").await.unwrap();
+    assert_table_eq!(result, [[8]]);
+    assert!(second.execute_sql("SELECT double_it(4)").await.is_err());
+    let result = second.execute_sql("SELECT triple_it(4)").await.unwrap();
+    assert_table_eq!(result, [[12]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_variables_are_private_to_their_session() {
+    let engine = YachtSQLEngine::with_shared_catalog();
+    let first = engine.create_session();
+    let second = engine.create_session();
+
+    first.session().set_variable("threshold", Value::int64(10));
+
+    assert_eq!(
+        first.session().get_variable("threshold"),
+        Some(Value::int64(10))
+    );
+    assert_eq!(second.session().get_variable("threshold"), None);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_schema_change_in_one_session_replans_in_another() {
+    let engine = YachtSQLEngine::with_shared_catalog();
+    let first = engine.create_session();
+    let second = engine.create_session();
+
+    first
+        .execute_sql("CREATE TABLE metrics (id INT64)")
+        .await
+        .unwrap();
+    first
+        .execute_sql("INSERT INTO metrics VALUES (1)")
+        .await
+        .unwrap();
+    let result = first.execute_sql("SELECT * FROM metrics").await.unwrap();
+    assert_table_eq!(result, [[1]]);
+
+    second
+        .execute_sql("ALTER TABLE metrics ADD COLUMN score INT64")
+        .await
+        .unwrap();
+    second
+        .execute_sql("UPDATE metrics SET score = 5 WHERE id = 1")
+        .await
+        .unwrap();
+
+    let result = first.execute_sql("SELECT * FROM metrics").await.unwrap();
+    assert_table_eq!(result, [[1, 5]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_rollback_keeps_other_sessions_commits() {
+    let engine = YachtSQLEngine::with_shared_catalog();
+    let first = engine.create_session();
+    let second = engine.create_session();
+
+    first
+        .execute_sql("CREATE TABLE orders (id INT64)")
+        .await
+        .unwrap();
+    first
+        .execute_sql("CREATE TABLE drafts (id INT64)")
+        .await
+        .unwrap();
+
+    first.execute_sql("BEGIN TRANSACTION").await.unwrap();
+    first
+        .execute_sql("INSERT INTO drafts VALUES (1)")
+        .await
+        .unwrap();
+    second
+        .execute_sql("INSERT INTO orders VALUES (2)")
+        .await
+        .unwrap();
+    first.execute_sql("ROLLBACK TRANSACTION").await.unwrap();
+
+    let result = first.execute_sql("SELECT id FROM orders").await.unwrap();
+    assert_table_eq!(result, [[2]]);
+    let result = second
+        .execute_sql("SELECT COUNT(*) FROM drafts")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[0]]);
+}
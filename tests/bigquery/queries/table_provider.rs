@@ -0,0 +1,232 @@
+use std::sync::{Arc, Mutex};
+
+use yachtsql::{
+    DataType, Error, Expr, Field, Result, Schema, Table, TableProvider, Value, YachtSQLEngine,
+};
+
+use crate::assert_table_eq;
+use crate::common::create_session;
+
+#[derive(Debug, Clone, Default)]
+struct LastScan {
+    projection: Option<Vec<usize>>,
+    filters: usize,
+    scans: usize,
+}
+
+#[derive(Debug)]
+struct Squares {
+    count: i64,
+    last_scan: Mutex<LastScan>,
+}
+
+impl Squares {
+    fn new(count: i64) -> Arc<Self> {
+        Arc::new(Self {
+            count,
+            last_scan: Mutex::new(LastScan::default()),
+        })
+    }
+
+    fn last_scan(&self) -> LastScan {
+        self.last_scan.lock().unwrap().clone()
+    }
+}
+
+impl TableProvider for Squares {
+    fn schema(&self) -> Schema {
+        Schema::from_fields(vec![
+            Field::required("n", DataType::Int64),
+            Field::nullable("square", DataType::Int64),
+            Field::nullable("label", DataType::String),
+        ])
+    }
+
+    fn scan(&self, projection: Option<&[usize]>, filters: &[Expr]) -> Result<Table> {
+        {
+            let mut last_scan = self.last_scan.lock().unwrap();
+            last_scan.projection = projection.map(<[usize]>::to_vec);
+            last_scan.filters = filters.len();
+            last_scan.scans += 1;
+        }
+        let schema = self.schema();
+        let fields: Vec<usize> = match projection {
+            Some(projection) => projection.to_vec(),
+            None => (0..schema.field_count()).collect(),
+        };
+        let rows = (1..=self.count)
+            .map(|n| {
+                let row = [
+                    Value::Int64(n),
+                    Value::Int64(n * n),
+                    Value::String(format!("#{}", n)),
+                ];
+                fields.iter().map(|&i| row[i].clone()).collect()
+            })
+            .collect();
+        let projected =
+            Schema::from_fields(fields.iter().map(|&i| schema.fields()[i].clone()).collect());
+        Table::from_values(projected, rows)
+    }
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_provider_table_can_be_queried() {
+    let session = create_session();
+    session
+        .catalog()
+        .register_table_provider("gen.squares", Squares::new(5))
+        .unwrap();
+
+    let result = session
+        .execute_sql("SELECT * FROM gen.squares WHERE n > 3 ORDER BY n")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[4, 16, "#4"], [5, 25, "#5"]]);
+
+    let result = session
+        .execute_sql("SELECT SUM(square), COUNT(*) FROM gen.squares")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[55, 5]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_provider_table_joins_with_stored_tables() {
+    let session = create_session();
+    session
+        .catalog()
+        .register_table_provider("my-project.gen.squares", Squares::new(4))
+        .unwrap();
+    session
+        .execute_sql("CREATE TABLE names (n INT64, name STRING)")
+        .await
+        .unwrap();
+    session
+        .execute_sql("INSERT INTO names VALUES (2, 'two'), (3, 'three'), (9, 'nine')")
+        .await
+        .unwrap();
+
+    let result = session
+        .execute_sql(
+            "SELECT s.n, s.square, names.name
+             FROM `my-project.gen.squares` AS s JOIN names ON s.n = names.n
+             ORDER BY s.n",
+        )
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[2, 4, "two"], [3, 9, "three"]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_provider_receives_projection_and_filter_hints() {
+    let session = create_session();
+    let squares = Squares::new(10);
+    session
+        .catalog()
+        .register_table_provider("gen.squares", squares.clone())
+        .unwrap();
+
+    let result = session
+        .execute_sql("SELECT square FROM gen.squares WHERE n BETWEEN 2 AND 3 AND square > 5")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[9]]);
+
+    let scan = squares.last_scan();
+    assert_eq!(scan.projection, Some(vec![0, 1]));
+    assert!(scan.filters >= 1, "{scan:?}");
+
+    session
+        .execute_sql("SELECT * FROM gen.squares")
+        .await
+        .unwrap();
+    let scan = squares.last_scan();
+    assert_eq!(scan.projection, None);
+    assert_eq!(scan.filters, 0);
+    assert_eq!(scan.scans, 2);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_provider_tables_are_read_only() {
+    let session = create_session();
+    session
+        .catalog()
+        .register_table_provider("gen.squares", Squares::new(3))
+        .unwrap();
+
+    let err = session
+        .execute_sql("INSERT INTO gen.squares (n) VALUES (4)")
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(&err, Error::InvalidQuery(msg) if msg.contains("read-only")),
+        "{err:?}"
+    );
+
+    let err = session
+        .execute_sql("CREATE TABLE gen.squares (n INT64)")
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(&err, Error::InvalidQuery(msg) if msg.contains("already exists")),
+        "{err:?}"
+    );
+
+    assert!(
+        session
+            .catalog()
+            .register_table_provider("gen.squares", Squares::new(1))
+            .is_err()
+    );
+
+    let result = session
+        .execute_sql("SELECT COUNT(*) FROM gen.squares")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[3]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_deregistered_provider_is_no_longer_visible() {
+    let session = create_session();
+    session
+        .catalog()
+        .register_table_provider("gen.squares", Squares::new(3))
+        .unwrap();
+    session
+        .execute_sql("SELECT COUNT(*) FROM gen.squares")
+        .await
+        .unwrap();
+
+    assert!(
+        session
+            .catalog()
+            .deregister_table_provider("gen.squares")
+            .is_some()
+    );
+
+    let err = session
+        .execute_sql("SELECT COUNT(*) FROM gen.squares")
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::TableNotFound(_)), "{err:?}");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_provider_is_shared_across_sessions() {
+    let engine = YachtSQLEngine::with_shared_catalog();
+    let first = engine.create_session();
+    let second = engine.create_session();
+
+    first
+        .catalog()
+        .register_table_provider("gen.squares", Squares::new(2))
+        .unwrap();
+
+    let result = second
+        .execute_sql("SELECT n, label FROM gen.squares ORDER BY n")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[1, "#1"], [2, "#2"]]);
+}
@@ -1192,6 +1192,25 @@ async fn test_scalar_function_generate_array_returns_array() {
     assert_table_eq!(result, [[[1, 2, 3, 4, 5]]]);
 }
 
+#[tokio::test(flavor = "current_thread")]
+async fn test_unnest_generated_arrays_keeps_element_types() {
+    let session = create_session();
+    let result = session
+        .execute_sql(
+            "SELECT i, d FROM UNNEST(GENERATE_ARRAY(1, 2)) AS i \
+             CROSS JOIN UNNEST(GENERATE_DATE_ARRAY(DATE '2024-01-01', DATE '2024-01-01')) AS d",
+        )
+        .await
+        .unwrap();
+    let types: Vec<_> = result
+        .schema()
+        .fields()
+        .iter()
+        .map(|f| f.data_type.to_bq_type())
+        .collect();
+    assert_eq!(types, ["INT64", "DATE"]);
+}
+
 #[tokio::test(flavor = "current_thread")]
 async fn test_scalar_function_starts_with_returns_bool() {
     let session = create_session();
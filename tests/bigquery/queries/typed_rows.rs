@@ -0,0 +1,402 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use yachtsql::{Error, Value, YachtSQLEngine};
+
+use crate::assert_table_eq;
+use crate::common::{create_session, d};
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct User {
+    name: String,
+    id: i64,
+    email: Option<String>,
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_query_as_maps_columns_by_name() {
+    let session = create_session();
+    session
+        .execute_sql("CREATE TABLE users (id INT64, name STRING, email STRING)")
+        .await
+        .unwrap();
+    session
+        .execute_sql("INSERT INTO users VALUES (1, 'Alice', 'alice@example.com'), (2, 'Bob', NULL)")
+        .await
+        .unwrap();
+
+    let users: Vec<User> = session
+        .query_as("SELECT * FROM users ORDER BY id")
+        .await
+        .unwrap();
+
+    assert_eq!(
+        users,
+        vec![
+            User {
+                name: "Alice".to_string(),
+                id: 1,
+                email: Some("alice@example.com".to_string()),
+            },
+            User {
+                name: "Bob".to_string(),
+                id: 2,
+                email: None,
+            },
+        ]
+    );
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Address {
+    city: String,
+    zip: String,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Customer {
+    id: i64,
+    address: Address,
+    tags: Vec<String>,
+    scores: Vec<Option<f64>>,
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_query_as_maps_structs_and_arrays() {
+    let session = create_session();
+
+    let customers: Vec<Customer> = session
+        .query_as(
+            "SELECT 7 AS id,
+                    STRUCT('Paris' AS city, '75001' AS zip) AS address,
+                    ['vip', 'new'] AS tags,
+                    [1.5, NULL] AS scores",
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        customers,
+        vec![Customer {
+            id: 7,
+            address: Address {
+                city: "Paris".to_string(),
+                zip: "75001".to_string(),
+            },
+            tags: vec!["vip".to_string(), "new".to_string()],
+            scores: vec![Some(1.5), None],
+        }]
+    );
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Payment {
+    amount: Decimal,
+    paid_on: NaiveDate,
+    paid_at: DateTime<Utc>,
+    local_time: NaiveDateTime,
+    cutoff: NaiveTime,
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_query_as_maps_numeric_and_temporal_types() {
+    let session = create_session();
+
+    let payments: Vec<Payment> = session
+        .query_as(
+            "SELECT NUMERIC '12.50' AS amount,
+                    DATE '2024-03-15' AS paid_on,
+                    TIMESTAMP '2024-03-15 10:30:00.250 UTC' AS paid_at,
+                    DATETIME '2024-03-15 12:30:00' AS local_time,
+                    TIME '17:00:00' AS cutoff",
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        payments,
+        vec![Payment {
+            amount: Decimal::new(1250, 2),
+            paid_on: NaiveDate::from_ymd_opt(2024, 3, 15).unwrap(),
+            paid_at: NaiveDate::from_ymd_opt(2024, 3, 15)
+                .unwrap()
+                .and_hms_milli_opt(10, 30, 0, 250)
+                .unwrap()
+                .and_utc(),
+            local_time: NaiveDate::from_ymd_opt(2024, 3, 15)
+                .unwrap()
+                .and_hms_opt(12, 30, 0)
+                .unwrap(),
+            cutoff: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
+        }]
+    );
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_query_as_tuples_and_scalars() {
+    let session = create_session();
+
+    let pairs: Vec<(i64, String)> = session
+        .query_as("SELECT x, CAST(x * 10 AS STRING) FROM UNNEST([1, 2]) AS x ORDER BY x")
+        .await
+        .unwrap();
+    assert_eq!(pairs, vec![(1, "10".to_string()), (2, "20".to_string())]);
+
+    let counts: Vec<i64> = session
+        .query_as("SELECT COUNT(*) FROM UNNEST([1, 2, 3])")
+        .await
+        .unwrap();
+    assert_eq!(counts, vec![3]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_query_as_reports_the_mismatched_column() {
+    let session = create_session();
+
+    let err = session
+        .query_as::<User>("SELECT 'one' AS id, 'Alice' AS name, NULL AS email")
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(&err, Error::SchemaMismatch(msg) if msg.contains("column `id`")),
+        "{err:?}"
+    );
+
+    let err = session
+        .query_as::<User>("SELECT 1 AS id")
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(&err, Error::SchemaMismatch(msg) if msg.contains("name")),
+        "{err:?}"
+    );
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Location {
+    city: String,
+    zip: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Order {
+    id: i64,
+    amount: Decimal,
+    placed_on: NaiveDate,
+    placed_at: DateTime<Utc>,
+    ship_to: Location,
+    items: Vec<String>,
+    note: Option<String>,
+}
+
+fn sample_orders() -> Vec<Order> {
+    let placed_at = NaiveDate::from_ymd_opt(2024, 1, 2)
+        .unwrap()
+        .and_hms_opt(8, 15, 0)
+        .unwrap()
+        .and_utc();
+    vec![
+        Order {
+            id: 1,
+            amount: Decimal::new(1999, 2),
+            placed_on: NaiveDate::from_ymd_opt(2024, 1, 2).unwrap(),
+            placed_at,
+            ship_to: Location {
+                city: "Berlin".to_string(),
+                zip: Some("10115".to_string()),
+            },
+            items: vec!["book".to_string(), "pen".to_string()],
+            note: None,
+        },
+        Order {
+            id: 2,
+            amount: Decimal::new(5, 0),
+            placed_on: NaiveDate::from_ymd_opt(2024, 1, 3).unwrap(),
+            placed_at,
+            ship_to: Location {
+                city: "Oslo".to_string(),
+                zip: None,
+            },
+            items: Vec::new(),
+            note: Some("gift".to_string()),
+        },
+    ]
+}
+
+async fn create_orders_table(session: &yachtsql::YachtSQLSession) {
+    session
+        .execute_sql(
+            "CREATE TABLE orders (
+                id INT64,
+                amount NUMERIC,
+                placed_on DATE,
+                placed_at TIMESTAMP,
+                ship_to STRUCT<zip STRING, city STRING>,
+                items ARRAY<STRING>,
+                note STRING
+            )",
+        )
+        .await
+        .unwrap();
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_insert_rows_round_trips_through_query_as() {
+    let session = create_session();
+    create_orders_table(&session).await;
+
+    let orders = sample_orders();
+    let inserted = session.insert_rows("orders", &orders).await.unwrap();
+    assert_eq!(inserted, 2);
+
+    let result = session
+        .execute_sql(
+            "SELECT id, CAST(amount AS STRING), placed_on, ship_to.city, ARRAY_LENGTH(items)
+             FROM orders ORDER BY id",
+        )
+        .await
+        .unwrap();
+    assert_table_eq!(
+        result,
+        [
+            [1, "19.99", d(2024, 1, 2), "Berlin", 2],
+            [2, "5", d(2024, 1, 3), "Oslo", 0],
+        ]
+    );
+
+    let read_back: Vec<Order> = session
+        .query_as("SELECT * FROM orders ORDER BY id")
+        .await
+        .unwrap();
+    assert_eq!(read_back, orders);
+}
+
+#[derive(Serialize)]
+struct PartialOrder {
+    #[serde(rename = "ID")]
+    id: i64,
+    amount: f64,
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_insert_rows_fills_missing_columns_with_null() {
+    let session = create_session();
+    create_orders_table(&session).await;
+
+    session
+        .insert_rows("orders", &[PartialOrder { id: 9, amount: 2.5 }])
+        .await
+        .unwrap();
+
+    let result = session
+        .execute_sql(
+            "SELECT id, CAST(amount AS STRING), note IS NULL, placed_on IS NULL FROM orders",
+        )
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[9, "2.5", true, true]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_insert_values_appends_struct_rows() {
+    let session = create_session();
+    create_orders_table(&session).await;
+
+    let appended = session
+        .insert_values(
+            "orders",
+            vec![Value::Struct(vec![
+                ("id".to_string(), Value::Int64(4)),
+                ("amount".to_string(), Value::String("1.25".to_string())),
+            ])],
+        )
+        .await
+        .unwrap();
+    assert_eq!(appended, 1);
+
+    let result = session
+        .execute_sql("SELECT id, CAST(amount AS STRING), note IS NULL FROM orders")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[4, "1.25", true]]);
+}
+
+#[derive(Serialize)]
+struct UnknownColumn {
+    id: i64,
+    colour: String,
+}
+
+#[derive(Serialize)]
+struct WrongType {
+    id: String,
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_insert_rows_rejects_rows_that_do_not_fit() {
+    let session = create_session();
+    create_orders_table(&session).await;
+
+    let err = session
+        .insert_rows(
+            "orders",
+            &[UnknownColumn {
+                id: 1,
+                colour: "red".to_string(),
+            }],
+        )
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(&err, Error::SchemaMismatch(msg) if msg.contains("colour")),
+        "{err:?}"
+    );
+
+    let err = session
+        .insert_rows(
+            "orders",
+            &[WrongType {
+                id: "one".to_string(),
+            }],
+        )
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(&err, Error::SchemaMismatch(msg) if msg.contains("`id`")),
+        "{err:?}"
+    );
+
+    let err = session
+        .insert_rows("missing_table", &sample_orders())
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::TableNotFound(_)), "{err:?}");
+
+    let result = session
+        .execute_sql("SELECT COUNT(*) FROM orders")
+        .await
+        .unwrap();
+    assert_table_eq!(result, [[0]]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_insert_rows_is_durable() {
+    let dir = tempfile::tempdir().unwrap();
+    {
+        let engine = YachtSQLEngine::open(dir.path()).await.unwrap();
+        let session = engine.create_session();
+        create_orders_table(&session).await;
+        session
+            .insert_rows("orders", &sample_orders())
+            .await
+            .unwrap();
+    }
+
+    let engine = YachtSQLEngine::open(dir.path()).await.unwrap();
+    let session = engine.create_session();
+    let read_back: Vec<Order> = session
+        .query_as("SELECT * FROM orders ORDER BY id")
+        .await
+        .unwrap();
+    assert_eq!(read_back, sample_orders());
+}
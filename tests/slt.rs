@@ -0,0 +1,127 @@
+use std::path::PathBuf;
+
+use yachtsql::YachtSQLEngine;
+use yachtsql_test_utils::slt::SltRunner;
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_slt_files() {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/slt");
+    let mut files: Vec<PathBuf> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|e| e == "slt"))
+        .collect();
+    files.sort();
+    assert!(!files.is_empty(), "no .slt files in {}", dir.display());
+
+    for file in files {
+        let mut runner = SltRunner::new(YachtSQLEngine::new().create_session());
+        if let Err(e) = runner.run_file(&file).await {
+            panic!("{e}");
+        }
+    }
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_slt_complete_rewrites_results() {
+    let script = "\
+# fixture
+statement ok
+CREATE TABLE t (a INT64)
+
+statement ok
+INSERT INTO t VALUES (2), (1)
+
+query I rowsort
+SELECT a FROM t
+----
+3
+
+statement ok
+SELECT * FROM missing_table
+
+query error
+SELECT a FROM t
+";
+    let mut runner = SltRunner::new(YachtSQLEngine::new().create_session());
+    assert!(runner.run("fixture.slt", script).await.is_err());
+
+    let mut runner = SltRunner::new(YachtSQLEngine::new().create_session());
+    let completed = runner.complete("fixture.slt", script).await.unwrap();
+    assert_eq!(
+        completed,
+        "\
+# fixture
+statement ok
+CREATE TABLE t (a INT64)
+
+statement ok
+INSERT INTO t VALUES (2), (1)
+
+query I rowsort
+SELECT a FROM t
+----
+1
+2
+
+statement error Table not found: missing_table
+SELECT * FROM missing_table
+
+query I
+SELECT a FROM t
+----
+2
+1
+"
+    );
+
+    let mut runner = SltRunner::new(YachtSQLEngine::new().create_session());
+    runner.run("fixture.slt", &completed).await.unwrap();
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_slt_completed_whitespace_values_run() {
+    let script = "\
+query TTT
+SELECT 'two  spaces', 'line\\nbreak', '   '
+----
+";
+    let mut runner = SltRunner::new(YachtSQLEngine::new().create_session());
+    let completed = runner.complete("fixture.slt", script).await.unwrap();
+    assert_eq!(
+        completed,
+        "\
+query TTT
+SELECT 'two  spaces', 'line\\nbreak', '   '
+----
+two spaces line@break (empty)
+"
+    );
+
+    let mut runner = SltRunner::new(YachtSQLEngine::new().create_session());
+    runner.run("fixture.slt", &completed).await.unwrap();
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_slt_checks_column_types() {
+    let script = "\
+query IT
+SELECT 1.5, 'a'
+----
+1.500 a
+";
+    let mut runner = SltRunner::new(YachtSQLEngine::new().create_session());
+    let err = runner.run("fixture.slt", script).await.unwrap_err();
+    assert!(err.message.contains("column 1 is FLOAT64"), "{err}");
+
+    let mut runner = SltRunner::new(YachtSQLEngine::new().create_session());
+    let completed = runner.complete("fixture.slt", script).await.unwrap();
+    assert!(completed.starts_with("query RT\n"), "{completed}");
+
+    let mut runner = SltRunner::new(YachtSQLEngine::new().create_session());
+    let err = runner
+        .run("fixture.slt", "query X\nSELECT 1\n")
+        .await
+        .unwrap_err();
+    assert!(err.message.contains("unknown column type 'X'"), "{err}");
+}